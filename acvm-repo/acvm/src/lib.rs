@@ -9,6 +9,9 @@ pub mod pwg;
 pub use acvm_blackbox_solver::{BlackBoxFunctionSolver, BlackBoxResolutionError};
 use pwg::OpcodeResolutionError;
 
+/// Version of the ACVM crate, as reported by cargo
+pub const ACVM_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 // re-export acir
 pub use acir;
 pub use acir::{AcirField, FieldElement};
@@ -140,6 +140,14 @@ pub enum OpcodeResolutionError<F> {
     AcirCallOutputsMismatch { opcode_location: ErrorLocation, results_size: u32, outputs_size: u32 },
 }
 
+/// Error returned when moving execution to an opcode the circuit doesn't have
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Error)]
+#[error("Instruction pointer {instruction_pointer} is out of range for a circuit of {opcodes_len} opcodes")]
+pub struct InstructionPointerOutOfRange {
+    pub instruction_pointer: usize,
+    pub opcodes_len: usize,
+}
+
 impl<F> From<BlackBoxResolutionError> for OpcodeResolutionError<F> {
     fn from(value: BlackBoxResolutionError) -> Self {
         match value {
@@ -228,6 +236,31 @@ impl<'a, F: AcirField, B: BlackBoxFunctionSolver<F>> ACVM<'a, F, B> {
         self.instruction_pointer
     }
 
+    /// Moves execution to the opcode at `instruction_pointer` without solving the
+    /// opcodes in between, abandoning any Brillig or foreign call in progress.
+    ///
+    /// This is meant for debuggers letting users skip or re-run opcodes: the
+    /// witnesses that skipped opcodes would have solved remain unassigned, and
+    /// re-run opcodes see the values already in the witness map.
+    ///
+    /// Returns an error, leaving the VM untouched, if the circuit has no opcode at
+    /// `instruction_pointer`.
+    pub fn set_instruction_pointer(
+        &mut self,
+        instruction_pointer: usize,
+    ) -> Result<(), InstructionPointerOutOfRange> {
+        if instruction_pointer >= self.opcodes.len() {
+            return Err(InstructionPointerOutOfRange {
+                instruction_pointer,
+                opcodes_len: self.opcodes.len(),
+            });
+        }
+        self.instruction_pointer = instruction_pointer;
+        self.brillig_solver = None;
+        self.status = ACVMStatus::InProgress;
+        Ok(())
+    }
+
     /// Finalize the ACVM execution, returning the resulting [`WitnessMap`].
     pub fn finalize(self) -> WitnessMap<F> {
         if self.status != ACVMStatus::Solved {
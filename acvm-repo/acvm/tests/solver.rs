@@ -12,7 +12,10 @@ use acir::{
     AcirField, FieldElement,
 };
 
-use acvm::pwg::{ACVMStatus, ErrorLocation, ForeignCallWaitInfo, OpcodeResolutionError, ACVM};
+use acvm::pwg::{
+    ACVMStatus, ErrorLocation, ForeignCallWaitInfo, InstructionPointerOutOfRange,
+    OpcodeResolutionError, ACVM,
+};
 use acvm_blackbox_solver::StubbedBlackBoxSolver;
 use brillig_vm::brillig::HeapValueType;
 
@@ -678,6 +681,36 @@ fn unsatisfied_opcode_resolved_brillig() {
     );
 }
 
+#[test]
+fn set_instruction_pointer_rejects_out_of_range_opcodes() {
+    let a = Witness(0);
+    let b = Witness(1);
+
+    // b = a + 1, twice
+    let increment = Opcode::AssertZero(Expression {
+        mul_terms: vec![],
+        linear_combinations: vec![(FieldElement::one(), a), (-FieldElement::one(), b)],
+        q_c: FieldElement::one(),
+    });
+    let opcodes = vec![increment.clone(), increment];
+    let values = WitnessMap::from(BTreeMap::from([(a, FieldElement::from(1u128))]));
+    let unconstrained_functions = vec![];
+    let mut acvm =
+        ACVM::new(&StubbedBlackBoxSolver, &opcodes, values, &unconstrained_functions, &[]);
+
+    assert_eq!(
+        acvm.set_instruction_pointer(2),
+        Err(InstructionPointerOutOfRange { instruction_pointer: 2, opcodes_len: 2 })
+    );
+    assert_eq!(acvm.instruction_pointer(), 0);
+    assert_eq!(acvm.get_status(), &ACVMStatus::InProgress);
+
+    // skipping the first opcode leaves `b` to the second one
+    assert_eq!(acvm.set_instruction_pointer(1), Ok(()));
+    assert_eq!(acvm.solve(), ACVMStatus::Solved);
+    assert_eq!(acvm.finalize()[&b], FieldElement::from(2u128));
+}
+
 #[test]
 fn memory_operations() {
     let initial_witness = WitnessMap::from(BTreeMap::from_iter([
@@ -19,6 +19,9 @@ pub use poseidon2::poseidon2_permutation;
 // without polluting the feature flags of the `acir_field` crate.
 type FieldElement = acir::acir_field::GenericFieldElement<ark_bn254::Fr>;
 
+/// Version of the solver crate, as reported by cargo
+pub const BN254_BLACKBOX_SOLVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[derive(Default)]
 pub struct Bn254BlackBoxSolver;
 
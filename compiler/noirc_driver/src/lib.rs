@@ -41,6 +41,9 @@ pub use program::CompiledProgram;
 const STD_CRATE_NAME: &str = "std";
 const DEBUG_CRATE_NAME: &str = "__debug";
 
+// Mixed into the hash of programs compiled with `--noop-debug-oracles`
+const DEBUG_ORACLES_LOWERED: &str = "noop-debug-oracles";
+
 pub const GIT_COMMIT: &str = env!("GIT_COMMIT");
 pub const GIT_DIRTY: &str = env!("GIT_DIRTY");
 pub const NOIRC_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -95,6 +98,12 @@ pub struct CompileOptions {
     #[arg(long, hide = true)]
     pub instrument_debug: bool,
 
+    /// Keep the debug symbols inserted by `--instrument-debug` but compile the
+    /// debugger's oracle calls to no-ops, so the artifact executes normally and
+    /// can still be stepped through by the debugger, without variable values
+    #[arg(long, hide = true, requires = "instrument_debug")]
+    pub noop_debug_oracles: bool,
+
     /// Force Brillig output (for step debugging)
     #[arg(long, hide = true)]
     pub force_brillig: bool,
@@ -528,7 +537,13 @@ pub fn compile_no_check(
         monomorphize(main_function, &mut context.def_interner)?
     };
 
-    let hash = fxhash::hash64(&program);
+    // The same program compiled with the debug oracles lowered to no-ops has a
+    // different output, so it must not match an artifact compiled without
+    let hash = if options.noop_debug_oracles {
+        fxhash::hash64(&(&program, DEBUG_ORACLES_LOWERED))
+    } else {
+        fxhash::hash64(&program)
+    };
     let hashes_match = cached_program.as_ref().map_or(false, |program| program.hash == hash);
     if options.show_monomorphized {
         println!("{program}");
@@ -549,6 +564,7 @@ pub fn compile_no_check(
         enable_brillig_logging: options.show_brillig,
         force_brillig_output: options.force_brillig,
         print_codegen_timings: options.benchmark_codegen,
+        noop_debug_oracles: options.noop_debug_oracles,
     };
 
     let SsaProgramArtifact { program, debug, warnings, names, error_types, .. } =
@@ -10,6 +10,7 @@ use serde::Serializer;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::io::Read;
 use std::io::Write;
@@ -30,6 +31,9 @@ pub struct DebugFnId(pub u32);
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 pub struct DebugTypeId(pub u32);
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+pub struct DebugAssertId(pub u32);
+
 #[derive(Debug, Clone, Hash, Deserialize, Serialize)]
 pub struct DebugVariable {
     pub name: String,
@@ -45,6 +49,7 @@ pub struct DebugFunction {
 pub type DebugVariables = BTreeMap<DebugVarId, DebugVariable>;
 pub type DebugFunctions = BTreeMap<DebugFnId, DebugFunction>;
 pub type DebugTypes = BTreeMap<DebugTypeId, PrintableType>;
+pub type DebugAsserts = BTreeMap<DebugAssertId, Location>;
 
 #[derive(Default, Debug, Clone, Deserialize, Serialize)]
 pub struct ProgramDebugInfo {
@@ -100,6 +105,16 @@ pub struct DebugInfo {
     pub variables: DebugVariables,
     pub functions: DebugFunctions,
     pub types: DebugTypes,
+    /// Source locations of the asserts of the instrumented program, which are
+    /// numbered in order of file path and position
+    #[serde(default)]
+    pub asserts: DebugAsserts,
+    /// Opcodes of the overflow checks the compiler inserts for integer
+    /// arithmetic, told apart from the asserts of the program failing with the
+    /// same messages
+    #[serde_as(as = "BTreeSet<DisplayFromStr>")]
+    #[serde(default)]
+    pub overflow_checks: BTreeSet<OpcodeLocation>,
 }
 
 /// Holds OpCodes Counts for Acir and Brillig Opcodes
@@ -116,8 +131,9 @@ impl DebugInfo {
         variables: DebugVariables,
         functions: DebugFunctions,
         types: DebugTypes,
+        asserts: DebugAsserts,
     ) -> Self {
-        Self { locations, variables, functions, types }
+        Self { locations, variables, functions, types, asserts, overflow_checks: BTreeSet::new() }
     }
 
     /// Updates the locations map when the [`Circuit`][acvm::acir::circuit::Circuit] is modified.
@@ -134,6 +150,11 @@ impl DebugInfo {
                 self.locations.insert(new_opcode_location, source_locations.clone());
             });
         }
+
+        let old_overflow_checks = mem::take(&mut self.overflow_checks);
+        for old_opcode_location in old_overflow_checks {
+            self.overflow_checks.extend(update_map.new_locations(old_opcode_location));
+        }
     }
 
     pub fn opcode_location(&self, loc: &OpcodeLocation) -> Option<Vec<Location>> {
@@ -5,7 +5,9 @@ use crate::brillig::brillig_ir::{
     BrilligBinaryOp, BrilligContext, BRILLIG_MEMORY_ADDRESSING_BIT_SIZE,
 };
 use crate::ssa::ir::dfg::CallStack;
-use crate::ssa::ir::instruction::ConstrainError;
+use crate::ssa::ir::instruction::{
+    ConstrainError, ADD_OVERFLOW_MESSAGE, MULTIPLY_OVERFLOW_MESSAGE, SUBTRACT_OVERFLOW_MESSAGE,
+};
 use crate::ssa::ir::{
     basic_block::BasicBlockId,
     dfg::DataFlowGraph,
@@ -1548,7 +1550,7 @@ impl<'block> BrilligBlock<'block> {
                     BrilligBinaryOp::LessThanEquals,
                 );
                 self.brillig_context
-                    .codegen_constrain(condition, Some("attempt to add with overflow".to_string()));
+                    .codegen_constrain(condition, Some(ADD_OVERFLOW_MESSAGE.to_string()));
                 self.brillig_context.deallocate_single_addr(condition);
             }
             (BrilligBinaryOp::Sub, false) => {
@@ -1567,10 +1569,8 @@ impl<'block> BrilligBlock<'block> {
                     condition,
                     BrilligBinaryOp::LessThanEquals,
                 );
-                self.brillig_context.codegen_constrain(
-                    condition,
-                    Some("attempt to subtract with overflow".to_string()),
-                );
+                self.brillig_context
+                    .codegen_constrain(condition, Some(SUBTRACT_OVERFLOW_MESSAGE.to_string()));
                 self.brillig_context.deallocate_single_addr(condition);
             }
             (BrilligBinaryOp::Mul, false) => {
@@ -1595,10 +1595,7 @@ impl<'block> BrilligBlock<'block> {
                     // Check that result / rhs == lhs
                     ctx.binary_instruction(result, right, division, BrilligBinaryOp::UnsignedDiv);
                     ctx.binary_instruction(division, left, condition, BrilligBinaryOp::Equals);
-                    ctx.codegen_constrain(
-                        condition,
-                        Some("attempt to multiply with overflow".to_string()),
-                    );
+                    ctx.codegen_constrain(condition, Some(MULTIPLY_OVERFLOW_MESSAGE.to_string()));
                     ctx.deallocate_single_addr(condition);
                     ctx.deallocate_single_addr(division);
                 });
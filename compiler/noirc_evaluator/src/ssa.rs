@@ -21,7 +21,9 @@ use acvm::{
     FieldElement,
 };
 
-use noirc_errors::debug_info::{DebugFunctions, DebugInfo, DebugTypes, DebugVariables};
+use noirc_errors::debug_info::{
+    DebugAsserts, DebugFunctions, DebugInfo, DebugTypes, DebugVariables,
+};
 
 use noirc_frontend::ast::Visibility;
 use noirc_frontend::{
@@ -58,6 +60,11 @@ pub(crate) fn optimize_into_acir(
         options.force_brillig_output,
         options.print_codegen_timings,
     )?
+    .run_pass_if(
+        options.noop_debug_oracles,
+        Ssa::remove_debug_oracle_calls,
+        "After Removing Debug Oracle Calls:",
+    )
     .run_pass(Ssa::defunctionalize, "After Defunctionalization:")
     .run_pass(Ssa::remove_paired_rc, "After Removing Paired rc_inc & rc_decs:")
     .run_pass(Ssa::separate_runtime, "After Runtime Separation:")
@@ -162,6 +169,9 @@ pub struct SsaEvaluatorOptions {
 
     /// Pretty print benchmark times of each code generation pass
     pub print_codegen_timings: bool,
+
+    /// Lower the calls to the debugger's oracles to no-ops
+    pub noop_debug_oracles: bool,
 }
 
 /// Compiles the [`Program`] into [`ACIR``][acvm::acir::circuit::Program].
@@ -175,6 +185,7 @@ pub fn create_program(
     let debug_variables = program.debug_variables.clone();
     let debug_types = program.debug_types.clone();
     let debug_functions = program.debug_functions.clone();
+    let debug_asserts = program.debug_asserts.clone();
 
     let func_sigs = program.function_signatures.clone();
 
@@ -198,6 +209,7 @@ pub fn create_program(
             debug_variables.clone(),
             debug_functions.clone(),
             debug_types.clone(),
+            debug_asserts.clone(),
         );
         program_artifact.add_circuit(circuit_artifact, is_main);
         is_main = false;
@@ -222,6 +234,7 @@ fn convert_generated_acir_into_circuit(
     debug_variables: DebugVariables,
     debug_functions: DebugFunctions,
     debug_types: DebugTypes,
+    debug_asserts: DebugAsserts,
 ) -> SsaCircuitArtifact {
     let opcodes = generated_acir.take_opcodes();
     let current_witness_index = generated_acir.current_witness_index().0;
@@ -230,6 +243,7 @@ fn convert_generated_acir_into_circuit(
         locations,
         input_witnesses,
         assertion_payloads: assert_messages,
+        overflow_checks,
         warnings,
         name,
         ..
@@ -258,7 +272,9 @@ fn convert_generated_acir_into_circuit(
         .map(|(index, locations)| (index, locations.into_iter().collect()))
         .collect();
 
-    let mut debug_info = DebugInfo::new(locations, debug_variables, debug_functions, debug_types);
+    let mut debug_info =
+        DebugInfo::new(locations, debug_variables, debug_functions, debug_types, debug_asserts);
+    debug_info.overflow_checks = overflow_checks;
 
     // Perform any ACIR-level optimizations
     let (optimized_circuit, transformation_map) = acvm::compiler::optimize(circuit);
@@ -336,6 +352,15 @@ impl SsaBuilder {
         self.print(msg)
     }
 
+    /// The same as `run_pass` but only runs the pass if `condition` holds
+    fn run_pass_if(self, condition: bool, pass: fn(Ssa) -> Ssa, msg: &str) -> Self {
+        if condition {
+            self.run_pass(pass, msg)
+        } else {
+            self
+        }
+    }
+
     /// The same as `run_pass` but for passes that may fail
     fn try_run_pass(
         mut self,
@@ -493,6 +493,23 @@ impl<F: AcirField> AcirContext<F> {
         }
     }
 
+    /// Constrains the `lhs` and `rhs` to be equal, failing with an error
+    /// message hardcoded by the compiler.
+    pub(crate) fn assert_eq_var_with_intrinsic_message(
+        &mut self,
+        lhs: AcirVar,
+        rhs: AcirVar,
+        message: String,
+    ) -> Result<(), RuntimeError> {
+        let opcodes = self.acir_ir.opcodes().len();
+        self.assert_eq_var(lhs, rhs, None)?;
+        if self.acir_ir.opcodes().len() > opcodes {
+            let location = self.acir_ir.last_acir_opcode_location();
+            self.acir_ir.insert_intrinsic_message(location, message);
+        }
+        Ok(())
+    }
+
     /// Constrains the `lhs` and `rhs` to be equal.
     pub(crate) fn assert_eq_var(
         &mut self,
@@ -1037,10 +1054,8 @@ impl<F: AcirField> AcirContext<F> {
                 let witness = self.var_to_witness(witness_var)?;
                 self.acir_ir.range_constraint(witness, *bit_size)?;
                 if let Some(message) = message {
-                    self.acir_ir.assertion_payloads.insert(
-                        self.acir_ir.last_acir_opcode_location(),
-                        AssertionPayload::StaticString(message.clone()),
-                    );
+                    let location = self.acir_ir.last_acir_opcode_location();
+                    self.acir_ir.insert_intrinsic_message(location, message);
                 }
             }
             NumericType::NativeField => {
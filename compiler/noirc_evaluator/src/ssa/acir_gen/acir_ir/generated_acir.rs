@@ -1,11 +1,11 @@
 //! `GeneratedAcir` is constructed as part of the `acir_gen` pass to accumulate all of the ACIR
 //! program as it is being converted from SSA form.
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use crate::{
     brillig::{brillig_gen::brillig_directive, brillig_ir::artifact::GeneratedBrillig},
     errors::{InternalError, RuntimeError, SsaReport},
-    ssa::ir::dfg::CallStack,
+    ssa::ir::{dfg::CallStack, instruction::is_overflow_message},
 };
 use acvm::acir::{
     circuit::{
@@ -59,6 +59,10 @@ pub(crate) struct GeneratedAcir<F: AcirField> {
     /// Correspondence between an opcode index and the error message associated with it.
     pub(crate) assertion_payloads: BTreeMap<OpcodeLocation, AssertionPayload<F>>,
 
+    /// Opcodes of the overflow checks inserted for integer arithmetic, which are
+    /// recorded in the debug info
+    pub(crate) overflow_checks: BTreeSet<OpcodeLocation>,
+
     pub(crate) warnings: Vec<SsaReport>,
 
     /// Name for the corresponding entry point represented by this Acir-gen output.
@@ -605,12 +609,12 @@ impl<F: AcirField> GeneratedAcir<F> {
             );
         }
         for (brillig_index, message) in generated_brillig.assert_messages.iter() {
-            self.assertion_payloads.insert(
+            self.insert_intrinsic_message(
                 OpcodeLocation::Brillig {
                     acir_index: self.opcodes.len() - 1,
                     brillig_index: *brillig_index,
                 },
-                AssertionPayload::StaticString(message.clone()),
+                message.clone(),
             );
         }
     }
@@ -634,6 +638,15 @@ impl<F: AcirField> GeneratedAcir<F> {
     pub(crate) fn last_acir_opcode_location(&self) -> OpcodeLocation {
         OpcodeLocation::Acir(self.opcodes.len() - 1)
     }
+
+    /// Attaches an error message hardcoded by the compiler to the opcode at
+    /// `location`, noting the overflow checks among them
+    pub(crate) fn insert_intrinsic_message(&mut self, location: OpcodeLocation, message: String) {
+        if is_overflow_message(&message) {
+            self.overflow_checks.insert(location);
+        }
+        self.assertion_payloads.insert(location, AssertionPayload::StaticString(message));
+    }
 }
 
 /// This function will return the number of inputs that a blackbox function
@@ -9,7 +9,10 @@ use self::acir_ir::generated_acir::BrilligStdlibFunc;
 use super::function_builder::data_bus::DataBus;
 use super::ir::dfg::CallStack;
 use super::ir::function::FunctionId;
-use super::ir::instruction::{ConstrainError, ErrorType};
+use super::ir::instruction::{
+    ConstrainError, ErrorType, ADD_OVERFLOW_MESSAGE, MULTIPLY_OVERFLOW_MESSAGE,
+    SUBTRACT_OVERFLOW_MESSAGE,
+};
 use super::ir::printer::try_to_extract_string_from_error_payload;
 use super::{
     ir::{
@@ -616,39 +619,37 @@ impl<'a> Context<'a> {
                 let lhs = self.convert_numeric_value(*lhs, dfg)?;
                 let rhs = self.convert_numeric_value(*rhs, dfg)?;
 
-                let assert_payload = if let Some(error) = assert_message {
-                    match error {
-                        ConstrainError::Intrinsic(string) => {
-                            Some(AssertionPayload::StaticString(string.clone()))
-                        }
-                        ConstrainError::UserDefined(error_selector, values) => {
-                            if let Some(constant_string) = try_to_extract_string_from_error_payload(
-                                *error_selector,
-                                values,
-                                dfg,
-                            ) {
-                                Some(AssertionPayload::StaticString(constant_string))
-                            } else {
-                                let acir_vars: Vec<_> = values
-                                    .iter()
-                                    .map(|value| self.convert_value(*value, dfg))
-                                    .collect();
-
-                                let expressions_or_memory =
-                                    self.acir_context.vars_to_expressions_or_memory(&acir_vars)?;
-
-                                Some(AssertionPayload::Dynamic(
-                                    error_selector.as_u64(),
-                                    expressions_or_memory,
-                                ))
-                            }
-                        }
+                match assert_message {
+                    Some(ConstrainError::Intrinsic(string)) => {
+                        self.acir_context.assert_eq_var_with_intrinsic_message(
+                            lhs,
+                            rhs,
+                            string.clone(),
+                        )?;
                     }
-                } else {
-                    None
-                };
-
-                self.acir_context.assert_eq_var(lhs, rhs, assert_payload)?;
+                    Some(ConstrainError::UserDefined(error_selector, values)) => {
+                        let assert_payload = if let Some(constant_string) =
+                            try_to_extract_string_from_error_payload(*error_selector, values, dfg)
+                        {
+                            AssertionPayload::StaticString(constant_string)
+                        } else {
+                            let acir_vars: Vec<_> = values
+                                .iter()
+                                .map(|value| self.convert_value(*value, dfg))
+                                .collect();
+
+                            let expressions_or_memory =
+                                self.acir_context.vars_to_expressions_or_memory(&acir_vars)?;
+
+                            AssertionPayload::Dynamic(
+                                error_selector.as_u64(),
+                                expressions_or_memory,
+                            )
+                        };
+                        self.acir_context.assert_eq_var(lhs, rhs, Some(assert_payload))?;
+                    }
+                    None => self.acir_context.assert_eq_var(lhs, rhs, None)?,
+                }
             }
             Instruction::Cast(value_id, _) => {
                 let acir_var = self.convert_numeric_value(*value_id, dfg)?;
@@ -1970,7 +1971,7 @@ impl<'a> Context<'a> {
                     // `lhs` and `rhs` have both been casted up from smaller types and so cannot overflow.
                     return Ok(());
                 }
-                "attempt to add with overflow".to_string()
+                ADD_OVERFLOW_MESSAGE.to_string()
             }
             BinaryOp::Sub => {
                 if dfg.is_constant(lhs) && max_lhs_bits > max_rhs_bits {
@@ -1978,7 +1979,7 @@ impl<'a> Context<'a> {
                     // Note strict inequality as `rhs > lhs` while `max_lhs_bits == max_rhs_bits` is possible.
                     return Ok(());
                 }
-                "attempt to subtract with overflow".to_string()
+                SUBTRACT_OVERFLOW_MESSAGE.to_string()
             }
             BinaryOp::Mul => {
                 if bit_size == 1 || max_lhs_bits + max_rhs_bits <= bit_size {
@@ -1986,7 +1987,7 @@ impl<'a> Context<'a> {
                     // or `lhs` and `rhs` have both been casted up from smaller types and so cannot overflow.
                     return Ok(());
                 }
-                "attempt to multiply with overflow".to_string()
+                MULTIPLY_OVERFLOW_MESSAGE.to_string()
             }
             _ => return Ok(()),
         };
@@ -743,6 +743,24 @@ pub(crate) enum ConstrainError {
     UserDefined(ErrorSelector, Vec<ValueId>),
 }
 
+/// Messages of the overflow checks inserted for integer arithmetic
+pub(crate) const ADD_OVERFLOW_MESSAGE: &str = "attempt to add with overflow";
+pub(crate) const SUBTRACT_OVERFLOW_MESSAGE: &str = "attempt to subtract with overflow";
+pub(crate) const MULTIPLY_OVERFLOW_MESSAGE: &str = "attempt to multiply with overflow";
+pub(crate) const BIT_SHIFT_OVERFLOW_MESSAGE: &str = "attempt to bit-shift with overflow";
+
+/// Returns whether a hardcoded error is the one of an overflow check, which is
+/// recorded in the debug info
+pub(crate) fn is_overflow_message(message: &str) -> bool {
+    [
+        ADD_OVERFLOW_MESSAGE,
+        SUBTRACT_OVERFLOW_MESSAGE,
+        MULTIPLY_OVERFLOW_MESSAGE,
+        BIT_SHIFT_OVERFLOW_MESSAGE,
+    ]
+    .contains(&message)
+}
+
 impl From<String> for ConstrainError {
     fn from(value: String) -> Self {
         ConstrainError::Intrinsic(value)
@@ -0,0 +1,106 @@
+//! Lowers the calls to the debugger's oracles, inserted by the debug
+//! instrumentation, to no-ops so that instrumented programs can be executed
+//! without a debugger and without paying for the instrumentation.
+use fxhash::FxHashSet as HashSet;
+
+use crate::ssa::{
+    ir::{
+        function::{Function, FunctionId},
+        instruction::Instruction,
+        value::Value,
+    },
+    ssa_gen::Ssa,
+};
+
+/// Prefix of the names of the debugger's oracles and of the functions the
+/// debug instrumentation wraps them in
+const DEBUG_ORACLE_PREFIX: &str = "__debug_";
+
+impl Ssa {
+    /// Removes the calls to the debugger's oracles and to the functions
+    /// wrapping them, none of which return a value. The debug information of
+    /// the program is kept, so a debugger can still step through it, although
+    /// without the values of its variables.
+    ///
+    /// This pass must run before inlining, which then drops the wrapper
+    /// functions as they are no longer called.
+    #[tracing::instrument(level = "trace", skip(self))]
+    pub(crate) fn remove_debug_oracle_calls(mut self) -> Ssa {
+        let debug_functions: HashSet<FunctionId> = self
+            .functions
+            .values()
+            .filter(|function| function.name().starts_with(DEBUG_ORACLE_PREFIX))
+            .map(Function::id)
+            .collect();
+        for function in self.functions.values_mut() {
+            remove_calls(function, &debug_functions);
+        }
+        self
+    }
+}
+
+fn remove_calls(function: &mut Function, debug_functions: &HashSet<FunctionId>) {
+    for block_id in function.reachable_blocks() {
+        let mut instructions = std::mem::take(function.dfg[block_id].instructions_mut());
+        instructions.retain(|instruction_id| {
+            let Instruction::Call { func, .. } = &function.dfg[*instruction_id] else {
+                return true;
+            };
+            match &function.dfg[*func] {
+                Value::ForeignFunction(name) => !name.starts_with(DEBUG_ORACLE_PREFIX),
+                Value::Function(id) => !debug_functions.contains(id),
+                _ => true,
+            }
+        });
+        *function.dfg[block_id].instructions_mut() = instructions;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ssa::{
+        function_builder::FunctionBuilder,
+        ir::{map::Id, types::Type},
+    };
+
+    #[test]
+    fn removes_debug_oracle_calls() {
+        // fn main f0 {
+        //   b0(v0: Field):
+        //     call __debug_var_assign(u32 0, v0)
+        //     call print(v0)
+        //     return v0
+        // }
+        // brillig fn __debug_var_assign f1 {
+        //   b0(v0: u32, v1: Field):
+        //     call __debug_var_assign(v0, v1)
+        //     return
+        // }
+        let main_id = Id::test_new(0);
+        let wrapper_id = Id::test_new(1);
+
+        let mut builder = FunctionBuilder::new("main".into(), main_id);
+        let v0 = builder.add_parameter(Type::field());
+        let var_id = builder.numeric_constant(0u128, Type::unsigned(32));
+        let wrapper = builder.import_function(wrapper_id);
+        builder.insert_call(wrapper, vec![var_id, v0], vec![]);
+        let print = builder.import_foreign_function("print");
+        builder.insert_call(print, vec![v0], vec![]);
+        builder.terminate_with_return(vec![v0]);
+
+        builder.new_brillig_function("__debug_var_assign".into(), wrapper_id);
+        let v0 = builder.add_parameter(Type::unsigned(32));
+        let v1 = builder.add_parameter(Type::field());
+        let oracle = builder.import_foreign_function("__debug_var_assign");
+        builder.insert_call(oracle, vec![v0, v1], vec![]);
+        builder.terminate_with_return(vec![]);
+
+        let ssa = builder.finish().remove_debug_oracle_calls();
+
+        // only the call to `print` is left
+        let main = ssa.main();
+        assert_eq!(main.dfg[main.entry_block()].instructions().len(), 1);
+        let wrapper = &ssa.functions[&wrapper_id];
+        assert!(wrapper.dfg[wrapper.entry_block()].instructions().is_empty());
+    }
+}
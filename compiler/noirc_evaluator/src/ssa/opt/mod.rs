@@ -8,6 +8,7 @@ mod as_slice_length;
 mod assert_constant;
 mod bubble_up_constrains;
 mod constant_folding;
+mod debug_oracles;
 mod defunctionalize;
 mod die;
 pub(crate) mod flatten_cfg;
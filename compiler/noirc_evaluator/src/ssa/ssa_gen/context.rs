@@ -15,6 +15,10 @@ use crate::ssa::ir::function::FunctionId as IrFunctionId;
 use crate::ssa::ir::function::{Function, RuntimeType};
 use crate::ssa::ir::instruction::BinaryOp;
 use crate::ssa::ir::instruction::Instruction;
+use crate::ssa::ir::instruction::{
+    ADD_OVERFLOW_MESSAGE, BIT_SHIFT_OVERFLOW_MESSAGE, MULTIPLY_OVERFLOW_MESSAGE,
+    SUBTRACT_OVERFLOW_MESSAGE,
+};
 use crate::ssa::ir::map::AtomicCounter;
 use crate::ssa::ir::types::{NumericType, Type};
 use crate::ssa::ir::value::ValueId;
@@ -402,7 +406,7 @@ impl<'a> FunctionContext<'a> {
         self.builder.set_location(location).insert_constrain(
             overflow,
             one,
-            Some("attempt to bit-shift with overflow".to_owned().into()),
+            Some(BIT_SHIFT_OVERFLOW_MESSAGE.to_owned().into()),
         );
         self.builder.insert_truncate(result, bit_size, bit_size + 1)
     }
@@ -441,9 +445,9 @@ impl<'a> FunctionContext<'a> {
         let message = if is_sub {
             // lhs - rhs = lhs + (-rhs)
             rhs_sign = self.builder.insert_not(rhs_sign);
-            "attempt to subtract with overflow".to_string()
+            SUBTRACT_OVERFLOW_MESSAGE.to_string()
         } else {
-            "attempt to add with overflow".to_string()
+            ADD_OVERFLOW_MESSAGE.to_string()
         };
         // same_sign is true if both operands have the same sign
         let same_sign = self.builder.insert_binary(lhs_sign, BinaryOp::Eq, rhs_sign);
@@ -471,7 +475,7 @@ impl<'a> FunctionContext<'a> {
                 self.builder.set_location(location).insert_range_check(
                     product_field,
                     bit_size,
-                    Some("attempt to multiply with overflow".to_string()),
+                    Some(MULTIPLY_OVERFLOW_MESSAGE.to_string()),
                 );
                 let product = self.builder.insert_cast(product_field, Type::unsigned(bit_size));
 
@@ -4,8 +4,9 @@ use crate::{
     ast::{Path, PathKind},
     parser::{Item, ItemKind},
 };
-use noirc_errors::debug_info::{DebugFnId, DebugFunction};
-use noirc_errors::{Span, Spanned};
+use fm::FileId;
+use noirc_errors::debug_info::{DebugAssertId, DebugFnId, DebugFunction};
+use noirc_errors::{Location, Span, Spanned};
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::mem::take;
@@ -31,12 +32,22 @@ pub struct DebugInstrumenter {
     // all collected function metadata (name + argument names)
     pub functions: HashMap<DebugFnId, DebugFunction>,
 
+    // number of let, assignment and for statements rewritten to track variables
+    pub instrumented_statements: usize,
+
+    // all collected assert and assert_eq statements, by the ID they were given
+    pub asserts: HashMap<DebugAssertId, Location>,
+
     next_var_id: u32,
     next_field_name_id: u32,
     next_fn_id: u32,
+    next_assert_id: u32,
 
     // last seen variable names and their IDs grouped by scope
     scope: Vec<HashMap<String, SourceVarId>>,
+
+    // file of the module being instrumented
+    file: Option<FileId>,
 }
 
 impl Default for DebugInstrumenter {
@@ -45,16 +56,24 @@ impl Default for DebugInstrumenter {
             variables: HashMap::default(),
             field_names: HashMap::default(),
             functions: HashMap::default(),
+            instrumented_statements: 0,
+            asserts: HashMap::default(),
             scope: vec![],
             next_var_id: 0,
             next_field_name_id: 1,
             next_fn_id: 0,
+            next_assert_id: 1,
+            file: None,
         }
     }
 }
 
 impl DebugInstrumenter {
-    pub fn instrument_module(&mut self, module: &mut ParsedModule) {
+    /// Instruments the functions of the module parsed from `file`. Asserts
+    /// are given IDs in the order they are found, so modules should be
+    /// instrumented in a fixed order for the IDs to be stable.
+    pub fn instrument_module(&mut self, module: &mut ParsedModule, file: FileId) {
+        self.file = Some(file);
         module.items.iter_mut().for_each(|item| {
             if let Item { kind: ItemKind::Function(f), .. } = item {
                 self.walk_fn(&mut f.def);
@@ -84,6 +103,13 @@ impl DebugInstrumenter {
         field_name_id
     }
 
+    fn insert_assert(&mut self, span: Span) {
+        let file = self.file.expect("asserts are only found while instrumenting a module");
+        let assert_id = DebugAssertId(self.next_assert_id);
+        self.next_assert_id += 1;
+        self.asserts.insert(assert_id, Location::new(span, file));
+    }
+
     fn insert_function(&mut self, fn_name: String, arguments: Vec<String>) -> DebugFnId {
         let fn_id = DebugFnId(self.next_fn_id);
         self.next_fn_id += 1;
@@ -271,7 +297,9 @@ impl DebugInstrumenter {
         //
         //   __debug_var_assign(17, __debug_expr);
         //   // or:
-        //   __debug_member_assign_{arity}(17, __debug_expr, _v0, _v1..., _v{arity});
+        //   __debug_member_assign_{arity}(17, __debug_expr, _v0, _v1..., _v{arity}, _l0, _l1..., _l{arity});
+        //
+        //   where each _lN is the length of the array indexed by _vN, or 0 for member accesses
         //
         //   __debug_expr
         // };
@@ -302,6 +330,7 @@ impl DebugInstrumenter {
             }
             _ => {
                 let mut indexes = vec![];
+                let mut lengths = vec![];
                 let mut cursor = &assign_stmt.lvalue;
                 let var_id;
                 loop {
@@ -316,10 +345,12 @@ impl DebugInstrumenter {
                             cursor = object;
                             let field_name_id = self.insert_field_name(&field_name.0.contents);
                             indexes.push(sint_expr(-(field_name_id.0 as i128), *span));
+                            lengths.push(uint_expr(0, *span));
                         }
-                        ast::LValue::Index { index, array, span: _ } => {
+                        ast::LValue::Index { index, array, span } => {
                             cursor = array;
                             indexes.push(index.clone());
+                            lengths.push(len_expr(array.as_expression(), *span));
                         }
                         ast::LValue::Dereference(_ref, _span) => {
                             unimplemented![]
@@ -329,6 +360,7 @@ impl DebugInstrumenter {
                 build_assign_member_stmt(
                     var_id,
                     &indexes,
+                    &lengths,
                     &id_expr(&ident("__debug_expr", expression_span)),
                 )
             }
@@ -446,9 +478,11 @@ impl DebugInstrumenter {
         match &mut stmt.kind {
             ast::StatementKind::Let(let_stmt) => {
                 *stmt = self.walk_let_statement(let_stmt, &stmt.span);
+                self.instrumented_statements += 1;
             }
             ast::StatementKind::Assign(assign_stmt) => {
                 *stmt = self.walk_assign_statement(assign_stmt, &stmt.span);
+                self.instrumented_statements += 1;
             }
             ast::StatementKind::Expression(expr) => {
                 self.walk_expr(expr);
@@ -458,8 +492,12 @@ impl DebugInstrumenter {
             }
             ast::StatementKind::For(ref mut for_stmt) => {
                 self.walk_for(for_stmt);
+                self.instrumented_statements += 1;
             }
-            _ => {} // Constrain, Error
+            ast::StatementKind::Constrain(_) => {
+                self.insert_assert(stmt.span);
+            }
+            _ => {} // Break, Continue, Comptime, Error
         }
     }
 
@@ -543,19 +581,24 @@ pub fn build_debug_crate_file() -> String {
                 let var_sig =
                     (0..n).map(|i| format!["_v{i}: Index"]).collect::<Vec<String>>().join(", ");
                 let vars = (0..n).map(|i| format!["_v{i}"]).collect::<Vec<String>>().join(", ");
+                // Lengths of the indexed arrays, so that the debugger can tell
+                // out of range indexes apart; 0 for member accesses.
+                let len_sig =
+                    (0..n).map(|i| format!["_l{i}: u32"]).collect::<Vec<String>>().join(", ");
+                let lens = (0..n).map(|i| format!["_l{i}"]).collect::<Vec<String>>().join(", ");
                 format!(
                     r#"
                 #[oracle(__debug_member_assign_{n})]
                 unconstrained fn __debug_oracle_member_assign_{n}<T, Index>(
-                    _var_id: u32, _value: T, {var_sig}
+                    _var_id: u32, _value: T, {var_sig}, {len_sig}
                 ) {{}}
                 unconstrained fn __debug_inner_member_assign_{n}<T, Index>(
-                    var_id: u32, value: T, {var_sig}
+                    var_id: u32, value: T, {var_sig}, {len_sig}
                 ) {{
-                    __debug_oracle_member_assign_{n}(var_id, value, {vars});
+                    __debug_oracle_member_assign_{n}(var_id, value, {vars}, {lens});
                 }}
-                pub fn __debug_member_assign_{n}<T, Index>(var_id: u32, value: T, {var_sig}) {{
-                    __debug_inner_member_assign_{n}(var_id, value, {vars});
+                pub fn __debug_member_assign_{n}<T, Index>(var_id: u32, value: T, {var_sig}, {len_sig}) {{
+                    __debug_inner_member_assign_{n}(var_id, value, {vars}, {lens});
                 }}
 
             "#
@@ -609,6 +652,7 @@ fn build_drop_var_stmt(var_id: SourceVarId, span: Span) -> ast::Statement {
 fn build_assign_member_stmt(
     var_id: SourceVarId,
     indexes: &[ast::Expression],
+    lengths: &[ast::Expression],
     expr: &ast::Expression,
 ) -> ast::Statement {
     let arity = indexes.len();
@@ -633,6 +677,7 @@ fn build_assign_member_stmt(
             vec![uint_expr(var_id.0 as u128, span)],
             vec![expr.clone()],
             indexes.iter().rev().cloned().collect(),
+            lengths.iter().rev().cloned().collect(),
         ]
         .concat(),
     }));
@@ -727,6 +772,19 @@ fn uint_expr(x: u128, span: Span) -> ast::Expression {
     }
 }
 
+fn len_expr(collection: ast::Expression, span: Span) -> ast::Expression {
+    ast::Expression {
+        kind: ast::ExpressionKind::MethodCall(Box::new(ast::MethodCallExpression {
+            object: collection,
+            method_name: ident("len", span),
+            generics: None,
+            arguments: vec![],
+            is_macro_call: false,
+        })),
+        span,
+    }
+}
+
 fn sint_expr(x: i128, span: Span) -> ast::Expression {
     ast::Expression {
         kind: ast::ExpressionKind::Literal(ast::Literal::Integer(x.abs().into(), x < 0)),
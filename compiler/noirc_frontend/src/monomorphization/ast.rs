@@ -1,7 +1,7 @@
 use acvm::FieldElement;
 use iter_extended::vecmap;
 use noirc_errors::{
-    debug_info::{DebugFunctions, DebugTypes, DebugVariables},
+    debug_info::{DebugAsserts, DebugFunctions, DebugTypes, DebugVariables},
     Location,
 };
 
@@ -311,6 +311,7 @@ pub struct Program {
     pub debug_variables: DebugVariables,
     pub debug_functions: DebugFunctions,
     pub debug_types: DebugTypes,
+    pub debug_asserts: DebugAsserts,
 }
 
 impl Program {
@@ -325,6 +326,7 @@ impl Program {
         debug_variables: DebugVariables,
         debug_functions: DebugFunctions,
         debug_types: DebugTypes,
+        debug_asserts: DebugAsserts,
     ) -> Program {
         Program {
             functions,
@@ -336,6 +338,7 @@ impl Program {
             debug_variables,
             debug_functions,
             debug_types,
+            debug_asserts,
         }
     }
 
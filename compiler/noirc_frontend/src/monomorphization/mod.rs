@@ -148,6 +148,7 @@ pub fn monomorphize_debug(
 
     let (debug_variables, debug_functions, debug_types) =
         monomorphizer.debug_type_tracker.extract_vars_and_types();
+    let debug_asserts = debug_instrumenter.asserts.clone().into_iter().collect();
     let program = Program::new(
         functions,
         func_sigs,
@@ -158,6 +159,7 @@ pub fn monomorphize_debug(
         debug_variables,
         debug_functions,
         debug_types,
+        debug_asserts,
     );
     Ok(program)
 }
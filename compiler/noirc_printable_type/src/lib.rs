@@ -47,6 +47,64 @@ pub enum PrintableType {
     Unit,
 }
 
+/// How many fields a value of a type takes once flattened, as values are
+/// passed to foreign calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldLayout {
+    /// Values of the type take no fields, eg. `()` or empty arrays
+    ZeroSize,
+    /// Values of the type always take this many fields
+    Fixed(usize),
+    /// The number of fields depends on the value, as for slices, which are
+    /// preceded by their length
+    Dynamic,
+}
+
+impl FieldLayout {
+    fn then(self, other: FieldLayout) -> FieldLayout {
+        match (self, other) {
+            (FieldLayout::Dynamic, _) | (_, FieldLayout::Dynamic) => FieldLayout::Dynamic,
+            (FieldLayout::ZeroSize, layout) | (layout, FieldLayout::ZeroSize) => layout,
+            (FieldLayout::Fixed(first), FieldLayout::Fixed(second)) => {
+                FieldLayout::Fixed(first + second)
+            }
+        }
+    }
+
+    fn repeat(self, count: usize) -> FieldLayout {
+        match self {
+            FieldLayout::Fixed(size) if count > 0 => FieldLayout::Fixed(size * count),
+            FieldLayout::Fixed(_) | FieldLayout::ZeroSize => FieldLayout::ZeroSize,
+            FieldLayout::Dynamic => FieldLayout::Dynamic,
+        }
+    }
+}
+
+impl PrintableType {
+    /// Returns how many fields `decode_value` consumes for a value of this type
+    pub fn field_layout(&self) -> FieldLayout {
+        match self {
+            PrintableType::Field
+            | PrintableType::SignedInteger { .. }
+            | PrintableType::UnsignedInteger { .. }
+            | PrintableType::Boolean => FieldLayout::Fixed(1),
+            PrintableType::Array { length, typ } => typ.field_layout().repeat(*length as usize),
+            PrintableType::Slice { .. } => FieldLayout::Dynamic,
+            PrintableType::Tuple { types } => types
+                .iter()
+                .fold(FieldLayout::ZeroSize, |layout, typ| layout.then(typ.field_layout())),
+            PrintableType::Struct { fields, .. } => fields
+                .iter()
+                .fold(FieldLayout::ZeroSize, |layout, (_, typ)| layout.then(typ.field_layout())),
+            PrintableType::String { length } => FieldLayout::Fixed(1).repeat(*length as usize),
+            // The function reference followed by its environment
+            PrintableType::Function { env, .. } => FieldLayout::Fixed(1).then(env.field_layout()),
+            PrintableType::MutableReference { typ } => typ.field_layout(),
+            PrintableType::Unit => FieldLayout::ZeroSize,
+        }
+    }
+}
+
 /// This is what all formats eventually transform into
 /// For example, a toml file will parse into TomlTypes
 /// and those TomlTypes will be mapped to Value
@@ -79,6 +137,15 @@ pub enum ForeignCallError {
 
     #[error("Assert message resolved after an unsatisified constrain. {0}")]
     ResolvedAssertMessage(String),
+
+    #[error("Could not decode string. {0}")]
+    StringDecodingError(String),
+
+    #[error("Oracle `{name}` {reason}")]
+    InvalidOutputs { name: String, reason: String },
+
+    #[error("Oracle `{name}` {reason}")]
+    InvalidInputs { name: String, reason: String },
 }
 
 impl<F: AcirField> TryFrom<&[ForeignCallParam<F>]> for PrintableValueDisplay<F> {
@@ -127,14 +194,22 @@ fn convert_fmt_string_inputs<F: AcirField>(
         .ok_or(ForeignCallError::MissingForeignCallInputs)?;
 
     let mut output = Vec::new();
-    let num_values = num_values.unwrap_field().to_u128() as usize;
+    let ForeignCallParam::Single(num_values) = num_values else {
+        return Err(ForeignCallError::MissingForeignCallInputs);
+    };
+    let types_start_at = usize::try_from(num_values.to_u128())
+        .ok()
+        .and_then(|num_values| input_and_printable_types.len().checked_sub(num_values))
+        .ok_or(ForeignCallError::MissingForeignCallInputs)?;
 
-    let types_start_at = input_and_printable_types.len() - num_values;
+    // Values of dynamic types, such as slices, span several inputs, so they
+    // are decoded from the fields of all the inputs in order
     let mut input_iter =
         input_and_printable_types[0..types_start_at].iter().flat_map(|param| param.fields());
     for printable_type in input_and_printable_types.iter().skip(types_start_at) {
         let printable_type = fetch_printable_type(printable_type)?;
-        let value = decode_value(&mut input_iter, &printable_type);
+        let value = try_decode_value(&mut input_iter, &printable_type)
+            .ok_or(ForeignCallError::MissingForeignCallInputs)?;
 
         output.push((value, printable_type));
     }
@@ -146,7 +221,7 @@ fn fetch_printable_type<F: AcirField>(
     printable_type: &ForeignCallParam<F>,
 ) -> Result<PrintableType, ForeignCallError> {
     let printable_type_as_fields = printable_type.fields();
-    let printable_type_as_string = decode_string_value(&printable_type_as_fields);
+    let printable_type_as_string = try_decode_string_value(&printable_type_as_fields)?;
     let printable_type: PrintableType = serde_json::from_str(&printable_type_as_string)?;
 
     Ok(printable_type)
@@ -249,6 +324,57 @@ fn to_string<F: AcirField>(value: &PrintableValue<F>, typ: &PrintableType) -> Op
     Some(output)
 }
 
+/// Renders a value like `to_string`, but laying out nested arrays, tuples and
+/// structs across multiple lines, one element per line, indented by nesting level.
+fn to_pretty_string<F: AcirField>(
+    value: &PrintableValue<F>,
+    typ: &PrintableType,
+    indent: usize,
+) -> Option<String> {
+    const INDENT: &str = "    ";
+
+    let render_elements = |open: &str, close: &str, elements: Vec<Option<String>>| {
+        if elements.is_empty() {
+            return Some(format!("{open}{close}"));
+        }
+        let inner_indent = INDENT.repeat(indent + 1);
+        let mut output = format!("{open}\n");
+        for element in elements {
+            output.push_str(&format!("{inner_indent}{},\n", element?));
+        }
+        output.push_str(&format!("{}{close}", INDENT.repeat(indent)));
+        Some(output)
+    };
+
+    match (value, typ) {
+        (PrintableValue::Vec { array_elements, is_slice }, PrintableType::Array { typ, .. })
+        | (PrintableValue::Vec { array_elements, is_slice }, PrintableType::Slice { typ }) => {
+            let elements = vecmap(array_elements, |value| to_pretty_string(value, typ, indent + 1));
+            let open = if *is_slice { "&[" } else { "[" };
+            render_elements(open, "]", elements)
+        }
+
+        (PrintableValue::Struct(map), PrintableType::Struct { name, fields, .. }) => {
+            let elements = vecmap(fields, |(key, field_type)| {
+                let value = to_pretty_string(&map[key], field_type, indent + 1)?;
+                Some(format!("{key}: {value}"))
+            });
+            render_elements(&format!("{name} {{"), "}", elements)
+        }
+
+        (PrintableValue::Vec { array_elements, .. }, PrintableType::Tuple { types }) => {
+            let elements = array_elements
+                .iter()
+                .zip(types)
+                .map(|(value, typ)| to_pretty_string(value, typ, indent + 1))
+                .collect();
+            render_elements("(", ")", elements)
+        }
+
+        _ => to_string(value, typ),
+    }
+}
+
 // Taken from Regex docs directly
 fn replace_all<E>(
     re: &Regex,
@@ -267,11 +393,17 @@ fn replace_all<E>(
     Ok(new)
 }
 
+/// The alternate flag (`{:#}`) renders plain values with the multi-line pretty-printer.
 impl<F: AcirField> std::fmt::Display for PrintableValueDisplay<F> {
     fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Plain(value, typ) => {
-                let output_string = to_string(value, typ).ok_or(std::fmt::Error)?;
+                let output_string = if fmt.alternate() {
+                    to_pretty_string(value, typ, 0)
+                } else {
+                    to_string(value, typ)
+                }
+                .ok_or(std::fmt::Error)?;
                 write!(fmt, "{output_string}")
             }
             Self::FmtString(template, values) => {
@@ -309,12 +441,21 @@ pub fn decode_value<F: AcirField>(
     field_iterator: &mut impl Iterator<Item = F>,
     typ: &PrintableType,
 ) -> PrintableValue<F> {
-    match typ {
+    try_decode_value(field_iterator, typ).expect("not enough data to decode value")
+}
+
+/// Decodes a value of the given type, returning None if the iterator runs out
+/// of fields before the value is complete
+pub fn try_decode_value<F: AcirField>(
+    field_iterator: &mut impl Iterator<Item = F>,
+    typ: &PrintableType,
+) -> Option<PrintableValue<F>> {
+    let value = match typ {
         PrintableType::Field
         | PrintableType::SignedInteger { .. }
         | PrintableType::UnsignedInteger { .. }
         | PrintableType::Boolean => {
-            let field_element = field_iterator.next().unwrap();
+            let field_element = field_iterator.next()?;
 
             PrintableValue::Field(field_element)
         }
@@ -322,29 +463,39 @@ pub fn decode_value<F: AcirField>(
             let length = *length as usize;
             let mut array_elements = Vec::with_capacity(length);
             for _ in 0..length {
-                array_elements.push(decode_value(field_iterator, typ));
+                array_elements.push(try_decode_value(field_iterator, typ)?);
             }
 
             PrintableValue::Vec { array_elements, is_slice: false }
         }
         PrintableType::Slice { typ } => {
-            let length = field_iterator
-                .next()
-                .expect("not enough data to decode variable array length")
-                .to_u128() as usize;
-            let mut array_elements = Vec::with_capacity(length);
+            let length = usize::try_from(field_iterator.next()?.to_u128()).ok()?;
+            // The length comes from the data, so it only sizes the vector
+            // up front if there are enough fields left to back it
+            let capacity = match typ.field_layout() {
+                FieldLayout::Fixed(size) => length.min(field_iterator.size_hint().0 / size),
+                FieldLayout::ZeroSize | FieldLayout::Dynamic => 0,
+            };
+            let mut array_elements = Vec::with_capacity(capacity);
             for _ in 0..length {
-                array_elements.push(decode_value(field_iterator, typ));
+                array_elements.push(try_decode_value(field_iterator, typ)?);
             }
 
             PrintableValue::Vec { array_elements, is_slice: true }
         }
         PrintableType::Tuple { types } => PrintableValue::Vec {
-            array_elements: vecmap(types, |typ| decode_value(field_iterator, typ)),
+            array_elements: types
+                .iter()
+                .map(|typ| try_decode_value(field_iterator, typ))
+                .collect::<Option<_>>()?,
             is_slice: false,
         },
         PrintableType::String { length } => {
-            let field_elements: Vec<F> = field_iterator.take(*length as usize).collect();
+            let length = *length as usize;
+            let field_elements: Vec<F> = field_iterator.take(length).collect();
+            if field_elements.len() < length {
+                return None;
+            }
 
             PrintableValue::String(decode_string_value(&field_elements))
         }
@@ -352,7 +503,7 @@ pub fn decode_value<F: AcirField>(
             let mut struct_map = BTreeMap::new();
 
             for (field_key, param_type) in fields {
-                let field_value = decode_value(field_iterator, param_type);
+                let field_value = try_decode_value(field_iterator, param_type)?;
 
                 struct_map.insert(field_key.to_owned(), field_value);
             }
@@ -360,29 +511,207 @@ pub fn decode_value<F: AcirField>(
             PrintableValue::Struct(struct_map)
         }
         PrintableType::Function { env, .. } => {
-            let field_element = field_iterator.next().unwrap();
+            let field_element = field_iterator.next()?;
             let func_ref = PrintableValue::Field(field_element);
             // we want to consume the fields from the environment, but for now they are not actually printed
-            decode_value(field_iterator, env);
+            try_decode_value(field_iterator, env)?;
             func_ref
         }
         PrintableType::MutableReference { typ } => {
             // we decode the reference, but it's not really used for printing
-            decode_value(field_iterator, typ)
+            try_decode_value(field_iterator, typ)?
         }
         PrintableType::Unit => PrintableValue::Field(F::zero()),
-    }
+    };
+    Some(value)
 }
 
+/// Strings longer than this many characters are truncated when decoded for display
+pub const MAX_DECODED_STRING_LENGTH: usize = 1 << 16;
+
+/// Decodes a string for display. Decoding never fails: elements which are not
+/// bytes and invalid UTF-8 sequences are rendered as hex escapes, and strings
+/// longer than [MAX_DECODED_STRING_LENGTH] are truncated with a notice.
 pub fn decode_string_value<F: AcirField>(field_elements: &[F]) -> String {
-    // TODO: Replace with `into` when Char is supported
-    let string_as_slice = vecmap(field_elements, |e| {
-        let mut field_as_bytes = e.to_be_bytes();
-        let char_byte = field_as_bytes.pop().unwrap(); // A character in a string is represented by a u8, thus we just want the last byte of the element
-        assert!(field_as_bytes.into_iter().all(|b| b == 0)); // Assert that the rest of the field element's bytes are empty
-        char_byte
-    });
-
-    let final_string = str::from_utf8(&string_as_slice).unwrap();
-    final_string.to_owned()
+    let (field_elements, truncated) = if field_elements.len() > MAX_DECODED_STRING_LENGTH {
+        field_elements.split_at(MAX_DECODED_STRING_LENGTH)
+    } else {
+        (field_elements, &[][..])
+    };
+
+    let mut output = String::new();
+    let mut bytes = Vec::new();
+    for element in field_elements {
+        match field_as_byte(element) {
+            Some(byte) => bytes.push(byte),
+            None => {
+                push_utf8_lossy(&mut output, &bytes);
+                bytes.clear();
+                let hex = format_field_string(*element);
+                output.push_str(&format!("\\x{{{}}}", hex.trim_start_matches("0x")));
+            }
+        }
+    }
+    push_utf8_lossy(&mut output, &bytes);
+
+    if !truncated.is_empty() {
+        output.push_str(&format!("... ({} more characters truncated)", truncated.len()));
+    }
+    output
+}
+
+/// Decodes a string which must be well formed, such as the serialized type of a
+/// printed value.
+pub fn try_decode_string_value<F: AcirField>(
+    field_elements: &[F],
+) -> Result<String, ForeignCallError> {
+    let bytes = field_elements
+        .iter()
+        .map(|element| {
+            field_as_byte(element).ok_or_else(|| {
+                ForeignCallError::StringDecodingError(format!(
+                    "{} is not a valid character",
+                    format_field_string(*element)
+                ))
+            })
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    String::from_utf8(bytes)
+        .map_err(|error| ForeignCallError::StringDecodingError(error.to_string()))
+}
+
+// A character in a string is represented by a u8, so all but the last byte of
+// the field element must be zero
+fn field_as_byte<F: AcirField>(element: &F) -> Option<u8> {
+    let mut field_as_bytes = element.to_be_bytes();
+    let char_byte = field_as_bytes.pop()?;
+    field_as_bytes.into_iter().all(|b| b == 0).then_some(char_byte)
+}
+
+/// Appends `bytes` to `output`, rendering invalid UTF-8 sequences as hex escapes
+fn push_utf8_lossy(output: &mut String, mut bytes: &[u8]) {
+    loop {
+        match str::from_utf8(bytes) {
+            Ok(valid) => {
+                output.push_str(valid);
+                return;
+            }
+            Err(error) => {
+                let (valid, rest) = bytes.split_at(error.valid_up_to());
+                output.push_str(str::from_utf8(valid).expect("prefix was validated as UTF-8"));
+                let invalid_length = error.error_len().unwrap_or(rest.len());
+                for byte in &rest[..invalid_length] {
+                    output.push_str(&format!("\\x{byte:02x}"));
+                }
+                bytes = &rest[invalid_length..];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_string_value, try_decode_string_value, FieldLayout, ForeignCallError, PrintableType,
+        PrintableValueDisplay, MAX_DECODED_STRING_LENGTH,
+    };
+    use acvm::{acir::brillig::ForeignCallParam, AcirField, FieldElement};
+
+    fn fields(bytes: &[u8]) -> Vec<FieldElement> {
+        bytes.iter().map(|byte| FieldElement::from(*byte as u128)).collect()
+    }
+
+    #[test]
+    fn decodes_valid_strings() {
+        let string = fields("héllo".as_bytes());
+        assert_eq!(decode_string_value(&string), "héllo");
+        assert_eq!(try_decode_string_value(&string).unwrap(), "héllo");
+    }
+
+    #[test]
+    fn renders_invalid_utf8_as_hex() {
+        let string = fields(&[b'a', 0xff, b'b']);
+        assert_eq!(decode_string_value(&string), "a\\xffb");
+        assert!(try_decode_string_value(&string).is_err());
+    }
+
+    #[test]
+    fn renders_non_byte_elements_as_hex() {
+        let mut string = fields(b"a");
+        string.push(FieldElement::from(256u128));
+        assert_eq!(decode_string_value(&string), "a\\x{0100}");
+        assert!(try_decode_string_value(&string).is_err());
+    }
+
+    #[test]
+    fn computes_field_layouts() {
+        let field = Box::new(PrintableType::Field);
+        let pair = PrintableType::Tuple { types: vec![PrintableType::Field, PrintableType::Unit] };
+        assert_eq!(pair.field_layout(), FieldLayout::Fixed(1));
+        let array = PrintableType::Array { length: 3, typ: Box::new(pair) };
+        assert_eq!(array.field_layout(), FieldLayout::Fixed(3));
+        let empty = PrintableType::Array { length: 0, typ: field.clone() };
+        assert_eq!(empty.field_layout(), FieldLayout::ZeroSize);
+        let function = PrintableType::Function {
+            arguments: vec![],
+            return_type: Box::new(PrintableType::Unit),
+            env: Box::new(PrintableType::Unit),
+        };
+        assert_eq!(function.field_layout(), FieldLayout::Fixed(1));
+        let slice = PrintableType::Slice { typ: field };
+        let with_slice = PrintableType::Struct {
+            name: "Foo".to_string(),
+            fields: vec![("x".to_string(), PrintableType::Field), ("y".to_string(), slice)],
+        };
+        assert_eq!(with_slice.field_layout(), FieldLayout::Dynamic);
+    }
+
+    fn fmt_string_inputs(
+        values: Vec<ForeignCallParam<FieldElement>>,
+        types: &[PrintableType],
+    ) -> Vec<ForeignCallParam<FieldElement>> {
+        let mut inputs = vec![ForeignCallParam::Array(fields(b"{} and {}"))];
+        inputs.push(ForeignCallParam::Single(FieldElement::from(types.len() as u128)));
+        inputs.extend(values);
+        for typ in types {
+            let typ = serde_json::to_string(typ).unwrap();
+            inputs.push(ForeignCallParam::Array(fields(typ.as_bytes())));
+        }
+        // The fmtstring flag
+        inputs.push(ForeignCallParam::Single(FieldElement::one()));
+        inputs
+    }
+
+    #[test]
+    fn decodes_fmt_strings_with_slices() {
+        let slice = PrintableType::Slice { typ: Box::new(PrintableType::Field) };
+        let values = vec![
+            ForeignCallParam::Single(FieldElement::from(2u128)),
+            ForeignCallParam::Array(vec![FieldElement::from(5u128), FieldElement::from(6u128)]),
+            ForeignCallParam::Single(FieldElement::from(7u128)),
+        ];
+        let inputs = fmt_string_inputs(values, &[slice, PrintableType::Field]);
+        let display = PrintableValueDisplay::try_from(inputs.as_slice()).unwrap();
+        assert_eq!(display.to_string(), "&[0x05, 0x06] and 0x07");
+    }
+
+    #[test]
+    fn fmt_strings_missing_values_are_errors() {
+        let slice = PrintableType::Slice { typ: Box::new(PrintableType::Field) };
+        let values = vec![ForeignCallParam::Single(FieldElement::from(3u128))];
+        let inputs = fmt_string_inputs(values, &[slice, PrintableType::Field]);
+        assert!(matches!(
+            PrintableValueDisplay::try_from(inputs.as_slice()),
+            Err(ForeignCallError::MissingForeignCallInputs)
+        ));
+    }
+
+    #[test]
+    fn truncates_oversized_strings() {
+        let string = fields(&vec![b'a'; MAX_DECODED_STRING_LENGTH + 3]);
+        let decoded = decode_string_value(&string);
+        assert!(decoded.starts_with(&"a".repeat(MAX_DECODED_STRING_LENGTH)));
+        assert!(decoded.ends_with("... (3 more characters truncated)"));
+    }
 }
@@ -0,0 +1,67 @@
+//! Headless debugger for embedding in other tools: serves a debugging session
+//! of a compiled program over a line-based JSON protocol on stdin and stdout.
+
+use std::collections::BTreeMap;
+use std::io::{stdin, stdout};
+use std::path::Path;
+use std::process::exit;
+
+use bn254_blackbox_solver::Bn254BlackBoxSolver;
+use noirc_abi::input_parser::Format;
+use noirc_artifacts::debug::DebugArtifact;
+use noirc_artifacts::program::ProgramArtifact;
+
+const USAGE: &str = "Usage: noir-debug-server <program.json> [<inputs.toml|inputs.json>]";
+
+fn main() {
+    if let Err(message) = run() {
+        eprintln!("{message}");
+        exit(1);
+    }
+}
+
+fn run() -> Result<(), String> {
+    let mut args = std::env::args().skip(1);
+    let program_path = args.next().ok_or(USAGE)?;
+    let program_json = std::fs::read_to_string(&program_path)
+        .map_err(|err| format!("Could not read {program_path}: {err}"))?;
+    let program: ProgramArtifact = serde_json::from_str(&program_json)
+        .map_err(|err| format!("Invalid program artifact {program_path}: {err}"))?;
+
+    let inputs = match args.next() {
+        Some(inputs_path) => {
+            let format = match Path::new(&inputs_path).extension().and_then(|ext| ext.to_str()) {
+                Some("json") => Format::Json,
+                _ => Format::Toml,
+            };
+            let contents = std::fs::read_to_string(&inputs_path)
+                .map_err(|err| format!("Could not read {inputs_path}: {err}"))?;
+            format
+                .parse(&contents, &program.abi)
+                .map_err(|err| format!("Invalid inputs in {inputs_path}: {err}"))?
+        }
+        None => BTreeMap::new(),
+    };
+    let initial_witness =
+        program.abi.encode(&inputs, None).map_err(|err| format!("Invalid inputs: {err}"))?;
+    let circuit = program
+        .bytecode
+        .functions
+        .first()
+        .ok_or_else(|| format!("Program artifact {program_path} has no functions"))?;
+
+    let debug_artifact = DebugArtifact {
+        debug_symbols: program.debug_symbols.debug_infos,
+        file_map: program.file_map,
+    };
+    noir_debugger::run_json_server(
+        stdin().lock(),
+        stdout().lock(),
+        &Bn254BlackBoxSolver,
+        circuit,
+        &debug_artifact,
+        initial_witness,
+        &program.bytecode.unconstrained_functions,
+    )
+    .map_err(|err| format!("Debug server failed: {err}"))
+}
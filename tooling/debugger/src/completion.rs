@@ -0,0 +1,76 @@
+use acvm::{BlackBoxFunctionSolver, FieldElement};
+
+use crate::context::DebugContext;
+
+/// Returns the completions for the word ending at byte offset `cursor` of
+/// `text`, which the Debug Console evaluates as an expression: the names of
+/// the variables in the current stack frame and witnesses (`_N`).
+pub(super) fn complete<B: BlackBoxFunctionSolver<FieldElement>>(
+    context: &DebugContext<B>,
+    text: &str,
+    cursor: usize,
+) -> Vec<String> {
+    let line = text.get(..cursor).unwrap_or(text);
+    let word_start = line.rfind(char::is_whitespace).map_or(0, |index| index + 1);
+    let word = &line[word_start..];
+
+    let variables: Vec<String> = context
+        .current_stack_frame()
+        .map(|frame| frame.variables.iter().map(|(name, ..)| name.to_string()).collect())
+        .unwrap_or_default();
+    let witnesses = context
+        .get_witness_map()
+        .clone()
+        .into_iter()
+        .map(|(witness, _)| format!("_{}", witness.witness_index()));
+
+    let mut completions: Vec<String> = variables
+        .into_iter()
+        .chain(witnesses)
+        .filter(|candidate| candidate.starts_with(word))
+        .collect();
+    completions.sort();
+    completions.dedup();
+    completions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::foreign_calls::DefaultDebugForeignCallExecutor;
+    use acvm::acir::circuit::Circuit;
+    use acvm::acir::native_types::Witness;
+    use acvm::blackbox_solver::StubbedBlackBoxSolver;
+    use noirc_artifacts::debug::DebugArtifact;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn completes_what_the_debug_console_evaluates() {
+        let circuit = &Circuit::default();
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+        let initial_witness = BTreeMap::from([
+            (Witness(1), FieldElement::from(1u128)),
+            (Witness(12), FieldElement::from(2u128)),
+        ])
+        .into();
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let brillig_funcs = &vec![];
+        let context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        assert_eq!(complete(&context, "_1", 2), vec!["_1", "_12"]);
+        assert_eq!(complete(&context, "_1 3", 2), vec!["_1", "_12"]);
+        assert_eq!(complete(&context, "", 0), vec!["_1", "_12"]);
+        // REPL commands are not evaluated by the Debug Console
+        assert!(complete(&context, "wit", 3).is_empty());
+        assert!(complete(&context, "x", 1).is_empty());
+    }
+}
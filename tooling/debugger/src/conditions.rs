@@ -0,0 +1,471 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use acvm::{AcirField, FieldElement};
+use noirc_printable_type::{PrintableType, PrintableValue};
+
+use crate::expressions::VariablePath;
+
+/// A breakpoint condition, such as `i > 10 && _12 == 0 && hits % 5 == 0`.
+///
+/// Conditions are built from:
+/// - variables in scope, with access paths (`point.x`, `rows[2].1`)
+/// - witnesses, as `_N`
+/// - `hits`, the number of times the breakpoint was reached, this one included
+/// - `opcodes`, the number of opcodes executed in the session
+/// - decimal and hexadecimal (`0x1f`) numbers, `true` and `false`
+/// - the operators `||`, `&&`, `==`, `!=`, `<`, `<=`, `>`, `>=`, `+`, `-`,
+///   `*`, `/`, `%` and `!`, with their usual precedence, and parentheses
+///
+/// Numbers are field elements; ordering, `/` and `%` work on their value as a
+/// signed integer, so that negative integers compare as expected.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Condition {
+    text: String,
+    expression: Expression,
+}
+
+/// What a condition refers to, besides constants
+pub(super) trait ConditionScope {
+    /// Returns the value of a variable path or witness
+    fn lookup(&self, path: &str) -> Result<(PrintableValue<FieldElement>, PrintableType), String>;
+    fn hits(&self) -> usize;
+    fn executed_opcodes(&self) -> usize;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Value {
+    Bool(bool),
+    Number(FieldElement),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expression {
+    Constant(Value),
+    Variable(String),
+    Hits,
+    Opcodes,
+    Not(Box<Expression>),
+    Negate(Box<Expression>),
+    Binary(BinaryOp, Box<Expression>, Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(FieldElement),
+    Name(String),
+    Operator(&'static str),
+    OpenParen,
+    CloseParen,
+}
+
+// Longer operators go first so that `<=` is not read as `<`
+const OPERATORS: &[&str] =
+    &["||", "&&", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "!"];
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut rest = text.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let length = if c == '(' || c == ')' {
+            tokens.push(if c == '(' { Token::OpenParen } else { Token::CloseParen });
+            1
+        } else if c.is_ascii_digit() {
+            let length = rest.find(|c: char| !c.is_ascii_alphanumeric()).unwrap_or(rest.len());
+            let number = &rest[..length];
+            let value = FieldElement::try_from_str(number)
+                .ok_or_else(|| format!("Invalid number {number}"))?;
+            tokens.push(Token::Number(value));
+            length
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            // Names extend over access paths such as `rows[2].x`
+            let mut length = 0;
+            let mut in_index = false;
+            for c in rest.chars() {
+                match c {
+                    '[' => in_index = true,
+                    ']' => in_index = false,
+                    c if c.is_ascii_alphanumeric() || c == '_' || c == '.' || in_index => (),
+                    _ => break,
+                }
+                length += c.len_utf8();
+            }
+            let name = &rest[..length];
+            name.parse::<VariablePath>()?;
+            tokens.push(Token::Name(name.to_string()));
+            length
+        } else if let Some(operator) = OPERATORS.iter().find(|operator| rest.starts_with(*operator))
+        {
+            tokens.push(Token::Operator(operator));
+            operator.len()
+        } else {
+            return Err(format!("Unexpected {c} in condition"));
+        };
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Binary operators by precedence level, from the loosest binding
+const PRECEDENCE: &[&[(&str, BinaryOp)]] = &[
+    &[("||", BinaryOp::Or)],
+    &[("&&", BinaryOp::And)],
+    &[
+        ("==", BinaryOp::Eq),
+        ("!=", BinaryOp::Ne),
+        ("<", BinaryOp::Lt),
+        ("<=", BinaryOp::Le),
+        (">", BinaryOp::Gt),
+        (">=", BinaryOp::Ge),
+    ],
+    &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+    &[("*", BinaryOp::Mul), ("/", BinaryOp::Div), ("%", BinaryOp::Rem)],
+];
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn parse_binary(&mut self, level: usize) -> Result<Expression, String> {
+        let Some(operators) = PRECEDENCE.get(level) else {
+            return self.parse_unary();
+        };
+        let mut left = self.parse_binary(level + 1)?;
+        while let Some(Token::Operator(text)) = self.peek() {
+            let Some((_, op)) = operators.iter().find(|(operator, _)| operator == text) else {
+                break;
+            };
+            let op = *op;
+            self.position += 1;
+            let right = self.parse_binary(level + 1)?;
+            left = Expression::Binary(op, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Operator("!")) => Ok(Expression::Not(Box::new(self.parse_unary()?))),
+            Some(Token::Operator("-")) => Ok(Expression::Negate(Box::new(self.parse_unary()?))),
+            Some(Token::Number(value)) => Ok(Expression::Constant(Value::Number(value))),
+            Some(Token::Name(name)) => Ok(match name.as_str() {
+                "true" => Expression::Constant(Value::Bool(true)),
+                "false" => Expression::Constant(Value::Bool(false)),
+                "hits" => Expression::Hits,
+                "opcodes" => Expression::Opcodes,
+                _ => Expression::Variable(name),
+            }),
+            Some(Token::OpenParen) => {
+                let expression = self.parse_binary(0)?;
+                match self.next() {
+                    Some(Token::CloseParen) => Ok(expression),
+                    _ => Err("Missing ) in condition".to_string()),
+                }
+            }
+            Some(token) => Err(format!("Unexpected {} in condition", token_text(&token))),
+            None => Err("Condition ends unexpectedly".to_string()),
+        }
+    }
+}
+
+fn token_text(token: &Token) -> String {
+    match token {
+        Token::Number(value) => value.to_string(),
+        Token::Name(name) => name.clone(),
+        Token::Operator(operator) => operator.to_string(),
+        Token::OpenParen => "(".to_string(),
+        Token::CloseParen => ")".to_string(),
+    }
+}
+
+impl FromStr for Condition {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { tokens: tokenize(text)?, position: 0 };
+        let expression = parser.parse_binary(0)?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {} in condition", token_text(token)));
+        }
+        Ok(Condition { text: text.trim().to_string(), expression })
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Condition {
+    /// Parses a hit condition as found in DAP breakpoints: a comparison with
+    /// the hit count (`>= 5`), a modulus (`% 3`, stopping on every third hit)
+    /// or a plain number of hits (`5`)
+    pub(super) fn parse_hit_condition(text: &str) -> Result<Self, String> {
+        let text = text.trim();
+        let condition = if text.starts_with('%') {
+            format!("hits {text} == 0")
+        } else if ["==", "!=", "<", ">"].iter().any(|operator| text.starts_with(operator)) {
+            format!("hits {text}")
+        } else {
+            format!("hits == {text}")
+        };
+        let mut condition: Condition = condition.parse()?;
+        condition.text = text.to_string();
+        Ok(condition)
+    }
+
+    pub(super) fn evaluate(&self, scope: &impl ConditionScope) -> Result<bool, String> {
+        match evaluate(&self.expression, scope)? {
+            Value::Bool(holds) => Ok(holds),
+            Value::Number(_) => Err(format!("Condition {self} is not a boolean")),
+        }
+    }
+}
+
+fn evaluate(expression: &Expression, scope: &impl ConditionScope) -> Result<Value, String> {
+    match expression {
+        Expression::Constant(value) => Ok(*value),
+        Expression::Variable(path) => {
+            let (value, typ) = scope.lookup(path)?;
+            to_value(path, value, &typ)
+        }
+        Expression::Hits => Ok(Value::Number(FieldElement::from(scope.hits() as u128))),
+        Expression::Opcodes => {
+            Ok(Value::Number(FieldElement::from(scope.executed_opcodes() as u128)))
+        }
+        Expression::Not(operand) => Ok(Value::Bool(!as_bool(evaluate(operand, scope)?)?)),
+        Expression::Negate(operand) => Ok(Value::Number(-as_number(evaluate(operand, scope)?)?)),
+        Expression::Binary(BinaryOp::Or, left, right) => {
+            Ok(Value::Bool(as_bool(evaluate(left, scope)?)? || as_bool(evaluate(right, scope)?)?))
+        }
+        Expression::Binary(BinaryOp::And, left, right) => {
+            Ok(Value::Bool(as_bool(evaluate(left, scope)?)? && as_bool(evaluate(right, scope)?)?))
+        }
+        Expression::Binary(op, left, right) => {
+            let (left, right) = (evaluate(left, scope)?, evaluate(right, scope)?);
+            match op {
+                BinaryOp::Eq => Ok(Value::Bool(equals(left, right)?)),
+                BinaryOp::Ne => Ok(Value::Bool(!equals(left, right)?)),
+                _ => arithmetic(*op, as_number(left)?, as_number(right)?),
+            }
+        }
+    }
+}
+
+fn to_value(
+    path: &str,
+    value: PrintableValue<FieldElement>,
+    typ: &PrintableType,
+) -> Result<Value, String> {
+    match (value, typ) {
+        (PrintableValue::Field(value), PrintableType::Boolean) => Ok(Value::Bool(!value.is_zero())),
+        (PrintableValue::Field(value), PrintableType::SignedInteger { width }) => {
+            // Negative values are stored in two's complement
+            let value = match signed_value(value, *width) {
+                Some(value) => from_i128(value),
+                None => value,
+            };
+            Ok(Value::Number(value))
+        }
+        (PrintableValue::Field(value), _) => Ok(Value::Number(value)),
+        _ => Err(format!("{path} is not a number or a boolean")),
+    }
+}
+
+fn signed_value(value: FieldElement, width: u32) -> Option<i128> {
+    if width == 0 || width >= 128 || value.num_bits() > 128 {
+        return None;
+    }
+    let value = value.to_u128();
+    if value >= 1 << (width - 1) {
+        Some(value as i128 - (1 << width))
+    } else {
+        Some(value as i128)
+    }
+}
+
+fn as_bool(value: Value) -> Result<bool, String> {
+    match value {
+        Value::Bool(value) => Ok(value),
+        Value::Number(value) => Err(format!("Expected a boolean, found {value}")),
+    }
+}
+
+fn as_number(value: Value) -> Result<FieldElement, String> {
+    match value {
+        Value::Number(value) => Ok(value),
+        Value::Bool(value) => Err(format!("Expected a number, found {value}")),
+    }
+}
+
+fn equals(left: Value, right: Value) -> Result<bool, String> {
+    match (left, right) {
+        (Value::Bool(left), Value::Bool(right)) => Ok(left == right),
+        (Value::Number(left), Value::Number(right)) => Ok(left == right),
+        _ => Err("Cannot compare a boolean with a number".to_string()),
+    }
+}
+
+/// Reads a field element as a signed integer, taking values close to the
+/// modulus as negative
+fn as_i128(value: FieldElement) -> Result<i128, String> {
+    if value.num_bits() <= 128 {
+        if let Ok(value) = i128::try_from(value.to_u128()) {
+            return Ok(value);
+        }
+    }
+    let negated = -value;
+    if negated.num_bits() <= 128 {
+        if let Ok(negated) = i128::try_from(negated.to_u128()) {
+            return Ok(-negated);
+        }
+    }
+    Err(format!("{value} is too large to be used as an integer"))
+}
+
+fn from_i128(value: i128) -> FieldElement {
+    let magnitude = FieldElement::from(value.unsigned_abs());
+    if value < 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+fn arithmetic(op: BinaryOp, left: FieldElement, right: FieldElement) -> Result<Value, String> {
+    let value = match op {
+        BinaryOp::Add => Value::Number(left + right),
+        BinaryOp::Sub => Value::Number(left - right),
+        BinaryOp::Mul => Value::Number(left * right),
+        BinaryOp::Lt => Value::Bool(as_i128(left)? < as_i128(right)?),
+        BinaryOp::Le => Value::Bool(as_i128(left)? <= as_i128(right)?),
+        BinaryOp::Gt => Value::Bool(as_i128(left)? > as_i128(right)?),
+        BinaryOp::Ge => Value::Bool(as_i128(left)? >= as_i128(right)?),
+        BinaryOp::Div | BinaryOp::Rem => {
+            let (left, right) = (as_i128(left)?, as_i128(right)?);
+            let result =
+                if op == BinaryOp::Div { left.checked_div(right) } else { left.checked_rem(right) };
+            Value::Number(from_i128(result.ok_or("Division by zero in condition")?))
+        }
+        BinaryOp::Or | BinaryOp::And | BinaryOp::Eq | BinaryOp::Ne => {
+            unreachable!("{op:?} is not an arithmetic operator")
+        }
+    };
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    struct TestScope {
+        hits: usize,
+    }
+
+    impl ConditionScope for TestScope {
+        fn lookup(
+            &self,
+            path: &str,
+        ) -> Result<(PrintableValue<FieldElement>, PrintableType), String> {
+            let (value, typ) = match path {
+                "i" => (FieldElement::from(12u128), PrintableType::UnsignedInteger { width: 32 }),
+                "_12" => (FieldElement::zero(), PrintableType::Field),
+                "delta" => (FieldElement::from(255u128), PrintableType::SignedInteger { width: 8 }),
+                "done" => (FieldElement::one(), PrintableType::Boolean),
+                "p.x" => {
+                    let value = PrintableValue::Struct(BTreeMap::from([(
+                        "x".to_string(),
+                        PrintableValue::Field(FieldElement::from(3u128)),
+                    )]));
+                    let typ = PrintableType::Struct {
+                        name: "Point".to_string(),
+                        fields: vec![("x".to_string(), PrintableType::Field)],
+                    };
+                    let path = "p.x".parse().unwrap();
+                    return crate::expressions::resolve_path(&path, &value, &typ);
+                }
+                _ => return Err(format!("No variable or witness named {path} in scope")),
+            };
+            Ok((PrintableValue::Field(value), typ))
+        }
+
+        fn hits(&self) -> usize {
+            self.hits
+        }
+
+        fn executed_opcodes(&self) -> usize {
+            100
+        }
+    }
+
+    fn holds(condition: &str, hits: usize) -> Result<bool, String> {
+        condition.parse::<Condition>()?.evaluate(&TestScope { hits })
+    }
+
+    #[test]
+    fn evaluates_conditions() {
+        let condition = "i > 10 && _12 == 0 && hits % 5 == 0";
+        assert_eq!(holds(condition, 10), Ok(true));
+        assert_eq!(holds(condition, 11), Ok(false));
+
+        assert_eq!(holds("delta == -1 && delta < 0", 1), Ok(true));
+        assert_eq!(holds("!done || (opcodes - 1) * 2 >= 198", 1), Ok(true));
+        assert_eq!(holds("p.x + 0x10 == 19", 1), Ok(true));
+        assert_eq!(holds("1 + 2 * 3 == 7", 1), Ok(true));
+
+        assert_eq!(holds("i", 1), Err("Condition i is not a boolean".to_string()));
+        assert_eq!(holds("i / 0 == 1", 1), Err("Division by zero in condition".to_string()));
+        assert!(holds("j == 1", 1).is_err());
+        assert!(holds("i == true", 1).is_err());
+        assert!("i >".parse::<Condition>().is_err());
+        assert!("(i > 1".parse::<Condition>().is_err());
+        assert!("i > 1 1".parse::<Condition>().is_err());
+        assert!("i # 1".parse::<Condition>().is_err());
+    }
+
+    #[test]
+    fn parses_hit_conditions() {
+        let hit_condition = |text: &str, hits| {
+            Condition::parse_hit_condition(text).unwrap().evaluate(&TestScope { hits })
+        };
+        assert_eq!(hit_condition(">= 5", 4), Ok(false));
+        assert_eq!(hit_condition(">= 5", 6), Ok(true));
+        assert_eq!(hit_condition("% 3", 6), Ok(true));
+        assert_eq!(hit_condition("%3", 7), Ok(false));
+        assert_eq!(hit_condition("2", 2), Ok(true));
+        assert_eq!(Condition::parse_hit_condition("% 3").unwrap().to_string(), "% 3");
+        assert!(Condition::parse_hit_condition("often").is_err());
+    }
+}
@@ -1,22 +1,55 @@
-use crate::foreign_calls::DebugForeignCallExecutor;
-use acvm::acir::circuit::brillig::BrilligBytecode;
-use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
-use acvm::acir::native_types::{Witness, WitnessMap};
+use crate::conditions::{Condition, ConditionScope};
+use crate::errors::DebuggerErrorKind;
+use crate::expressions::{resolve_path, VariablePath};
+use crate::foreign_calls::{
+    default_foreign_call_output, read_foreign_call_inputs, validate_foreign_call_outputs,
+    DebugForeignCall, DebugForeignCallExecutor,
+};
+use crate::memory_view::CellType;
+use crate::oracle_schema::OracleSchema;
+use crate::source_code_printer::format_location_path;
+use crate::source_paths::PathSubstitutions;
+use crate::timeline::{Timeline, TimelineEvent, TimelineEventKind};
+use crate::{BreakpointSnapshot, BrilligLimits, FailureReport};
+use acvm::acir::brillig::{
+    BlackBoxOp, ForeignCallParam, ForeignCallResult, HeapArray, HeapVector, MemoryAddress,
+    Opcode as BrilligOpcode, ValueOrArray,
+};
+use acvm::acir::circuit::brillig::{BrilligBytecode, BrilligInputs, BrilligOutputs};
+use acvm::acir::circuit::directives::Directive;
+use acvm::acir::circuit::opcodes::{BlackBoxFuncCall, BlockId};
+use acvm::acir::circuit::{
+    AssertionPayload, Circuit, ErrorSelector, Opcode, OpcodeLocation, ResolvedAssertionPayload,
+};
+use acvm::acir::native_types::{Expression, Witness, WitnessMap, WitnessStack};
+use acvm::acir::BlackBoxFunc;
 use acvm::brillig_vm::MemoryValue;
 use acvm::pwg::{
-    ACVMStatus, BrilligSolver, BrilligSolverStatus, ForeignCallWaitInfo, StepResult, ACVM,
+    ACVMStatus, BrilligSolver, BrilligSolverStatus, ForeignCallWaitInfo, OpcodeResolutionError,
+    StepResult, ACVM,
 };
-use acvm::{BlackBoxFunctionSolver, FieldElement};
+use acvm::{AcirField, BlackBoxFunctionSolver, FieldElement};
 
 use codespan_reporting::files::{Files, SimpleFile};
 use fm::FileId;
 use nargo::errors::{ExecutionError, Location};
 use nargo::NargoError;
+use noirc_abi::{display_abi_error, AbiErrorType};
 use noirc_artifacts::debug::{DebugArtifact, StackFrame};
 use noirc_driver::DebugFile;
+use noirc_errors::debug_info::DebugVarId;
+use noirc_printable_type::{
+    ForeignCallError, PrintableType, PrintableValue, PrintableValueDisplay,
+};
 
-use std::collections::BTreeMap;
-use std::collections::{hash_set::Iter, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fmt::Write;
+use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
 #[derive(Debug)]
 pub(super) enum DebugCommandResult {
@@ -26,18 +59,302 @@ pub(super) enum DebugCommandResult {
     Error(NargoError<FieldElement>),
 }
 
+/// A breakpoint set on an opcode location, along with its hit state
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Breakpoint {
+    pub(super) id: usize,
+    pub(super) location: OpcodeLocation,
+    /// Number of upcoming hits on which execution does not stop
+    pub(super) ignore_count: usize,
+    /// Number of times execution reached the breakpoint location
+    pub(super) hit_count: usize,
+    /// Message printed instead of stopping when this is a logpoint. `{name}`
+    /// placeholders are replaced by the values of the variables in scope.
+    pub(super) log_message: Option<String>,
+    /// Variables whose values are recorded every time the breakpoint is hit
+    pub(super) snapshot_variables: Vec<String>,
+    /// Condition that must hold for execution to stop
+    pub(super) condition: Option<Condition>,
+    /// Condition on the hit count that must hold for execution to stop
+    pub(super) hit_condition: Option<Condition>,
+}
+
+/// The scope in which the conditions of a breakpoint are evaluated
+struct BreakpointScope<'c, 'a, B: BlackBoxFunctionSolver<FieldElement>> {
+    context: &'c DebugContext<'a, B>,
+    hits: usize,
+}
+
+impl<'c, 'a, B: BlackBoxFunctionSolver<FieldElement>> ConditionScope
+    for BreakpointScope<'c, 'a, B>
+{
+    fn lookup(&self, path: &str) -> Result<(PrintableValue<FieldElement>, PrintableType), String> {
+        self.context.evaluate(path, None)
+    }
+
+    fn hits(&self) -> usize {
+        self.hits
+    }
+
+    fn executed_opcodes(&self) -> usize {
+        self.context.executed_opcodes
+    }
+}
+
+/// Time spent in each phase of restarting a debugging session
+#[derive(Debug, Default)]
+pub(super) struct RestartTimings {
+    pub(super) vm: Duration,
+    pub(super) foreign_call_executor: Duration,
+}
+
+impl RestartTimings {
+    pub(super) fn total(&self) -> Duration {
+        self.vm + self.foreign_call_executor
+    }
+}
+
+/// What replaying the current run must leave as it was
+struct ReplayState {
+    ignore_counts: Vec<(usize, usize)>,
+    breakpoint_snapshots: Vec<BreakpointSnapshot>,
+}
+
+/// A black box function call executed during the session
+#[derive(Debug, Clone)]
+pub(super) struct BlackBoxCall {
+    pub(super) location: OpcodeLocation,
+    pub(super) name: &'static str,
+    pub(super) duration: Duration,
+    /// Hashes of the input and output values, read from the witness map for
+    /// ACIR calls and from VM memory for Brillig calls
+    pub(super) inputs_hash: Option<u64>,
+    pub(super) outputs_hash: Option<u64>,
+}
+
+/// Calls made to a function of the program, as reported by the debug
+/// instrumentation
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct FunctionCalls {
+    pub(super) calls: usize,
+    /// Opcodes stepped through while the function was executing, including
+    /// those of the functions it called
+    pub(super) opcodes: usize,
+}
+
+/// An `assert` or `assert_eq` of the program, with the id the compiler gave
+/// it when instrumenting the program
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct AssertInfo {
+    pub(super) id: usize,
+    pub(super) location: Location,
+    /// First opcode in program order compiled from the assert, if any was,
+    /// as asserts may be optimized away
+    pub(super) first_opcode: Option<OpcodeLocation>,
+}
+
+/// Evaluations of an assert during the session
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct AssertOutcome {
+    pub(super) evaluations: usize,
+    pub(super) failed: bool,
+}
+
+/// Outcome of a run of the program made to profile it
+pub(super) struct Profile {
+    pub(super) result: DebugCommandResult,
+    pub(super) executed_opcodes: usize,
+    pub(super) function_calls: BTreeMap<String, FunctionCalls>,
+    /// Opcodes executed at each source location, most executed first
+    pub(super) locations: Vec<(Location, usize)>,
+}
+
+/// Point of execution right before a foreign call of the program, which
+/// execution can be rewound to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ForeignCallCheckpoint {
+    pub(super) function: String,
+    /// Steps taken before the one making the call
+    pub(super) executed_opcodes: usize,
+}
+
+/// What to do when the resolver of an oracle fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum OraclePolicy {
+    /// Call the oracle again, up to this many times, waiting longer before
+    /// each attempt
+    Retry(usize),
+    /// Resolve the call with zeroed values of the expected shape, if the
+    /// shape is known
+    Skip,
+}
+
+/// An opcode executed during the session along with the witnesses it wrote
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct JournalEntry {
+    pub(super) location: OpcodeLocation,
+    /// Witnesses written when the opcode completed; empty for Brillig opcodes
+    /// other than the last one of a block, and for opcodes that failed
+    pub(super) written: Vec<(Witness, FieldElement)>,
+}
+
+/// A step of a run of the program, recorded to compare it with the
+/// previous run when checking for non-determinism
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct TraceStep {
+    pub(super) location: OpcodeLocation,
+    /// Oracle or black box function called by the step, if any
+    pub(super) call: Option<TracedCall>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct TracedCall {
+    pub(super) name: String,
+    pub(super) is_black_box: bool,
+    pub(super) inputs_hash: u64,
+    pub(super) outputs_hash: u64,
+}
+
+/// First step at which a run of the program differed from the previous one.
+/// Either the step executes a different opcode, in which case it is found
+/// before executing it and `actual.call` is `None`, or it calls an oracle or
+/// black box function with different inputs or results.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Divergence {
+    /// Number of steps executed before the one that differed
+    pub(super) step: usize,
+    pub(super) expected: TraceStep,
+    pub(super) actual: TraceStep,
+    /// Last call made before the step, whose results led execution there
+    pub(super) last_call: Option<TracedCall>,
+}
+
+// Bit sizes of the integer types, which Brillig memory cells hold besides fields
+const INTEGER_BIT_SIZES: [u32; 6] = [1, 8, 16, 32, 64, 128];
+
+// Delay before the first retry of a failed oracle, doubled on every retry up
+// to the maximum so transient failures have time to clear
+const ORACLE_RETRY_INITIAL_DELAY: Duration = Duration::from_millis(100);
+const ORACLE_RETRY_MAX_DELAY: Duration = Duration::from_secs(5);
+
+// Foreign call made by `print` and `println`
+pub(super) const PRINT_ORACLE: &str = "print";
+
+/// An integer overflow check inserted by the compiler that failed
+#[derive(Debug, PartialEq)]
+pub(super) struct IntegerOverflow {
+    pub(super) message: String,
+    pub(super) location: OpcodeLocation,
+    /// Bit size of the integer type and the value that overflowed it, known
+    /// when the check is an ACIR range constraint
+    pub(super) bit_size: Option<u32>,
+    pub(super) value: Option<FieldElement>,
+}
+
+/// A node in the tree of opcodes a witness value depends on
+#[derive(Debug, PartialEq)]
+pub(super) struct WitnessDependency {
+    pub(super) witness: Witness,
+    /// ACIR index of the opcode writing the witness, or `None` if the witness is
+    /// a circuit input or not written by any opcode
+    pub(super) acir_index: Option<usize>,
+    /// Dependencies of the opcode writing the witness; empty when the maximum
+    /// depth was reached or the witness was already visited
+    pub(super) dependencies: Vec<WitnessDependency>,
+}
+
 pub(super) struct DebugContext<'a, B: BlackBoxFunctionSolver<FieldElement>> {
     acvm: ACVM<'a, FieldElement, B>,
+    blackbox_solver: &'a B,
+    circuit: &'a Circuit<FieldElement>,
     brillig_solver: Option<BrilligSolver<'a, FieldElement, B>>,
+    // Number of steps taken in the Brillig block being executed
+    brillig_steps: usize,
+    brillig_limits: BrilligLimits,
+    // Description of the Brillig limit the last step exceeded, if any
+    exceeded_brillig_limit: Option<String>,
     foreign_call_executor: Box<dyn DebugForeignCallExecutor + 'a>,
     debug_artifact: &'a DebugArtifact,
-    breakpoints: HashSet<OpcodeLocation>,
+    breakpoints: BTreeMap<usize, Breakpoint>,
+    // Ids of the breakpoints by location, checked on every executed opcode
+    breakpoint_ids: HashMap<OpcodeLocation, usize>,
+    next_breakpoint_id: usize,
     source_to_opcodes: BTreeMap<FileId, Vec<(usize, OpcodeLocation)>>,
     unconstrained_functions: &'a [BrilligBytecode<FieldElement>],
 
     // Absolute (in terms of all the opcodes ACIR+Brillig) addresses of the ACIR
     // opcodes with one additional entry for to indicate the last valid address.
     acir_opcode_addresses: Vec<usize>,
+
+    // Brillig memory addresses the instrumented variables were last assigned
+    // from in the current Brillig call, with the values assigned
+    variable_addresses: HashMap<DebugVarId, (usize, FieldElement)>,
+
+    blackbox_calls: Vec<BlackBoxCall>,
+    // Foreign calls, black box calls and assert evaluations of the current run
+    timeline: Timeline,
+
+    // Most recently executed opcodes, holding at most `journal_capacity` entries.
+    // Journaling is disabled when the capacity is zero.
+    journal: VecDeque<JournalEntry>,
+    journal_capacity: usize,
+    // Witnesses written by each ACIR opcode, only built when journaling is enabled
+    opcode_outputs: HashMap<usize, Vec<Witness>>,
+
+    // Messages rendered by logpoints hit since the output was last taken
+    log_output: Vec<String>,
+    // Snapshots taken in every run of the session, kept across restarts so
+    // that runs can be compared
+    breakpoint_snapshots: Vec<BreakpointSnapshot>,
+    // Number of the current run of the program, counting from 1
+    run: usize,
+
+    // Number of steps taken since the session started
+    executed_opcodes: usize,
+    // Foreign calls made by the program, not counting the debugger's oracles
+    foreign_calls: usize,
+    // Point of execution right before each of the program's foreign calls,
+    // kept across the replays rewinding the run
+    foreign_call_checkpoints: Vec<ForeignCallCheckpoint>,
+    // Whether the results of the program's foreign calls are checked against
+    // the types expected by the Brillig bytecode
+    validate_foreign_calls: bool,
+    // Whether execution stops right before each `print` foreign call
+    break_on_print: bool,
+    oracle_policies: HashMap<String, OraclePolicy>,
+    // Declared signatures of the program's oracles, checked on every call
+    oracle_schema: OracleSchema,
+    // Warnings raised by the context itself, shown along with the executor's
+    debug_warnings: Vec<String>,
+    function_calls: BTreeMap<String, FunctionCalls>,
+    // Functions being executed, along with the step count when they were entered
+    entered_functions: Vec<(String, usize)>,
+    // Names of the functions on whose entry execution stops
+    function_breakpoints: BTreeSet<String>,
+
+    asserts: Vec<AssertInfo>,
+    // Id of the assert each opcode was compiled from
+    assert_ids: BTreeMap<OpcodeLocation, usize>,
+    assert_outcomes: BTreeMap<usize, AssertOutcome>,
+    // Assert of the last opcode executed, to count an evaluation only when
+    // execution enters an assert
+    last_assert: Option<usize>,
+
+    // Whether each run is compared with the previous one, stopping at the
+    // first step in which they differ
+    check_determinism: bool,
+    trace: Vec<TraceStep>,
+    // Trace the current run is compared with
+    reference_trace: Vec<TraceStep>,
+    // Oracle or black box call made by the step being executed
+    pending_call: Option<TracedCall>,
+    divergence_found: bool,
+    // Divergence found in this run and not yet reported
+    divergence: Option<Divergence>,
+
+    // Raised from outside the context, eg. on Ctrl-C, to stop the commands
+    // taking several steps before they are done
+    interrupt: Option<&'a AtomicBool>,
 }
 
 impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
@@ -51,6 +368,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     ) -> Self {
         let source_to_opcodes = build_source_to_opcode_debug_mappings(debug_artifact);
         let acir_opcode_addresses = build_acir_opcode_offsets(circuit, unconstrained_functions);
+        let (asserts, assert_ids) = build_asserts(debug_artifact);
         Self {
             // TODO: need to handle brillig pointer in the debugger
             acvm: ACVM::new(
@@ -60,24 +378,128 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
                 unconstrained_functions,
                 &circuit.assert_messages,
             ),
+            blackbox_solver,
+            circuit,
             brillig_solver: None,
+            brillig_steps: 0,
+            brillig_limits: BrilligLimits::default(),
+            exceeded_brillig_limit: None,
             foreign_call_executor,
             debug_artifact,
-            breakpoints: HashSet::new(),
+            breakpoints: BTreeMap::new(),
+            breakpoint_ids: HashMap::new(),
+            next_breakpoint_id: 1,
             source_to_opcodes,
             unconstrained_functions,
             acir_opcode_addresses,
+            variable_addresses: HashMap::new(),
+            blackbox_calls: Vec::new(),
+            timeline: Timeline::default(),
+            journal: VecDeque::new(),
+            journal_capacity: 0,
+            opcode_outputs: HashMap::new(),
+            log_output: Vec::new(),
+            breakpoint_snapshots: Vec::new(),
+            run: 1,
+            executed_opcodes: 0,
+            foreign_calls: 0,
+            foreign_call_checkpoints: Vec::new(),
+            validate_foreign_calls: false,
+            break_on_print: false,
+            oracle_policies: HashMap::new(),
+            oracle_schema: OracleSchema::default(),
+            debug_warnings: Vec::new(),
+            function_calls: BTreeMap::new(),
+            entered_functions: Vec::new(),
+            function_breakpoints: BTreeSet::new(),
+            asserts,
+            assert_ids,
+            assert_outcomes: BTreeMap::new(),
+            last_assert: None,
+            check_determinism: false,
+            trace: Vec::new(),
+            reference_trace: Vec::new(),
+            pending_call: None,
+            divergence_found: false,
+            divergence: None,
+            interrupt: None,
+        }
+    }
+
+    /// Restarts execution from the beginning with the given initial witness.
+    /// Breakpoints and the data derived from the circuit and debug artifact
+    /// (source mappings, opcode addresses, variable metadata) are kept.
+    pub(super) fn restart(&mut self, initial_witness: WitnessMap<FieldElement>) -> RestartTimings {
+        let circuit: &'a Circuit<FieldElement> = self.circuit;
+
+        let start = Instant::now();
+        self.acvm = ACVM::new(
+            self.blackbox_solver,
+            &circuit.opcodes,
+            initial_witness,
+            self.unconstrained_functions,
+            &circuit.assert_messages,
+        );
+        self.brillig_solver = None;
+        self.brillig_steps = 0;
+        self.exceeded_brillig_limit = None;
+        self.variable_addresses.clear();
+        self.blackbox_calls.clear();
+        self.timeline.clear();
+        self.journal.clear();
+        self.log_output.clear();
+        self.run += 1;
+        self.executed_opcodes = 0;
+        self.foreign_calls = 0;
+        self.foreign_call_checkpoints.clear();
+        self.function_calls.clear();
+        self.entered_functions.clear();
+        self.assert_outcomes.clear();
+        self.last_assert = None;
+        // The longest run so far is kept as reference, so that rewinding,
+        // which replays part of the run, does not shorten it
+        let trace = std::mem::take(&mut self.trace);
+        if trace.len() > self.reference_trace.len() {
+            self.reference_trace = trace;
         }
+        self.pending_call = None;
+        self.divergence_found = false;
+        self.divergence = None;
+        for breakpoint in self.breakpoints.values_mut() {
+            breakpoint.hit_count = 0;
+        }
+        let vm = start.elapsed();
+
+        let start = Instant::now();
+        self.foreign_call_executor.reset();
+        let foreign_call_executor = start.elapsed();
+
+        RestartTimings { vm, foreign_call_executor }
     }
 
     pub(super) fn get_opcodes(&self) -> &[Opcode<FieldElement>] {
         self.acvm.opcodes()
     }
 
+    pub(super) fn get_circuit(&self) -> &Circuit<FieldElement> {
+        self.circuit
+    }
+
+    pub(super) fn get_unconstrained_functions(&self) -> &[BrilligBytecode<FieldElement>] {
+        self.unconstrained_functions
+    }
+
     pub(super) fn get_witness_map(&self) -> &WitnessMap<FieldElement> {
         self.acvm.witness_map()
     }
 
+    /// Returns the witness stack of the execution so far, as saved on failure.
+    /// Calls to other ACIR functions are not supported by the debugger yet,
+    /// so the stack only holds the witness map of the main circuit.
+    pub(super) fn get_witness_stack(&self) -> WitnessStack<FieldElement> {
+        WitnessStack::from(self.get_witness_map().clone())
+    }
+
     pub(super) fn overwrite_witness(
         &mut self,
         witness: Witness,
@@ -164,6 +586,112 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         Some(found_index)
     }
 
+    /// Find the opcode for a column within a source line, for breakpoints on
+    /// one of several expressions on the same line (eg. chained calls). Among
+    /// the opcodes mapped to the line, the one with the narrowest source span
+    /// containing the column is returned, the first in program order on ties.
+    /// Columns start at 1.
+    pub(super) fn find_opcode_for_source_column(
+        &self,
+        file_id: &FileId,
+        line: i64,
+        column: i64,
+    ) -> Option<OpcodeLocation> {
+        let line_index = usize::try_from(line - 1).ok()?;
+        let line_range = self.debug_artifact.line_range(*file_id, line_index).ok()?;
+        let offset = line_range.start + usize::try_from(column - 1).ok()?;
+        let locations = &self.debug_artifact.debug_symbols.first()?.locations;
+
+        self.source_to_opcodes
+            .get(file_id)?
+            .iter()
+            .filter(|(opcode_line, _)| *opcode_line == line_index + 1)
+            .filter_map(|(_, opcode_location)| {
+                let span_length = locations
+                    .get(opcode_location)?
+                    .iter()
+                    .filter(|location| location.file == *file_id)
+                    .map(|location| location.span)
+                    .filter(|span| span.start() as usize <= offset && offset < span.end() as usize)
+                    .map(|span| span.end() - span.start())
+                    .min()?;
+                Some((span_length, *opcode_location))
+            })
+            .min_by_key(|(span_length, _)| *span_length)
+            .map(|(_, opcode_location)| opcode_location)
+    }
+
+    /// Returns the positions within `lines` of a file where breakpoints can be
+    /// set, as `(line, column)` pairs starting at 1 in increasing order. These
+    /// are the starts of the expressions compiled to opcodes.
+    pub(super) fn get_breakpoint_positions(
+        &self,
+        file_id: &FileId,
+        lines: RangeInclusive<usize>,
+    ) -> Vec<(usize, usize)> {
+        let (Some(line_to_opcodes), Some(debug_info)) =
+            (self.source_to_opcodes.get(file_id), self.debug_artifact.debug_symbols.first())
+        else {
+            return vec![];
+        };
+        let positions: BTreeSet<_> = line_to_opcodes
+            .iter()
+            .filter(|(line, _)| lines.contains(line))
+            .flat_map(|(line, opcode_location)| {
+                let source_locations = debug_info.locations.get(opcode_location);
+                source_locations.into_iter().flatten().filter_map(|location| {
+                    if location.file != *file_id
+                        || self.debug_artifact.location_line_number(*location).ok()? != *line
+                    {
+                        return None;
+                    }
+                    let column = self.debug_artifact.location_column_number(*location).ok()?;
+                    Some((*line, column))
+                })
+            })
+            .collect();
+        positions.into_iter().collect()
+    }
+
+    /// Returns the ACIR opcodes mapped to the given source line, in program
+    /// order, which are the points execution can jump to for that line
+    pub(super) fn find_jump_targets(&self, file_id: &FileId, line: i64) -> Vec<OpcodeLocation> {
+        let Ok(line) = usize::try_from(line) else {
+            return vec![];
+        };
+        let Some(line_to_opcodes) = self.source_to_opcodes.get(file_id) else {
+            return vec![];
+        };
+        let mut targets: Vec<_> = line_to_opcodes
+            .iter()
+            .filter(|(opcode_line, location)| {
+                *opcode_line == line && matches!(location, OpcodeLocation::Acir(_))
+            })
+            .map(|(_, location)| *location)
+            .collect();
+        targets.sort_by_key(|location| self.opcode_location_to_address(location));
+        targets.dedup();
+        targets
+    }
+
+    /// Moves execution to the given ACIR opcode without executing the opcodes
+    /// in between, abandoning the Brillig call being executed, if any. The ACVM
+    /// cannot restore its state, so jumping backwards re-runs opcodes over the
+    /// witnesses already solved, and jumping forward leaves the witnesses of the
+    /// skipped opcodes unassigned: keeping them consistent is up to the user.
+    /// Rewinding past the jump replays the program without it.
+    pub(super) fn jump_to(&mut self, location: OpcodeLocation) -> Result<(), String> {
+        let OpcodeLocation::Acir(acir_index) = location else {
+            return Err(String::from("Jumping into unconstrained code is not supported"));
+        };
+        self.acvm.set_instruction_pointer(acir_index).map_err(|error| error.to_string())?;
+        self.brillig_solver = None;
+        self.variable_addresses.clear();
+        self.brillig_steps = 0;
+        self.pending_call = None;
+        Ok(())
+    }
+
     /// Returns the callstack in source code locations for the currently
     /// executing opcode. This can be `None` if the execution finished (and
     /// `get_current_opcode_location()` returns `None`) or if the opcode is not
@@ -231,6 +759,12 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         }
     }
 
+    /// Returns the number of addresses the program spans, Brillig opcodes
+    /// included
+    pub(super) fn get_address_count(&self) -> usize {
+        *self.acir_opcode_addresses.last().unwrap_or(&0)
+    }
+
     pub fn address_to_opcode_location(&self, address: usize) -> Option<OpcodeLocation> {
         if address >= *self.acir_opcode_addresses.last().unwrap_or(&0) {
             return None;
@@ -272,13 +806,46 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     }
 
     fn step_brillig_opcode(&mut self) -> DebugCommandResult {
+        let black_box = match self.get_current_brillig_opcode() {
+            Some(BrilligOpcode::BlackBox(op)) => {
+                self.get_current_opcode_location().map(|location| {
+                    let (name, inputs, outputs) = brillig_black_box_operands(op);
+                    let memory = self.get_brillig_memory().unwrap_or_default();
+                    (location, name, hash_brillig_operands(memory, &inputs), outputs)
+                })
+            }
+            _ => None,
+        };
         let Some(mut solver) = self.brillig_solver.take() else {
             unreachable!("Missing Brillig solver");
         };
-        match solver.step() {
+        self.exceeded_brillig_limit = None;
+        self.brillig_steps += 1;
+        let start = Instant::now();
+        let status = solver.step();
+        if let Some((location, name, inputs_hash, outputs)) = black_box {
+            let duration = start.elapsed();
+            let outputs_hash = hash_brillig_operands(solver.get_memory(), &outputs);
+            self.record_timeline_event(
+                Some(location),
+                TimelineEventKind::BlackBox { name, duration_micros: duration.as_micros() as u64 },
+            );
+            self.blackbox_calls.push(BlackBoxCall {
+                location,
+                name,
+                duration,
+                inputs_hash: Some(inputs_hash),
+                outputs_hash: Some(outputs_hash),
+            });
+        }
+        match status {
             Ok(BrilligSolverStatus::InProgress) => {
+                let exceeded_limit = self.check_brillig_limits(&solver);
                 self.brillig_solver = Some(solver);
-                if self.breakpoint_reached() {
+                if let Some(message) = exceeded_limit {
+                    return self.fail_on_brillig_limit(message);
+                }
+                if self.breakpoint_reached() || self.print_breakpoint_reached() {
                     DebugCommandResult::BreakpointReached(
                         self.get_current_opcode_location()
                             .expect("Breakpoint reached but we have no location"),
@@ -288,6 +855,8 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
                 }
             }
             Ok(BrilligSolverStatus::Finished) => {
+                // The memory of the call is gone, and later calls reuse its addresses
+                self.variable_addresses.clear();
                 let status = self.acvm.finish_brillig_with_solver(solver);
                 self.handle_acvm_status(status)
             }
@@ -295,10 +864,13 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
                 self.brillig_solver = Some(solver);
                 self.handle_foreign_call(foreign_call)
             }
-            Err(err) => DebugCommandResult::Error(NargoError::ExecutionError(
-                // TODO: debugger does not handle multiple acir calls
-                ExecutionError::SolvingError(err, None),
-            )),
+            Err(err) => {
+                self.variable_addresses.clear();
+                DebugCommandResult::Error(NargoError::ExecutionError(
+                    // TODO: debugger does not handle multiple acir calls
+                    ExecutionError::SolvingError(err, None),
+                ))
+            }
         }
     }
 
@@ -306,7 +878,43 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         &mut self,
         foreign_call: ForeignCallWaitInfo<FieldElement>,
     ) -> DebugCommandResult {
-        let foreign_call_result = self.foreign_call_executor.execute(&foreign_call);
+        self.record_variable_address(&foreign_call);
+        let debug_call = DebugForeignCall::lookup(&foreign_call.function);
+        match debug_call {
+            Some(DebugForeignCall::FnExit) => self.record_function_exit(),
+            None => self.record_foreign_call_checkpoint(&foreign_call.function),
+            _ => (),
+        }
+        if debug_call.is_none() {
+            if let Err(error) = self.check_oracle_inputs(&foreign_call) {
+                return DebugCommandResult::Error(error.into());
+            }
+        }
+        let foreign_call_result = self.execute_foreign_call(&foreign_call);
+        let mut function_breakpoint_reached = false;
+        if let (Some(DebugForeignCall::FnEnter), Ok(_)) = (&debug_call, &foreign_call_result) {
+            self.record_function_entry();
+            function_breakpoint_reached = self.function_breakpoint_reached();
+        }
+        let foreign_call_result = foreign_call_result.and_then(|result| {
+            if self.validate_foreign_calls && debug_call.is_none() {
+                self.validate_foreign_call_result(&foreign_call.function, &result)?;
+            }
+            if debug_call.is_none() {
+                self.check_oracle_outputs(&foreign_call, &result)?;
+            }
+            Ok(result)
+        });
+        if let (true, None, Ok(result)) =
+            (self.check_determinism, &debug_call, &foreign_call_result)
+        {
+            self.pending_call = Some(TracedCall {
+                name: foreign_call.function.clone(),
+                is_black_box: false,
+                inputs_hash: hash_foreign_call_params(&foreign_call.inputs),
+                outputs_hash: hash_foreign_call_params(&result.values),
+            });
+        }
         match foreign_call_result {
             Ok(foreign_call_result) => {
                 if let Some(mut solver) = self.brillig_solver.take() {
@@ -315,6 +923,12 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
                 } else {
                     self.acvm.resolve_pending_foreign_call(foreign_call_result);
                 }
+                if function_breakpoint_reached {
+                    return DebugCommandResult::BreakpointReached(
+                        self.get_current_opcode_location()
+                            .expect("Breakpoint reached but we have no location"),
+                    );
+                }
                 // TODO: should we retry executing the opcode somehow in this case?
                 DebugCommandResult::Ok
             }
@@ -322,6 +936,293 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         }
     }
 
+    /// Executes a foreign call, applying the policy set for the oracle if
+    /// its resolver fails
+    fn execute_foreign_call(
+        &mut self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<ForeignCallResult<FieldElement>, ForeignCallError> {
+        let name = &foreign_call.function;
+        let mut result = self.foreign_call_executor.execute(foreign_call);
+        match self.oracle_policies.get(name).copied() {
+            Some(OraclePolicy::Retry(retries)) => {
+                let mut delay = ORACLE_RETRY_INITIAL_DELAY;
+                for attempt in 1..=retries {
+                    let Err(error) = &result else {
+                        break;
+                    };
+                    self.debug_warnings.push(format!(
+                        "oracle `{name}` failed ({error}); retrying in {}ms ({attempt} of {retries})",
+                        delay.as_millis()
+                    ));
+                    std::thread::sleep(delay);
+                    delay = (delay * 2).min(ORACLE_RETRY_MAX_DELAY);
+                    result = self.foreign_call_executor.execute(foreign_call);
+                }
+            }
+            Some(OraclePolicy::Skip) => {
+                if let Err(error) = &result {
+                    match self.default_foreign_call_result() {
+                        Some(default_result) => {
+                            self.debug_warnings.push(format!(
+                                "oracle `{name}` failed ({error}); skipped, using default values"
+                            ));
+                            result = Ok(default_result);
+                        }
+                        None => self.debug_warnings.push(format!(
+                            "oracle `{name}` failed ({error}); can't skip it as the shape of its outputs is unknown"
+                        )),
+                    }
+                }
+            }
+            None => (),
+        }
+        result
+    }
+
+    /// Zeroed outputs of the shape expected by the Brillig opcode waiting on
+    /// a foreign call, or `None` if that opcode isn't known
+    fn default_foreign_call_result(&self) -> Option<ForeignCallResult<FieldElement>> {
+        let Some(BrilligOpcode::ForeignCall { destination_value_types, .. }) =
+            self.get_current_brillig_opcode()
+        else {
+            return None;
+        };
+        Some(ForeignCallResult {
+            values: destination_value_types.iter().map(default_foreign_call_output).collect(),
+        })
+    }
+
+    /// Sets what to do when the resolver of the oracle `name` fails, or
+    /// removes its policy so that the failure stops execution
+    pub(super) fn set_oracle_policy(&mut self, name: &str, policy: Option<OraclePolicy>) {
+        match policy {
+            Some(policy) => self.oracle_policies.insert(name.to_string(), policy),
+            None => self.oracle_policies.remove(name),
+        };
+    }
+
+    pub(super) fn set_validate_foreign_calls(&mut self, validate: bool) {
+        self.validate_foreign_calls = validate;
+    }
+
+    pub(super) fn set_break_on_print(&mut self, enabled: bool) {
+        self.break_on_print = enabled;
+    }
+
+    pub(super) fn is_break_on_print(&self) -> bool {
+        self.break_on_print
+    }
+
+    fn print_breakpoint_reached(&self) -> bool {
+        self.break_on_print
+            && matches!(
+                self.get_current_brillig_opcode(),
+                Some(BrilligOpcode::ForeignCall { function, .. }) if function == PRINT_ORACLE
+            )
+    }
+
+    /// Returns the foreign call the current Brillig opcode is about to make,
+    /// with its arguments as they are in memory now
+    pub(super) fn get_pending_foreign_call(&self) -> Option<ForeignCallWaitInfo<FieldElement>> {
+        let Some(BrilligOpcode::ForeignCall { function, inputs, input_value_types, .. }) =
+            self.get_current_brillig_opcode()
+        else {
+            return None;
+        };
+        let memory = self.get_brillig_memory()?;
+        Some(ForeignCallWaitInfo {
+            function: function.clone(),
+            inputs: read_foreign_call_inputs(memory, inputs, input_value_types)?,
+        })
+    }
+
+    pub(super) fn set_oracle_schema(&mut self, schema: OracleSchema) {
+        self.oracle_schema = schema;
+    }
+
+    pub(super) fn get_oracle_schema(&self) -> &OracleSchema {
+        &self.oracle_schema
+    }
+
+    /// Renders a foreign call with its arguments named as in the oracle
+    /// schema, or None if the oracle is not declared in it
+    pub(super) fn format_oracle_call(
+        &self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Option<String> {
+        let signature = self.oracle_schema.get(&foreign_call.function)?;
+        Some(signature.format_call(&foreign_call.function, &foreign_call.inputs))
+    }
+
+    /// Checks the arguments of a call to an oracle declared in the schema
+    fn check_oracle_inputs(
+        &self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+    ) -> Result<(), ForeignCallError> {
+        let name = &foreign_call.function;
+        let Some(signature) = self.oracle_schema.get(name) else {
+            return Ok(());
+        };
+        signature.check_inputs(&foreign_call.inputs).map_err(|reason| {
+            let call = signature.format_call(name, &foreign_call.inputs);
+            ForeignCallError::InvalidInputs {
+                name: name.clone(),
+                reason: format!("{reason} (called as {call})"),
+            }
+        })
+    }
+
+    /// Checks the values returned by an oracle declared in the schema
+    fn check_oracle_outputs(
+        &self,
+        foreign_call: &ForeignCallWaitInfo<FieldElement>,
+        result: &ForeignCallResult<FieldElement>,
+    ) -> Result<(), ForeignCallError> {
+        let name = &foreign_call.function;
+        let Some(signature) = self.oracle_schema.get(name) else {
+            return Ok(());
+        };
+        signature.check_outputs(&result.values).map_err(|reason| {
+            let call = signature.format_call(name, &foreign_call.inputs);
+            ForeignCallError::InvalidOutputs {
+                name: name.clone(),
+                reason: format!("{reason} (called as {call})"),
+            }
+        })
+    }
+
+    /// Checks the result of a foreign call against the output types of the
+    /// Brillig opcode waiting for it. Calls made while the ACVM runs a Brillig
+    /// function on its own are not checked, since that opcode isn't known.
+    fn validate_foreign_call_result(
+        &self,
+        function: &str,
+        result: &ForeignCallResult<FieldElement>,
+    ) -> Result<(), ForeignCallError> {
+        let Some(BrilligOpcode::ForeignCall { destination_value_types, .. }) =
+            self.get_current_brillig_opcode()
+        else {
+            return Ok(());
+        };
+        validate_foreign_call_outputs(&result.values, destination_value_types).map_err(|reason| {
+            let location = self
+                .get_current_source_location()
+                .and_then(|locations| locations.last().copied())
+                .map(|location| {
+                    format_location_path(
+                        self.debug_artifact,
+                        &PathSubstitutions::default(),
+                        location,
+                    )
+                });
+            let reason = match location {
+                Some(location) => format!("{reason} (called at {location})"),
+                None => reason,
+            };
+            ForeignCallError::InvalidOutputs { name: function.to_string(), reason }
+        })
+    }
+
+    fn record_function_entry(&mut self) {
+        let Some(frame) = self.foreign_call_executor.current_stack_frame() else {
+            return;
+        };
+        let name = frame.function_name.to_string();
+        self.function_calls.entry(name.clone()).or_default().calls += 1;
+        self.entered_functions.push((name, self.executed_opcodes));
+    }
+
+    /// Checks whether the function just entered has a function breakpoint
+    fn function_breakpoint_reached(&self) -> bool {
+        self.foreign_call_executor.current_stack_frame().map_or(false, |frame| {
+            self.function_breakpoints.contains(&frame.function_name.to_string())
+        })
+    }
+
+    /// Returns the names of the functions of the program, as found in the
+    /// debug symbols of the artifact. Only functions instrumented for
+    /// debugging are known.
+    pub(super) fn get_function_names(&self) -> BTreeSet<String> {
+        self.debug_artifact
+            .debug_symbols
+            .iter()
+            .flat_map(|debug_info| debug_info.functions.values())
+            .map(|function| function.name.clone())
+            .collect()
+    }
+
+    /// Sets the functions on whose entry execution stops, replacing any set
+    /// before
+    pub(super) fn set_function_breakpoints(&mut self, names: BTreeSet<String>) {
+        self.function_breakpoints = names;
+    }
+
+    fn record_function_exit(&mut self) {
+        if let Some((name, entered_at)) = self.entered_functions.pop() {
+            self.function_calls.entry(name).or_default().opcodes +=
+                self.executed_opcodes - entered_at;
+        }
+    }
+
+    /// Returns the calls made to each function of the program so far. The
+    /// opcodes of the functions still executing are counted up to this point.
+    pub(super) fn get_function_calls(&self) -> BTreeMap<String, FunctionCalls> {
+        let mut function_calls = self.function_calls.clone();
+        for (name, entered_at) in &self.entered_functions {
+            function_calls.entry(name.clone()).or_default().opcodes +=
+                self.executed_opcodes - entered_at;
+        }
+        function_calls
+    }
+
+    /// Remembers the Brillig memory address holding the value of a variable
+    /// assignment, and the value itself, so that `set_variable` can later
+    /// overwrite it in place if the cell was not reused in the meantime.
+    fn record_variable_address(&mut self, foreign_call: &ForeignCallWaitInfo<FieldElement>) {
+        let Some(DebugForeignCall::VarAssign) = DebugForeignCall::lookup(&foreign_call.function)
+        else {
+            return;
+        };
+        let Some(ForeignCallParam::Single(var_id_value)) = foreign_call.inputs.first() else {
+            return;
+        };
+        let var_id = DebugVarId(var_id_value.to_u128() as u32);
+        let Some(ForeignCallParam::Single(value)) = foreign_call.inputs.get(1) else {
+            self.variable_addresses.remove(&var_id);
+            return;
+        };
+
+        match self.get_current_brillig_opcode() {
+            Some(BrilligOpcode::ForeignCall { inputs, .. }) => match inputs.get(1) {
+                Some(ValueOrArray::MemoryAddress(address)) => {
+                    self.variable_addresses.insert(var_id, (address.to_usize(), *value));
+                }
+                _ => {
+                    self.variable_addresses.remove(&var_id);
+                }
+            },
+            _ => {
+                self.variable_addresses.remove(&var_id);
+            }
+        }
+    }
+
+    fn get_current_brillig_opcode(&self) -> Option<&'a BrilligOpcode<FieldElement>> {
+        let OpcodeLocation::Brillig { acir_index, brillig_index } =
+            self.get_current_opcode_location()?
+        else {
+            return None;
+        };
+        let circuit: &'a Circuit<FieldElement> = self.circuit;
+        let unconstrained_functions: &'a [BrilligBytecode<FieldElement>] =
+            self.unconstrained_functions;
+        let Opcode::BrilligCall { id, .. } = &circuit.opcodes[acir_index] else {
+            return None;
+        };
+        unconstrained_functions[*id as usize].bytecode.get(brillig_index)
+    }
+
     fn handle_acvm_status(&mut self, status: ACVMStatus<FieldElement>) -> DebugCommandResult {
         if let ACVMStatus::RequiresForeignCall(foreign_call) = status {
             return self.handle_foreign_call(foreign_call);
@@ -352,47 +1253,314 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         }
     }
 
-    pub(super) fn step_into_opcode(&mut self) -> DebugCommandResult {
-        if self.brillig_solver.is_some() {
-            return self.step_brillig_opcode();
-        }
-
-        match self.acvm.step_into_brillig() {
-            StepResult::IntoBrillig(solver) => {
-                self.brillig_solver = Some(solver);
-                self.step_brillig_opcode()
-            }
-            StepResult::Status(status) => self.handle_acvm_status(status),
+    /// Keeps a journal of the last `capacity` opcodes executed and the
+    /// witnesses they wrote
+    pub(super) fn enable_journal(&mut self, capacity: usize) {
+        let mut opcode_outputs: HashMap<usize, Vec<Witness>> = HashMap::new();
+        for (witness, (acir_index, _)) in build_witness_producers(self.circuit) {
+            opcode_outputs.entry(acir_index).or_default().push(witness);
         }
+        self.opcode_outputs = opcode_outputs;
+        self.journal_capacity = capacity;
+        self.journal.truncate(capacity);
     }
 
-    fn get_current_acir_index(&self) -> Option<usize> {
-        self.get_current_opcode_location().map(|opcode_location| match opcode_location {
-            OpcodeLocation::Acir(acir_index) => acir_index,
-            OpcodeLocation::Brillig { acir_index, .. } => acir_index,
-        })
+    pub(super) fn get_journal(&self) -> impl Iterator<Item = &JournalEntry> {
+        self.journal.iter()
     }
 
-    fn step_out_of_brillig_opcode(&mut self) -> DebugCommandResult {
-        let Some(start_acir_index) = self.get_current_acir_index() else {
-            return DebugCommandResult::Done;
-        };
-        loop {
-            let result = self.step_into_opcode();
-            if !matches!(result, DebugCommandResult::Ok) {
-                return result;
+    /// Runs `step`, recording the opcode it executes in the journal and in the
+    /// evaluations of the asserts
+    fn journal_step(
+        &mut self,
+        step: impl FnOnce(&mut Self) -> DebugCommandResult,
+    ) -> DebugCommandResult {
+        let location = self.get_current_opcode_location();
+        if let Some(location) = location.filter(|_| self.check_determinism) {
+            if self.check_trace_location(location) {
+                return DebugCommandResult::BreakpointReached(location);
             }
-            let new_acir_index = self.get_current_acir_index().unwrap();
-            if new_acir_index != start_acir_index {
-                return DebugCommandResult::Ok;
+        }
+        self.executed_opcodes += 1;
+        let assert_id = location.and_then(|location| self.assert_ids.get(&location).copied());
+        let result = match location.filter(|_| self.journal_capacity > 0) {
+            Some(location) => self.step_journaled(location, step),
+            None => step(self),
+        };
+        self.record_assert_evaluation(location, assert_id, &result);
+        match location.filter(|_| self.check_determinism) {
+            Some(location) if self.record_trace_step(location) => {
+                match (&result, self.get_current_opcode_location()) {
+                    (DebugCommandResult::Ok, Some(current)) => {
+                        DebugCommandResult::BreakpointReached(current)
+                    }
+                    _ => result,
+                }
             }
+            _ => result,
         }
     }
 
-    pub(super) fn is_executing_brillig(&self) -> bool {
-        if self.brillig_solver.is_some() {
-            return true;
-        }
+    /// Sets the flag which, once raised, stops the commands taking several
+    /// steps after the step being executed. The flag is never cleared by the
+    /// context.
+    pub(super) fn set_interrupt_flag(&mut self, interrupt: &'a AtomicBool) {
+        self.interrupt = Some(interrupt);
+    }
+
+    fn is_interrupted(&self) -> bool {
+        self.interrupt.map_or(false, |interrupt| interrupt.load(Ordering::SeqCst))
+    }
+
+    /// Enables or disables comparing each run of the program with the
+    /// previous one after a restart
+    pub(super) fn set_check_determinism(&mut self, check: bool) {
+        self.check_determinism = check;
+        self.trace.clear();
+        self.reference_trace.clear();
+        self.pending_call = None;
+    }
+
+    pub(super) fn is_checking_determinism(&self) -> bool {
+        self.check_determinism
+    }
+
+    /// Returns the divergence from the previous run found since the last
+    /// call, if any. Only the first divergence of a run is reported.
+    pub(super) fn take_divergence(&mut self) -> Option<Divergence> {
+        self.divergence.take()
+    }
+
+    fn reference_step(&self) -> Option<&TraceStep> {
+        if self.divergence_found {
+            return None;
+        }
+        self.reference_trace.get(self.trace.len())
+    }
+
+    fn last_traced_call(&self) -> Option<TracedCall> {
+        self.trace.iter().rev().find_map(|step| step.call.clone())
+    }
+
+    /// Checks that the step about to be executed is at the same opcode as in
+    /// the reference run, returning true if it diverges
+    fn check_trace_location(&mut self, location: OpcodeLocation) -> bool {
+        let Some(expected) = self.reference_step().filter(|step| step.location != location) else {
+            return false;
+        };
+        let expected = expected.clone();
+        self.divergence_found = true;
+        self.divergence = Some(Divergence {
+            step: self.trace.len(),
+            expected,
+            actual: TraceStep { location, call: None },
+            last_call: self.last_traced_call(),
+        });
+        true
+    }
+
+    /// Records the step just executed in the trace, returning true if its
+    /// call differs from the one of the reference run
+    fn record_trace_step(&mut self, location: OpcodeLocation) -> bool {
+        let step = TraceStep { location, call: self.pending_call.take() };
+        let expected = self.reference_step().filter(|expected| expected.call != step.call).cloned();
+        let diverges = expected.is_some();
+        if let Some(expected) = expected {
+            self.divergence_found = true;
+            self.divergence = Some(Divergence {
+                step: self.trace.len(),
+                expected,
+                actual: step.clone(),
+                last_call: self.last_traced_call(),
+            });
+        }
+        self.trace.push(step);
+        diverges
+    }
+
+    fn step_journaled(
+        &mut self,
+        location: OpcodeLocation,
+        step: impl FnOnce(&mut Self) -> DebugCommandResult,
+    ) -> DebugCommandResult {
+        let acir_index = self.acvm.instruction_pointer();
+        let result = step(self);
+
+        let written = if self.acvm.instruction_pointer() != acir_index {
+            let witness_map = self.acvm.witness_map();
+            self.opcode_outputs
+                .get(&acir_index)
+                .into_iter()
+                .flatten()
+                .filter_map(|witness| witness_map.get(witness).map(|value| (*witness, *value)))
+                .collect()
+        } else {
+            vec![]
+        };
+        if self.journal.len() == self.journal_capacity {
+            self.journal.pop_front();
+        }
+        self.journal.push_back(JournalEntry { location, written });
+        result
+    }
+
+    /// Counts an evaluation of the assert an opcode was compiled from when
+    /// execution enters it, and marks the assert as failed if the opcode failed
+    fn record_assert_evaluation(
+        &mut self,
+        location: Option<OpcodeLocation>,
+        assert_id: Option<usize>,
+        result: &DebugCommandResult,
+    ) {
+        let previous = std::mem::replace(&mut self.last_assert, assert_id);
+        let Some(id) = assert_id else {
+            return;
+        };
+        let outcome = self.assert_outcomes.entry(id).or_default();
+        if previous != Some(id) {
+            outcome.evaluations += 1;
+            self.record_timeline_event(
+                location,
+                TimelineEventKind::Assert { assert_id: id, failed: false },
+            );
+        }
+        if matches!(result, DebugCommandResult::Error(_)) {
+            self.assert_outcomes.entry(id).or_default().failed = true;
+            let evaluation =
+                self.timeline.events_mut().rev().find_map(|event| match &mut event.kind {
+                    TimelineEventKind::Assert { assert_id, failed } if *assert_id == id => {
+                        Some(failed)
+                    }
+                    _ => None,
+                });
+            if let Some(failed) = evaluation {
+                *failed = true;
+            }
+        }
+    }
+
+    pub(super) fn get_asserts(&self) -> &[AssertInfo] {
+        &self.asserts
+    }
+
+    pub(super) fn get_assert(&self, id: usize) -> Option<&AssertInfo> {
+        self.asserts.iter().find(|assert| assert.id == id)
+    }
+
+    pub(super) fn get_assert_outcome(&self, id: usize) -> Option<&AssertOutcome> {
+        self.assert_outcomes.get(&id)
+    }
+
+    pub(super) fn step_into_opcode(&mut self) -> DebugCommandResult {
+        self.journal_step(Self::step_into_opcode_unjournaled)
+    }
+
+    fn step_into_opcode_unjournaled(&mut self) -> DebugCommandResult {
+        if self.brillig_solver.is_some() {
+            return self.step_brillig_opcode();
+        }
+
+        match self.solve_acir_opcode(|acvm| acvm.step_into_brillig()) {
+            StepResult::IntoBrillig(solver) => {
+                self.brillig_solver = Some(solver);
+                self.variable_addresses.clear();
+                self.brillig_steps = 0;
+                self.step_brillig_opcode()
+            }
+            StepResult::Status(status) => self.handle_acvm_status(status),
+        }
+    }
+
+    /// Runs `solve` on the ACVM, recording the current opcode in the black box
+    /// call log if it is a black box function call
+    fn solve_acir_opcode<T>(
+        &mut self,
+        solve: impl FnOnce(&mut ACVM<'a, FieldElement, B>) -> T,
+    ) -> T {
+        let circuit: &'a Circuit<FieldElement> = self.circuit;
+        let call = match self.get_current_opcode_location() {
+            Some(OpcodeLocation::Acir(acir_index)) => match &circuit.opcodes[acir_index] {
+                Opcode::BlackBoxFuncCall(call) => Some((acir_index, call)),
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some((acir_index, call)) = call else {
+            return solve(&mut self.acvm);
+        };
+
+        let inputs = call.get_inputs_vec().into_iter().map(|input| input.witness);
+        let inputs_hash = hash_witness_values(self.acvm.witness_map(), inputs);
+        let start = Instant::now();
+        let result = solve(&mut self.acvm);
+        let duration = start.elapsed();
+        let outputs_hash = hash_witness_values(self.acvm.witness_map(), call.get_outputs_vec());
+
+        let name = call.get_black_box_func().name();
+        self.record_timeline_event(
+            Some(OpcodeLocation::Acir(acir_index)),
+            TimelineEventKind::BlackBox { name, duration_micros: duration.as_micros() as u64 },
+        );
+        self.blackbox_calls.push(BlackBoxCall {
+            location: OpcodeLocation::Acir(acir_index),
+            name,
+            duration,
+            inputs_hash: Some(inputs_hash),
+            outputs_hash: Some(outputs_hash),
+        });
+        if self.check_determinism {
+            self.pending_call = Some(TracedCall {
+                name: name.to_string(),
+                is_black_box: true,
+                inputs_hash,
+                outputs_hash,
+            });
+        }
+        result
+    }
+
+    pub(super) fn get_blackbox_calls(&self) -> &[BlackBoxCall] {
+        &self.blackbox_calls
+    }
+
+    fn record_timeline_event(&mut self, location: Option<OpcodeLocation>, kind: TimelineEventKind) {
+        self.timeline.push(TimelineEvent { step: self.executed_opcodes, location, kind });
+    }
+
+    /// Returns the foreign calls, black box calls and assert evaluations of
+    /// the current run, in the order they happened, up to the timeline's
+    /// capacity
+    pub(super) fn get_timeline(&self) -> &Timeline {
+        &self.timeline
+    }
+
+    fn get_current_acir_index(&self) -> Option<usize> {
+        self.get_current_opcode_location().map(|opcode_location| match opcode_location {
+            OpcodeLocation::Acir(acir_index) => acir_index,
+            OpcodeLocation::Brillig { acir_index, .. } => acir_index,
+        })
+    }
+
+    fn step_out_of_brillig_opcode(&mut self) -> DebugCommandResult {
+        let Some(start_acir_index) = self.get_current_acir_index() else {
+            return DebugCommandResult::Done;
+        };
+        loop {
+            let result = self.step_into_opcode();
+            if !matches!(result, DebugCommandResult::Ok) {
+                return result;
+            }
+            let new_acir_index = self.get_current_acir_index().unwrap();
+            if new_acir_index != start_acir_index || self.is_interrupted() {
+                return DebugCommandResult::Ok;
+            }
+        }
+    }
+
+    pub(super) fn is_executing_brillig(&self) -> bool {
+        if self.brillig_solver.is_some() {
+            return true;
+        }
 
         match self.get_current_opcode_location() {
             Some(OpcodeLocation::Brillig { .. }) => true,
@@ -403,12 +1571,235 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         }
     }
 
+    /// Returns how many steps were taken in the Brillig block being executed.
+    /// Along with the current opcode location, this identifies the point of
+    /// execution the session is at.
+    pub(super) fn get_brillig_steps(&self) -> usize {
+        self.brillig_steps
+    }
+
+    /// Executes from the current point until reaching `location` once
+    /// `brillig_steps` steps were taken in its Brillig block, or until
+    /// execution ends if no location is given. Reaching the same point as a
+    /// previous run requires the foreign calls along the way to be
+    /// deterministic.
+    ///
+    /// Returns the result of the last step taken. Breakpoints do not stop the
+    /// replay, and it also stops without error when the location is passed.
+    pub(super) fn replay_to(
+        &mut self,
+        location: Option<OpcodeLocation>,
+        brillig_steps: usize,
+    ) -> DebugCommandResult {
+        let target_acir_index = location.map(|location| match location {
+            OpcodeLocation::Acir(acir_index) => acir_index,
+            OpcodeLocation::Brillig { acir_index, .. } => acir_index,
+        });
+        loop {
+            let Some(current) = self.get_current_opcode_location() else {
+                return DebugCommandResult::Done;
+            };
+            if Some(current) == location
+                && (matches!(current, OpcodeLocation::Acir(_))
+                    || self.brillig_steps == brillig_steps)
+            {
+                return DebugCommandResult::Ok;
+            }
+            let current_acir_index = self.get_current_acir_index().unwrap_or_default();
+            let result = match target_acir_index {
+                Some(target) if current_acir_index > target => return DebugCommandResult::Ok,
+                Some(target) if current_acir_index == target => self.step_into_opcode(),
+                _ => self.step_acir_opcode(),
+            };
+            if !matches!(result, DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_))
+                || self.is_interrupted()
+            {
+                return result;
+            }
+        }
+    }
+
+    /// Counts a foreign call of the program and records the point of
+    /// execution right before it. Foreign calls are handled within the step
+    /// executing them, which is already counted.
+    fn record_foreign_call_checkpoint(&mut self, function: &str) {
+        let checkpoint = ForeignCallCheckpoint {
+            function: function.to_string(),
+            executed_opcodes: self.executed_opcodes.saturating_sub(1),
+        };
+        self.record_timeline_event(
+            self.get_current_opcode_location(),
+            TimelineEventKind::ForeignCall { function: function.to_string() },
+        );
+        // Replays reach the checkpoints of the run being rewound again
+        match self.foreign_call_checkpoints.get_mut(self.foreign_calls) {
+            Some(recorded) => *recorded = checkpoint,
+            None => self.foreign_call_checkpoints.push(checkpoint),
+        }
+        self.foreign_calls += 1;
+    }
+
+    /// Returns the checkpoint before each foreign call of the program made
+    /// in the current run, including the ones past the current point if
+    /// execution was rewound
+    pub(super) fn get_foreign_call_checkpoints(&self) -> &[ForeignCallCheckpoint] {
+        &self.foreign_call_checkpoints
+    }
+
+    /// Moves execution back (or forward, if it was rewound past it) to the
+    /// point right before the `call`th foreign call of the program, counting
+    /// from 1
+    pub(super) fn rewind_to_foreign_call(
+        &mut self,
+        initial_witness: WitnessMap<FieldElement>,
+        call: usize,
+    ) -> Result<DebugCommandResult, String> {
+        let Some(checkpoint) = call.checked_sub(1).and_then(|index| {
+            self.foreign_call_checkpoints.get(index).map(|checkpoint| checkpoint.executed_opcodes)
+        }) else {
+            return Err(format!(
+                "No foreign call #{call}; {} foreign call(s) made so far",
+                self.foreign_call_checkpoints.len()
+            ));
+        };
+        Ok(self.rewind_to(initial_witness, checkpoint))
+    }
+
+    /// Restarts execution to replay the current run, keeping its foreign call
+    /// checkpoints, which the replay reaches again. Returns what the replay
+    /// must not change, to restore with `finish_replay` once it is done.
+    fn restart_for_replay(&mut self, initial_witness: WitnessMap<FieldElement>) -> ReplayState {
+        let checkpoints = std::mem::take(&mut self.foreign_call_checkpoints);
+        let breakpoint_snapshots = std::mem::take(&mut self.breakpoint_snapshots);
+        let run = self.run;
+        self.restart(initial_witness);
+        self.foreign_call_checkpoints = checkpoints;
+        self.run = run;
+        let ignore_counts = self
+            .breakpoints
+            .values()
+            .map(|breakpoint| (breakpoint.id, breakpoint.ignore_count))
+            .collect();
+        ReplayState { ignore_counts, breakpoint_snapshots }
+    }
+
+    /// Puts back the ignore counts saved before a replay, which passes the
+    /// breakpoints again but must not consume their ignore counts, or stepping
+    /// back would change where execution stops later. Snapshots taken in the
+    /// replay are dropped, as the run already took them.
+    fn finish_replay(&mut self, state: ReplayState) {
+        for (id, ignore_count) in state.ignore_counts {
+            if let Some(breakpoint) = self.breakpoints.get_mut(&id) {
+                breakpoint.ignore_count = ignore_count;
+            }
+        }
+        self.breakpoint_snapshots = state.breakpoint_snapshots;
+    }
+
+    /// Moves execution back to the point reached after `executed_opcodes`
+    /// steps. Since the state of the VMs cannot be copied, points of
+    /// execution serve as snapshots, which are restored by replaying the
+    /// program from the start with the same inputs. This requires the foreign
+    /// calls along the way to be deterministic. Breakpoint ignore counts are
+    /// left as they were before rewinding.
+    ///
+    /// Returns the result of the last step taken in the replay.
+    pub(super) fn rewind_to(
+        &mut self,
+        initial_witness: WitnessMap<FieldElement>,
+        executed_opcodes: usize,
+    ) -> DebugCommandResult {
+        let replay_state = self.restart_for_replay(initial_witness);
+        let mut result = DebugCommandResult::Ok;
+        while self.executed_opcodes < executed_opcodes {
+            result = self.step_into_opcode();
+            if !matches!(result, DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_))
+                || self.is_interrupted()
+            {
+                break;
+            }
+        }
+        self.finish_replay(replay_state);
+        // Logpoints hit on the way were already shown when first executed
+        self.log_output.clear();
+        result
+    }
+
+    /// Replays the program from the start up to the current point, returning
+    /// the points before it at which `select` holds. `select` also receives
+    /// the result of the step reaching each point, which is `Ok` for the
+    /// start of execution. Execution is left at the point the replay stopped.
+    fn find_previous_points(
+        &mut self,
+        initial_witness: WitnessMap<FieldElement>,
+        mut select: impl FnMut(&Self, &DebugCommandResult) -> bool,
+    ) -> Vec<usize> {
+        let current = self.executed_opcodes;
+        let replay_state = self.restart_for_replay(initial_witness);
+        let mut points = vec![];
+        let mut result = DebugCommandResult::Ok;
+        while self.executed_opcodes < current {
+            if select(self, &result) {
+                points.push(self.executed_opcodes);
+            }
+            result = self.step_into_opcode();
+            if !matches!(result, DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_))
+                || self.is_interrupted()
+            {
+                break;
+            }
+        }
+        self.finish_replay(replay_state);
+        points
+    }
+
+    /// Steps back to the opcode executed before the current one
+    pub(super) fn step_back_opcode(
+        &mut self,
+        initial_witness: WitnessMap<FieldElement>,
+    ) -> DebugCommandResult {
+        let target = self.executed_opcodes.saturating_sub(1);
+        self.rewind_to(initial_witness, target)
+    }
+
+    /// Steps back to the first opcode of the previous source location, the
+    /// reverse of `next_into`
+    pub(super) fn step_back_into(
+        &mut self,
+        initial_witness: WitnessMap<FieldElement>,
+    ) -> DebugCommandResult {
+        let current_location = self.get_current_source_location();
+        let mut previous_location = None;
+        let points = self.find_previous_points(initial_witness.clone(), |context, _| {
+            let location = context.get_current_source_location();
+            let is_start =
+                location.is_some() && location != current_location && location != previous_location;
+            previous_location = location;
+            is_start
+        });
+        self.rewind_to(initial_witness, points.last().copied().unwrap_or_default())
+    }
+
+    /// Moves execution back to the last point before the current one at
+    /// which a breakpoint stopped it, or to the start if there is none
+    pub(super) fn reverse_continue(
+        &mut self,
+        initial_witness: WitnessMap<FieldElement>,
+    ) -> DebugCommandResult {
+        let points = self.find_previous_points(initial_witness.clone(), |_, result| {
+            matches!(result, DebugCommandResult::BreakpointReached(_))
+        });
+        self.rewind_to(initial_witness, points.last().copied().unwrap_or_default())
+    }
+
     pub(super) fn step_acir_opcode(&mut self) -> DebugCommandResult {
         if self.is_executing_brillig() {
             self.step_out_of_brillig_opcode()
         } else {
-            let status = self.acvm.solve_opcode();
-            self.handle_acvm_status(status)
+            self.journal_step(|context| {
+                let status = context.solve_acir_opcode(|acvm| acvm.solve_opcode());
+                context.handle_acvm_status(status)
+            })
         }
     }
 
@@ -421,7 +1812,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
                 return result;
             }
             let new_location = self.get_current_source_location();
-            if new_location.is_some() && new_location != start_location {
+            if (new_location.is_some() && new_location != start_location) || self.is_interrupted() {
                 return DebugCommandResult::Ok;
             }
         }
@@ -437,7 +1828,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
                 return result;
             }
             let new_call_stack = self.get_source_call_stack();
-            if new_call_stack.len() <= start_call_stack.len() {
+            if new_call_stack.len() <= start_call_stack.len() || self.is_interrupted() {
                 return DebugCommandResult::Ok;
             }
         }
@@ -453,7 +1844,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
                 return result;
             }
             let new_call_stack = self.get_source_call_stack();
-            if new_call_stack.len() < start_call_stack.len() {
+            if new_call_stack.len() < start_call_stack.len() || self.is_interrupted() {
                 return DebugCommandResult::Ok;
             }
         }
@@ -462,16 +1853,122 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
     pub(super) fn cont(&mut self) -> DebugCommandResult {
         loop {
             let result = self.step_into_opcode();
-            if !matches!(result, DebugCommandResult::Ok) {
+            if !matches!(result, DebugCommandResult::Ok) || self.is_interrupted() {
                 return result;
             }
         }
     }
 
+    pub(super) fn set_brillig_limits(&mut self, limits: BrilligLimits) {
+        self.brillig_limits = limits;
+    }
+
+    pub(super) fn get_brillig_limits(&self) -> BrilligLimits {
+        self.brillig_limits
+    }
+
+    /// Returns the description of the Brillig limit exceeded by the last step,
+    /// if it failed because of one
+    pub(super) fn get_exceeded_brillig_limit(&self) -> Option<&str> {
+        self.exceeded_brillig_limit.as_deref()
+    }
+
+    /// Describes the Brillig limit the state of `solver` exceeds, if any
+    fn check_brillig_limits(&self, solver: &BrilligSolver<'a, FieldElement, B>) -> Option<String> {
+        let memory = solver.get_memory().len();
+        let depth = solver.get_call_stack().len();
+        match self.brillig_limits {
+            BrilligLimits { max_memory: Some(max_memory), .. } if memory > max_memory => Some(
+                format!("Brillig memory grew to {memory} cells, over the limit of {max_memory}"),
+            ),
+            BrilligLimits { max_depth: Some(max_depth), .. } if depth > max_depth => {
+                Some(format!("Brillig calls nested {depth} deep, over the limit of {max_depth}"))
+            }
+            _ => None,
+        }
+    }
+
+    /// Fails the current Brillig call for exceeding a limit. Execution stays at
+    /// the opcode that exceeded it, so it can be inspected, and goes on if the
+    /// limit is raised.
+    fn fail_on_brillig_limit(&mut self, message: String) -> DebugCommandResult {
+        self.exceeded_brillig_limit = Some(message.clone());
+        let error = OpcodeResolutionError::BrilligFunctionFailed {
+            call_stack: self.get_call_stack(),
+            payload: Some(ResolvedAssertionPayload::String(message)),
+        };
+        DebugCommandResult::Error(NargoError::ExecutionError(ExecutionError::SolvingError(
+            error, None,
+        )))
+    }
+
     pub(super) fn get_brillig_memory(&self) -> Option<&[MemoryValue<FieldElement>]> {
         self.brillig_solver.as_ref().map(|solver| solver.get_memory())
     }
 
+    /// Checks a write of `value` as a `bit_size` integer, or a field, to the
+    /// Brillig memory cell `ptr`. Values that don't fit the bit size are
+    /// rejected; writes that are possible but likely to corrupt execution
+    /// return the reasons why: the cell is past the end of the memory, its
+    /// type changes or it holds a variable tracked by the debugger.
+    pub(super) fn check_brillig_memory_write(
+        &self,
+        ptr: usize,
+        value: FieldElement,
+        bit_size: u32,
+    ) -> Result<Vec<String>, String> {
+        let Some(memory) = self.get_brillig_memory() else {
+            return Err(String::from("Not executing a Brillig block"));
+        };
+        if !INTEGER_BIT_SIZES.contains(&bit_size) && bit_size != FieldElement::max_num_bits() {
+            return Err(format!(
+                "Invalid bit size {bit_size}; expected one of {} or {} for a field",
+                INTEGER_BIT_SIZES.map(|bit_size| bit_size.to_string()).join(", "),
+                FieldElement::max_num_bits()
+            ));
+        }
+        let Some(new_value) = MemoryValue::new_checked(value, bit_size) else {
+            return Err(format!("Value {value} does not fit in {bit_size} bits"));
+        };
+
+        let mut warnings = vec![];
+        match memory.get(ptr) {
+            None => warnings.push(format!(
+                "Cell {ptr} is past the end of memory ({} cells); writing grows it",
+                memory.len()
+            )),
+            Some(current) if current.bit_size() != new_value.bit_size() => {
+                warnings.push(format!(
+                    "Cell {ptr} holds a {}, not a {}",
+                    CellType::of(current),
+                    CellType::of(&new_value)
+                ));
+            }
+            Some(_) => (),
+        }
+        if let Some(name) = self.variable_at_brillig_address(ptr) {
+            warnings.push(format!(
+                "Cell {ptr} holds variable {name}; consider `set var {name}` instead"
+            ));
+        }
+        Ok(warnings)
+    }
+
+    /// Returns the name of the variable of the current stack frame whose
+    /// value is held in the given Brillig memory cell, if any
+    pub(super) fn variable_at_brillig_address(&self, ptr: usize) -> Option<String> {
+        let frame = self.foreign_call_executor.current_stack_frame()?;
+        frame
+            .variables
+            .iter()
+            .find(|(name, ..)| {
+                let var_id = self.foreign_call_executor.lookup_variable(name).map(|(id, _)| id);
+                var_id.and_then(|id| self.variable_addresses.get(&id)).map(|(address, _)| *address)
+                    == Some(ptr)
+            })
+            .map(|(name, ..)| name.to_string())
+    }
+
     pub(super) fn write_brillig_memory(&mut self, ptr: usize, value: FieldElement, bit_size: u32) {
         if let Some(solver) = self.brillig_solver.as_mut() {
             solver.write_memory_at(
@@ -482,6 +1979,49 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         }
     }
 
+    /// Overwrites the value of a variable in the current stack frame. Only
+    /// variables of Field, integer and boolean types are supported.
+    ///
+    /// Returns whether the value was also written to the Brillig memory cell
+    /// the variable was last assigned from; otherwise only the value tracked by
+    /// the debugger is updated. The cell is only written while it still holds
+    /// the assigned value, since the program may have reused it for something
+    /// else since then.
+    pub(super) fn set_variable(&mut self, name: &str, value: FieldElement) -> Result<bool, String> {
+        let Some((var_id, typ)) = self.foreign_call_executor.lookup_variable(name) else {
+            return Err(format!("Unknown variable {name} in the current stack frame"));
+        };
+        let bit_size = match typ {
+            PrintableType::Field => FieldElement::max_num_bits(),
+            PrintableType::UnsignedInteger { width } | PrintableType::SignedInteger { width } => {
+                width
+            }
+            PrintableType::Boolean => 1,
+            _ => {
+                return Err(format!(
+                    "Cannot set {name}: only Field, integer and bool variables are supported"
+                ))
+            }
+        };
+        if bit_size < FieldElement::max_num_bits() && value.num_bits() > bit_size {
+            return Err(format!("Value does not fit in the {bit_size} bits of {name}"));
+        }
+
+        self.foreign_call_executor.assign_variable(var_id, &[value]);
+
+        match (self.brillig_solver.as_mut(), self.variable_addresses.get_mut(&var_id)) {
+            (Some(solver), Some((address, assigned_value)))
+                if solver.get_memory().get(*address).map(MemoryValue::to_field)
+                    == Some(*assigned_value) =>
+            {
+                solver.write_memory_at(*address, MemoryValue::new_from_field(value, bit_size));
+                *assigned_value = value;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     pub(super) fn get_variables(&self) -> Vec<StackFrame<FieldElement>> {
         return self.foreign_call_executor.get_variables();
     }
@@ -490,64 +2030,785 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> DebugContext<'a, B> {
         return self.foreign_call_executor.current_stack_frame();
     }
 
-    fn breakpoint_reached(&self) -> bool {
-        if let Some(location) = self.get_current_opcode_location() {
-            self.breakpoints.contains(&location)
-        } else {
-            false
+    /// Evaluates an access path into a variable of the stack frame with the
+    /// given index (by default the current one), such as `point.x` or
+    /// `rows[2].1`, or a raw witness such as `_12`
+    pub(super) fn evaluate(
+        &self,
+        expression: &str,
+        frame_index: Option<usize>,
+    ) -> Result<(PrintableValue<FieldElement>, PrintableType), String> {
+        let path: VariablePath = expression.parse()?;
+        let frames = self.get_variables();
+        let frame = match frame_index {
+            Some(index) => frames.get(index),
+            None => frames.last(),
+        };
+        // Later entries shadow earlier ones with the same name
+        let variable = frame
+            .and_then(|frame| frame.variables.iter().rev().find(|(name, ..)| *name == path.root));
+        if let Some((_, value, typ)) = variable {
+            return resolve_path(&path, value, typ);
         }
-    }
 
-    pub(super) fn is_valid_opcode_location(&self, location: &OpcodeLocation) -> bool {
-        let opcodes = self.get_opcodes();
-        match *location {
-            OpcodeLocation::Acir(acir_index) => acir_index < opcodes.len(),
-            OpcodeLocation::Brillig { acir_index, brillig_index } => {
-                if acir_index < opcodes.len() {
-                    match &opcodes[acir_index] {
-                        Opcode::BrilligCall { id, .. } => {
-                            let bytecode = &self.unconstrained_functions[*id as usize].bytecode;
-                            brillig_index < bytecode.len()
-                        }
-                        _ => false,
-                    }
-                } else {
-                    false
-                }
+        let witness = path
+            .root
+            .strip_prefix('_')
+            .and_then(|index| index.parse::<u32>().ok())
+            .and_then(|index| self.get_witness_map().get(&Witness(index)));
+        match witness {
+            Some(value) if path.segments.is_empty() => {
+                Ok((PrintableValue::Field(*value), PrintableType::Field))
             }
+            _ => Err(format!("No variable or witness named {} in scope", path.root)),
         }
     }
 
-    pub(super) fn is_breakpoint_set(&self, location: &OpcodeLocation) -> bool {
-        self.breakpoints.contains(location)
+    pub(super) fn get_assignment_log(&self) -> Vec<String> {
+        self.foreign_call_executor.get_assignment_log()
     }
 
-    pub(super) fn add_breakpoint(&mut self, location: OpcodeLocation) -> bool {
-        self.breakpoints.insert(location)
+    pub(super) fn take_debug_warnings(&mut self) -> Vec<String> {
+        let mut warnings = self.foreign_call_executor.take_warnings();
+        warnings.append(&mut self.debug_warnings);
+        warnings
     }
 
-    pub(super) fn delete_breakpoint(&mut self, location: &OpcodeLocation) -> bool {
-        self.breakpoints.remove(location)
+    /// Walks the circuit backwards from the given witness, returning the tree
+    /// of opcodes whose outputs (transitively) feed into it, up to `max_depth`
+    /// levels deep.
+    pub(super) fn get_witness_dependencies(
+        &self,
+        witness: Witness,
+        max_depth: usize,
+    ) -> WitnessDependency {
+        let producers = build_witness_producers(self.circuit);
+        let mut visited = HashSet::new();
+        build_witness_dependency(&producers, witness, max_depth, &mut visited)
     }
 
-    pub(super) fn iterate_breakpoints(&self) -> Iter<'_, OpcodeLocation> {
-        self.breakpoints.iter()
-    }
+    /// Checks whether execution should stop at a breakpoint in the current
+    /// location, counting the hit and consuming the breakpoint's ignore count.
+    /// Hits on which the breakpoint's conditions don't hold are otherwise
+    /// ignored. Logpoints never stop execution; their message is rendered
+    /// instead.
+    fn breakpoint_reached(&mut self) -> bool {
+        let Some(location) = self.get_current_opcode_location() else {
+            return false;
+        };
+        let Some(breakpoint) =
+            self.breakpoint_ids.get(&location).and_then(|id| self.breakpoints.get_mut(id))
+        else {
+            return false;
+        };
 
-    pub(super) fn clear_breakpoints(&mut self) {
-        self.breakpoints.clear();
+        breakpoint.hit_count += 1;
+        let conditions = [breakpoint.hit_condition.clone(), breakpoint.condition.clone()];
+        let (breakpoint_id, hit) = (breakpoint.id, breakpoint.hit_count);
+        for condition in conditions.iter().flatten() {
+            let scope = BreakpointScope { context: self, hits: hit };
+            match condition.evaluate(&scope) {
+                Ok(true) => (),
+                Ok(false) => return false,
+                Err(error) => {
+                    // Stop, so that the user can fix the condition
+                    self.debug_warnings.push(format!(
+                        "Could not evaluate condition {condition} of breakpoint {breakpoint_id}: {error}"
+                    ));
+                    break;
+                }
+            }
+        }
+        let Some(breakpoint) = self.breakpoints.get_mut(&breakpoint_id) else {
+            return false;
+        };
+        let ignored = breakpoint.ignore_count > 0;
+        if ignored {
+            breakpoint.ignore_count -= 1;
+        }
+        let log_message = breakpoint.log_message.clone();
+        let snapshot_variables = breakpoint.snapshot_variables.clone();
+        if !snapshot_variables.is_empty() {
+            let values = self.render_variables(&snapshot_variables);
+            self.breakpoint_snapshots.push(BreakpointSnapshot {
+                run: self.run,
+                breakpoint_id,
+                location,
+                hit,
+                values,
+            });
+        }
+        if ignored {
+            return false;
+        }
+        let Some(message) = log_message else {
+            return true;
+        };
+        let output = self.render_log_message(&message);
+        self.log_output.push(output);
+        false
     }
 
-    pub(super) fn is_solved(&self) -> bool {
-        matches!(self.acvm.get_status(), ACVMStatus::Solved)
-    }
+    /// Renders a logpoint message, replacing each `{name}` placeholder with
+    /// the value of the variable of that name in the current stack frame.
+    /// Placeholders naming unknown variables are rendered as `<unknown name>`.
+    pub(super) fn render_log_message(&self, message: &str) -> String {
+        let frame = self.current_stack_frame();
+        let variables = frame.as_ref().map(|frame| frame.variables.as_slice()).unwrap_or_default();
+
+        let mut template = String::new();
+        let mut values = Vec::new();
+        let mut rest = message;
+        while let Some(start) = rest.find('{') {
+            template.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let name = after.find('}').map(|end| &after[..end]).filter(|name| {
+                !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            });
+            let Some(name) = name else {
+                template.push('{');
+                rest = after;
+                continue;
+            };
+            // Later entries shadow earlier ones with the same name
+            match variables.iter().rev().find(|(var_name, ..)| *var_name == name) {
+                Some((_, value, typ)) => {
+                    template.push_str(&format!("{{{name}}}"));
+                    values.push(((*value).clone(), (*typ).clone()));
+                }
+                None => template.push_str(&format!("<unknown {name}>")),
+            }
+            rest = &after[name.len() + 1..];
+        }
+        template.push_str(rest);
+
+        let mut output = String::new();
+        match write!(output, "{}", PrintableValueDisplay::FmtString(template, values)) {
+            Ok(()) => output,
+            Err(_) => format!("{message} (could not render values)"),
+        }
+    }
+
+    /// Runs the program from the start in a separate context, so that the
+    /// state of the session is kept, counting the opcodes executed at each
+    /// source location. Breakpoints don't apply to this run, and its foreign
+    /// calls go to `foreign_call_executor`. A run interrupted or stopped after
+    /// `max_opcodes` ends with an `Ok` result.
+    pub(super) fn profile(
+        &self,
+        initial_witness: WitnessMap<FieldElement>,
+        foreign_call_executor: Box<dyn DebugForeignCallExecutor + 'a>,
+        max_opcodes: usize,
+    ) -> Profile {
+        let mut context = DebugContext::new(
+            self.blackbox_solver,
+            self.circuit,
+            self.debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            self.unconstrained_functions,
+        );
+        context.interrupt = self.interrupt;
+        let mut samples: HashMap<Location, usize> = HashMap::new();
+        let result = loop {
+            if context.executed_opcodes >= max_opcodes {
+                break DebugCommandResult::Ok;
+            }
+            if let Some(location) = context
+                .get_current_source_location()
+                .and_then(|locations| locations.last().copied())
+            {
+                *samples.entry(location).or_default() += 1;
+            }
+            match context.step_into_opcode() {
+                DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_)
+                    if context.is_interrupted() =>
+                {
+                    break DebugCommandResult::Ok
+                }
+                DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(_) => (),
+                result => break result,
+            }
+        };
+
+        let mut locations: Vec<_> = samples.into_iter().collect();
+        locations.sort_by(|(_, a), (_, b)| b.cmp(a));
+        Profile {
+            result,
+            executed_opcodes: context.executed_opcodes,
+            function_calls: context.get_function_calls(),
+            locations,
+        }
+    }
+
+    pub(super) fn get_executed_opcodes(&self) -> usize {
+        self.executed_opcodes
+    }
+
+    pub(super) fn get_foreign_call_count(&self) -> usize {
+        self.foreign_calls
+    }
+
+    /// Returns the messages rendered by logpoints since the last call
+    pub(super) fn take_log_output(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.log_output)
+    }
+
+    /// Renders the values of the named variables of the current stack frame,
+    /// with `<unknown>` for the names not in scope
+    fn render_variables(&self, names: &[String]) -> BTreeMap<String, String> {
+        let frame = self.current_stack_frame();
+        let variables = frame.as_ref().map(|frame| frame.variables.as_slice()).unwrap_or_default();
+        names
+            .iter()
+            .map(|name| {
+                // Later entries shadow earlier ones with the same name
+                let value = match variables.iter().rev().find(|(var_name, ..)| var_name == name) {
+                    Some((_, value, typ)) => {
+                        PrintableValueDisplay::Plain((*value).clone(), (*typ).clone()).to_string()
+                    }
+                    None => "<unknown>".to_string(),
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Returns the variable snapshots taken at breakpoint hits since the
+    /// program started
+    pub(super) fn get_breakpoint_snapshots(&self) -> &[BreakpointSnapshot] {
+        &self.breakpoint_snapshots
+    }
+
+    pub(super) fn is_valid_opcode_location(&self, location: &OpcodeLocation) -> bool {
+        let opcodes = self.get_opcodes();
+        match *location {
+            OpcodeLocation::Acir(acir_index) => acir_index < opcodes.len(),
+            OpcodeLocation::Brillig { acir_index, brillig_index } => {
+                if acir_index < opcodes.len() {
+                    match &opcodes[acir_index] {
+                        Opcode::BrilligCall { id, .. } => {
+                            let bytecode = &self.unconstrained_functions[*id as usize].bytecode;
+                            brillig_index < bytecode.len()
+                        }
+                        _ => false,
+                    }
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Returns true if the debugger can stop at the given location: it must be
+    /// an opcode of the program that is not part of the debugger
+    /// instrumentation
+    pub(super) fn is_valid_debug_location(&self, location: &OpcodeLocation) -> bool {
+        self.is_valid_opcode_location(location) && !self.is_instrumentation_opcode(location)
+    }
+
+    /// Opcodes whose source locations all lie in the debug module are only
+    /// there to track variables, and do not match any code of the user
+    fn is_instrumentation_opcode(&self, location: &OpcodeLocation) -> bool {
+        self.debug_artifact
+            .debug_symbols
+            .first()
+            .and_then(|debug_info| debug_info.opcode_location(location))
+            .map_or(false, |source_locations| {
+                !source_locations.is_empty()
+                    && source_locations.iter().all(|source_location| {
+                        self.is_source_location_in_debug_module(source_location)
+                    })
+            })
+    }
+
+    /// Finds where a breakpoint requested at the given location stops: the
+    /// location itself if it is a valid debug location, or else the next one
+    /// that is, either in the same Brillig function or among the ACIR opcodes
+    pub(super) fn find_debug_location_from(
+        &self,
+        location: &OpcodeLocation,
+    ) -> Option<OpcodeLocation> {
+        let mut candidate = *location;
+        while self.is_valid_opcode_location(&candidate) {
+            if !self.is_instrumentation_opcode(&candidate) {
+                return Some(candidate);
+            }
+            candidate = match candidate {
+                OpcodeLocation::Acir(acir_index) => OpcodeLocation::Acir(acir_index + 1),
+                OpcodeLocation::Brillig { acir_index, brillig_index } => {
+                    OpcodeLocation::Brillig { acir_index, brillig_index: brillig_index + 1 }
+                }
+            };
+        }
+        None
+    }
+
+    pub(super) fn is_breakpoint_set(&self, location: &OpcodeLocation) -> bool {
+        self.get_breakpoint_at(location).is_some()
+    }
+
+    pub(super) fn get_breakpoint_at(&self, location: &OpcodeLocation) -> Option<&Breakpoint> {
+        self.breakpoint_ids.get(location).and_then(|id| self.breakpoints.get(id))
+    }
+
+    pub(super) fn add_breakpoint(&mut self, location: OpcodeLocation) -> bool {
+        if self.is_breakpoint_set(&location) {
+            return false;
+        }
+        let id = self.next_breakpoint_id;
+        self.next_breakpoint_id += 1;
+        self.breakpoint_ids.insert(location, id);
+        self.breakpoints.insert(
+            id,
+            Breakpoint {
+                id,
+                location,
+                ignore_count: 0,
+                hit_count: 0,
+                log_message: None,
+                snapshot_variables: vec![],
+                condition: None,
+                hit_condition: None,
+            },
+        );
+        true
+    }
+
+    /// Sets the variables recorded every time the breakpoint is hit. Returns
+    /// false if there is no breakpoint with the given identifier.
+    pub(super) fn set_breakpoint_snapshot(&mut self, id: usize, variables: Vec<String>) -> bool {
+        match self.breakpoints.get_mut(&id) {
+            Some(breakpoint) => {
+                breakpoint.snapshot_variables = variables;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Adds a logpoint, which prints `message` every time execution reaches
+    /// the location and then keeps going. Returns false if a breakpoint is
+    /// already set there.
+    pub(super) fn add_logpoint(&mut self, location: OpcodeLocation, message: String) -> bool {
+        if !self.add_breakpoint(location) {
+            return false;
+        }
+        let id = self.next_breakpoint_id - 1;
+        if let Some(breakpoint) = self.breakpoints.get_mut(&id) {
+            breakpoint.log_message = Some(message);
+        }
+        true
+    }
+
+    pub(super) fn delete_breakpoint(&mut self, location: &OpcodeLocation) -> bool {
+        let Some(id) = self.breakpoint_ids.remove(location) else {
+            return false;
+        };
+        self.breakpoints.remove(&id).is_some()
+    }
+
+    /// Makes execution skip the breakpoint the next `count` times it is reached.
+    /// Returns false if there is no breakpoint with the given identifier.
+    pub(super) fn set_breakpoint_ignore_count(&mut self, id: usize, count: usize) -> bool {
+        match self.breakpoints.get_mut(&id) {
+            Some(breakpoint) => {
+                breakpoint.ignore_count = count;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the condition that must hold for the breakpoint to stop
+    /// execution. Returns false if there is no breakpoint with the given
+    /// identifier.
+    pub(super) fn set_breakpoint_condition(
+        &mut self,
+        id: usize,
+        condition: Option<Condition>,
+    ) -> bool {
+        match self.breakpoints.get_mut(&id) {
+            Some(breakpoint) => {
+                breakpoint.condition = condition;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets how many times the breakpoint was hit, to carry the count over
+    /// when breakpoints are installed again. Returns false if there is no
+    /// breakpoint with the given identifier.
+    pub(super) fn set_breakpoint_hit_count(&mut self, id: usize, hit_count: usize) -> bool {
+        match self.breakpoints.get_mut(&id) {
+            Some(breakpoint) => {
+                breakpoint.hit_count = hit_count;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sets the condition on the hit count that must hold for the breakpoint
+    /// to stop execution. Returns false if there is no breakpoint with the
+    /// given identifier.
+    pub(super) fn set_breakpoint_hit_condition(
+        &mut self,
+        id: usize,
+        hit_condition: Option<Condition>,
+    ) -> bool {
+        match self.breakpoints.get_mut(&id) {
+            Some(breakpoint) => {
+                breakpoint.hit_condition = hit_condition;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(super) fn iterate_breakpoints(&self) -> impl Iterator<Item = &OpcodeLocation> {
+        self.breakpoints.values().map(|breakpoint| &breakpoint.location)
+    }
+
+    pub(super) fn get_breakpoints(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.breakpoints.values()
+    }
+
+    pub(super) fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+        self.breakpoint_ids.clear();
+    }
+
+    /// Recognizes execution errors caused by the overflow checks the compiler
+    /// inserts for integer arithmetic, whose opcodes are recorded in the debug
+    /// info, and decodes the overflowing value when possible
+    pub(super) fn get_integer_overflow(
+        &self,
+        error: &NargoError<FieldElement>,
+    ) -> Option<IntegerOverflow> {
+        let NargoError::ExecutionError(ExecutionError::SolvingError(error, _)) = error else {
+            return None;
+        };
+        let (location, payload) = match error {
+            OpcodeResolutionError::UnsatisfiedConstrain { payload, .. } => {
+                (self.get_current_opcode_location()?, payload)
+            }
+            OpcodeResolutionError::BrilligFunctionFailed { payload, call_stack } => {
+                (*call_stack.last()?, payload)
+            }
+            _ => return None,
+        };
+        let debug_info = self.debug_artifact.debug_symbols.first()?;
+        if !debug_info.overflow_checks.contains(&location) {
+            return None;
+        }
+        let message = match payload {
+            Some(ResolvedAssertionPayload::String(message)) => message.clone(),
+            _ => self.find_static_assert_message(location)?,
+        };
+
+        let (bit_size, value) = match location {
+            OpcodeLocation::Acir(acir_index) => match &self.get_opcodes()[acir_index] {
+                Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE { input }) => {
+                    (Some(input.num_bits), self.get_witness_map().get(&input.witness).copied())
+                }
+                _ => (None, None),
+            },
+            OpcodeLocation::Brillig { .. } => (None, None),
+        };
+        Some(IntegerOverflow { message, location, bit_size, value })
+    }
+
+    /// Returns the static message of the assertion at `location`. Brillig
+    /// failures reach the debugger without it resolved, so it is looked up in
+    /// the circuit.
+    fn find_static_assert_message(&self, location: OpcodeLocation) -> Option<String> {
+        self.circuit.assert_messages.iter().find_map(|(assert_location, payload)| match payload {
+            AssertionPayload::StaticString(message) if *assert_location == location => {
+                Some(message.clone())
+            }
+            _ => None,
+        })
+    }
+
+    /// Returns the location of the opcode that failed with `error` and the
+    /// message of the failed assertion, if any. Custom error payloads are
+    /// decoded with the program's `error_types`.
+    pub(super) fn get_failed_assertion(
+        &self,
+        error: &NargoError<FieldElement>,
+        error_types: &BTreeMap<ErrorSelector, AbiErrorType>,
+    ) -> Option<(OpcodeLocation, Option<String>)> {
+        let NargoError::ExecutionError(ExecutionError::SolvingError(error, _)) = error else {
+            return None;
+        };
+        let (location, payload) = match error {
+            OpcodeResolutionError::UnsatisfiedConstrain { payload, .. } => {
+                (self.get_current_opcode_location()?, payload)
+            }
+            OpcodeResolutionError::BrilligFunctionFailed { payload, call_stack } => {
+                (*call_stack.last()?, payload)
+            }
+            _ => return None,
+        };
+        let message = match payload {
+            Some(ResolvedAssertionPayload::String(message)) => Some(message.clone()),
+            Some(ResolvedAssertionPayload::Raw(raw)) => error_types
+                .get(&raw.selector)
+                .map(|error_type| display_abi_error(&raw.data, error_type.clone()).to_string()),
+            None => self.find_static_assert_message(location),
+        };
+        Some((location, message))
+    }
+
+    /// Describes the status of the ACVM, which tells why execution is paused
+    /// when it isn't at a breakpoint
+    pub(super) fn describe_status(&self) -> String {
+        match self.acvm.get_status() {
+            ACVMStatus::Failure(error) => format!("Execution failure: {error}"),
+            ACVMStatus::RequiresForeignCall(foreign_call) => {
+                let call = self
+                    .format_oracle_call(foreign_call)
+                    .unwrap_or_else(|| foreign_call.function.clone());
+                format!("Waiting on foreign call `{call}`")
+            }
+            ACVMStatus::RequiresAcirCall(acir_call) => {
+                format!("Waiting on ACIR call to function {}", acir_call.id)
+            }
+            ACVMStatus::InProgress if self.brillig_solver.is_some() => {
+                String::from("In progress (executing a Brillig block)")
+            }
+            status => status.to_string(),
+        }
+    }
+
+    /// Returns the witnesses holding the program's return values
+    pub(super) fn get_return_witnesses(&self) -> Vec<Witness> {
+        self.circuit.return_values.0.iter().copied().collect()
+    }
+
+    /// Returns the witnesses read by the ACIR opcodes executed so far,
+    /// including the Brillig call in progress, if any
+    pub(super) fn get_read_witnesses(&self) -> BTreeSet<Witness> {
+        let executed = match self.get_current_opcode_location() {
+            Some(OpcodeLocation::Acir(acir_index)) => acir_index,
+            Some(OpcodeLocation::Brillig { acir_index, .. }) => acir_index + 1,
+            None => self.circuit.opcodes.len(),
+        };
+        self.circuit.opcodes[..executed].iter().flat_map(opcode_read_witnesses).collect()
+    }
+
+    pub(super) fn is_solved(&self) -> bool {
+        matches!(self.acvm.get_status(), ACVMStatus::Solved)
+    }
 
     pub fn finalize(self) -> WitnessMap<FieldElement> {
         self.acvm.finalize()
     }
+
+    /// Describes the state execution stopped in after failing with `error`,
+    /// or None if it is not a failure of the program itself, such as an
+    /// oracle that could not be resolved
+    pub(super) fn failure_report(&self, error: &NargoError<FieldElement>) -> Option<FailureReport> {
+        if DebuggerErrorKind::from(error) != DebuggerErrorKind::Solver {
+            return None;
+        }
+        let call_stack = self
+            .get_source_call_stack()
+            .iter()
+            .map(|(_, location)| {
+                format_location_path(self.debug_artifact, &PathSubstitutions::default(), *location)
+            })
+            .collect();
+        Some(FailureReport {
+            error: error.to_string(),
+            location: self.get_current_opcode_location(),
+            call_stack,
+            executed_opcodes: self.executed_opcodes,
+            solved_witnesses: self.get_witness_map().clone().into_iter().count(),
+        })
+    }
+}
+
+fn hash_witness_values(
+    witness_map: &WitnessMap<FieldElement>,
+    witnesses: impl IntoIterator<Item = Witness>,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for witness in witnesses {
+        witness_map.get(&witness).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Operand of a Brillig black box opcode, pointing to values in VM memory
+enum BrilligOperand {
+    Value(MemoryAddress),
+    Array(HeapArray),
+    Vector(HeapVector),
+}
+
+/// Returns the name of the function a Brillig black box opcode evaluates,
+/// along with its input and output operands
+fn brillig_black_box_operands(
+    op: &BlackBoxOp,
+) -> (&'static str, Vec<BrilligOperand>, Vec<BrilligOperand>) {
+    use BrilligOperand::{Array, Value, Vector};
+    let (func, inputs, outputs) = match *op {
+        BlackBoxOp::AES128Encrypt { inputs, iv, key, outputs } => (
+            BlackBoxFunc::AES128Encrypt,
+            vec![Vector(inputs), Array(iv), Array(key)],
+            vec![Vector(outputs)],
+        ),
+        BlackBoxOp::Sha256 { message, output } => {
+            (BlackBoxFunc::SHA256, vec![Vector(message)], vec![Array(output)])
+        }
+        BlackBoxOp::Blake2s { message, output } => {
+            (BlackBoxFunc::Blake2s, vec![Vector(message)], vec![Array(output)])
+        }
+        BlackBoxOp::Blake3 { message, output } => {
+            (BlackBoxFunc::Blake3, vec![Vector(message)], vec![Array(output)])
+        }
+        BlackBoxOp::Keccak256 { message, output } => {
+            (BlackBoxFunc::Keccak256, vec![Vector(message)], vec![Array(output)])
+        }
+        BlackBoxOp::Keccakf1600 { message, output } => {
+            (BlackBoxFunc::Keccakf1600, vec![Vector(message)], vec![Array(output)])
+        }
+        BlackBoxOp::EcdsaSecp256k1 {
+            hashed_msg,
+            public_key_x,
+            public_key_y,
+            signature,
+            result,
+        } => (
+            BlackBoxFunc::EcdsaSecp256k1,
+            vec![Vector(hashed_msg), Array(public_key_x), Array(public_key_y), Array(signature)],
+            vec![Value(result)],
+        ),
+        BlackBoxOp::EcdsaSecp256r1 {
+            hashed_msg,
+            public_key_x,
+            public_key_y,
+            signature,
+            result,
+        } => (
+            BlackBoxFunc::EcdsaSecp256r1,
+            vec![Vector(hashed_msg), Array(public_key_x), Array(public_key_y), Array(signature)],
+            vec![Value(result)],
+        ),
+        BlackBoxOp::SchnorrVerify { public_key_x, public_key_y, message, signature, result } => (
+            BlackBoxFunc::SchnorrVerify,
+            vec![Value(public_key_x), Value(public_key_y), Vector(message), Vector(signature)],
+            vec![Value(result)],
+        ),
+        BlackBoxOp::PedersenCommitment { inputs, domain_separator, output } => (
+            BlackBoxFunc::PedersenCommitment,
+            vec![Vector(inputs), Value(domain_separator)],
+            vec![Array(output)],
+        ),
+        BlackBoxOp::PedersenHash { inputs, domain_separator, output } => (
+            BlackBoxFunc::PedersenHash,
+            vec![Vector(inputs), Value(domain_separator)],
+            vec![Value(output)],
+        ),
+        BlackBoxOp::MultiScalarMul { points, scalars, outputs } => (
+            BlackBoxFunc::MultiScalarMul,
+            vec![Vector(points), Vector(scalars)],
+            vec![Array(outputs)],
+        ),
+        BlackBoxOp::EmbeddedCurveAdd {
+            input1_x,
+            input1_y,
+            input1_infinite,
+            input2_x,
+            input2_y,
+            input2_infinite,
+            result,
+        } => (
+            BlackBoxFunc::EmbeddedCurveAdd,
+            [input1_x, input1_y, input1_infinite, input2_x, input2_y, input2_infinite]
+                .map(Value)
+                .into(),
+            vec![Array(result)],
+        ),
+        BlackBoxOp::BigIntAdd { lhs, rhs, output } => {
+            (BlackBoxFunc::BigIntAdd, vec![Value(lhs), Value(rhs)], vec![Value(output)])
+        }
+        BlackBoxOp::BigIntSub { lhs, rhs, output } => {
+            (BlackBoxFunc::BigIntSub, vec![Value(lhs), Value(rhs)], vec![Value(output)])
+        }
+        BlackBoxOp::BigIntMul { lhs, rhs, output } => {
+            (BlackBoxFunc::BigIntMul, vec![Value(lhs), Value(rhs)], vec![Value(output)])
+        }
+        BlackBoxOp::BigIntDiv { lhs, rhs, output } => {
+            (BlackBoxFunc::BigIntDiv, vec![Value(lhs), Value(rhs)], vec![Value(output)])
+        }
+        BlackBoxOp::BigIntFromLeBytes { inputs, modulus, output } => (
+            BlackBoxFunc::BigIntFromLeBytes,
+            vec![Vector(inputs), Vector(modulus)],
+            vec![Value(output)],
+        ),
+        BlackBoxOp::BigIntToLeBytes { input, output } => {
+            (BlackBoxFunc::BigIntToLeBytes, vec![Value(input)], vec![Vector(output)])
+        }
+        BlackBoxOp::Poseidon2Permutation { message, output, len } => (
+            BlackBoxFunc::Poseidon2Permutation,
+            vec![Vector(message), Value(len)],
+            vec![Array(output)],
+        ),
+        BlackBoxOp::Sha256Compression { input, hash_values, output } => (
+            BlackBoxFunc::Sha256Compression,
+            vec![Vector(input), Vector(hash_values)],
+            vec![Array(output)],
+        ),
+        // Not an ACIR black box function, so it has no name of its own
+        BlackBoxOp::ToRadix { input, radix: _, output } => {
+            return ("to_radix", vec![Value(input)], vec![Array(output)]);
+        }
+    };
+    (func.name(), inputs, outputs)
+}
+
+/// Hashes the values the operands of a Brillig black box opcode point to.
+/// Cells past the end of memory, which the VM has not written yet, are left
+/// out.
+fn hash_brillig_operands(memory: &[MemoryValue<FieldElement>], operands: &[BrilligOperand]) -> u64 {
+    let read = |address: usize| memory.get(address).map(MemoryValue::to_field);
+    let read_pointer = |address: MemoryAddress| {
+        read(address.to_usize())
+            .and_then(|pointer| pointer.try_into_u128())
+            .and_then(|pointer| usize::try_from(pointer).ok())
+    };
+    let read_cells = |pointer: MemoryAddress, size: usize| {
+        let start = read_pointer(pointer).unwrap_or(memory.len());
+        memory.iter().skip(start).take(size).map(MemoryValue::to_field).collect::<Vec<_>>()
+    };
+
+    let mut hasher = DefaultHasher::new();
+    for operand in operands {
+        match *operand {
+            BrilligOperand::Value(address) => read(address.to_usize()).hash(&mut hasher),
+            BrilligOperand::Array(array) => read_cells(array.pointer, array.size).hash(&mut hasher),
+            BrilligOperand::Vector(vector) => {
+                let size = read_pointer(vector.size).unwrap_or(0);
+                read_cells(vector.pointer, size).hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
 }
 
-fn is_debug_file_in_debug_crate(debug_file: &DebugFile) -> bool {
+fn hash_foreign_call_params(params: &[ForeignCallParam<FieldElement>]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for param in params {
+        param.fields().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+pub(super) fn is_debug_file_in_debug_crate(debug_file: &DebugFile) -> bool {
     debug_file.path.starts_with("__debug/")
 }
 
@@ -593,6 +2854,55 @@ fn build_source_to_opcode_debug_mappings(
     result
 }
 
+/// Collects the asserts numbered by the compiler in the debug information,
+/// returning them along with the assert id of the opcodes compiled from them
+fn build_asserts(
+    debug_artifact: &DebugArtifact,
+) -> (Vec<AssertInfo>, BTreeMap<OpcodeLocation, usize>) {
+    // Every debug info holds the asserts of the whole program
+    let assert_locations: BTreeMap<usize, Location> = debug_artifact
+        .debug_symbols
+        .iter()
+        .flat_map(|debug_info| &debug_info.asserts)
+        .map(|(id, location)| (id.0 as usize, *location))
+        .collect();
+
+    // TODO: handle the opcodes of the other circuits when we support
+    // debugging programs with ACIR calls
+    let mut assert_ids = BTreeMap::new();
+    if let Some(debug_info) = debug_artifact.debug_symbols.first() {
+        for (opcode_location, source_locations) in &debug_info.locations {
+            // The opcode belongs to the innermost assert containing one of its
+            // locations, from the innermost call outwards, as the functions
+            // an assert calls may have asserts of their own
+            let assert_id = source_locations.iter().rev().find_map(|location| {
+                assert_locations
+                    .iter()
+                    .filter(|(_, assert_location)| assert_location.contains(location))
+                    .min_by_key(|(_, assert_location)| {
+                        assert_location.span.end() - assert_location.span.start()
+                    })
+                    .map(|(id, _)| *id)
+            });
+            if let Some(id) = assert_id {
+                assert_ids.insert(*opcode_location, id);
+            }
+        }
+    }
+
+    let asserts = assert_locations
+        .into_iter()
+        .map(|(id, location)| {
+            let first_opcode = assert_ids
+                .iter()
+                .find(|(_, assert_id)| **assert_id == id)
+                .map(|(opcode_location, _)| *opcode_location);
+            AssertInfo { id, location, first_opcode }
+        })
+        .collect();
+    (asserts, assert_ids)
+}
+
 fn build_acir_opcode_offsets(
     circuit: &Circuit<FieldElement>,
     unconstrained_functions: &[BrilligBytecode<FieldElement>],
@@ -615,36 +2925,234 @@ fn build_acir_opcode_offsets(
     result
 }
 
-// TODO: update all debugger tests to use unconstrained brillig pointers
-#[cfg(test)]
-mod tests {
-    use super::*;
+fn expression_witnesses(expr: &Expression<FieldElement>) -> impl Iterator<Item = Witness> + '_ {
+    expr.mul_terms
+        .iter()
+        .flat_map(|(_, lhs, rhs)| [*lhs, *rhs])
+        .chain(expr.linear_combinations.iter().map(|(_, witness)| *witness))
+}
 
-    use crate::foreign_calls::DefaultDebugForeignCallExecutor;
-    use acvm::{
-        acir::{
-            circuit::{
-                brillig::{BrilligInputs, BrilligOutputs},
-                opcodes::BlockId,
-            },
-            native_types::Expression,
-            AcirField,
-        },
-        blackbox_solver::StubbedBlackBoxSolver,
-        brillig_vm::brillig::{
-            BinaryFieldOp, HeapValueType, MemoryAddress, Opcode as BrilligOpcode, ValueOrArray,
-        },
-    };
+/// Returns the witnesses an opcode reads. Memory reads and Brillig calls
+/// taking memory arrays read the witnesses the memory block was initialized
+/// with, which are counted as read by the `MemoryInit` opcode instead.
+fn opcode_read_witnesses(opcode: &Opcode<FieldElement>) -> Vec<Witness> {
+    match opcode {
+        Opcode::AssertZero(expr) => expression_witnesses(expr).collect(),
+        Opcode::BlackBoxFuncCall(call) => {
+            call.get_inputs_vec().iter().map(|input| input.witness).collect()
+        }
+        Opcode::Directive(Directive::ToLeRadix { a, .. }) => expression_witnesses(a).collect(),
+        Opcode::MemoryInit { init, .. } => init.clone(),
+        Opcode::MemoryOp { op, .. } => {
+            expression_witnesses(&op.index).chain(expression_witnesses(&op.value)).collect()
+        }
+        Opcode::BrilligCall { inputs, .. } => inputs
+            .iter()
+            .flat_map(|input| -> Vec<Witness> {
+                match input {
+                    BrilligInputs::Single(expr) => expression_witnesses(expr).collect(),
+                    BrilligInputs::Array(exprs) => {
+                        exprs.iter().flat_map(expression_witnesses).collect()
+                    }
+                    BrilligInputs::MemoryArray(_) => vec![],
+                }
+            })
+            .collect(),
+        Opcode::Call { inputs, .. } => inputs.clone(),
+    }
+}
 
-    #[test]
-    fn test_resolve_foreign_calls_stepping_into_brillig() {
-        let fe_0 = FieldElement::zero();
-        let fe_1 = FieldElement::one();
-        let w_x = Witness(1);
+/// Maps every witness written by an opcode to the ACIR index of that opcode
+/// and the witnesses it reads. `AssertZero` opcodes don't state which witness
+/// they solve for, so the witnesses not yet known at that point in program
+/// order are taken to be their outputs.
+fn build_witness_producers(
+    circuit: &Circuit<FieldElement>,
+) -> BTreeMap<Witness, (usize, Vec<Witness>)> {
+    let mut known: BTreeSet<Witness> = circuit
+        .private_parameters
+        .iter()
+        .chain(circuit.public_parameters.0.iter())
+        .copied()
+        .collect();
+    let mut memory_blocks: HashMap<BlockId, Vec<Witness>> = HashMap::new();
+    let mut producers = BTreeMap::new();
 
-        let brillig_bytecode = BrilligBytecode {
-            bytecode: vec![
-                BrilligOpcode::CalldataCopy {
+    for (acir_index, opcode) in circuit.opcodes.iter().enumerate() {
+        let (inputs, outputs): (Vec<Witness>, Vec<Witness>) = match opcode {
+            Opcode::AssertZero(expr) => {
+                let witnesses: BTreeSet<Witness> = expression_witnesses(expr).collect();
+                witnesses.into_iter().partition(|witness| known.contains(witness))
+            }
+            Opcode::BlackBoxFuncCall(call) => (
+                call.get_inputs_vec().iter().map(|input| input.witness).collect(),
+                call.get_outputs_vec(),
+            ),
+            Opcode::Directive(Directive::ToLeRadix { a, b, .. }) => {
+                (expression_witnesses(a).collect(), b.clone())
+            }
+            Opcode::MemoryInit { block_id, init, .. } => {
+                memory_blocks.insert(*block_id, init.clone());
+                (vec![], vec![])
+            }
+            Opcode::MemoryOp { block_id, op, .. } => {
+                let block = memory_blocks.entry(*block_id).or_default();
+                let mut inputs: Vec<Witness> = expression_witnesses(&op.index).collect();
+                if op.operation.is_zero() {
+                    // reads depend on everything ever written to the block
+                    inputs.extend(block.iter().copied());
+                    (inputs, op.value.to_witness().into_iter().collect())
+                } else {
+                    block.extend(expression_witnesses(&op.value));
+                    (vec![], vec![])
+                }
+            }
+            Opcode::BrilligCall { inputs, outputs, .. } => {
+                let inputs = inputs
+                    .iter()
+                    .flat_map(|input| -> Vec<Witness> {
+                        match input {
+                            BrilligInputs::Single(expr) => expression_witnesses(expr).collect(),
+                            BrilligInputs::Array(exprs) => {
+                                exprs.iter().flat_map(expression_witnesses).collect()
+                            }
+                            BrilligInputs::MemoryArray(block_id) => {
+                                memory_blocks.get(block_id).cloned().unwrap_or_default()
+                            }
+                        }
+                    })
+                    .collect();
+                let outputs = outputs
+                    .iter()
+                    .flat_map(|output| match output {
+                        BrilligOutputs::Simple(witness) => vec![*witness],
+                        BrilligOutputs::Array(witnesses) => witnesses.clone(),
+                    })
+                    .collect();
+                (inputs, outputs)
+            }
+            Opcode::Call { inputs, outputs, .. } => (inputs.clone(), outputs.clone()),
+        };
+
+        for output in outputs {
+            if known.insert(output) {
+                producers.insert(output, (acir_index, inputs.clone()));
+            }
+        }
+    }
+
+    producers
+}
+
+fn build_witness_dependency(
+    producers: &BTreeMap<Witness, (usize, Vec<Witness>)>,
+    witness: Witness,
+    depth: usize,
+    visited: &mut HashSet<Witness>,
+) -> WitnessDependency {
+    let Some((acir_index, inputs)) = producers.get(&witness) else {
+        return WitnessDependency { witness, acir_index: None, dependencies: vec![] };
+    };
+    let dependencies = if depth > 0 && visited.insert(witness) {
+        inputs
+            .iter()
+            .map(|input| build_witness_dependency(producers, *input, depth - 1, visited))
+            .collect()
+    } else {
+        vec![]
+    };
+    WitnessDependency { witness, acir_index: Some(*acir_index), dependencies }
+}
+
+// TODO: update all debugger tests to use unconstrained brillig pointers
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::foreign_calls::DefaultDebugForeignCallExecutor;
+    use acvm::{
+        acir::{
+            circuit::{
+                brillig::{BrilligInputs, BrilligOutputs},
+                opcodes::{BlackBoxFuncCall, BlockId, FunctionInput},
+            },
+            native_types::Expression,
+            AcirField,
+        },
+        blackbox_solver::StubbedBlackBoxSolver,
+        brillig_vm::brillig::{
+            BinaryFieldOp, HeapValueType, MemoryAddress, Opcode as BrilligOpcode, ValueOrArray,
+        },
+    };
+    use noirc_errors::debug_info::{
+        DebugAssertId, DebugFnId, DebugFunction, DebugInfo, DebugTypeId, DebugVariable,
+    };
+    use noirc_errors::Span;
+    use noirc_printable_type::PrintableValue;
+
+    /// Builds a program whose Brillig function decrements `_1` down to zero,
+    /// running the loop body at Brillig index 5 once per iteration
+    fn countdown_loop_program() -> (Circuit<FieldElement>, Vec<BrilligBytecode<FieldElement>>) {
+        let fe_0 = FieldElement::zero();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+
+        let brillig_bytecode = BrilligBytecode {
+            bytecode: vec![
+                BrilligOpcode::CalldataCopy {
+                    destination_address: MemoryAddress(0),
+                    size: 1,
+                    offset: 0,
+                },
+                BrilligOpcode::Const {
+                    destination: MemoryAddress::from(1),
+                    value: fe_1,
+                    bit_size: FieldElement::max_num_bits(),
+                },
+                BrilligOpcode::Const {
+                    destination: MemoryAddress::from(2),
+                    value: fe_0,
+                    bit_size: FieldElement::max_num_bits(),
+                },
+                BrilligOpcode::BinaryFieldOp {
+                    destination: MemoryAddress::from(3),
+                    op: BinaryFieldOp::Equals,
+                    lhs: MemoryAddress::from(0),
+                    rhs: MemoryAddress::from(2),
+                },
+                BrilligOpcode::JumpIf { condition: MemoryAddress::from(3), location: 7 },
+                BrilligOpcode::BinaryFieldOp {
+                    destination: MemoryAddress::from(0),
+                    op: BinaryFieldOp::Sub,
+                    lhs: MemoryAddress::from(0),
+                    rhs: MemoryAddress::from(1),
+                },
+                BrilligOpcode::Jump { location: 3 },
+                BrilligOpcode::Stop { return_data_offset: 0, return_data_size: 0 },
+            ],
+        };
+        let opcodes = vec![Opcode::BrilligCall {
+            id: 0,
+            inputs: vec![BrilligInputs::Single(Expression {
+                linear_combinations: vec![(fe_1, w_x)],
+                ..Expression::default()
+            })],
+            outputs: vec![],
+            predicate: None,
+        }];
+        let circuit = Circuit { current_witness_index: 1, opcodes, ..Circuit::default() };
+        (circuit, vec![brillig_bytecode])
+    }
+
+    #[test]
+    fn test_resolve_foreign_calls_stepping_into_brillig() {
+        let fe_0 = FieldElement::zero();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+
+        let brillig_bytecode = BrilligBytecode {
+            bytecode: vec![
+                BrilligOpcode::CalldataCopy {
                     destination_address: MemoryAddress(0),
                     size: 1,
                     offset: 0,
@@ -681,7 +3189,7 @@ mod tests {
         let file_map = BTreeMap::new();
         let debug_artifact = &DebugArtifact { debug_symbols, file_map };
 
-        let initial_witness = BTreeMap::from([(Witness(1), fe_1)]).into();
+        let initial_witness: WitnessMap<FieldElement> = BTreeMap::from([(Witness(1), fe_1)]).into();
 
         let foreign_call_executor =
             Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
@@ -689,7 +3197,7 @@ mod tests {
             &StubbedBlackBoxSolver,
             circuit,
             debug_artifact,
-            initial_witness,
+            initial_witness.clone(),
             foreign_call_executor,
             brillig_funcs,
         );
@@ -732,6 +3240,20 @@ mod tests {
         let result = context.step_into_opcode();
         assert!(matches!(result, DebugCommandResult::Done));
         assert_eq!(context.get_current_opcode_location(), None);
+
+        // the foreign call was checkpointed and execution can go back to it
+        assert_eq!(
+            context.get_foreign_call_checkpoints(),
+            &[ForeignCallCheckpoint { function: "clear_mock".into(), executed_opcodes: 2 }]
+        );
+        let result = context.rewind_to_foreign_call(initial_witness.clone(), 1);
+        assert!(matches!(result, Ok(DebugCommandResult::Ok)));
+        assert_eq!(
+            context.get_current_opcode_location(),
+            Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 2 })
+        );
+        assert_eq!(context.get_foreign_call_checkpoints().len(), 1);
+        assert!(context.rewind_to_foreign_call(initial_witness, 2).is_err());
     }
 
     #[test]
@@ -885,6 +3407,9 @@ mod tests {
 
         // check edge cases
         assert_eq!(None, context.address_to_opcode_location(8));
+        assert_eq!(8, context.get_address_count());
+        assert!(context.is_valid_debug_location(&OpcodeLocation::Acir(3)));
+        assert!(!context.is_valid_debug_location(&OpcodeLocation::Acir(4)));
         assert_eq!(
             0,
             context.opcode_location_to_address(&OpcodeLocation::Brillig {
@@ -900,4 +3425,1319 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_witness_dependencies() {
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+        let w_r = Witness(4);
+
+        let opcodes = vec![
+            // z = x + y
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x), (fe_1, w_y), (-fe_1, w_z)],
+                q_c: FieldElement::zero(),
+            }),
+            // r = z * z
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![(fe_1, w_z, w_z)],
+                linear_combinations: vec![(-fe_1, w_r)],
+                q_c: FieldElement::zero(),
+            }),
+        ];
+        let circuit = Circuit {
+            current_witness_index: 4,
+            opcodes,
+            private_parameters: BTreeSet::from([w_x, w_y]),
+            ..Circuit::default()
+        };
+        let debug_artifact = DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+        let context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            &circuit,
+            &debug_artifact,
+            WitnessMap::new(),
+            Box::new(DefaultDebugForeignCallExecutor::new(true)),
+            &[],
+        );
+
+        let input = |witness| WitnessDependency { witness, acir_index: None, dependencies: vec![] };
+        assert_eq!(
+            context.get_witness_dependencies(w_r, 5),
+            WitnessDependency {
+                witness: w_r,
+                acir_index: Some(1),
+                dependencies: vec![WitnessDependency {
+                    witness: w_z,
+                    acir_index: Some(0),
+                    dependencies: vec![input(w_x), input(w_y)],
+                }],
+            }
+        );
+
+        // dependencies past the maximum depth are not expanded
+        assert_eq!(
+            context.get_witness_dependencies(w_r, 1),
+            WitnessDependency {
+                witness: w_r,
+                acir_index: Some(1),
+                dependencies: vec![WitnessDependency {
+                    witness: w_z,
+                    acir_index: Some(0),
+                    dependencies: vec![],
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn test_brillig_limits_stop_execution() {
+        let brillig_bytecode = BrilligBytecode {
+            bytecode: vec![
+                BrilligOpcode::Const {
+                    destination: MemoryAddress::from(9),
+                    value: FieldElement::one(),
+                    bit_size: FieldElement::max_num_bits(),
+                },
+                BrilligOpcode::Stop { return_data_offset: 0, return_data_size: 0 },
+            ],
+        };
+        let opcodes =
+            vec![Opcode::BrilligCall { id: 0, inputs: vec![], outputs: vec![], predicate: None }];
+        let circuit = &Circuit { opcodes, ..Circuit::default() };
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+        let brillig_funcs = &vec![brillig_bytecode];
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            WitnessMap::new(),
+            Box::new(DefaultDebugForeignCallExecutor::new(true)),
+            brillig_funcs,
+        );
+        context.set_brillig_limits(BrilligLimits { max_memory: Some(5), max_depth: None });
+
+        // writing to cell 9 grows the memory to 10 cells
+        assert!(matches!(context.cont(), DebugCommandResult::Error(_)));
+        assert_eq!(
+            context.get_exceeded_brillig_limit(),
+            Some("Brillig memory grew to 10 cells, over the limit of 5")
+        );
+        assert_eq!(
+            context.get_current_opcode_location(),
+            Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 1 })
+        );
+
+        // raising the limit lets execution go on
+        context.set_brillig_limits(BrilligLimits { max_memory: Some(10), max_depth: None });
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.get_exceeded_brillig_limit(), None);
+    }
+
+    #[test]
+    fn test_jump_skips_and_reruns_opcodes() {
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+        let w_r = Witness(4);
+
+        let opcodes = vec![
+            // z = x + y
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x), (fe_1, w_y), (-fe_1, w_z)],
+                q_c: FieldElement::zero(),
+            }),
+            // r = z * z
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![(fe_1, w_z, w_z)],
+                linear_combinations: vec![(-fe_1, w_r)],
+                q_c: FieldElement::zero(),
+            }),
+        ];
+        let circuit = Circuit {
+            current_witness_index: 4,
+            opcodes,
+            private_parameters: BTreeSet::from([w_x, w_y]),
+            ..Circuit::default()
+        };
+        let debug_artifact = DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+        let initial_witness =
+            BTreeMap::from([(w_x, FieldElement::from(2u128)), (w_y, FieldElement::from(3u128))]);
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            &circuit,
+            &debug_artifact,
+            initial_witness.into(),
+            Box::new(DefaultDebugForeignCallExecutor::new(true)),
+            &[],
+        );
+
+        // skipping the opcode solving z leaves the next one unsolvable
+        assert_eq!(context.jump_to(OpcodeLocation::Acir(1)), Ok(()));
+        assert!(matches!(context.step_acir_opcode(), DebugCommandResult::Error(_)));
+
+        // jumping back re-runs it, and execution can go on from the failure
+        assert_eq!(context.jump_to(OpcodeLocation::Acir(0)), Ok(()));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.get_witness_map().get(&w_r), Some(&FieldElement::from(25u128)));
+
+        assert!(context.jump_to(OpcodeLocation::Acir(2)).is_err());
+        let brillig_location = OpcodeLocation::Brillig { acir_index: 0, brillig_index: 0 };
+        assert!(context.jump_to(brillig_location).is_err());
+    }
+
+    #[test]
+    fn test_set_variable_in_brillig_memory() {
+        let fe_0 = FieldElement::zero();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+
+        let brillig_bytecode = BrilligBytecode {
+            bytecode: vec![
+                BrilligOpcode::CalldataCopy {
+                    destination_address: MemoryAddress(0),
+                    size: 1,
+                    offset: 0,
+                },
+                BrilligOpcode::Const {
+                    destination: MemoryAddress::from(1),
+                    value: fe_0,
+                    bit_size: 32,
+                },
+                BrilligOpcode::ForeignCall {
+                    function: "__debug_fn_enter".into(),
+                    destinations: vec![],
+                    destination_value_types: vec![],
+                    inputs: vec![ValueOrArray::MemoryAddress(MemoryAddress::from(1))],
+                    input_value_types: vec![HeapValueType::field()],
+                },
+                BrilligOpcode::ForeignCall {
+                    function: "__debug_var_assign".into(),
+                    destinations: vec![],
+                    destination_value_types: vec![],
+                    inputs: vec![
+                        ValueOrArray::MemoryAddress(MemoryAddress::from(1)),
+                        ValueOrArray::MemoryAddress(MemoryAddress::from(0)),
+                    ],
+                    input_value_types: vec![HeapValueType::field(), HeapValueType::field()],
+                },
+                BrilligOpcode::Stop { return_data_offset: 0, return_data_size: 0 },
+            ],
+        };
+        let opcodes = vec![Opcode::BrilligCall {
+            id: 0,
+            inputs: vec![BrilligInputs::Single(Expression {
+                linear_combinations: vec![(fe_1, w_x)],
+                ..Expression::default()
+            })],
+            outputs: vec![],
+            predicate: None,
+        }];
+        let brillig_funcs = &vec![brillig_bytecode];
+        let circuit = &Circuit { current_witness_index: 1, opcodes, ..Circuit::default() };
+
+        let debug_symbols = vec![DebugInfo::new(
+            BTreeMap::default(),
+            BTreeMap::from([(
+                DebugVarId(0),
+                DebugVariable { name: "x".to_string(), debug_type_id: DebugTypeId(0) },
+            )]),
+            BTreeMap::from([(
+                DebugFnId(0),
+                DebugFunction { name: "main".to_string(), arg_names: vec!["x".to_string()] },
+            )]),
+            BTreeMap::from([(DebugTypeId(0), PrintableType::Field)]),
+            BTreeMap::default(),
+        )];
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map: BTreeMap::new() };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1)]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        // No stack frame has been entered yet
+        assert!(context.set_variable("x", fe_0).is_err());
+
+        // Run until right after the variable assignment has been resolved
+        while context.get_current_opcode_location()
+            != Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 4 })
+        {
+            let result = context.step_into_opcode();
+            assert!(matches!(result, DebugCommandResult::Ok));
+        }
+
+        // writes to the cell of a tracked variable, past the end of memory or
+        // of values that don't fit the bit size are flagged
+        let warnings = context.check_brillig_memory_write(0, fe_1, FieldElement::max_num_bits());
+        assert_eq!(warnings.unwrap().len(), 1);
+        let warnings = context.check_brillig_memory_write(1000, fe_1, FieldElement::max_num_bits());
+        assert_eq!(warnings.unwrap().len(), 1);
+        assert!(context.check_brillig_memory_write(0, FieldElement::from(256u128), 8).is_err());
+        // as are bit sizes of no integer type
+        assert!(context.check_brillig_memory_write(0, fe_1, 7).is_err());
+        assert!(context.check_brillig_memory_write(0, fe_1, 200).is_err());
+        assert!(context.check_brillig_memory_write(0, fe_1, 128).is_ok());
+
+        let new_value = FieldElement::from(42u128);
+        assert_eq!(context.set_variable("x", new_value), Ok(true));
+        assert_eq!(context.get_brillig_memory().unwrap()[0], MemoryValue::new_field(new_value));
+
+        let frame = context.current_stack_frame().unwrap();
+        assert_eq!(frame.variables.len(), 1);
+        assert_eq!(*frame.variables[0].1, PrintableValue::Field(new_value));
+
+        // once the cell holds something else only the tracked value is updated
+        context.write_brillig_memory(0, fe_0, FieldElement::max_num_bits());
+        assert_eq!(context.set_variable("x", fe_1), Ok(false));
+        assert_eq!(context.get_brillig_memory().unwrap()[0], MemoryValue::new_field(fe_0));
+
+        assert!(context.set_variable("y", new_value).is_err());
+    }
+
+    #[test]
+    fn test_breakpoint_ignore_count() {
+        let w_x = Witness(1);
+        let (circuit, brillig_funcs) = countdown_loop_program();
+        let circuit = &circuit;
+        let brillig_funcs = &brillig_funcs;
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+
+        let initial_witness = BTreeMap::from([(w_x, FieldElement::from(3u128))]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        // break on the loop body, skipping its first execution
+        let breakpoint_location = OpcodeLocation::Brillig { acir_index: 0, brillig_index: 5 };
+        assert!(context.add_breakpoint(breakpoint_location));
+        let id = context.get_breakpoint_at(&breakpoint_location).unwrap().id;
+        assert!(context.set_breakpoint_ignore_count(id, 1));
+        assert!(!context.set_breakpoint_ignore_count(id + 1, 1));
+
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        let breakpoint = context.get_breakpoint_at(&breakpoint_location).unwrap();
+        assert_eq!(breakpoint.hit_count, 2);
+        assert_eq!(breakpoint.ignore_count, 0);
+
+        // the third iteration stops again, and then execution finishes
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::Done));
+        assert_eq!(context.get_breakpoint_at(&breakpoint_location).unwrap().hit_count, 3);
+
+        // restarting keeps the breakpoint but resets its hit count
+        context.restart(BTreeMap::from([(w_x, FieldElement::from(3u128))]).into());
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(0)));
+        assert_eq!(context.get_breakpoint_at(&breakpoint_location).unwrap().hit_count, 0);
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.get_breakpoint_at(&breakpoint_location).unwrap().hit_count, 1);
+
+        // a hit count carried over to a reinstalled breakpoint keeps counting
+        context.clear_breakpoints();
+        assert!(context.add_breakpoint(breakpoint_location));
+        let id = context.get_breakpoint_at(&breakpoint_location).unwrap().id;
+        assert!(context.set_breakpoint_hit_count(id, 1));
+        assert!(!context.set_breakpoint_hit_count(id + 1, 1));
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.get_breakpoint_at(&breakpoint_location).unwrap().hit_count, 2);
+
+        // stepping back replays the hits without consuming the ignore count
+        assert!(context.set_breakpoint_ignore_count(id, 5));
+        context.step_back_opcode(BTreeMap::from([(w_x, FieldElement::from(3u128))]).into());
+        assert_eq!(context.get_breakpoint_at(&breakpoint_location).unwrap().ignore_count, 5);
+    }
+
+    #[test]
+    fn test_conditional_breakpoint() {
+        let w_x = Witness(1);
+        let (circuit, brillig_funcs) = countdown_loop_program();
+        let circuit = &circuit;
+        let brillig_funcs = &brillig_funcs;
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+
+        let initial_witness = BTreeMap::from([(w_x, FieldElement::from(3u128))]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        // break on the loop body when a condition on the hit count and on a
+        // witness holds, which skips the second iteration
+        let breakpoint_location = OpcodeLocation::Brillig { acir_index: 0, brillig_index: 5 };
+        assert!(context.add_breakpoint(breakpoint_location));
+        let id = context.get_breakpoint_at(&breakpoint_location).unwrap().id;
+        let condition = "hits % 2 == 1 && _1 == 3".parse().unwrap();
+        assert!(context.set_breakpoint_condition(id, Some(condition)));
+
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.get_breakpoint_at(&breakpoint_location).unwrap().hit_count, 1);
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.get_breakpoint_at(&breakpoint_location).unwrap().hit_count, 3);
+
+        // conditions that cannot be evaluated stop execution with a warning
+        let condition = "missing > 1".parse().unwrap();
+        assert!(context.set_breakpoint_condition(id, Some(condition)));
+        context.restart(BTreeMap::from([(w_x, FieldElement::from(3u128))]).into());
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.take_debug_warnings().len(), 1);
+    }
+
+    #[test]
+    fn test_breakpoint_snapshots_are_recorded_on_every_hit() {
+        let w_x = Witness(1);
+        let (circuit, brillig_funcs) = countdown_loop_program();
+        let circuit = &circuit;
+        let brillig_funcs = &brillig_funcs;
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+
+        let initial_witness = BTreeMap::from([(w_x, FieldElement::from(3u128))]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        // record x on the loop body, including the ignored first hit
+        let breakpoint_location = OpcodeLocation::Brillig { acir_index: 0, brillig_index: 5 };
+        assert!(context.add_breakpoint(breakpoint_location));
+        let id = context.get_breakpoint_at(&breakpoint_location).unwrap().id;
+        assert!(context.set_breakpoint_ignore_count(id, 1));
+        assert!(context.set_breakpoint_snapshot(id, vec!["x".to_string()]));
+        assert!(!context.set_breakpoint_snapshot(id + 1, vec!["x".to_string()]));
+
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        let snapshots = context.get_breakpoint_snapshots();
+        assert_eq!(snapshots.iter().map(|snapshot| snapshot.hit).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(snapshots.iter().all(
+            |snapshot| snapshot.breakpoint_id == id && snapshot.location == breakpoint_location
+        ));
+        // without instrumentation there are no variables in scope
+        assert_eq!(
+            snapshots[0].values,
+            BTreeMap::from([("x".to_string(), "<unknown>".to_string())])
+        );
+
+        // snapshots are kept across restarts, tagged with the run taking them
+        context.restart(BTreeMap::from([(w_x, FieldElement::from(3u128))]).into());
+        assert_eq!(context.get_breakpoint_snapshots().len(), 2);
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        let snapshots = context.get_breakpoint_snapshots();
+        assert_eq!(
+            snapshots.iter().map(|snapshot| (snapshot.run, snapshot.hit)).collect::<Vec<_>>(),
+            vec![(1, 1), (1, 2), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn test_logpoint_prints_without_stopping() {
+        let fe_0 = FieldElement::zero();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+
+        let brillig_bytecode = BrilligBytecode {
+            bytecode: vec![
+                BrilligOpcode::CalldataCopy {
+                    destination_address: MemoryAddress(0),
+                    size: 1,
+                    offset: 0,
+                },
+                BrilligOpcode::Const {
+                    destination: MemoryAddress::from(1),
+                    value: fe_0,
+                    bit_size: 32,
+                },
+                BrilligOpcode::ForeignCall {
+                    function: "__debug_fn_enter".into(),
+                    destinations: vec![],
+                    destination_value_types: vec![],
+                    inputs: vec![ValueOrArray::MemoryAddress(MemoryAddress::from(1))],
+                    input_value_types: vec![HeapValueType::field()],
+                },
+                BrilligOpcode::ForeignCall {
+                    function: "__debug_var_assign".into(),
+                    destinations: vec![],
+                    destination_value_types: vec![],
+                    inputs: vec![
+                        ValueOrArray::MemoryAddress(MemoryAddress::from(1)),
+                        ValueOrArray::MemoryAddress(MemoryAddress::from(0)),
+                    ],
+                    input_value_types: vec![HeapValueType::field(), HeapValueType::field()],
+                },
+                BrilligOpcode::Stop { return_data_offset: 0, return_data_size: 0 },
+            ],
+        };
+        let opcodes = vec![Opcode::BrilligCall {
+            id: 0,
+            inputs: vec![BrilligInputs::Single(Expression {
+                linear_combinations: vec![(fe_1, w_x)],
+                ..Expression::default()
+            })],
+            outputs: vec![],
+            predicate: None,
+        }];
+        let brillig_funcs = &vec![brillig_bytecode];
+        let circuit = &Circuit { current_witness_index: 1, opcodes, ..Circuit::default() };
+
+        let debug_symbols = vec![DebugInfo::new(
+            BTreeMap::default(),
+            BTreeMap::from([(
+                DebugVarId(0),
+                DebugVariable { name: "x".to_string(), debug_type_id: DebugTypeId(0) },
+            )]),
+            BTreeMap::from([(
+                DebugFnId(0),
+                DebugFunction { name: "main".to_string(), arg_names: vec!["x".to_string()] },
+            )]),
+            BTreeMap::from([(DebugTypeId(0), PrintableType::Field)]),
+            BTreeMap::default(),
+        )];
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map: BTreeMap::new() };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1)]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        // Log right after the variable assignment has been resolved
+        let location = OpcodeLocation::Brillig { acir_index: 0, brillig_index: 4 };
+        assert!(context.add_logpoint(location, "x = {x}, y = {y} {".to_string()));
+        assert!(!context.add_logpoint(location, "again".to_string()));
+
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::Done));
+        assert_eq!(context.take_log_output(), vec!["x = 0x01, y = <unknown y> {"]);
+        assert!(context.take_log_output().is_empty());
+        assert_eq!(context.get_breakpoint_at(&location).unwrap().hit_count, 1);
+    }
+
+    #[test]
+    fn test_replay_to_saved_point() {
+        let w_x = Witness(1);
+        let (circuit, brillig_funcs) = countdown_loop_program();
+        let circuit = &circuit;
+        let brillig_funcs = &brillig_funcs;
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+
+        let initial_witness = BTreeMap::from([(w_x, FieldElement::from(3u128))]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        // stop in the second iteration of the loop body
+        for _ in 0..9 {
+            let result = context.step_into_opcode();
+            assert!(matches!(result, DebugCommandResult::Ok));
+        }
+        let location = context.get_current_opcode_location();
+        let brillig_steps = context.get_brillig_steps();
+        let memory = context.get_brillig_memory().unwrap().to_vec();
+        assert_eq!(location, Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 5 }));
+
+        context.restart(BTreeMap::from([(w_x, FieldElement::from(3u128))]).into());
+        let result = context.replay_to(location, brillig_steps);
+        assert!(matches!(result, DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), location);
+        assert_eq!(context.get_brillig_steps(), brillig_steps);
+        assert_eq!(context.get_brillig_memory().unwrap(), memory.as_slice());
+
+        // without a location, the replay runs until execution finishes
+        let result = context.replay_to(None, 0);
+        assert!(matches!(result, DebugCommandResult::Done));
+    }
+
+    #[test]
+    fn test_interrupt_stops_multi_step_commands() {
+        let w_x = Witness(1);
+        let (circuit, brillig_funcs) = countdown_loop_program();
+        let circuit = &circuit;
+        let brillig_funcs = &brillig_funcs;
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+        let interrupt = AtomicBool::new(false);
+
+        let initial_witness = BTreeMap::from([(w_x, FieldElement::from(3u128))]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+        context.set_interrupt_flag(&interrupt);
+
+        // without debug symbols there is no source location to stop at, so
+        // each of these would otherwise run the whole program
+        interrupt.store(true, Ordering::SeqCst);
+        assert!(matches!(context.next_into(), DebugCommandResult::Ok));
+        assert_eq!(context.get_executed_opcodes(), 1);
+        assert!(matches!(context.next_over(), DebugCommandResult::Ok));
+        assert!(matches!(context.next_out(), DebugCommandResult::Ok));
+        assert!(matches!(context.step_acir_opcode(), DebugCommandResult::Ok));
+        assert!(matches!(context.cont(), DebugCommandResult::Ok));
+        assert_eq!(context.get_executed_opcodes(), 5);
+        assert_eq!(
+            context.get_current_opcode_location(),
+            Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 4 })
+        );
+
+        // once the flag is lowered stepping runs to the end again
+        interrupt.store(false, Ordering::SeqCst);
+        assert!(matches!(context.next_into(), DebugCommandResult::Done));
+    }
+
+    #[test]
+    fn test_profile_stops_after_max_opcodes() {
+        let w_x = Witness(1);
+        let (circuit, brillig_funcs) = countdown_loop_program();
+        let circuit = &circuit;
+        let brillig_funcs = &brillig_funcs;
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+
+        let initial_witness: WitnessMap<FieldElement> =
+            BTreeMap::from([(w_x, FieldElement::from(3u128))]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness.clone(),
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        let profile = context.profile(
+            initial_witness.clone(),
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(false, debug_artifact)),
+            5,
+        );
+        assert!(matches!(profile.result, DebugCommandResult::Ok));
+        assert_eq!(profile.executed_opcodes, 5);
+
+        let profile = context.profile(
+            initial_witness,
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(false, debug_artifact)),
+            usize::MAX,
+        );
+        assert!(matches!(profile.result, DebugCommandResult::Done));
+        assert!(profile.executed_opcodes > 5);
+
+        // the session itself is left where it was
+        assert_eq!(context.get_executed_opcodes(), 0);
+    }
+
+    #[test]
+    fn test_divergence_from_previous_run_is_detected() {
+        let w_x = Witness(1);
+        let (circuit, brillig_funcs) = countdown_loop_program();
+        let circuit = &circuit;
+        let brillig_funcs = &brillig_funcs;
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+
+        let witness_with = |x: u128| BTreeMap::from([(w_x, FieldElement::from(x))]).into();
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            witness_with(3),
+            foreign_call_executor,
+            brillig_funcs,
+        );
+        context.set_check_determinism(true);
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+
+        // the same run does not diverge
+        context.restart(witness_with(3));
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.take_divergence(), None);
+
+        // leaving the loop earlier stops execution where the runs differ
+        context.restart(witness_with(2));
+        let result = context.cont();
+        let leave_loop = OpcodeLocation::Brillig { acir_index: 0, brillig_index: 7 };
+        assert!(
+            matches!(result, DebugCommandResult::BreakpointReached(location) if location == leave_loop)
+        );
+        let divergence = context.take_divergence().unwrap();
+        assert_eq!(
+            divergence.expected.location,
+            OpcodeLocation::Brillig { acir_index: 0, brillig_index: 5 }
+        );
+        assert_eq!(divergence.actual, TraceStep { location: leave_loop, call: None });
+        assert_eq!(divergence.last_call, None);
+
+        // only the first divergence is reported
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        assert_eq!(context.take_divergence(), None);
+    }
+
+    #[test]
+    fn test_function_calls_are_counted() {
+        let fe_0 = FieldElement::zero();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+
+        let brillig_bytecode = BrilligBytecode {
+            bytecode: vec![
+                BrilligOpcode::CalldataCopy {
+                    destination_address: MemoryAddress(0),
+                    size: 1,
+                    offset: 0,
+                },
+                BrilligOpcode::Const {
+                    destination: MemoryAddress::from(1),
+                    value: fe_0,
+                    bit_size: 32,
+                },
+                BrilligOpcode::ForeignCall {
+                    function: "__debug_fn_enter".into(),
+                    destinations: vec![],
+                    destination_value_types: vec![],
+                    inputs: vec![ValueOrArray::MemoryAddress(MemoryAddress::from(1))],
+                    input_value_types: vec![HeapValueType::field()],
+                },
+                BrilligOpcode::ForeignCall {
+                    function: "__debug_var_assign".into(),
+                    destinations: vec![],
+                    destination_value_types: vec![],
+                    inputs: vec![
+                        ValueOrArray::MemoryAddress(MemoryAddress::from(1)),
+                        ValueOrArray::MemoryAddress(MemoryAddress::from(0)),
+                    ],
+                    input_value_types: vec![HeapValueType::field(), HeapValueType::field()],
+                },
+                BrilligOpcode::Stop { return_data_offset: 0, return_data_size: 0 },
+            ],
+        };
+        let opcodes = vec![Opcode::BrilligCall {
+            id: 0,
+            inputs: vec![BrilligInputs::Single(Expression {
+                linear_combinations: vec![(fe_1, w_x)],
+                ..Expression::default()
+            })],
+            outputs: vec![],
+            predicate: None,
+        }];
+        let brillig_funcs = &vec![brillig_bytecode];
+        let circuit = &Circuit { current_witness_index: 1, opcodes, ..Circuit::default() };
+
+        let debug_symbols = vec![DebugInfo::new(
+            BTreeMap::default(),
+            BTreeMap::from([(
+                DebugVarId(0),
+                DebugVariable { name: "x".to_string(), debug_type_id: DebugTypeId(0) },
+            )]),
+            BTreeMap::from([(
+                DebugFnId(0),
+                DebugFunction { name: "main".to_string(), arg_names: vec!["x".to_string()] },
+            )]),
+            BTreeMap::from([(DebugTypeId(0), PrintableType::Field)]),
+            BTreeMap::default(),
+        )];
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map: BTreeMap::new() };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1)]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        assert!(context.get_function_calls().is_empty());
+
+        // Step until right after entering main
+        while context.get_current_opcode_location()
+            != Some(OpcodeLocation::Brillig { acir_index: 0, brillig_index: 3 })
+        {
+            let result = context.step_into_opcode();
+            assert!(matches!(result, DebugCommandResult::Ok));
+        }
+        let calls = context.get_function_calls();
+        // resuming the enter oracle call counts as a step within main
+        assert_eq!(calls.get("main"), Some(&FunctionCalls { calls: 1, opcodes: 1 }));
+
+        // main never exits, so its opcodes are counted up to the current point
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::Done));
+        let calls = context.get_function_calls();
+        assert_eq!(calls["main"].calls, 1);
+        assert!(calls["main"].opcodes > 0);
+
+        context.restart(BTreeMap::from([(w_x, fe_1)]).into());
+        assert!(context.get_function_calls().is_empty());
+
+        // a function breakpoint stops right after entering main
+        let function_names = context.get_function_names();
+        assert_eq!(function_names, BTreeSet::from(["main".to_string()]));
+        context.set_function_breakpoints(function_names);
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.get_function_calls()["main"].calls, 1);
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::Done));
+    }
+
+    #[test]
+    fn test_find_opcode_for_source_column() {
+        let source = "fn main(x: Field) {\n    foo(bar(x));\n}\n";
+        let file_id = FileId::dummy();
+        let file_map = BTreeMap::from([(
+            file_id,
+            DebugFile { source: source.to_string(), path: "main.nr".into() },
+        )]);
+        // `bar(x)` and the enclosing `foo(bar(x))` on line 2
+        let bar_call = Location::new(Span::from(28..34), file_id);
+        let foo_call = Location::new(Span::from(24..35), file_id);
+        let opcode_locations = BTreeMap::from([
+            (OpcodeLocation::Acir(0), vec![bar_call]),
+            (OpcodeLocation::Acir(1), vec![foo_call]),
+        ]);
+        let debug_symbols = vec![DebugInfo::new(
+            opcode_locations,
+            BTreeMap::default(),
+            BTreeMap::default(),
+            BTreeMap::default(),
+            BTreeMap::default(),
+        )];
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let circuit = &Circuit::default();
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let brillig_funcs = &vec![];
+        let context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            WitnessMap::new(),
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        assert_eq!(
+            context.find_opcode_for_source_column(&file_id, 2, 5),
+            Some(OpcodeLocation::Acir(1))
+        );
+        assert_eq!(
+            context.find_opcode_for_source_column(&file_id, 2, 9),
+            Some(OpcodeLocation::Acir(0))
+        );
+        assert_eq!(context.find_opcode_for_source_column(&file_id, 2, 1), None);
+        assert_eq!(
+            context.find_opcode_for_source_location(&file_id, 2),
+            Some(OpcodeLocation::Acir(0))
+        );
+
+        assert_eq!(context.get_breakpoint_positions(&file_id, 1..=3), vec![(2, 5), (2, 9)]);
+        assert!(context.get_breakpoint_positions(&file_id, 3..=3).is_empty());
+    }
+
+    #[test]
+    fn test_debug_locations_skip_instrumentation() {
+        let file_id = FileId::dummy();
+        let file_map = BTreeMap::from([(
+            file_id,
+            DebugFile {
+                source: String::from("fn __debug_var_assign() {}"),
+                path: "__debug/lib.nr".into(),
+            },
+        )]);
+        let instrumentation = Location::new(Span::from(0..10), file_id);
+        let opcode_locations = BTreeMap::from([
+            (OpcodeLocation::Acir(0), vec![instrumentation]),
+            (OpcodeLocation::Acir(1), vec![instrumentation]),
+        ]);
+        let debug_symbols = vec![DebugInfo::new(
+            opcode_locations,
+            BTreeMap::default(),
+            BTreeMap::default(),
+            BTreeMap::default(),
+            BTreeMap::default(),
+        )];
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let opcodes = vec![
+            Opcode::AssertZero(Expression::default()),
+            Opcode::AssertZero(Expression::default()),
+            Opcode::AssertZero(Expression::default()),
+        ];
+        let circuit = &Circuit { opcodes, ..Circuit::default() };
+        let brillig_funcs = &vec![];
+        let context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            WitnessMap::new(),
+            Box::new(DefaultDebugForeignCallExecutor::new(true)),
+            brillig_funcs,
+        );
+
+        assert!(!context.is_valid_debug_location(&OpcodeLocation::Acir(0)));
+        assert!(context.is_valid_debug_location(&OpcodeLocation::Acir(2)));
+        assert_eq!(
+            context.find_debug_location_from(&OpcodeLocation::Acir(0)),
+            Some(OpcodeLocation::Acir(2))
+        );
+        assert_eq!(
+            context.find_debug_location_from(&OpcodeLocation::Acir(2)),
+            Some(OpcodeLocation::Acir(2))
+        );
+        assert_eq!(context.find_debug_location_from(&OpcodeLocation::Acir(3)), None);
+    }
+
+    #[test]
+    fn test_brillig_black_box_operands_are_hashed_by_value() {
+        let field = |value: u128| MemoryValue::new_field(FieldElement::from(value));
+        // Two vectors of length 2 holding the same values at 4 and 6, and a
+        // third one at 8 holding different values
+        let memory: Vec<MemoryValue<FieldElement>> = vec![
+            MemoryValue::from(4usize),
+            MemoryValue::from(6usize),
+            MemoryValue::from(8usize),
+            MemoryValue::from(2usize),
+            field(1),
+            field(2),
+            field(1),
+            field(2),
+            field(3),
+            field(4),
+        ];
+        let vector = |pointer: usize| {
+            BrilligOperand::Vector(HeapVector {
+                pointer: MemoryAddress(pointer),
+                size: MemoryAddress(3),
+            })
+        };
+        let hash = |operand| hash_brillig_operands(&memory, &[operand]);
+        assert_eq!(hash(vector(0)), hash(vector(1)));
+        assert_ne!(hash(vector(0)), hash(vector(2)));
+
+        let (name, inputs, outputs) = brillig_black_box_operands(&BlackBoxOp::Sha256 {
+            message: HeapVector { pointer: MemoryAddress(0), size: MemoryAddress(3) },
+            output: HeapArray { pointer: MemoryAddress(2), size: 2 },
+        });
+        assert_eq!(name, "sha256");
+        assert_eq!(hash_brillig_operands(&memory, &inputs), hash(vector(1)));
+        assert_eq!(outputs.len(), 1);
+    }
+
+    #[test]
+    fn test_asserts_are_numbered_and_their_outcomes_recorded() {
+        let source = "fn main(x: Field) {\n    assert(x == 1);\n    assert_eq(\n        x,\n        2\n    );\n    assert(true);\n}\n";
+        let file_id = FileId::dummy();
+        let file_map = BTreeMap::from([(
+            file_id,
+            DebugFile { source: source.to_string(), path: "main.nr".into() },
+        )]);
+        // The condition of the `assert` and an argument of the `assert_eq`,
+        // which spans several lines. The last assert has no opcodes.
+        let assert_condition = Location::new(Span::from(31..37), file_id);
+        let assert_eq_argument = Location::new(Span::from(63..64), file_id);
+        let opcode_locations = BTreeMap::from([
+            (OpcodeLocation::Acir(0), vec![assert_condition]),
+            (OpcodeLocation::Acir(1), vec![assert_eq_argument]),
+        ]);
+        let asserts = BTreeMap::from([
+            (DebugAssertId(1), Location::new(Span::from(24..38), file_id)),
+            (DebugAssertId(2), Location::new(Span::from(44..81), file_id)),
+            (DebugAssertId(3), Location::new(Span::from(87..99), file_id)),
+        ]);
+        let debug_symbols = vec![DebugInfo::new(
+            opcode_locations,
+            BTreeMap::default(),
+            BTreeMap::default(),
+            BTreeMap::default(),
+            asserts,
+        )];
+        let debug_artifact = &DebugArtifact { debug_symbols, file_map };
+
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let opcodes = vec![
+            // x - 1 = 0
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x)],
+                q_c: -fe_1,
+            }),
+            // x - 2 = 0
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x)],
+                q_c: -FieldElement::from(2u128),
+            }),
+        ];
+        let circuit = &Circuit { current_witness_index: 1, opcodes, ..Circuit::default() };
+        let initial_witness = BTreeMap::from([(w_x, fe_1)]).into();
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let brillig_funcs = &vec![];
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        let asserts: Vec<_> = context
+            .get_asserts()
+            .iter()
+            .map(|assert| (assert.id, assert.location.span.start(), assert.first_opcode))
+            .collect();
+        assert_eq!(
+            asserts,
+            vec![
+                (1, 24, Some(OpcodeLocation::Acir(0))),
+                (2, 44, Some(OpcodeLocation::Acir(1))),
+                (3, 87, None)
+            ]
+        );
+        assert_eq!(context.get_assert(2).map(|assert| assert.id), Some(2));
+        assert!(context.get_assert(0).is_none());
+
+        assert!(matches!(context.cont(), DebugCommandResult::Error(_)));
+        assert_eq!(
+            context.get_assert_outcome(1),
+            Some(&AssertOutcome { evaluations: 1, failed: false })
+        );
+        assert_eq!(
+            context.get_assert_outcome(2),
+            Some(&AssertOutcome { evaluations: 1, failed: true })
+        );
+    }
+
+    #[test]
+    fn test_step_back_and_reverse_continue() {
+        let fe_0 = FieldElement::zero();
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let w_z = Witness(3);
+
+        // y = x + 1; z = y + 1; x - y + 1 = 0
+        let opcodes = vec![
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x), (-fe_1, w_y)],
+                q_c: fe_1,
+            }),
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_y), (-fe_1, w_z)],
+                q_c: fe_1,
+            }),
+            Opcode::AssertZero(Expression {
+                mul_terms: vec![],
+                linear_combinations: vec![(fe_1, w_x), (-fe_1, w_y)],
+                q_c: fe_1,
+            }),
+        ];
+        let circuit = &Circuit { current_witness_index: 3, opcodes, ..Circuit::default() };
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+        let initial_witness: WitnessMap<FieldElement> = BTreeMap::from([(w_x, fe_0)]).into();
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let brillig_funcs = &vec![];
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness.clone(),
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        assert!(context.add_breakpoint(OpcodeLocation::Acir(1)));
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::BreakpointReached(_)));
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(2)));
+        assert_eq!(context.get_witness_map().get(&w_z), Some(&FieldElement::from(2u128)));
+
+        let result = context.step_back_opcode(initial_witness.clone());
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(1)));
+        assert_eq!(context.get_witness_map().get(&w_z), None);
+
+        assert!(matches!(context.cont(), DebugCommandResult::Done));
+        let result = context.reverse_continue(initial_witness.clone());
+        assert!(matches!(result, DebugCommandResult::BreakpointReached(_)));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(1)));
+
+        let result = context.reverse_continue(initial_witness);
+        assert!(matches!(result, DebugCommandResult::Ok));
+        assert_eq!(context.get_current_opcode_location(), Some(OpcodeLocation::Acir(0)));
+        assert_eq!(context.get_executed_opcodes(), 0);
+    }
+
+    #[test]
+    fn test_blackbox_calls_are_recorded() {
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+        let range = |witness| {
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                input: FunctionInput { witness, num_bits: 8 },
+            })
+        };
+
+        let opcodes = vec![range(w_x), range(w_y), range(w_x)];
+        let circuit = &Circuit { current_witness_index: 2, opcodes, ..Circuit::default() };
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+
+        let initial_witness =
+            BTreeMap::from([(w_x, FieldElement::from(1u128)), (w_y, FieldElement::from(2u128))])
+                .into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let brillig_funcs = &vec![];
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness.clone(),
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        assert!(matches!(context.step_acir_opcode(), DebugCommandResult::Ok));
+        assert!(matches!(context.step_into_opcode(), DebugCommandResult::Ok));
+        assert!(matches!(context.step_acir_opcode(), DebugCommandResult::Done));
+
+        let calls = context.get_blackbox_calls();
+        assert_eq!(calls.len(), 3);
+        assert!(calls.iter().all(|call| call.name == "range"));
+        assert_eq!(
+            calls.iter().map(|call| call.location).collect::<Vec<_>>(),
+            vec![OpcodeLocation::Acir(0), OpcodeLocation::Acir(1), OpcodeLocation::Acir(2)]
+        );
+        assert_eq!(calls[0].inputs_hash, calls[2].inputs_hash);
+        assert_ne!(calls[0].inputs_hash, calls[1].inputs_hash);
+        assert_eq!(
+            context.get_timeline().events().iter().map(|event| event.step).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(context
+            .get_timeline()
+            .events()
+            .iter()
+            .all(|event| matches!(event.kind, TimelineEventKind::BlackBox { name: "range", .. })));
+
+        context.restart(initial_witness);
+        assert!(context.get_blackbox_calls().is_empty());
+        assert!(context.get_timeline().events().is_empty());
+    }
+
+    #[test]
+    fn test_journal_records_written_witnesses() {
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+
+        // y = x + 1, followed by a range check on y that fails
+        let opcodes = vec![
+            Opcode::AssertZero(Expression {
+                linear_combinations: vec![(fe_1, w_x), (-fe_1, w_y)],
+                q_c: fe_1,
+                ..Expression::default()
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                input: FunctionInput { witness: w_y, num_bits: 1 },
+            }),
+        ];
+        let circuit = &Circuit {
+            current_witness_index: 2,
+            opcodes,
+            private_parameters: BTreeSet::from([w_x]),
+            ..Circuit::default()
+        };
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+
+        let initial_witness = BTreeMap::from([(w_x, fe_1)]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let brillig_funcs = &vec![];
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        context.enable_journal(10);
+        let result = context.cont();
+        assert!(matches!(result, DebugCommandResult::Error(_)));
+
+        let journal: Vec<_> = context.get_journal().cloned().collect();
+        assert_eq!(
+            journal,
+            vec![
+                JournalEntry {
+                    location: OpcodeLocation::Acir(0),
+                    written: vec![(w_y, FieldElement::from(2u128))],
+                },
+                JournalEntry { location: OpcodeLocation::Acir(1), written: vec![] },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_integer_overflow_is_decoded() {
+        let fe_1 = FieldElement::one();
+        let w_x = Witness(1);
+        let w_y = Witness(2);
+
+        // y = x + 1 as an 8-bit addition, with its overflow check
+        let opcodes = vec![
+            Opcode::AssertZero(Expression {
+                linear_combinations: vec![(fe_1, w_x), (-fe_1, w_y)],
+                q_c: fe_1,
+                ..Expression::default()
+            }),
+            Opcode::BlackBoxFuncCall(BlackBoxFuncCall::RANGE {
+                input: FunctionInput { witness: w_y, num_bits: 8 },
+            }),
+        ];
+        let circuit = &Circuit {
+            current_witness_index: 2,
+            opcodes,
+            private_parameters: BTreeSet::from([w_x]),
+            assert_messages: vec![(
+                OpcodeLocation::Acir(1),
+                AssertionPayload::StaticString("attempt to add with overflow".to_string()),
+            )],
+            ..Circuit::default()
+        };
+        let mut debug_info = DebugInfo::default();
+        debug_info.overflow_checks.insert(OpcodeLocation::Acir(1));
+        let debug_artifact =
+            &DebugArtifact { debug_symbols: vec![debug_info], file_map: BTreeMap::new() };
+
+        let initial_witness: WitnessMap<_> =
+            BTreeMap::from([(w_x, FieldElement::from(255u128))]).into();
+
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let brillig_funcs = &vec![];
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness.clone(),
+            foreign_call_executor,
+            brillig_funcs,
+        );
+
+        let DebugCommandResult::Error(error) = context.cont() else {
+            panic!("Expected the overflow check to fail");
+        };
+        assert_eq!(
+            context.get_integer_overflow(&error),
+            Some(IntegerOverflow {
+                message: "attempt to add with overflow".to_string(),
+                location: OpcodeLocation::Acir(1),
+                bit_size: Some(8),
+                value: Some(FieldElement::from(256u128)),
+            })
+        );
+
+        // the failure is reported along with how far execution got
+        let report = context.failure_report(&error).unwrap();
+        assert_eq!(report.location, Some(OpcodeLocation::Acir(1)));
+        assert_eq!(report.solved_witnesses, context.get_witness_map().clone().into_iter().count());
+
+        // an assert of the program failing with the same message is not taken for one
+        let debug_artifact =
+            &DebugArtifact { debug_symbols: vec![DebugInfo::default()], file_map: BTreeMap::new() };
+        let foreign_call_executor =
+            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
+        let mut context = DebugContext::new(
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            initial_witness,
+            foreign_call_executor,
+            brillig_funcs,
+        );
+        let DebugCommandResult::Error(error) = context.cont() else {
+            panic!("Expected the assert to fail");
+        };
+        assert_eq!(context.get_integer_overflow(&error), None);
+    }
 }
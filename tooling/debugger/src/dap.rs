@@ -1,92 +1,267 @@
 use std::collections::BTreeMap;
 use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
-use acvm::acir::circuit::brillig::BrilligBytecode;
-use acvm::acir::circuit::{Circuit, OpcodeLocation};
+use acvm::acir::circuit::{ErrorSelector, Opcode, OpcodeLocation, Program};
 use acvm::acir::native_types::WitnessMap;
+use acvm::pwg::OpcodeResolutionError;
 use acvm::{BlackBoxFunctionSolver, FieldElement};
 
+use crate::completion;
+use crate::conditions::Condition;
 use crate::context::DebugCommandResult;
-use crate::context::DebugContext;
-use crate::foreign_calls::DefaultDebugForeignCallExecutor;
+use crate::context::{is_debug_file_in_debug_crate, DebugContext};
+use crate::dap_input::TIMELINE_COMMAND;
+use crate::dap_server::{send_compile_diagnostics, LoadedProgram, ProgramReloader};
+use crate::disassembly::{brillig_function_symbol, render_brillig_opcode};
+use crate::errors::LoadError;
+use crate::expressions::value_components;
+use crate::foreign_calls::{DefaultDebugForeignCallExecutor, ForeignCallResolverConfig};
+use crate::memory_view::{
+    format_cell_value, patch_memory_cells, read_memory_bytes, CellType, CELL_BYTES,
+};
+use crate::source_paths::PathSubstitutions;
+use crate::timeline::{
+    merge_timeline, page_range, TimelineArguments, TimelineEntry, TimelineEvent, TimelineEventKind,
+    TimelinePage, TimelineSource, TIMELINE_SCHEMA_VERSION,
+};
+use crate::{DebugOutcome, FailureReport};
+use nargo::errors::{ExecutionError, NargoError};
+use nargo::ops::PrintOutput;
 
+use base64::Engine;
 use dap::errors::ServerError;
-use dap::events::StoppedEventBody;
+use dap::events::{BreakpointEventBody, OutputEventBody, StoppedEventBody};
 use dap::prelude::Event;
 use dap::requests::{Command, Request, SetBreakpointsArguments};
 use dap::responses::{
-    ContinueResponse, DisassembleResponse, ResponseBody, ScopesResponse, SetBreakpointsResponse,
-    SetExceptionBreakpointsResponse, SetInstructionBreakpointsResponse, StackTraceResponse,
-    ThreadsResponse, VariablesResponse,
+    BreakpointLocationsResponse, CompletionsResponse, ContinueResponse, DisassembleResponse,
+    EvaluateResponse, ExceptionInfoResponse, GotoTargetsResponse, LoadedSourcesResponse,
+    ModulesResponse, ReadMemoryResponse, ResponseBody, ScopesResponse, SetBreakpointsResponse,
+    SetExceptionBreakpointsResponse, SetFunctionBreakpointsResponse,
+    SetInstructionBreakpointsResponse, SourceResponse, StackTraceResponse, ThreadsResponse,
+    VariablesResponse, WriteMemoryResponse,
 };
 use dap::server::Server;
 use dap::types::{
-    Breakpoint, DisassembledInstruction, Scope, Source, StackFrame, SteppingGranularity,
+    Breakpoint, BreakpointEventReason, BreakpointLocation, CompletionItem, DisassembledInstruction,
+    ExceptionBreakMode, ExceptionDetails, GotoTarget, InstructionBreakpoint, Module, ModuleId,
+    OutputEventCategory, Scope, Source, SourceBreakpoint, StackFrame, SteppingGranularity,
     StoppedEventReason, Thread, Variable,
 };
+use noirc_abi::AbiErrorType;
 use noirc_artifacts::debug::DebugArtifact;
+use noirc_printable_type::{PrintableType, PrintableValue, PrintableValueDisplay};
 
 use fm::FileId;
-use noirc_driver::CompiledProgram;
 
 type BreakpointId = i64;
 
+/// Settings of a DAP session, which carry over to the sessions debugging the
+/// program again after it is recompiled on restart
+#[derive(Clone)]
+pub struct DapSessionOptions<'a> {
+    /// Rewrites of source paths, eg. from the embedded stdlib to a checkout on disk
+    pub path_substitutions: PathSubstitutions,
+    pub resolver_config: ForeignCallResolverConfig,
+    /// Recompiles the program when the session is restarted, if enabled
+    pub reload: Option<&'a ProgramReloader<'a>>,
+    /// Set to pause a running `continue`
+    pub interrupt: Arc<AtomicBool>,
+}
+
 pub struct DapSession<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> {
     server: Server<R, W>,
     context: DebugContext<'a, B>,
     debug_artifact: &'a DebugArtifact,
+    initial_witness: WitnessMap<FieldElement>,
     running: bool,
+    // Raised when a request stopping execution arrives, to interrupt a
+    // running `continue`
+    interrupt: Arc<AtomicBool>,
+    next_breakpoint_id: BreakpointId,
+    instruction_breakpoints: Vec<(OpcodeLocation, BreakpointId, BreakpointOptions)>,
+    // Breakpoints set in each source file, along with their options
+    source_breakpoints: BTreeMap<FileId, Vec<(OpcodeLocation, BreakpointId, BreakpointOptions)>>,
+    // Last request setting the breakpoints of each source file and the
+    // breakpoints answered, to set them again if the program is recompiled
+    source_breakpoint_requests: BTreeMap<FileId, (SetBreakpointsArguments, Vec<Breakpoint>)>,
+    // Breakpoints on entry to the functions with the given names
+    function_breakpoints: Vec<(String, BreakpointId)>,
+    path_substitutions: PathSubstitutions,
+    // What each `variablesReference` handed to the client refers to, offset
+    // by one since zero means a variable can't be expanded. They are only
+    // valid while execution is stopped.
+    variable_references: Vec<VariableReference>,
+    // Types of the custom error payloads, to decode failed assertions
+    error_types: BTreeMap<ErrorSelector, AbiErrorType>,
+    // Error execution last stopped at, reported in `exceptionInfo`
+    exception: Option<ExceptionReport>,
+    // What the program printed since execution last stopped, since stdout
+    // is taken by the protocol
+    program_output: Arc<Mutex<String>>,
+    // Failure of the program execution last stopped on, reported when the
+    // session ends
+    failure: Option<FailureReport>,
+    // Points execution stopped at in the current run, for the timeline
+    stops: Vec<TimelineEvent>,
+    // Recompiles the program when restarting after its sources changed
+    reload: Option<&'a ProgramReloader<'a>>,
+    // Program recompiled by a restart request, to be debugged by a new
+    // session taking over from this one
+    reloaded: Option<(Request, LoadedProgram)>,
+}
+
+/// What a session hands over to the one debugging the program it recompiled
+struct SessionHandover {
+    restart_request: Request,
     next_breakpoint_id: BreakpointId,
-    instruction_breakpoints: Vec<(OpcodeLocation, BreakpointId)>,
-    source_breakpoints: BTreeMap<FileId, Vec<(OpcodeLocation, BreakpointId)>>,
+    source_breakpoint_requests: Vec<(SetBreakpointsArguments, Vec<Breakpoint>)>,
+    function_breakpoints: Vec<(String, BreakpointId)>,
 }
 
-enum ScopeReferences {
-    Locals = 1,
-    WitnessMap = 2,
-    InvalidScope = 0,
+/// Details of an execution error, as shown by the IDE's exception UI
+struct ExceptionReport {
+    exception_id: String,
+    description: String,
+    /// Message of the failed assertion, if any
+    message: Option<String>,
+    /// Source call stack at the failing opcode, innermost frame first
+    stack_trace: String,
 }
 
-impl From<i64> for ScopeReferences {
-    fn from(value: i64) -> Self {
-        match value {
-            1 => Self::Locals,
-            2 => Self::WitnessMap,
-            _ => Self::InvalidScope,
-        }
+enum VariableReference {
+    /// The variables of the stack frame with the given index
+    Locals(usize),
+    WitnessMap,
+    BrilligMemory,
+    /// A struct, array or tuple value, expanded into its components
+    Value(PrintableValue<FieldElement>, PrintableType),
+}
+
+/// Options of a source breakpoint that are kept to install it again
+struct BreakpointOptions {
+    /// Message to log instead of stopping, for logpoints
+    log_message: Option<String>,
+    condition: Option<Condition>,
+    hit_condition: Option<Condition>,
+}
+
+impl BreakpointOptions {
+    fn from_source_breakpoint(breakpoint: &SourceBreakpoint) -> Result<Self, String> {
+        Self::parse(
+            breakpoint.log_message.clone(),
+            breakpoint.condition.as_deref(),
+            breakpoint.hit_condition.as_deref(),
+        )
+    }
+
+    fn from_instruction_breakpoint(breakpoint: &InstructionBreakpoint) -> Result<Self, String> {
+        Self::parse(None, breakpoint.condition.as_deref(), breakpoint.hit_condition.as_deref())
+    }
+
+    fn parse(
+        log_message: Option<String>,
+        condition: Option<&str>,
+        hit_condition: Option<&str>,
+    ) -> Result<Self, String> {
+        let condition = condition
+            .filter(|condition| !condition.trim().is_empty())
+            .map(str::parse)
+            .transpose()?;
+        let hit_condition = hit_condition
+            .filter(|hit_condition| !hit_condition.trim().is_empty())
+            .map(Condition::parse_hit_condition)
+            .transpose()?;
+        Ok(BreakpointOptions { log_message, condition, hit_condition })
     }
 }
 
 impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<'a, R, W, B> {
+    /// Starts a session debugging the first circuit of the program
     pub fn new(
         server: Server<R, W>,
         solver: &'a B,
-        circuit: &'a Circuit<FieldElement>,
+        program: &'a Program<FieldElement>,
         debug_artifact: &'a DebugArtifact,
         initial_witness: WitnessMap<FieldElement>,
-        unconstrained_functions: &'a [BrilligBytecode<FieldElement>],
+        options: DapSessionOptions<'a>,
+        error_types: BTreeMap<ErrorSelector, AbiErrorType>,
     ) -> Self {
+        let DapSessionOptions { path_substitutions, resolver_config, reload, interrupt } = options;
+        let program_output = Arc::new(Mutex::new(String::new()));
+        let mut foreign_call_executor = DefaultDebugForeignCallExecutor::with_resolver(
+            PrintOutput::Buffer(program_output.clone()),
+            resolver_config,
+        );
+        foreign_call_executor.load_artifact(debug_artifact);
         let context = DebugContext::new(
             solver,
-            circuit,
+            &program.functions[0],
             debug_artifact,
-            initial_witness,
-            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact)),
-            unconstrained_functions,
+            initial_witness.clone(),
+            Box::new(foreign_call_executor),
+            &program.unconstrained_functions,
         );
         Self {
             server,
             context,
             debug_artifact,
+            initial_witness,
             running: false,
+            interrupt,
             next_breakpoint_id: 1,
             instruction_breakpoints: vec![],
             source_breakpoints: BTreeMap::new(),
+            source_breakpoint_requests: BTreeMap::new(),
+            function_breakpoints: vec![],
+            path_substitutions,
+            variable_references: vec![],
+            error_types,
+            exception: None,
+            program_output,
+            failure: None,
+            stops: vec![],
+            reload,
+            reloaded: None,
         }
     }
 
+    fn send_output(
+        &mut self,
+        category: OutputEventCategory,
+        output: String,
+    ) -> Result<(), ServerError> {
+        self.server.send_event(Event::Output(OutputEventBody {
+            category: Some(category),
+            output,
+            ..OutputEventBody::default()
+        }))?;
+        Ok(())
+    }
+
+    /// Reports a diagnostic of the adapter itself, which clients usually
+    /// don't show to users
+    fn send_telemetry(&mut self, message: String) -> Result<(), ServerError> {
+        self.send_output(OutputEventCategory::Telemetry, format!("{message}\n"))
+    }
+
+    /// Records that execution stopped at the current point, forgetting the
+    /// stops past it if execution was rewound
+    fn record_stop(&mut self, reason: &'static str) {
+        let step = self.context.get_executed_opcodes();
+        self.stops.retain(|stop| stop.step <= step);
+        self.stops.push(TimelineEvent {
+            step,
+            location: self.context.get_current_opcode_location(),
+            kind: TimelineEventKind::Stop { reason },
+        });
+    }
+
     fn send_stopped_event(&mut self, reason: StoppedEventReason) -> Result<(), ServerError> {
-        let description = format!("{:?}", &reason);
+        let description = format!("{:?} ({})", &reason, self.context.describe_status());
         self.server.send_event(Event::Stopped(StoppedEventBody {
             reason,
             description: Some(description),
@@ -99,7 +274,9 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         Ok(())
     }
 
-    pub fn run_loop(&mut self) -> Result<(), ServerError> {
+    /// Moves execution to the first opcode with a source location, if the
+    /// program has not finished
+    fn start_execution(&mut self) {
         self.running = self.context.get_current_opcode_location().is_some();
 
         if self.running && self.context.get_current_source_location().is_none() {
@@ -109,10 +286,64 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
             // first breakpoint set.
             _ = self.context.next_into();
         }
+    }
+
+    pub fn run_loop(&mut self) -> Result<(), ServerError> {
+        self.start_execution();
 
         self.server.send_event(Event::Initialized)?;
+        self.record_stop("entry");
         self.send_stopped_event(StoppedEventReason::Entry)?;
+        self.serve_requests()
+    }
+
+    /// Takes over from the session of the program before it was recompiled
+    /// on restart: sets the breakpoints of that session again, resolving the
+    /// source ones against the new debug artifact, and stops at the entry.
+    /// Instruction breakpoints are dropped since opcodes may have moved.
+    fn resume(&mut self, handover: SessionHandover) -> Result<(), ServerError> {
+        self.next_breakpoint_id = handover.next_breakpoint_id;
+        for (args, previous) in handover.source_breakpoint_requests {
+            for breakpoint in self.replay_source_breakpoints(&args, &previous) {
+                self.server.send_event(Event::Breakpoint(BreakpointEventBody {
+                    reason: BreakpointEventReason::Changed,
+                    breakpoint,
+                }))?;
+            }
+        }
+        let function_names = self.context.get_function_names();
+        self.function_breakpoints = handover
+            .function_breakpoints
+            .into_iter()
+            .filter(|(name, _)| function_names.contains(name))
+            .collect();
+        self.context.set_function_breakpoints(
+            self.function_breakpoints.iter().map(|(name, _)| name.clone()).collect(),
+        );
+        self.reinstall_breakpoints();
+
+        self.start_execution();
+        self.server.respond(handover.restart_request.ack()?)?;
+        if self.running {
+            self.record_stop("entry");
+            self.send_stopped_event(StoppedEventReason::Entry)?;
+        }
+        self.serve_requests()
+    }
 
+    /// Ends a session whose program was recompiled, returning the server and
+    /// what the session debugging the new program needs to take over
+    fn hand_over(self, restart_request: Request) -> (Server<R, W>, SessionHandover) {
+        let handover = SessionHandover {
+            restart_request,
+            next_breakpoint_id: self.next_breakpoint_id,
+            source_breakpoint_requests: self.source_breakpoint_requests.into_values().collect(),
+            function_breakpoints: self.function_breakpoints,
+        };
+        (self.server, handover)
+    }
+
+    fn serve_requests(&mut self) -> Result<(), ServerError> {
         while self.running {
             let req = match self.server.poll_request()? {
                 Some(req) => req,
@@ -120,13 +351,48 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
             };
             match req.command {
                 Command::Disconnect(_) => {
-                    eprintln!("INFO: ending debugging session");
+                    self.send_telemetry(String::from("ending debugging session"))?;
                     self.server.respond(req.ack()?)?;
                     break;
                 }
+                Command::Terminate(_) => {
+                    self.send_telemetry(String::from("terminating debugging session"))?;
+                    self.interrupt.store(false, Ordering::SeqCst);
+                    self.server.respond(req.ack()?)?;
+                    self.server.send_event(Event::Terminated(None))?;
+                    self.running = false;
+                }
+                Command::Pause(_) => {
+                    // A running `continue` has already been interrupted, and
+                    // reported the pause
+                    self.interrupt.store(false, Ordering::SeqCst);
+                    self.server.respond(req.ack()?)?;
+                }
+                Command::Cancel(_) => {
+                    // Requests are handled in order, so the one being
+                    // cancelled either completed or, if it was the running
+                    // `continue`, was interrupted already
+                    self.interrupt.store(false, Ordering::SeqCst);
+                    self.server.respond(req.ack()?)?;
+                }
+                Command::Restart(_) => {
+                    self.handle_restart(req)?;
+                }
                 Command::SetBreakpoints(_) => {
                     self.handle_set_source_breakpoints(req)?;
                 }
+                Command::BreakpointLocations(_) => {
+                    self.handle_breakpoint_locations(req)?;
+                }
+                Command::LoadedSources => {
+                    self.handle_loaded_sources(req)?;
+                }
+                Command::Modules(_) => {
+                    self.handle_modules(req)?;
+                }
+                Command::Source(_) => {
+                    self.handle_source(req)?;
+                }
                 Command::SetExceptionBreakpoints(_) => {
                     self.server.respond(req.success(ResponseBody::SetExceptionBreakpoints(
                         SetExceptionBreakpointsResponse { breakpoints: None },
@@ -135,6 +401,9 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 Command::SetInstructionBreakpoints(_) => {
                     self.handle_set_instruction_breakpoints(req)?;
                 }
+                Command::SetFunctionBreakpoints(_) => {
+                    self.handle_set_function_breakpoints(req)?;
+                }
                 Command::Threads => {
                     self.server.respond(req.success(ResponseBody::Threads(ThreadsResponse {
                         threads: vec![Thread { id: 0, name: "main".to_string() }],
@@ -158,7 +427,7 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                     let granularity =
                         args.granularity.as_ref().unwrap_or(&SteppingGranularity::Statement);
                     match granularity {
-                        SteppingGranularity::Instruction => self.handle_step(req)?,
+                        SteppingGranularity::Instruction => self.handle_step_out_opcode(req)?,
                         _ => self.handle_next_out(req)?,
                     }
                 }
@@ -166,27 +435,181 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                     let granularity =
                         args.granularity.as_ref().unwrap_or(&SteppingGranularity::Statement);
                     match granularity {
-                        SteppingGranularity::Instruction => self.handle_step(req)?,
+                        SteppingGranularity::Instruction => self.handle_step_over_opcode(req)?,
                         _ => self.handle_next_over(req)?,
                     }
                 }
                 Command::Continue(_) => {
                     self.handle_continue(req)?;
                 }
+                Command::StepBack(ref args) => {
+                    let granularity =
+                        args.granularity.as_ref().unwrap_or(&SteppingGranularity::Statement);
+                    match granularity {
+                        SteppingGranularity::Instruction => self.handle_step_back_opcode(req)?,
+                        _ => self.handle_step_back_into(req)?,
+                    }
+                }
+                Command::ReverseContinue(_) => {
+                    self.handle_reverse_continue(req)?;
+                }
                 Command::Scopes(_) => {
                     self.handle_scopes(req)?;
                 }
                 Command::Variables(ref _args) => {
                     self.handle_variables(req)?;
                 }
+                Command::Completions(_) => {
+                    self.handle_completions(req)?;
+                }
+                Command::Evaluate(_) => {
+                    self.handle_evaluate(req)?;
+                }
+                Command::ExceptionInfo(_) => {
+                    self.handle_exception_info(req)?;
+                }
+                Command::ReadMemory(_) => {
+                    self.handle_read_memory(req)?;
+                }
+                Command::WriteMemory(_) => {
+                    self.handle_write_memory(req)?;
+                }
+                Command::GotoTargets(_) => {
+                    self.handle_goto_targets(req)?;
+                }
+                Command::Goto(_) => {
+                    self.handle_goto(req)?;
+                }
                 _ => {
-                    eprintln!("ERROR: unhandled command: {:?}", req.command);
+                    self.send_telemetry(format!("unhandled command: {:?}", req.command))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Ends the session, returning the solved witness if execution finished,
+    /// or the partial one if it stopped on a failure
+    fn finish(self) -> DebugOutcome {
+        if self.context.is_solved() {
+            DebugOutcome::Solved(self.context.finalize())
+        } else if let Some(report) = self.failure {
+            DebugOutcome::Failed(self.context.get_witness_stack(), report)
+        } else {
+            DebugOutcome::Halted
+        }
+    }
+
+    /// Runs the program again from the start, keeping the breakpoints set.
+    /// If the session recompiles on restart and the sources changed, the
+    /// recompiled program is handed over to a new session instead.
+    fn handle_restart(&mut self, req: Request) -> Result<(), ServerError> {
+        if let Some(reload) = self.reload.filter(|_| self.sources_changed()) {
+            match reload() {
+                Ok(loaded) => {
+                    self.reloaded = Some((req, loaded));
+                    self.running = false;
+                }
+                Err(error) => {
+                    // The session goes on with the program as it was
+                    if let LoadError::CompileFailed(diagnostics) = &error {
+                        send_compile_diagnostics(&mut self.server, diagnostics)?;
+                    }
+                    self.server.respond(req.error(&error.dap_message()))?;
                 }
             }
+            return Ok(());
         }
+        self.context.restart(self.initial_witness.clone());
+        self.reinstall_breakpoints();
+        self.variable_references.clear();
+        self.exception = None;
+        self.failure = None;
+        self.stops.clear();
+        self.start_execution();
+        self.server.respond(req.ack()?)?;
+        if self.running {
+            self.record_stop("entry");
+            self.send_stopped_event(StoppedEventReason::Entry)?;
+        }
+        Ok(())
+    }
+
+    /// Whether a source file of the program changed on disk since it was
+    /// compiled. Files not on disk, such as those of the standard library,
+    /// are taken to be unchanged.
+    fn sources_changed(&self) -> bool {
+        self.debug_artifact.file_map.values().any(|debug_file| {
+            let path = self.path_substitutions.apply(&debug_file.path);
+            path.is_file()
+                && std::fs::read_to_string(&path).map_or(true, |source| source != debug_file.source)
+        })
+    }
+
+    /// Lists the opcodes of a source line execution can jump to, identified
+    /// by their address
+    fn handle_goto_targets(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::GotoTargets(ref args) = req.command else {
+            unreachable!("handle_goto_targets called on a different request");
+        };
+        let file_id = self.find_source_file_id(&args.source);
+        let locations = match file_id {
+            Some(file_id) => self.context.find_jump_targets(&file_id, args.line),
+            None => vec![],
+        };
+        let targets = locations
+            .iter()
+            .map(|location| {
+                let address = self.context.opcode_location_to_address(location);
+                GotoTarget {
+                    id: address as i64,
+                    label: format!("opcode {location}"),
+                    line: args.line,
+                    column: None,
+                    end_line: None,
+                    end_column: None,
+                    instruction_pointer_reference: Some(address.to_string()),
+                }
+            })
+            .collect();
+        self.server
+            .respond(req.success(ResponseBody::GotoTargets(GotoTargetsResponse { targets })))?;
         Ok(())
     }
 
+    /// Moves execution to a target returned by `gotoTargets`, skipping or
+    /// re-running the opcodes in between
+    fn handle_goto(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::Goto(ref args) = req.command else {
+            unreachable!("handle_goto called on a different request");
+        };
+        let Some(location) = usize::try_from(args.target_id)
+            .ok()
+            .and_then(|address| self.context.address_to_opcode_location(address))
+        else {
+            self.server.respond(req.error("Unknown goto target"))?;
+            return Ok(());
+        };
+        if let Err(message) = self.context.jump_to(location) {
+            self.server.respond(req.error(&message))?;
+            return Ok(());
+        }
+        self.variable_references.clear();
+        self.exception = None;
+        self.failure = None;
+        self.server.respond(req.ack()?)?;
+        self.send_output(
+            OutputEventCategory::Important,
+            format!(
+                "Jumped to opcode {location}. Skipped opcodes were not executed and re-run \
+                 ones see the witnesses already solved: keeping the witness values \
+                 consistent is your responsibility.\n"
+            ),
+        )?;
+        self.record_stop("goto");
+        self.send_stopped_event(StoppedEventReason::Goto)
+    }
+
     fn build_stack_trace(&self) -> Vec<StackFrame> {
         let stack_frames = self.context.get_variables();
 
@@ -209,16 +632,11 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 StackFrame {
                     id: index as i64,
                     name,
-                    source: Some(Source {
-                        path: self.debug_artifact.file_map[&source_location.file]
-                            .path
-                            .to_str()
-                            .map(String::from),
-                        ..Source::default()
-                    }),
+                    source: Some(self.build_source(&source_location.file)),
                     line: line_number as i64,
                     column: column_number as i64,
                     instruction_pointer_reference: Some(address.to_string()),
+                    module_id: Some(ModuleId::Number(self.module_id_for_location(opcode_location))),
                     ..StackFrame::default()
                 }
             })
@@ -226,6 +644,123 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
             .collect()
     }
 
+    /// Describes an execution error for the IDE, with the message of the
+    /// failed assertion and the source call stack where it failed
+    fn build_exception_report(&self, err: &NargoError<FieldElement>) -> ExceptionReport {
+        let exception_id = match err {
+            NargoError::ExecutionError(ExecutionError::SolvingError(error, _)) => match error {
+                OpcodeResolutionError::UnsatisfiedConstrain { .. } => "UnsatisfiedConstraint",
+                OpcodeResolutionError::BrilligFunctionFailed { .. } => "BrilligFunctionFailed",
+                OpcodeResolutionError::IndexOutOfBounds { .. } => "IndexOutOfBounds",
+                _ => "SolvingError",
+            },
+            _ => "ExecutionError",
+        };
+        let failed_assertion = self.context.get_failed_assertion(err, &self.error_types);
+        let description = match &failed_assertion {
+            Some((location, _)) => format!("Failed assertion at opcode {location}"),
+            None => err.to_string(),
+        };
+        let stack_trace = self
+            .build_stack_trace()
+            .iter()
+            .map(|frame| {
+                let path = frame.source.as_ref().and_then(|source| source.path.as_deref());
+                format!(
+                    "at {} ({}:{}:{})",
+                    frame.name,
+                    path.unwrap_or("<unknown>"),
+                    frame.line,
+                    frame.column
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        ExceptionReport {
+            exception_id: exception_id.to_string(),
+            description,
+            message: failed_assertion.and_then(|(_, message)| message),
+            stack_trace,
+        }
+    }
+
+    /// Modules are the ACIR circuit, with id 0, and each unconstrained
+    /// function, with its function id offset by one
+    fn module_id_for_location(&self, location: &OpcodeLocation) -> i64 {
+        match location {
+            OpcodeLocation::Acir(_) => 0,
+            OpcodeLocation::Brillig { acir_index, .. } => {
+                match &self.context.get_opcodes()[*acir_index] {
+                    Opcode::BrilligCall { id, .. } => *id as i64 + 1,
+                    _ => 0,
+                }
+            }
+        }
+    }
+
+    /// Describes the structure of the program: the circuit, spanning the
+    /// whole address space of the disassembly, and the unconstrained
+    /// functions it calls, along with their sizes in opcodes
+    fn handle_modules(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::Modules(ref args) = req.command else {
+            unreachable!("handle_modules called on a different request");
+        };
+        let opcode_count = self.context.get_opcodes().len();
+        let address_count = self.context.get_address_count();
+        let circuit = Module {
+            id: ModuleId::Number(0),
+            name: format!("main ({opcode_count} ACIR opcodes)"),
+            address_range: (address_count > 0).then(|| format!("0-{}", address_count - 1)),
+            ..Module::default()
+        };
+        let functions =
+            self.context.get_unconstrained_functions().iter().enumerate().map(|(id, function)| {
+                Module {
+                    id: ModuleId::Number(id as i64 + 1),
+                    name: format!(
+                        "{} ({} Brillig opcodes)",
+                        brillig_function_symbol(id as u32),
+                        function.bytecode.len()
+                    ),
+                    ..Module::default()
+                }
+            });
+        let modules: Vec<Module> = std::iter::once(circuit).chain(functions).collect();
+        let total_modules = Some(modules.len() as i64);
+
+        let start = args.start_module.unwrap_or(0).max(0) as usize;
+        let modules = match args.module_count {
+            Some(count) if count > 0 => {
+                modules.into_iter().skip(start).take(count as usize).collect()
+            }
+            _ => modules.into_iter().skip(start).collect(),
+        };
+        self.server.respond(
+            req.success(ResponseBody::Modules(ModulesResponse { modules, total_modules })),
+        )?;
+        Ok(())
+    }
+
+    fn handle_exception_info(&mut self, req: Request) -> Result<(), ServerError> {
+        let Some(exception) = &self.exception else {
+            self.server.respond(req.error("Execution is not stopped at an error"))?;
+            return Ok(());
+        };
+        let response = ExceptionInfoResponse {
+            exception_id: exception.exception_id.clone(),
+            description: Some(exception.description.clone()),
+            break_mode: ExceptionBreakMode::Unhandled,
+            details: Some(ExceptionDetails {
+                message: exception.message.clone(),
+                type_name: Some(exception.exception_id.clone()),
+                stack_trace: Some(exception.stack_trace.clone()),
+                ..ExceptionDetails::default()
+            }),
+        };
+        self.server.respond(req.success(ResponseBody::ExceptionInfo(response)))?;
+        Ok(())
+    }
+
     fn handle_stack_trace(&mut self, req: Request) -> Result<(), ServerError> {
         let frames = self.build_stack_trace();
         let total_frames = Some(frames.len() as i64);
@@ -241,41 +776,37 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
             unreachable!("handle_disassemble called on a non disassemble request");
         };
 
-        // we assume memory references are unsigned integers
-        let starting_address = args.memory_reference.parse::<i64>().unwrap_or(0);
-        let instruction_offset = args.instruction_offset.unwrap_or(0);
-
-        let mut address = starting_address + instruction_offset;
-        let mut count = args.instruction_count;
-
-        let mut instructions: Vec<DisassembledInstruction> = vec![];
-
-        while count > 0 {
-            let opcode_location = if address >= 0 {
-                self.context.address_to_opcode_location(address as usize)
-            } else {
-                None
-            };
+        // Memory references are the absolute addresses of opcodes, as handed
+        // out in `instructionPointerReference`. Each opcode takes one address,
+        // so the byte offset and the instruction offset add up the same way.
+        let starting_address = args.memory_reference.parse::<i64>().unwrap_or(0)
+            + args.offset.unwrap_or(0)
+            + args.instruction_offset.unwrap_or(0);
+        let current_location = self.context.get_current_opcode_location();
 
-            if let Some(opcode_location) = opcode_location {
-                instructions.push(DisassembledInstruction {
-                    address: address.to_string(),
-                    // we'll use the instruction_bytes field to render the OpcodeLocation
-                    instruction_bytes: Some(opcode_location.to_string()),
-                    instruction: self.context.render_opcode_at_location(&opcode_location),
-                    ..DisassembledInstruction::default()
-                });
-            } else {
-                // entry for invalid location to fill up the request
-                instructions.push(DisassembledInstruction {
-                    address: "---".to_owned(),
-                    instruction: "---".to_owned(),
-                    ..DisassembledInstruction::default()
-                });
-            }
-            count -= 1;
-            address += 1;
-        }
+        // Clients page through large programs by asking for instructions
+        // before and after the ones they show, so addresses out of the program
+        // are answered with placeholders to keep the count requested
+        let instructions = (0..args.instruction_count.max(0))
+            .map(|index| {
+                let address = starting_address + index;
+                let opcode_location = usize::try_from(address)
+                    .ok()
+                    .and_then(|address| self.context.address_to_opcode_location(address));
+                match opcode_location {
+                    Some(opcode_location) => self.build_disassembled_instruction(
+                        address,
+                        opcode_location,
+                        Some(opcode_location) == current_location,
+                    ),
+                    None => DisassembledInstruction {
+                        address: address.to_string(),
+                        instruction: String::from("<invalid>"),
+                        ..DisassembledInstruction::default()
+                    },
+                }
+            })
+            .collect();
 
         self.server.respond(
             req.success(ResponseBody::Disassemble(DisassembleResponse { instructions })),
@@ -283,52 +814,166 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         Ok(())
     }
 
+    /// Describes an opcode for the disassembly view. Brillig opcodes are
+    /// rendered with their memory operands and labelled with the symbol of
+    /// their unconstrained function; the current opcode is marked with `=>`.
+    fn build_disassembled_instruction(
+        &self,
+        address: i64,
+        opcode_location: OpcodeLocation,
+        is_current: bool,
+    ) -> DisassembledInstruction {
+        let (acir_index, brillig_index) = match opcode_location {
+            OpcodeLocation::Acir(acir_index) => (acir_index, 0),
+            OpcodeLocation::Brillig { acir_index, brillig_index } => (acir_index, brillig_index),
+        };
+        let (instruction, symbol) = match &self.context.get_opcodes()[acir_index] {
+            Opcode::BrilligCall { id, .. } => {
+                let bytecode = &self.context.get_unconstrained_functions()[*id as usize].bytecode;
+                let rendered = render_brillig_opcode(&bytecode[brillig_index], *id);
+                let instruction = match opcode_location {
+                    OpcodeLocation::Acir(_) => format!("BRILLIG {rendered}"),
+                    OpcodeLocation::Brillig { .. } => format!("      | {rendered}"),
+                };
+                (instruction, Some(brillig_function_symbol(*id)))
+            }
+            _ => (self.context.render_opcode_at_location(&opcode_location), None),
+        };
+        let marker = if is_current { "=> " } else { "   " };
+
+        let source_location =
+            self.context.get_source_location_for_opcode_location(&opcode_location).last().copied();
+        let (location, line) = match source_location {
+            Some(source_location) => (
+                Some(self.build_source(&source_location.file)),
+                self.debug_artifact
+                    .location_line_number(source_location)
+                    .ok()
+                    .map(|line| line as i64),
+            ),
+            None => (None, None),
+        };
+
+        DisassembledInstruction {
+            address: address.to_string(),
+            // we'll use the instruction_bytes field to render the OpcodeLocation
+            instruction_bytes: Some(opcode_location.to_string()),
+            instruction: format!("{marker}{instruction}"),
+            symbol,
+            location,
+            line,
+            ..DisassembledInstruction::default()
+        }
+    }
+
     fn handle_step(&mut self, req: Request) -> Result<(), ServerError> {
         let result = self.context.step_into_opcode();
-        eprintln!("INFO: stepped by instruction with result {result:?}");
+        self.send_telemetry(format!("stepped by instruction with result {result:?}"))?;
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    /// Steps over an instruction: a single Brillig opcode while executing
+    /// Brillig code, and a whole ACIR opcode, Brillig calls included, otherwise
+    fn handle_step_over_opcode(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = if self.context.is_executing_brillig() {
+            self.context.step_into_opcode()
+        } else {
+            self.context.step_acir_opcode()
+        };
+        self.send_telemetry(format!("stepped over by instruction with result {result:?}"))?;
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    /// Steps out of the Brillig code being executed to the next ACIR opcode.
+    /// There are no instructions to step out of at the ACIR level, so there it
+    /// steps out of the current function as a statement step would.
+    fn handle_step_out_opcode(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = if self.context.is_executing_brillig() {
+            self.context.step_acir_opcode()
+        } else {
+            self.context.next_out()
+        };
+        self.send_telemetry(format!("stepped out by instruction with result {result:?}"))?;
         self.server.respond(req.ack()?)?;
         self.handle_execution_result(result)
     }
 
     fn handle_next_into(&mut self, req: Request) -> Result<(), ServerError> {
         let result = self.context.next_into();
-        eprintln!("INFO: stepped into by statement with result {result:?}");
+        self.send_telemetry(format!("stepped into by statement with result {result:?}"))?;
         self.server.respond(req.ack()?)?;
         self.handle_execution_result(result)
     }
 
     fn handle_next_out(&mut self, req: Request) -> Result<(), ServerError> {
         let result = self.context.next_out();
-        eprintln!("INFO: stepped out by statement with result {result:?}");
+        self.send_telemetry(format!("stepped out by statement with result {result:?}"))?;
         self.server.respond(req.ack()?)?;
         self.handle_execution_result(result)
     }
 
     fn handle_next_over(&mut self, req: Request) -> Result<(), ServerError> {
         let result = self.context.next_over();
-        eprintln!("INFO: stepped over by statement with result {result:?}");
+        self.send_telemetry(format!("stepped over by statement with result {result:?}"))?;
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_step_back_opcode(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.step_back_opcode(self.initial_witness.clone());
+        self.send_telemetry(format!("stepped back by instruction with result {result:?}"))?;
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_step_back_into(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.step_back_into(self.initial_witness.clone());
+        self.send_telemetry(format!("stepped back by statement with result {result:?}"))?;
+        self.server.respond(req.ack()?)?;
+        self.handle_execution_result(result)
+    }
+
+    fn handle_reverse_continue(&mut self, req: Request) -> Result<(), ServerError> {
+        let result = self.context.reverse_continue(self.initial_witness.clone());
+        self.send_telemetry(format!("reverse continue with result {result:?}"))?;
         self.server.respond(req.ack()?)?;
         self.handle_execution_result(result)
     }
 
     fn handle_continue(&mut self, req: Request) -> Result<(), ServerError> {
-        let result = self.context.cont();
-        eprintln!("INFO: continue with result {result:?}");
         self.server.respond(req.success(ResponseBody::Continue(ContinueResponse {
             all_threads_continued: Some(true),
         })))?;
+        let result = self.continue_until_interrupted();
+        self.send_telemetry(format!("continue with result {result:?}"))?;
         self.handle_execution_result(result)
     }
 
+    /// Continues execution until it stops or a request interrupting it
+    /// arrives, in which case execution is reported as paused
+    fn continue_until_interrupted(&mut self) -> DebugCommandResult {
+        loop {
+            if self.interrupt.load(Ordering::SeqCst) {
+                return DebugCommandResult::Ok;
+            }
+            let result = self.context.step_into_opcode();
+            if !matches!(result, DebugCommandResult::Ok) {
+                return result;
+            }
+        }
+    }
+
     fn find_breakpoints_at_location(&self, opcode_location: &OpcodeLocation) -> Vec<i64> {
         let mut result = vec![];
-        for (location, id) in &self.instruction_breakpoints {
+        for (location, id, _) in &self.instruction_breakpoints {
             if opcode_location == location {
                 result.push(*id);
             }
         }
         for breakpoints in self.source_breakpoints.values() {
-            for (location, id) in breakpoints {
+            for (location, id, _) in breakpoints {
                 if opcode_location == location {
                     result.push(*id);
                 }
@@ -337,12 +982,42 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         result
     }
 
+    /// Returns the function breakpoints of the function being executed, which
+    /// is where execution stops when none of the location breakpoints match
+    fn find_breakpoints_for_current_function(&self) -> Vec<i64> {
+        let Some(frame) = self.context.current_stack_frame() else {
+            return vec![];
+        };
+        let function_name = frame.function_name.to_string();
+        self.function_breakpoints
+            .iter()
+            .filter(|(name, _)| *name == function_name)
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
     fn handle_execution_result(&mut self, result: DebugCommandResult) -> Result<(), ServerError> {
+        self.variable_references.clear();
+        self.exception = None;
+        self.failure = None;
+        let program_output = std::mem::take(&mut *self.program_output.lock().unwrap());
+        if !program_output.is_empty() {
+            self.send_output(OutputEventCategory::Stdout, program_output)?;
+        }
+        // Logpoints hit while executing don't stop, their messages are shown
+        // once execution does, along with the warnings about oracles
+        for message in self.context.take_debug_warnings() {
+            self.send_output(OutputEventCategory::Console, format!("warning: {message}\n"))?;
+        }
+        for message in self.context.take_log_output() {
+            self.send_output(OutputEventCategory::Console, format!("{message}\n"))?;
+        }
         match result {
             DebugCommandResult::Done => {
                 self.running = false;
             }
             DebugCommandResult::Ok => {
+                self.record_stop("pause");
                 self.server.send_event(Event::Stopped(StoppedEventBody {
                     reason: StoppedEventReason::Pause,
                     description: None,
@@ -354,7 +1029,11 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 }))?;
             }
             DebugCommandResult::BreakpointReached(location) => {
-                let breakpoint_ids = self.find_breakpoints_at_location(&location);
+                let mut breakpoint_ids = self.find_breakpoints_at_location(&location);
+                if breakpoint_ids.is_empty() {
+                    breakpoint_ids = self.find_breakpoints_for_current_function();
+                }
+                self.record_stop("breakpoint");
                 self.server.send_event(Event::Stopped(StoppedEventBody {
                     reason: StoppedEventReason::Breakpoint,
                     description: Some(String::from("Paused at breakpoint")),
@@ -366,12 +1045,17 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 }))?;
             }
             DebugCommandResult::Error(err) => {
+                self.failure = self.context.failure_report(&err);
+                let exception = self.build_exception_report(&err);
+                let text = exception.message.clone();
+                self.exception = Some(exception);
+                self.record_stop("exception");
                 self.server.send_event(Event::Stopped(StoppedEventBody {
                     reason: StoppedEventReason::Exception,
                     description: Some(format!("{err:?}")),
                     thread_id: Some(0),
                     preserve_focus_hint: Some(false),
-                    text: None,
+                    text,
                     all_threads_stopped: Some(false),
                     hit_breakpoint_ids: None,
                 }))?;
@@ -387,13 +1071,33 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
     }
 
     fn reinstall_breakpoints(&mut self) {
+        // Breakpoints that stay in place keep their hit counts, so setting
+        // the breakpoints of one file doesn't reset the hit conditions of the
+        // others
+        let hit_counts: BTreeMap<OpcodeLocation, usize> = self
+            .context
+            .get_breakpoints()
+            .map(|breakpoint| (breakpoint.location, breakpoint.hit_count))
+            .collect();
         self.context.clear_breakpoints();
-        for (location, _) in &self.instruction_breakpoints {
-            self.context.add_breakpoint(*location);
-        }
-        for breakpoints in self.source_breakpoints.values() {
-            for (location, _) in breakpoints {
-                self.context.add_breakpoint(*location);
+        let breakpoints = self
+            .instruction_breakpoints
+            .iter()
+            .chain(self.source_breakpoints.values().flatten())
+            .map(|(location, _, options)| (location, options));
+        for (location, options) in breakpoints {
+            match &options.log_message {
+                Some(message) => self.context.add_logpoint(*location, message.clone()),
+                None => self.context.add_breakpoint(*location),
+            };
+            let Some(id) = self.context.get_breakpoint_at(location).map(|breakpoint| breakpoint.id)
+            else {
+                continue;
+            };
+            self.context.set_breakpoint_condition(id, options.condition.clone());
+            self.context.set_breakpoint_hit_condition(id, options.hit_condition.clone());
+            if let Some(hit_count) = hit_counts.get(location) {
+                self.context.set_breakpoint_hit_count(id, *hit_count);
             }
         }
     }
@@ -404,36 +1108,68 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         };
 
         // compute breakpoints to set and return
-        let mut breakpoints_to_set: Vec<(OpcodeLocation, i64)> = vec![];
+        let mut breakpoints_to_set: Vec<(OpcodeLocation, i64, BreakpointOptions)> = vec![];
         let breakpoints: Vec<Breakpoint> = args
             .breakpoints
             .iter()
             .map(|breakpoint| {
                 let offset = breakpoint.offset.unwrap_or(0);
-                let address = breakpoint.instruction_reference.parse::<i64>().unwrap_or(0) + offset;
-                let Ok(address): Result<usize, _> = address.try_into() else {
+                let address = breakpoint
+                    .instruction_reference
+                    .parse::<i64>()
+                    .ok()
+                    .and_then(|reference| usize::try_from(reference + offset).ok());
+                let Some(address) = address else {
                     return Breakpoint {
                         verified: false,
                         message: Some(String::from("Invalid instruction reference/offset")),
                         ..Breakpoint::default()
                     };
                 };
+                // Breakpoints on the debugger instrumentation are moved to the
+                // next opcode of the user code
                 let Some(location) = self
                     .context
                     .address_to_opcode_location(address)
-                    .filter(|location| self.context.is_valid_opcode_location(location))
+                    .and_then(|location| self.context.find_debug_location_from(&location))
                 else {
                     return Breakpoint {
                         verified: false,
-                        message: Some(String::from("Invalid opcode location")),
+                        message: Some(String::from("No executable opcode at this location")),
                         ..Breakpoint::default()
                     };
                 };
+                let options = match BreakpointOptions::from_instruction_breakpoint(breakpoint) {
+                    Ok(options) => options,
+                    Err(message) => {
+                        return Breakpoint {
+                            verified: false,
+                            message: Some(message),
+                            ..Breakpoint::default()
+                        };
+                    }
+                };
                 let id = self.get_next_breakpoint_id();
-                breakpoints_to_set.push((location, id));
+                breakpoints_to_set.push((location, id, options));
+                let address = self.context.opcode_location_to_address(&location);
+                let source_location =
+                    self.context.get_source_location_for_opcode_location(&location).pop();
                 Breakpoint {
                     id: Some(id),
                     verified: true,
+                    source: source_location.map(|location| self.build_source(&location.file)),
+                    line: source_location.and_then(|location| {
+                        self.debug_artifact
+                            .location_line_number(location)
+                            .ok()
+                            .map(|line| line as i64)
+                    }),
+                    column: source_location.and_then(|location| {
+                        self.debug_artifact
+                            .location_column_number(location)
+                            .ok()
+                            .map(|column| column as i64)
+                    }),
                     offset: Some(0),
                     instruction_reference: Some(address.to_string()),
                     ..Breakpoint::default()
@@ -452,32 +1188,106 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         Ok(())
     }
 
+    /// Describes a file of the program to the IDE. Files not found on disk,
+    /// such as the ones of the embedded standard library, are also given a
+    /// reference the IDE can request their contents with.
+    fn build_source(&self, file_id: &FileId) -> Source {
+        let path = self.path_substitutions.apply(&self.debug_artifact.file_map[file_id].path);
+        let source_reference = (!path.is_file()).then(|| source_reference_of(file_id));
+        Source {
+            name: path.file_name().and_then(|name| name.to_str()).map(String::from),
+            path: path.to_str().map(String::from),
+            source_reference,
+            ..Source::default()
+        }
+    }
+
+    /// Finds the file a source given by the IDE refers to, by its reference if
+    /// it has one or otherwise by its path
+    fn find_source_file_id(&self, source: &Source) -> Option<FileId> {
+        match source.source_reference {
+            Some(reference) if reference > 0 => self
+                .debug_artifact
+                .file_map
+                .keys()
+                .find(|file_id| source_reference_of(file_id) == reference)
+                .copied(),
+            _ => self.find_file_id(source.path.as_deref()?),
+        }
+    }
+
+    /// Serves the contents of a file given a reference by `build_source`, in
+    /// the stack trace, loaded sources, disassembly or breakpoints, from the
+    /// copy kept in the debug artifact
+    fn handle_source(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::Source(ref args) = req.command else {
+            unreachable!("handle_source called on a different request");
+        };
+        // The reference of the source takes precedence over the one of the
+        // request, which is kept for backwards compatibility
+        let source = args.source.clone().unwrap_or_default();
+        let source = match source.source_reference {
+            Some(reference) if reference > 0 => source,
+            _ => Source { source_reference: Some(args.source_reference), ..source },
+        };
+        let Some(file_id) = self.find_source_file_id(&source) else {
+            self.server.respond(req.error("Source not found in the program"))?;
+            return Ok(());
+        };
+        let content = self.debug_artifact.file_map[&file_id].source.clone();
+        self.server.respond(req.success(ResponseBody::Source(SourceResponse {
+            content,
+            mime_type: Some(String::from("text/x-noir")),
+        })))?;
+        Ok(())
+    }
+
+    fn handle_loaded_sources(&mut self, req: Request) -> Result<(), ServerError> {
+        let sources = self
+            .debug_artifact
+            .file_map
+            .iter()
+            .filter(|(_, debug_file)| !is_debug_file_in_debug_crate(debug_file))
+            .map(|(file_id, _)| self.build_source(file_id))
+            .collect();
+        self.server
+            .respond(req.success(ResponseBody::LoadedSources(LoadedSourcesResponse { sources })))?;
+        Ok(())
+    }
+
     fn find_file_id(&self, source_path: &str) -> Option<FileId> {
         let file_map = &self.debug_artifact.file_map;
-        let found = file_map.iter().find(|(_, debug_file)| match debug_file.path.to_str() {
-            Some(debug_file_path) => debug_file_path == source_path,
-            None => false,
-        });
+        // Sources opened through a substituted path are found by their
+        // original path in the artifact
+        let source_path = self.path_substitutions.reverse(Path::new(source_path));
+        let found = file_map.iter().find(|(_, debug_file)| debug_file.path == source_path);
         found.map(|iter| *iter.0)
     }
 
     fn map_source_breakpoints(&mut self, args: &SetBreakpointsArguments) -> Vec<Breakpoint> {
-        let Some(ref source) = &args.source.path else {
-            return vec![];
-        };
-        let Some(file_id) = self.find_file_id(source) else {
-            eprintln!("WARN: file ID for source {source} not found");
+        let Some(file_id) = self.find_source_file_id(&args.source) else {
+            // A failure to send is noticed when answering the request
+            _ = self.send_telemetry(format!("file ID for source {:?} not found", args.source.path));
             return vec![];
         };
         let Some(ref breakpoints) = &args.breakpoints else {
             return vec![];
         };
-        let mut breakpoints_to_set: Vec<(OpcodeLocation, i64)> = vec![];
+        let mut breakpoints_to_set: Vec<(OpcodeLocation, i64, BreakpointOptions)> = vec![];
         let breakpoints = breakpoints
             .iter()
             .map(|breakpoint| {
                 let line = breakpoint.line;
-                let Some(location) = self.context.find_opcode_for_source_location(&file_id, line)
+                // Inline breakpoints target the expression at the given
+                // column, falling back to the whole line if none matches
+                let column_location = breakpoint.column.and_then(|column| {
+                    self.context
+                        .find_opcode_for_source_column(&file_id, line, column)
+                        .map(|location| (location, column))
+                });
+                let Some(location) = column_location
+                    .map(|(location, _)| location)
+                    .or_else(|| self.context.find_opcode_for_source_location(&file_id, line))
                 else {
                     return Breakpoint {
                         verified: false,
@@ -490,22 +1300,33 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
                 // TODO: line will not necessarily be the one requested; we
                 // should do the reverse mapping and retrieve the actual source
                 // code line number
-                if !self.context.is_valid_opcode_location(&location) {
+                if !self.context.is_valid_debug_location(&location) {
                     return Breakpoint {
                         verified: false,
                         message: Some(String::from("Invalid opcode location")),
                         ..Breakpoint::default()
                     };
                 }
+                let options = match BreakpointOptions::from_source_breakpoint(breakpoint) {
+                    Ok(options) => options,
+                    Err(message) => {
+                        return Breakpoint {
+                            verified: false,
+                            message: Some(message),
+                            ..Breakpoint::default()
+                        };
+                    }
+                };
                 let breakpoint_address = self.context.opcode_location_to_address(&location);
                 let instruction_reference = format!("{}", breakpoint_address);
                 let breakpoint_id = self.get_next_breakpoint_id();
-                breakpoints_to_set.push((location, breakpoint_id));
+                breakpoints_to_set.push((location, breakpoint_id, options));
                 Breakpoint {
                     id: Some(breakpoint_id),
                     verified: true,
                     source: Some(args.source.clone()),
                     line: Some(line),
+                    column: column_location.map(|(_, column)| column),
                     instruction_reference: Some(instruction_reference),
                     offset: Some(0),
                     ..Breakpoint::default()
@@ -518,11 +1339,86 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         breakpoints
     }
 
+    /// Sets the breakpoints of a request of the previous session again,
+    /// returning them with the ids the client knows them by. Breakpoints that
+    /// can no longer be placed are returned unverified.
+    fn replay_source_breakpoints(
+        &mut self,
+        args: &SetBreakpointsArguments,
+        previous: &[Breakpoint],
+    ) -> Vec<Breakpoint> {
+        let mut breakpoints = self.map_source_breakpoints(args);
+        let file_id = self.find_source_file_id(&args.source);
+        let mut replayed = vec![];
+        for (index, previous) in previous.iter().enumerate() {
+            let Some(previous_id) = previous.id else {
+                continue;
+            };
+            let Some(breakpoint) = breakpoints.get_mut(index) else {
+                replayed.push(Breakpoint {
+                    id: Some(previous_id),
+                    verified: false,
+                    message: Some(String::from("Source not found in the program")),
+                    ..Breakpoint::default()
+                });
+                continue;
+            };
+            let installed = file_id.and_then(|file_id| self.source_breakpoints.get_mut(&file_id));
+            for (_, installed_id, _) in installed.into_iter().flatten() {
+                if Some(*installed_id) == breakpoint.id {
+                    *installed_id = previous_id;
+                }
+            }
+            breakpoint.id = Some(previous_id);
+            replayed.push(breakpoint.clone());
+        }
+        if let Some(file_id) = file_id {
+            self.source_breakpoint_requests.insert(file_id, (args.clone(), breakpoints));
+        }
+        replayed
+    }
+
+    fn handle_set_function_breakpoints(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::SetFunctionBreakpoints(ref args) = req.command else {
+            unreachable!("handle_set_function_breakpoints called on a different request");
+        };
+        let function_names = self.context.get_function_names();
+        let mut function_breakpoints = vec![];
+        let breakpoints = args
+            .breakpoints
+            .iter()
+            .map(|breakpoint| {
+                if !function_names.contains(&breakpoint.name) {
+                    return Breakpoint {
+                        verified: false,
+                        message: Some(format!("Unknown function {}", breakpoint.name)),
+                        ..Breakpoint::default()
+                    };
+                }
+                let breakpoint_id = self.get_next_breakpoint_id();
+                function_breakpoints.push((breakpoint.name.clone(), breakpoint_id));
+                Breakpoint { id: Some(breakpoint_id), verified: true, ..Breakpoint::default() }
+            })
+            .collect();
+
+        self.context.set_function_breakpoints(
+            function_breakpoints.iter().map(|(name, _)| name.clone()).collect(),
+        );
+        self.function_breakpoints = function_breakpoints;
+        self.server.respond(req.success(ResponseBody::SetFunctionBreakpoints(
+            SetFunctionBreakpointsResponse { breakpoints },
+        )))?;
+        Ok(())
+    }
+
     fn handle_set_source_breakpoints(&mut self, req: Request) -> Result<(), ServerError> {
         let Command::SetBreakpoints(ref args) = req.command else {
             unreachable!("handle_set_source_breakpoints called on a different request");
         };
         let breakpoints = self.map_source_breakpoints(args);
+        if let Some(file_id) = self.find_source_file_id(&args.source) {
+            self.source_breakpoint_requests.insert(file_id, (args.clone(), breakpoints.clone()));
+        }
         self.reinstall_breakpoints();
         self.server.respond(
             req.success(ResponseBody::SetBreakpoints(SetBreakpointsResponse { breakpoints })),
@@ -530,41 +1426,97 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
         Ok(())
     }
 
+    /// Lists the positions of a source range where breakpoints can be set,
+    /// so that the IDE can show them before one is set
+    fn handle_breakpoint_locations(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::BreakpointLocations(ref args) = req.command else {
+            unreachable!("handle_breakpoint_locations called on a different request");
+        };
+        let file_id = self.find_source_file_id(&args.source);
+        let start = (args.line.max(1) as usize, args.column.unwrap_or(1).max(1) as usize);
+        let end_line = args.end_line.unwrap_or(args.line).max(1) as usize;
+        let end = (end_line, args.end_column.map_or(usize::MAX, |column| column.max(1) as usize));
+        let positions = match file_id {
+            Some(file_id) => self.context.get_breakpoint_positions(&file_id, start.0..=end.0),
+            None => vec![],
+        };
+        let breakpoints = positions
+            .into_iter()
+            .filter(|position| start <= *position && *position <= end)
+            .map(|(line, column)| BreakpointLocation {
+                line: line as i64,
+                column: Some(column as i64),
+                end_line: None,
+                end_column: None,
+            })
+            .collect();
+        self.server.respond(req.success(ResponseBody::BreakpointLocations(
+            BreakpointLocationsResponse { breakpoints },
+        )))?;
+        Ok(())
+    }
+
     fn handle_scopes(&mut self, req: Request) -> Result<(), ServerError> {
-        self.server.respond(req.success(ResponseBody::Scopes(ScopesResponse {
-            scopes: vec![
-                Scope {
-                    name: String::from("Locals"),
-                    variables_reference: ScopeReferences::Locals as i64,
-                    ..Scope::default()
-                },
-                Scope {
-                    name: String::from("Witness Map"),
-                    variables_reference: ScopeReferences::WitnessMap as i64,
-                    ..Scope::default()
-                },
-            ],
-        })))?;
+        let Command::Scopes(ref args) = req.command else {
+            unreachable!("handle_scopes called on a different request");
+        };
+        let locals = self.add_variable_reference(VariableReference::Locals(args.frame_id as usize));
+        let witness_map = self.add_variable_reference(VariableReference::WitnessMap);
+        let mut scopes = vec![
+            Scope { name: String::from("Locals"), variables_reference: locals, ..Scope::default() },
+            Scope {
+                name: String::from("Witness Map"),
+                variables_reference: witness_map,
+                ..Scope::default()
+            },
+        ];
+        if self.context.get_brillig_memory().is_some() {
+            let memory = self.add_variable_reference(VariableReference::BrilligMemory);
+            scopes.push(Scope {
+                name: String::from("Brillig Memory"),
+                variables_reference: memory,
+                ..Scope::default()
+            });
+        }
+        self.server.respond(req.success(ResponseBody::Scopes(ScopesResponse { scopes })))?;
         Ok(())
     }
 
-    fn build_local_variables(&self) -> Vec<Variable> {
-        let Some(current_stack_frame) = self.context.current_stack_frame() else {
-            return vec![];
+    fn add_variable_reference(&mut self, reference: VariableReference) -> i64 {
+        self.variable_references.push(reference);
+        self.variable_references.len() as i64
+    }
+
+    fn build_variable(
+        &mut self,
+        name: String,
+        value: PrintableValue<FieldElement>,
+        typ: PrintableType,
+    ) -> Variable {
+        let rendered = PrintableValueDisplay::Plain(value.clone(), typ.clone()).to_string();
+        let variables_reference = if value_components(&value, &typ).is_empty() {
+            0
+        } else {
+            self.add_variable_reference(VariableReference::Value(value, typ))
         };
+        Variable { name, value: rendered, variables_reference, ..Variable::default() }
+    }
 
-        let mut variables = current_stack_frame
+    fn build_local_variables(&mut self, frame_index: usize) -> Vec<Variable> {
+        let Some(stack_frame) = self.context.get_variables().into_iter().nth(frame_index) else {
+            return vec![];
+        };
+        let mut variables: Vec<_> = stack_frame
             .variables
             .iter()
-            .map(|(name, value, _var_type)| Variable {
-                name: String::from(*name),
-                value: format!("{:?}", *value),
-                ..Variable::default()
-            })
-            .collect::<Vec<Variable>>();
+            .map(|(name, value, typ)| (name.to_string(), (*value).clone(), (*typ).clone()))
+            .collect();
+        variables.sort_by(|a, b| a.0.cmp(&b.0));
 
-        variables.sort_by(|a, b| a.name.partial_cmp(&b.name).unwrap());
         variables
+            .into_iter()
+            .map(|(name, value, typ)| self.build_variable(name, value, typ))
+            .collect()
     }
 
     fn build_witness_map(&self) -> Vec<Variable> {
@@ -580,19 +1532,233 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
             .collect()
     }
 
+    /// Lists the Brillig memory cells, each with a `memoryReference` to open
+    /// the byte view of memory at it
+    fn build_brillig_memory(&self) -> Vec<Variable> {
+        let Some(memory) = self.context.get_brillig_memory() else {
+            return vec![];
+        };
+        memory
+            .iter()
+            .enumerate()
+            .map(|(address, value)| {
+                let cell_type = CellType::of(value);
+                Variable {
+                    name: address.to_string(),
+                    value: format_cell_value(value, cell_type),
+                    type_field: Some(cell_type.to_string()),
+                    memory_reference: Some(format_memory_reference(address * CELL_BYTES)),
+                    ..Variable::default()
+                }
+            })
+            .collect()
+    }
+
+    fn handle_read_memory(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::ReadMemory(ref args) = req.command else {
+            unreachable!("handle_read_memory called on a different request");
+        };
+        let Some(memory) = self.context.get_brillig_memory() else {
+            self.server
+                .respond(req.error("Brillig memory is only available in unconstrained code"))?;
+            return Ok(());
+        };
+        let Some(start) = parse_memory_reference(&args.memory_reference, args.offset) else {
+            self.server.respond(req.error("Invalid memory reference"))?;
+            return Ok(());
+        };
+        let count = usize::try_from(args.count).unwrap_or(0);
+        let bytes = read_memory_bytes(memory, start, count);
+        let unreadable_bytes = (count - bytes.len()) as i64;
+        self.server.respond(req.success(ResponseBody::ReadMemory(ReadMemoryResponse {
+            address: format_memory_reference(start),
+            unreadable_bytes: (unreadable_bytes > 0).then_some(unreadable_bytes),
+            data: Some(base64::engine::general_purpose::STANDARD.encode(bytes)),
+        })))?;
+        Ok(())
+    }
+
+    fn handle_write_memory(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::WriteMemory(ref args) = req.command else {
+            unreachable!("handle_write_memory called on a different request");
+        };
+        let Some(memory) = self.context.get_brillig_memory() else {
+            self.server
+                .respond(req.error("Brillig memory is only available in unconstrained code"))?;
+            return Ok(());
+        };
+        let Some(start) = parse_memory_reference(&args.memory_reference, args.offset) else {
+            self.server.respond(req.error("Invalid memory reference"))?;
+            return Ok(());
+        };
+        let data = match base64::engine::general_purpose::STANDARD.decode(&args.data) {
+            Ok(data) => data,
+            Err(err) => {
+                self.server.respond(req.error(&format!("Invalid memory data: {err}")))?;
+                return Ok(());
+            }
+        };
+        let cells = match patch_memory_cells(memory, start, &data) {
+            Ok(cells) => cells,
+            Err(message) => {
+                self.server.respond(req.error(&message))?;
+                return Ok(());
+            }
+        };
+        for (address, value, bit_size) in cells {
+            self.context.write_brillig_memory(address, value, bit_size);
+        }
+        self.server.respond(req.success(ResponseBody::WriteMemory(WriteMemoryResponse {
+            offset: None,
+            bytes_written: Some(data.len() as i64),
+        })))?;
+        Ok(())
+    }
+
+    fn handle_completions(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::Completions(ref args) = req.command else {
+            unreachable!("handle_completions called on a different request");
+        };
+        // columns are 1-based and count characters
+        let cursor = args
+            .text
+            .char_indices()
+            .nth((args.column - 1).max(0) as usize)
+            .map_or(args.text.len(), |(index, _)| index);
+        let targets = completion::complete(&self.context, &args.text, cursor)
+            .into_iter()
+            .map(|label| CompletionItem { label, ..CompletionItem::default() })
+            .collect();
+        self.server
+            .respond(req.success(ResponseBody::Completions(CompletionsResponse { targets })))?;
+        Ok(())
+    }
+
+    fn handle_evaluate(&mut self, req: Request) -> Result<(), ServerError> {
+        let Command::Evaluate(ref args) = req.command else {
+            unreachable!("handle_evaluate called on a different request");
+        };
+        if let Some(arguments) = args.expression.strip_prefix(TIMELINE_COMMAND) {
+            let arguments = arguments.trim().to_string();
+            return self.handle_timeline(req, &arguments);
+        }
+        // The same expressions are accepted in every context (watch, hover
+        // and the Debug Console)
+        let frame_index = args.frame_id.map(|frame_id| frame_id as usize);
+        match self.context.evaluate(&args.expression, frame_index) {
+            Ok((value, typ)) => {
+                let variable = self.build_variable(args.expression.clone(), value, typ);
+                self.server.respond(req.success(ResponseBody::Evaluate(EvaluateResponse {
+                    result: variable.value,
+                    variables_reference: variable.variables_reference,
+                    ..EvaluateResponse::default()
+                })))?;
+            }
+            Err(message) => self.server.respond(req.error(&message))?,
+        }
+        Ok(())
+    }
+
+    /// Answers a `noir/timeline` request, arriving as an `evaluate` of its
+    /// name followed by its arguments, with a page of the events of the
+    /// session so far. The page goes out as JSON in the `result` of an
+    /// `evaluate` response, which is made the body of the `noir/timeline`
+    /// response on its way to the client.
+    fn handle_timeline(&mut self, req: Request, arguments: &str) -> Result<(), ServerError> {
+        let arguments = match arguments {
+            "" => TimelineArguments::default(),
+            arguments => match serde_json::from_str(arguments) {
+                Ok(arguments) => arguments,
+                Err(error) => {
+                    let message = format!("Invalid {TIMELINE_COMMAND} arguments: {error}");
+                    self.server.respond(req.error(&message))?;
+                    return Ok(());
+                }
+            },
+        };
+        let page = {
+            let timeline = self.context.get_timeline();
+            // Stops before the oldest event kept are dropped along with the
+            // events before them
+            let dropped_stops = match timeline.events().front() {
+                Some(oldest) if timeline.dropped() > 0 => {
+                    self.stops.partition_point(|stop| stop.step < oldest.step)
+                }
+                _ => 0,
+            };
+            let events = merge_timeline(timeline.events(), &self.stops[dropped_stops..]);
+            let first_index = timeline.dropped() + dropped_stops;
+            let total_events = first_index + events.len();
+            let range = page_range(&arguments, first_index, total_events);
+            let entries = events[range.start - first_index..range.end - first_index]
+                .iter()
+                .zip(range)
+                .map(|(event, index)| TimelineEntry {
+                    index,
+                    step: event.step,
+                    address: event.location.map(|location| location.to_string()),
+                    source: event
+                        .location
+                        .and_then(|location| self.build_timeline_source(location)),
+                    kind: &event.kind,
+                })
+                .collect();
+            let page = TimelinePage {
+                version: TIMELINE_SCHEMA_VERSION,
+                first_index,
+                total_events,
+                events: entries,
+            };
+            serde_json::to_string(&page).expect("timeline pages are serializable")
+        };
+        self.server.respond(req.success(ResponseBody::Evaluate(EvaluateResponse {
+            result: page,
+            variables_reference: 0,
+            ..EvaluateResponse::default()
+        })))?;
+        Ok(())
+    }
+
+    /// Returns the innermost source location of an opcode in the timeline
+    fn build_timeline_source(&self, location: OpcodeLocation) -> Option<TimelineSource> {
+        let source_location =
+            self.context.get_source_location_for_opcode_location(&location).last().copied()?;
+        let path = self
+            .path_substitutions
+            .apply(&self.debug_artifact.file_map[&source_location.file].path);
+        Some(TimelineSource {
+            path: path.to_string_lossy().into_owned(),
+            line: self.debug_artifact.location_line_number(source_location).ok()?,
+            column: self.debug_artifact.location_column_number(source_location).ok()?,
+        })
+    }
+
     fn handle_variables(&mut self, req: Request) -> Result<(), ServerError> {
         let Command::Variables(ref args) = req.command else {
             unreachable!("handle_variables called on a different request");
         };
-        let scope: ScopeReferences = args.variables_reference.into();
-        let variables: Vec<_> = match scope {
-            ScopeReferences::Locals => self.build_local_variables(),
-            ScopeReferences::WitnessMap => self.build_witness_map(),
-            _ => {
-                eprintln!(
+        let reference = usize::try_from(args.variables_reference - 1)
+            .ok()
+            .and_then(|index| self.variable_references.get(index));
+        let variables: Vec<_> = match reference {
+            Some(VariableReference::Locals(frame_index)) => {
+                let frame_index = *frame_index;
+                self.build_local_variables(frame_index)
+            }
+            Some(VariableReference::WitnessMap) => self.build_witness_map(),
+            Some(VariableReference::BrilligMemory) => self.build_brillig_memory(),
+            Some(VariableReference::Value(value, typ)) => {
+                let components = value_components(value, typ);
+                components
+                    .into_iter()
+                    .map(|(name, value, typ)| self.build_variable(name, value, typ))
+                    .collect()
+            }
+            None => {
+                self.send_telemetry(format!(
                     "handle_variables with an unknown variables_reference {}",
                     args.variables_reference
-                );
+                ))?;
                 vec![]
             }
         };
@@ -602,21 +1768,75 @@ impl<'a, R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>> DapSession<
     }
 }
 
+/// Memory references handed to the client are byte offsets into the byte view
+/// of Brillig memory, in hexadecimal
+fn format_memory_reference(byte: usize) -> String {
+    format!("0x{byte:x}")
+}
+
+fn parse_memory_reference(reference: &str, offset: Option<i64>) -> Option<usize> {
+    let base = match reference.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok()?,
+        None => reference.parse().ok()?,
+    };
+    let byte = i64::try_from(base).ok()?.checked_add(offset.unwrap_or(0))?;
+    usize::try_from(byte).ok()
+}
+
+/// Source references handed to the client are the ids of the files in the
+/// debug artifact plus one, as the protocol reserves 0 for sources read from
+/// their path
+fn source_reference_of(file_id: &FileId) -> i64 {
+    file_id.as_usize() as i64 + 1
+}
+
 pub fn run_session<R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>>(
     server: Server<R, W>,
     solver: &B,
-    program: CompiledProgram,
-    initial_witness: WitnessMap<FieldElement>,
-) -> Result<(), ServerError> {
-    let debug_artifact = DebugArtifact { debug_symbols: program.debug, file_map: program.file_map };
-    let mut session = DapSession::new(
-        server,
-        solver,
-        &program.program.functions[0],
-        &debug_artifact,
-        initial_witness,
-        &program.program.unconstrained_functions,
-    );
-
-    session.run_loop()
+    program: LoadedProgram,
+    options: DapSessionOptions<'_>,
+) -> Result<DebugOutcome, ServerError> {
+    let mut server = server;
+    let mut loaded = program;
+    let mut handover = None;
+    // Each recompilation on restart is debugged by a new session, taking over
+    // the server and the breakpoints from the previous one
+    loop {
+        let LoadedProgram {
+            compiled_program: program,
+            initial_witness,
+            compile_warnings,
+            oracle_schema,
+        } = loaded;
+        let debug_artifact =
+            DebugArtifact { debug_symbols: program.debug, file_map: program.file_map };
+        let mut session = DapSession::new(
+            server,
+            solver,
+            &program.program,
+            &debug_artifact,
+            initial_witness,
+            options.clone(),
+            program.abi.error_types.clone(),
+        );
+        session.context.set_oracle_schema(oracle_schema);
+
+        // Unused variable warnings often explain why a variable is missing from
+        // the scopes, so they are shown before execution starts
+        for warning in compile_warnings {
+            session.send_output(OutputEventCategory::Console, format!("{warning}\n"))?;
+        }
+        match handover.take() {
+            Some(handover) => session.resume(handover)?,
+            None => session.run_loop()?,
+        }
+
+        let Some((restart_request, reloaded)) = session.reloaded.take() else {
+            return Ok(session.finish());
+        };
+        let (next_server, next_handover) = session.hand_over(restart_request);
+        server = next_server;
+        handover = Some(next_handover);
+        loaded = reloaded;
+    }
 }
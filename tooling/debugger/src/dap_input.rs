@@ -0,0 +1,406 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Requests that interrupt a running `continue` as soon as they arrive. A
+/// `cancel` also does, but only when it names the `continue`.
+const INTERRUPTING_COMMANDS: &[&str] = &["pause", "terminate", "disconnect"];
+
+/// Custom request answering a launch that matched several targets, naming the
+/// one picked among the candidates listed in the launch error
+pub const SELECT_TARGET_COMMAND: &str = "noir/selectTarget";
+
+/// Custom request for a page of the execution timeline of the session, whose
+/// response has the page as its body
+pub const TIMELINE_COMMAND: &str = "noir/timeline";
+
+/// Commands of the custom requests passed on as standard ones, by sequence
+/// number, so that their responses are sent under the custom command
+pub type CustomRequests = Arc<Mutex<HashMap<i64, &'static str>>>;
+
+/// Input of a DAP server whose messages are read ahead on a separate thread,
+/// so that requests to stop execution are noticed while a `continue` is
+/// running. Messages are passed on unchanged, except for the custom requests,
+/// which are recorded in `custom_requests`; `interrupt` is raised as soon as
+/// one of the requests stopping execution arrives.
+pub struct WatchedInput {
+    messages: Receiver<Vec<u8>>,
+    message: Vec<u8>,
+    position: usize,
+}
+
+impl WatchedInput {
+    pub fn new<R: Read + Send + 'static>(
+        input: R,
+        interrupt: Arc<AtomicBool>,
+        custom_requests: CustomRequests,
+    ) -> Self {
+        let (sender, messages) = channel();
+        thread::spawn(move || {
+            let mut input = BufReader::new(input);
+            let mut last_continue = None;
+            while let Ok(Some((message, body_start))) = read_message(&mut input) {
+                if is_interrupting_request(&message[body_start..], &mut last_continue) {
+                    interrupt.store(true, Ordering::SeqCst);
+                }
+                let message = translate_custom_request(message, body_start, &custom_requests);
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+        WatchedInput { messages, message: vec![], position: 0 }
+    }
+}
+
+impl Read for WatchedInput {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.position == self.message.len() {
+            match self.messages.recv() {
+                Ok(message) => {
+                    self.message = message;
+                    self.position = 0;
+                }
+                // The reading thread only stops at the end of the input
+                Err(_) => return Ok(0),
+            }
+        }
+        let length = buf.len().min(self.message.len() - self.position);
+        buf[..length].copy_from_slice(&self.message[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+/// Output of a DAP server which sends the responses to the custom requests
+/// recorded by [`WatchedInput`] under their custom command. Messages are
+/// held back until they are complete, as the server writes them in pieces.
+pub struct TranslatedOutput<W: Write> {
+    output: W,
+    pending: Vec<u8>,
+    custom_requests: CustomRequests,
+}
+
+impl<W: Write> TranslatedOutput<W> {
+    pub fn new(output: W, custom_requests: CustomRequests) -> Self {
+        TranslatedOutput { output, pending: vec![], custom_requests }
+    }
+}
+
+impl<W: Write> Write for TranslatedOutput<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        while let Some((body_start, length)) = complete_message(&self.pending) {
+            let message: Vec<u8> = self.pending.drain(..length).collect();
+            let message = translate_custom_response(message, body_start, &self.custom_requests);
+            self.output.write_all(&message)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
+
+/// Returns the offset of the body and the length of the message at the
+/// start of `buffer`, once the whole message is in it
+fn complete_message(buffer: &[u8]) -> Option<(usize, usize)> {
+    let body_start = buffer.windows(4).position(|window| window == b"\r\n\r\n")? + 4;
+    let content_length = String::from_utf8_lossy(&buffer[..body_start])
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("Content-Length"))
+        .and_then(|(_, value)| value.trim().parse().ok())
+        .unwrap_or(0);
+    let length = body_start + content_length;
+    (buffer.len() >= length).then_some((body_start, length))
+}
+
+/// Reads a whole message, headers included, returning it along with the
+/// offset of its body, or None at the end of the input
+fn read_message(input: &mut impl BufRead) -> std::io::Result<Option<(Vec<u8>, usize)>> {
+    let mut message = Vec::new();
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        message.extend_from_slice(line.as_bytes());
+        let header = line.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let body_start = message.len();
+    message.resize(body_start + content_length, 0);
+    input.read_exact(&mut message[body_start..])?;
+    Ok(Some((message, body_start)))
+}
+
+/// Checks whether a message is a request interrupting a running `continue`,
+/// keeping in `last_continue` the sequence number of the latest `continue`,
+/// which is the only request a `cancel` interrupts. Cancels naming a progress
+/// never interrupt, as the session reports no progress.
+fn is_interrupting_request(body: &[u8], last_continue: &mut Option<i64>) -> bool {
+    let Ok(message) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return false;
+    };
+    if message["type"] != "request" {
+        return false;
+    }
+    match message["command"].as_str() {
+        Some("continue") => {
+            *last_continue = message["seq"].as_i64();
+            false
+        }
+        Some("cancel") => {
+            let request_id = message["arguments"]["requestId"].as_i64();
+            request_id.is_some() && request_id == *last_continue
+        }
+        Some(command) => INTERRUPTING_COMMANDS.contains(&command),
+        None => false,
+    }
+}
+
+/// Passes on the custom requests the DAP library cannot parse as the standard
+/// requests handling them: `noir/selectTarget` becomes a `launch` holding the
+/// selection in its `selectTarget` argument, and `noir/timeline` an
+/// `evaluate` of its name followed by its arguments
+fn translate_custom_request(
+    message: Vec<u8>,
+    body_start: usize,
+    custom_requests: &CustomRequests,
+) -> Vec<u8> {
+    let Ok(mut request) = serde_json::from_slice::<serde_json::Value>(&message[body_start..])
+    else {
+        return message;
+    };
+    if request["type"] != "request" {
+        return message;
+    }
+    let arguments = request["arguments"].take();
+    let command = if request["command"] == SELECT_TARGET_COMMAND {
+        request["command"] = "launch".into();
+        request["arguments"] = serde_json::json!({ "selectTarget": arguments });
+        SELECT_TARGET_COMMAND
+    } else if request["command"] == TIMELINE_COMMAND {
+        let arguments = if arguments.is_null() { String::new() } else { arguments.to_string() };
+        request["command"] = "evaluate".into();
+        request["arguments"] =
+            serde_json::json!({ "expression": format!("{TIMELINE_COMMAND} {arguments}") });
+        TIMELINE_COMMAND
+    } else {
+        return message;
+    };
+    if let Some(seq) = request["seq"].as_i64() {
+        custom_requests.lock().expect("custom requests lock poisoned").insert(seq, command);
+    }
+    frame_message(&request)
+}
+
+/// Sends the response to a custom request recorded by
+/// `translate_custom_request` under its custom command
+fn translate_custom_response(
+    message: Vec<u8>,
+    body_start: usize,
+    custom_requests: &CustomRequests,
+) -> Vec<u8> {
+    let Ok(mut response) = serde_json::from_slice::<serde_json::Value>(&message[body_start..])
+    else {
+        return message;
+    };
+    if response["type"] != "response" {
+        return message;
+    }
+    let Some(request_seq) = response["request_seq"].as_i64() else {
+        return message;
+    };
+    let command =
+        custom_requests.lock().expect("custom requests lock poisoned").remove(&request_seq);
+    let Some(command) = command else {
+        return message;
+    };
+    response["command"] = command.into();
+    if command == TIMELINE_COMMAND {
+        // The page is answered as the result of the `evaluate` passed on
+        let page =
+            response["body"]["result"].as_str().and_then(|page| serde_json::from_str(page).ok());
+        if let Some(page) = page {
+            response["body"] = page;
+        }
+    }
+    frame_message(&response)
+}
+
+fn frame_message(body: &serde_json::Value) -> Vec<u8> {
+    let body = body.to_string();
+    format!("Content-Length: {}\r\n\r\n{body}", body.len()).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(body: &str) -> String {
+        format!("Content-Length: {}\r\n\r\n{body}", body.len())
+    }
+
+    #[test]
+    fn passes_messages_through_and_flags_interrupting_requests() {
+        let input = [
+            frame(r#"{"seq": 1, "type": "request", "command": "continue"}"#),
+            frame(r#"{"seq": 2, "type": "request", "command": "terminate"}"#),
+        ]
+        .concat();
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let mut watched = WatchedInput::new(
+            std::io::Cursor::new(input.clone()),
+            interrupt.clone(),
+            CustomRequests::default(),
+        );
+
+        let mut output = String::new();
+        watched.read_to_string(&mut output).unwrap();
+        assert_eq!(output, input);
+        assert!(interrupt.load(Ordering::SeqCst));
+
+        let continue_only = frame(r#"{"seq": 1, "type": "request", "command": "continue"}"#);
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let mut watched = WatchedInput::new(
+            std::io::Cursor::new(continue_only),
+            interrupt.clone(),
+            CustomRequests::default(),
+        );
+        watched.read_to_string(&mut String::new()).unwrap();
+        assert!(!interrupt.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn only_cancels_of_the_running_continue_interrupt_it() {
+        let cancel = |request_id: i64| {
+            frame(&format!(
+                r#"{{"seq": 9, "type": "request", "command": "cancel", "arguments": {{"requestId": {request_id}}}}}"#
+            ))
+        };
+        let interrupted_by = |cancel: String| {
+            let input = [
+                frame(r#"{"seq": 1, "type": "request", "command": "continue"}"#),
+                frame(r#"{"seq": 2, "type": "request", "command": "evaluate"}"#),
+                cancel,
+            ]
+            .concat();
+            let interrupt = Arc::new(AtomicBool::new(false));
+            let mut watched = WatchedInput::new(
+                std::io::Cursor::new(input),
+                interrupt.clone(),
+                CustomRequests::default(),
+            );
+            watched.read_to_string(&mut String::new()).unwrap();
+            interrupt.load(Ordering::SeqCst)
+        };
+        assert!(interrupted_by(cancel(1)));
+        assert!(!interrupted_by(cancel(2)));
+        let progress_cancel = frame(
+            r#"{"seq": 9, "type": "request", "command": "cancel", "arguments": {"progressId": "1"}}"#,
+        );
+        assert!(!interrupted_by(progress_cancel));
+    }
+
+    #[test]
+    fn translates_target_selection_into_launch() {
+        let input = frame(
+            r#"{"seq": 3, "type": "request", "command": "noir/selectTarget", "arguments": {"package": "bar"}}"#,
+        );
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let custom_requests = CustomRequests::default();
+        let mut watched =
+            WatchedInput::new(std::io::Cursor::new(input), interrupt, custom_requests.clone());
+
+        let mut output = String::new();
+        watched.read_to_string(&mut output).unwrap();
+        let (_, body) = output.split_once("\r\n\r\n").unwrap();
+        let request: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(request["seq"], 3);
+        assert_eq!(custom_requests.lock().unwrap().get(&3), Some(&SELECT_TARGET_COMMAND));
+        assert_eq!(request["command"], "launch");
+        assert_eq!(request["arguments"]["selectTarget"]["package"], "bar");
+        assert_eq!(output, frame(body));
+    }
+
+    #[test]
+    fn translates_timeline_request_into_evaluate() {
+        let input = frame(
+            r#"{"seq": 4, "type": "request", "command": "noir/timeline", "arguments": {"startIndex": 10}}"#,
+        );
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let mut watched =
+            WatchedInput::new(std::io::Cursor::new(input), interrupt, CustomRequests::default());
+
+        let mut output = String::new();
+        watched.read_to_string(&mut output).unwrap();
+        let (_, body) = output.split_once("\r\n\r\n").unwrap();
+        let request: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(request["seq"], 4);
+        assert_eq!(request["command"], "evaluate");
+        assert_eq!(request["arguments"]["expression"], r#"noir/timeline {"startIndex":10}"#);
+    }
+
+    #[test]
+    fn answers_custom_requests_under_their_command() {
+        let custom_requests = CustomRequests::default();
+        custom_requests.lock().unwrap().insert(3, SELECT_TARGET_COMMAND);
+        let mut output = TranslatedOutput::new(vec![], custom_requests.clone());
+
+        // The server writes the headers and the body of a message separately
+        let response = r#"{"seq": 7, "type": "response", "request_seq": 3, "command": "launch", "success": true}"#;
+        let event = frame(r#"{"seq": 8, "type": "event", "event": "initialized"}"#);
+        write!(output, "Content-Length: {}\r\n\r\n", response.len()).unwrap();
+        assert!(output.output.is_empty());
+        output.write_all(response.as_bytes()).unwrap();
+        output.write_all(event.as_bytes()).unwrap();
+
+        let written = String::from_utf8(output.output).unwrap();
+        let (_, body) = written.split_once("\r\n\r\n").unwrap();
+        let (response, _) = body.split_at(body.find("Content-Length").unwrap());
+        let response: serde_json::Value = serde_json::from_str(response).unwrap();
+        assert_eq!(response["command"], SELECT_TARGET_COMMAND);
+        assert_eq!(response["request_seq"], 3);
+        assert!(written.ends_with(&event));
+        assert!(custom_requests.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn answers_timeline_requests_with_the_page_as_body() {
+        let custom_requests = CustomRequests::default();
+        custom_requests.lock().unwrap().insert(4, TIMELINE_COMMAND);
+        let mut output = TranslatedOutput::new(vec![], custom_requests);
+
+        let page =
+            serde_json::json!({ "version": 1, "firstIndex": 0, "totalEvents": 0, "events": [] });
+        let response = serde_json::json!({
+            "seq": 9,
+            "type": "response",
+            "request_seq": 4,
+            "command": "evaluate",
+            "success": true,
+            "body": { "result": page.to_string(), "variablesReference": 0 },
+        });
+        output.write_all(&frame_message(&response)).unwrap();
+
+        let written = String::from_utf8(output.output).unwrap();
+        let (_, body) = written.split_once("\r\n\r\n").unwrap();
+        let response: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(response["command"], TIMELINE_COMMAND);
+        assert_eq!(response["body"], page);
+    }
+}
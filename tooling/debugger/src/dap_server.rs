@@ -0,0 +1,666 @@
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::net::TcpStream;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use acvm::acir::native_types::{WitnessMap, WitnessStack};
+use acvm::{BlackBoxFunctionSolver, FieldElement};
+use base64::Engine;
+use dap::errors::ServerError;
+use dap::events::{Event, ExitedEventBody, OutputEventBody};
+use dap::requests::{Command, Request};
+use dap::responses::{Response, ResponseBody};
+use dap::server::Server;
+use dap::types::{Capabilities, Message, OutputEventCategory, Source};
+use nargo::constants::{PROVER_INPUT_FILE, TARGET_DIR};
+use nargo::ops::{OracleResolverOptions, PrintOutput};
+use noirc_driver::CompiledProgram;
+use serde_json::{Map, Value};
+
+use crate::dap_input::{CustomRequests, TranslatedOutput, WatchedInput};
+use crate::errors::{CompileDiagnostic, DapError, DebuggerErrorKind, LoadError};
+use crate::foreign_calls::{load_oracle_mocks, ForeignCallResolverConfig};
+use crate::oracle_schema::OracleSchema;
+use crate::source_paths::PathSubstitutions;
+use crate::{DapSessionOptions, DebugOutcome, FailureReport};
+
+/// Streams a DAP server talks to its client over
+pub struct DapTransport<R, W> {
+    pub input: R,
+    pub output: W,
+}
+
+impl DapTransport<std::io::Stdin, std::io::Stdout> {
+    pub fn stdio() -> Self {
+        DapTransport { input: std::io::stdin(), output: std::io::stdout() }
+    }
+}
+
+impl DapTransport<TcpStream, TcpStream> {
+    /// Transport over a connection accepted from a DAP client
+    pub fn tcp(stream: TcpStream) -> std::io::Result<Self> {
+        Ok(DapTransport { input: stream.try_clone()?, output: stream })
+    }
+}
+
+/// What a DAP client asks to debug in its `launch` request
+#[derive(Debug, Clone, Default)]
+pub struct LaunchArguments {
+    pub project_folder: String,
+    pub package: Option<String>,
+    /// Prover file to read the program inputs from
+    pub prover_name: String,
+    /// Inputs document in TOML or JSON format taking the place of the prover
+    /// file, either given as is or built from the inline `inputs` object
+    pub inputs_document: Option<String>,
+    pub generate_acir: bool,
+    pub skip_instrumentation: bool,
+    pub path_substitutions: PathSubstitutions,
+    /// Save the partial witness and a failure report to the target directory
+    /// of the project folder if the session ends on an execution failure
+    pub save_witness_on_failure: bool,
+    /// Run the program without instrumentation nor breakpoints, only
+    /// reporting its output and result, for "Run Without Debugging"
+    pub no_debug: bool,
+    /// Oracle resolver and mocks answering the program's foreign calls
+    pub foreign_call_resolver: ForeignCallResolverConfig,
+    /// Recompile the program when restarting the session after its sources
+    /// changed
+    pub recompile_on_restart: bool,
+}
+
+/// Id of the error message answering a launch that matched several targets
+const AMBIGUOUS_LAUNCH_ERROR_ID: i64 = 1;
+
+/// A program compiled for a launch request, ready to be debugged
+pub struct LoadedProgram {
+    pub compiled_program: CompiledProgram,
+    pub initial_witness: WitnessMap<FieldElement>,
+    /// Warnings the compiler reported, shown in the debug console at launch
+    pub compile_warnings: Vec<String>,
+    /// Signatures of the oracles declared in the package's oracles.toml
+    pub oracle_schema: OracleSchema,
+}
+
+type ProgramLoader<'a> = dyn Fn(&LaunchArguments) -> Result<LoadedProgram, LoadError> + 'a;
+
+/// Compiles the launched program again, for sessions recompiling on restart
+pub type ProgramReloader<'a> = dyn Fn() -> Result<LoadedProgram, LoadError> + 'a;
+
+/// How a DAP server solves black box functions and gets the program to debug
+pub struct DapServerOptions<'a, B: BlackBoxFunctionSolver<FieldElement>> {
+    pub solver: &'a B,
+    /// Compiles the program to debug and encodes its inputs, for each launch request
+    pub load_program: Box<ProgramLoader<'a>>,
+}
+
+/// Serves a DAP debugging session over `transport`: answers the client until
+/// it launches a program, then debugs it until the client disconnects. The
+/// caller only needs to provide how programs are compiled, so that hosts
+/// other than `nargo dap` can run the server in-process.
+pub fn run_dap_server<R, W, B>(
+    transport: DapTransport<R, W>,
+    options: DapServerOptions<B>,
+) -> Result<(), DapError>
+where
+    R: Read + Send + 'static,
+    W: Write,
+    B: BlackBoxFunctionSolver<FieldElement>,
+{
+    // Requests are read ahead so that one stopping execution can interrupt a
+    // running `continue`. Custom requests are passed on as standard ones and
+    // answered under their own command.
+    let interrupt = Arc::new(AtomicBool::new(false));
+    let custom_requests = CustomRequests::default();
+    let input = BufReader::new(WatchedInput::new(
+        transport.input,
+        interrupt.clone(),
+        custom_requests.clone(),
+    ));
+    let output = TranslatedOutput::new(transport.output, custom_requests);
+    let server = Server::new(input, BufWriter::new(output));
+
+    loop_uninitialized_dap(server, options, interrupt)
+}
+
+fn loop_uninitialized_dap<R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>>(
+    mut server: Server<R, W>,
+    options: DapServerOptions<B>,
+    interrupt: Arc<AtomicBool>,
+) -> Result<(), DapError> {
+    // Launch that matched several targets, waiting for the client to pick one
+    let mut pending_launch: Option<LaunchArguments> = None;
+    loop {
+        let req = match server.poll_request()? {
+            Some(req) => req,
+            None => break,
+        };
+
+        match req.command {
+            Command::Initialize(_) => {
+                let rsp = req.success(ResponseBody::Initialize(Capabilities {
+                    supports_breakpoint_locations_request: Some(true),
+                    supports_completions_request: Some(true),
+                    supports_conditional_breakpoints: Some(true),
+                    supports_hit_conditional_breakpoints: Some(true),
+                    supports_disassemble_request: Some(true),
+                    supports_exception_info_request: Some(true),
+                    supports_read_memory_request: Some(true),
+                    supports_write_memory_request: Some(true),
+                    supports_function_breakpoints: Some(true),
+                    supports_goto_targets_request: Some(true),
+                    supports_instruction_breakpoints: Some(true),
+                    supports_loaded_sources_request: Some(true),
+                    supports_modules_request: Some(true),
+                    supports_restart_request: Some(true),
+                    supports_stepping_granularity: Some(true),
+                    supports_step_back: Some(true),
+                    supports_terminate_request: Some(true),
+                    supports_cancel_request: Some(true),
+                    ..Default::default()
+                }));
+                server.respond(rsp)?;
+            }
+
+            Command::Launch(ref arguments) => {
+                let Some(Value::Object(ref additional_data)) = arguments.additional_data else {
+                    let message = format!(
+                        "[{}] Missing launch arguments",
+                        DebuggerErrorKind::Protocol.category()
+                    );
+                    server.respond(req.error(&message))?;
+                    continue;
+                };
+                let launch = match additional_data.get("selectTarget") {
+                    Some(selection) => select_launch_target(pending_launch.take(), selection),
+                    None => parse_launch_arguments(additional_data),
+                };
+                let launch = match launch {
+                    Ok(launch) => launch,
+                    Err(message) => {
+                        server.respond(req.error(&message))?;
+                        continue;
+                    }
+                };
+
+                eprintln!("Project folder: {}", launch.project_folder);
+                eprintln!("Package: {}", launch.package.as_deref().unwrap_or("(default)"));
+                if launch.inputs_document.is_some() {
+                    eprintln!("Prover inputs: (from launch arguments)");
+                } else {
+                    eprintln!("Prover name: {}", launch.prover_name);
+                }
+
+                match (options.load_program)(&launch) {
+                    Ok(loaded_program) => {
+                        server.respond(req.ack()?)?;
+                        if launch.no_debug {
+                            run_without_debugging(
+                                &mut server,
+                                options.solver,
+                                loaded_program,
+                                launch.foreign_call_resolver,
+                            )?;
+                            // The client disconnects once it sees the program exited
+                            continue;
+                        }
+
+                        let reload = || (options.load_program)(&launch);
+                        let session_options = DapSessionOptions {
+                            path_substitutions: launch.path_substitutions.clone(),
+                            resolver_config: launch.foreign_call_resolver.clone(),
+                            reload: launch
+                                .recompile_on_restart
+                                .then_some(&reload as &ProgramReloader),
+                            interrupt: interrupt.clone(),
+                        };
+                        let outcome = crate::run_dap_loop(
+                            server,
+                            options.solver,
+                            loaded_program,
+                            session_options,
+                        )?;
+                        if let (true, DebugOutcome::Failed(witness_stack, report)) =
+                            (launch.save_witness_on_failure, outcome)
+                        {
+                            save_launch_failure(
+                                &launch.project_folder,
+                                launch.package.as_deref(),
+                                witness_stack,
+                                &report,
+                            );
+                        }
+                        break;
+                    }
+                    Err(error @ LoadError::Ambiguous { .. }) => {
+                        server.respond(ambiguous_launch_response(req, &error))?;
+                        // Launched again once the client selects a target
+                        pending_launch = Some(launch);
+                    }
+                    Err(error) => {
+                        if let LoadError::CompileFailed(diagnostics) = &error {
+                            send_compile_diagnostics(&mut server, diagnostics)?;
+                        }
+                        server.respond(req.error(&error.dap_message()))?;
+                    }
+                }
+            }
+
+            Command::Disconnect(_) => {
+                server.respond(req.ack()?)?;
+                break;
+            }
+
+            _ => {
+                let command = req.command;
+                eprintln!("ERROR: unhandled command: {command:?}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads the arguments of a launch request, returning the message to report
+/// to the client if they are invalid
+fn parse_launch_arguments(additional_data: &Map<String, Value>) -> Result<LaunchArguments, String> {
+    let Some(Value::String(project_folder)) = additional_data.get("projectFolder") else {
+        return Err(format!(
+            "[{}] Missing project folder argument",
+            DebuggerErrorKind::Input.category()
+        ));
+    };
+
+    let package = additional_data.get("package").and_then(|v| v.as_str()).map(String::from);
+    let prover_name =
+        additional_data.get("proverName").and_then(|v| v.as_str()).unwrap_or(PROVER_INPUT_FILE);
+    // An inputs document in TOML or JSON format, base64 encoded, taking the
+    // place of the prover file
+    let inputs_document = match additional_data.get("proverInputs") {
+        Some(Value::String(encoded)) => Some(decode_inputs_document(encoded).map_err(|error| {
+            format!(
+                "[{}] Invalid proverInputs argument: {error}",
+                DebuggerErrorKind::Input.category()
+            )
+        })?),
+        _ => None,
+    };
+    // Input values given inline, eg. { "x": "1", "y": "2" }, read as a JSON
+    // inputs document so that launch configurations don't need a prover file
+    let inputs_document = match (additional_data.get("inputs"), inputs_document) {
+        (Some(Value::Object(_)), Some(_)) => {
+            return Err(format!(
+                "[{}] The inputs and proverInputs arguments can't be given together",
+                DebuggerErrorKind::Input.category()
+            ));
+        }
+        (Some(inputs @ Value::Object(_)), None) => Some(inputs.to_string()),
+        (Some(_), _) => {
+            return Err(format!(
+                "[{}] Invalid inputs argument: expected an object mapping input names to values",
+                DebuggerErrorKind::Input.category()
+            ));
+        }
+        (None, inputs_document) => inputs_document,
+    };
+
+    let generate_acir =
+        additional_data.get("generateAcir").and_then(|v| v.as_bool()).unwrap_or(false);
+    let no_debug = additional_data.get("noDebug").and_then(|v| v.as_bool()).unwrap_or(false);
+    // Instrumentation only serves to inspect variables, which running
+    // without debugging does not allow
+    let skip_instrumentation = no_debug
+        || additional_data
+            .get("skipInstrumentation")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(generate_acir);
+    let save_witness_on_failure =
+        additional_data.get("saveWitnessOnFailure").and_then(|v| v.as_bool()).unwrap_or(false);
+    let recompile_on_restart =
+        additional_data.get("recompileOnRestart").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    // Maps source path prefixes to directories on disk, eg. to open the
+    // embedded stdlib from a checkout: { "std": "/path/to/noir_stdlib/src" }
+    let mut path_substitutions = PathSubstitutions::default();
+    if let Some(Value::Object(substitute_path)) = additional_data.get("substitutePath") {
+        for (from, to) in substitute_path {
+            if let Some(to) = to.as_str() {
+                path_substitutions.add(from, to);
+            }
+        }
+    }
+
+    let foreign_call_resolver = match additional_data.get("oracleResolver") {
+        Some(resolver) => parse_oracle_resolver(resolver, project_folder).map_err(|error| {
+            format!(
+                "[{}] Invalid oracleResolver argument: {error}",
+                DebuggerErrorKind::Input.category()
+            )
+        })?,
+        None => ForeignCallResolverConfig::default(),
+    };
+
+    Ok(LaunchArguments {
+        project_folder: project_folder.clone(),
+        package,
+        prover_name: prover_name.to_string(),
+        inputs_document,
+        generate_acir,
+        skip_instrumentation,
+        path_substitutions,
+        save_witness_on_failure,
+        no_debug,
+        foreign_call_resolver,
+        recompile_on_restart,
+    })
+}
+
+/// Reads the `oracleResolver` launch argument, either the URL of the resolver
+/// or an object with any of its `url`, the `headers` to send to it, its
+/// `timeout` in milliseconds and a `mocks` fixture file, relative to the
+/// project folder
+fn parse_oracle_resolver(
+    resolver: &Value,
+    project_folder: &str,
+) -> Result<ForeignCallResolverConfig, String> {
+    let options = match resolver {
+        Value::String(url) => return resolver_config_for_url(url),
+        Value::Object(options) => options,
+        _ => return Err("expected a URL or an object".to_string()),
+    };
+
+    let mut config = match options.get("url") {
+        Some(Value::String(url)) => resolver_config_for_url(url)?,
+        Some(_) => return Err("url must be a string".to_string()),
+        None => ForeignCallResolverConfig::default(),
+    };
+    if let Some(resolver) = &mut config.resolver {
+        if let Some(timeout) = options.get("timeout") {
+            let timeout = timeout.as_u64().ok_or("timeout must be a number of milliseconds")?;
+            resolver.timeout = Some(Duration::from_millis(timeout));
+        }
+        match options.get("headers") {
+            Some(Value::Object(headers)) => {
+                for (name, value) in headers {
+                    let value =
+                        value.as_str().ok_or_else(|| format!("header {name} must be a string"))?;
+                    resolver.headers.push((name.clone(), value.to_string()));
+                }
+            }
+            Some(_) => return Err("headers must be an object".to_string()),
+            None => (),
+        }
+    } else if options.contains_key("timeout") || options.contains_key("headers") {
+        return Err("timeout and headers need a url".to_string());
+    }
+    match options.get("mocks") {
+        Some(Value::String(mocks)) => {
+            config.mocks = load_oracle_mocks(&Path::new(project_folder).join(mocks))?;
+        }
+        Some(_) => return Err("mocks must be the path of a fixture file".to_string()),
+        None => (),
+    }
+    Ok(config)
+}
+
+/// Checks that the resolver URL is one the HTTP client can connect to, so a
+/// typo fails the launch instead of the client built for it
+fn resolver_config_for_url(url: &str) -> Result<ForeignCallResolverConfig, String> {
+    let host = url.strip_prefix("http://").or_else(|| url.strip_prefix("https://"));
+    match host {
+        Some(host) if !host.is_empty() && !host.starts_with('/') => (),
+        _ => return Err(format!("{url} is not an http:// or https:// URL")),
+    }
+    Ok(ForeignCallResolverConfig {
+        resolver: Some(OracleResolverOptions::from_url(url)),
+        ..ForeignCallResolverConfig::default()
+    })
+}
+
+/// Narrows down a launch that matched several targets to the one the client
+/// picked with a `noir/selectTarget` request
+fn select_launch_target(
+    pending_launch: Option<LaunchArguments>,
+    selection: &Value,
+) -> Result<LaunchArguments, String> {
+    let Some(mut launch) = pending_launch else {
+        return Err(format!(
+            "[{}] No launch is waiting for a target to be selected",
+            DebuggerErrorKind::Protocol.category()
+        ));
+    };
+    let Some(package) = selection.get("package").and_then(|v| v.as_str()) else {
+        return Err(format!(
+            "[{}] Missing package in the target selection",
+            DebuggerErrorKind::Input.category()
+        ));
+    };
+    launch.package = Some(package.to_string());
+    Ok(launch)
+}
+
+/// Error response to a launch matching several targets. The candidates are
+/// given as `name: path` variables of the error message, so that the client
+/// can offer a choice and answer with a `noir/selectTarget` request.
+fn ambiguous_launch_response(req: Request, error: &LoadError) -> Response {
+    let message = error.dap_message();
+    let candidates = match error {
+        LoadError::Ambiguous { candidates, .. } => candidates.as_slice(),
+        _ => &[],
+    };
+    let mut response = req.error(&message);
+    response.error = Some(Message {
+        id: AMBIGUOUS_LAUNCH_ERROR_ID,
+        format: message,
+        variables: Some(
+            candidates
+                .iter()
+                .map(|candidate| (candidate.name.clone(), candidate.path.clone()))
+                .collect(),
+        ),
+        send_telemetry: None,
+        show_user: Some(false),
+        url: None,
+        url_label: None,
+    });
+    response
+}
+
+/// Executes a launched program to completion without a debugging session,
+/// reporting what it printed and its result as output events before telling
+/// the client that it exited
+fn run_without_debugging<R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>>(
+    server: &mut Server<R, W>,
+    solver: &B,
+    loaded_program: LoadedProgram,
+    resolver_config: ForeignCallResolverConfig,
+) -> Result<(), ServerError> {
+    let LoadedProgram { compiled_program, initial_witness, compile_warnings, .. } = loaded_program;
+    for warning in compile_warnings {
+        send_output(server, OutputEventCategory::Console, format!("{warning}\n"))?;
+    }
+
+    let program_output = Arc::new(Mutex::new(String::new()));
+    let result = crate::execute_program(
+        solver,
+        &compiled_program,
+        initial_witness,
+        PrintOutput::Buffer(program_output.clone()),
+        resolver_config,
+    );
+    let printed = std::mem::take(&mut *program_output.lock().unwrap());
+    if !printed.is_empty() {
+        send_output(server, OutputEventCategory::Stdout, printed)?;
+    }
+
+    let exit_code = match result {
+        Ok(witness_stack) => {
+            let main_witness = &witness_stack
+                .peek()
+                .expect("Should have at least one witness on the stack")
+                .witness;
+            let message = match compiled_program.abi.decode(main_witness) {
+                Ok((_, Some(return_value))) => {
+                    format!(
+                        "Circuit witness successfully solved\nCircuit output: {return_value:?}\n"
+                    )
+                }
+                Ok((_, None)) => String::from("Circuit witness successfully solved\n"),
+                Err(error) => format!(
+                    "Circuit witness solved, but its output could not be decoded: {error}\n"
+                ),
+            };
+            send_output(server, OutputEventCategory::Console, message)?;
+            0
+        }
+        Err(error) => {
+            send_output(
+                server,
+                OutputEventCategory::Stderr,
+                format!("Execution failed: {error}\n"),
+            )?;
+            1
+        }
+    };
+    server.send_event(Event::Exited(ExitedEventBody { exit_code }))?;
+    server.send_event(Event::Terminated(None))?;
+    Ok(())
+}
+
+/// Reports the diagnostics of a failed compilation as output events located
+/// in the source, so that clients can link them to the offending code
+pub(crate) fn send_compile_diagnostics<R: Read, W: Write>(
+    server: &mut Server<R, W>,
+    diagnostics: &[CompileDiagnostic],
+) -> Result<(), ServerError> {
+    for diagnostic in diagnostics {
+        let category = if diagnostic.is_error {
+            OutputEventCategory::Stderr
+        } else {
+            OutputEventCategory::Console
+        };
+        let location = diagnostic.location.as_ref();
+        server.send_event(Event::Output(OutputEventBody {
+            category: Some(category),
+            output: format!("{diagnostic}\n"),
+            source: location.map(|location| Source {
+                name: location.path.file_name().and_then(|name| name.to_str()).map(String::from),
+                path: location.path.to_str().map(String::from),
+                ..Source::default()
+            }),
+            line: location.map(|location| location.line as i64),
+            column: location.map(|location| location.column as i64),
+            ..OutputEventBody::default()
+        }))?;
+    }
+    Ok(())
+}
+
+fn send_output<R: Read, W: Write>(
+    server: &mut Server<R, W>,
+    category: OutputEventCategory,
+    output: String,
+) -> Result<(), ServerError> {
+    server.send_event(Event::Output(OutputEventBody {
+        category: Some(category),
+        output,
+        ..OutputEventBody::default()
+    }))?;
+    Ok(())
+}
+
+/// Saves the partial witness stack and the report of a session that ended on
+/// a failure to the target directory of the launched project folder
+fn save_launch_failure(
+    project_folder: &str,
+    package: Option<&str>,
+    witness_stack: WitnessStack<FieldElement>,
+    report: &FailureReport,
+) {
+    let dir = Path::new(project_folder).join(TARGET_DIR);
+    let name = format!("{}-failure", package.unwrap_or("debug"));
+    match crate::save_failure(&dir, &name, witness_stack, report) {
+        Ok((witness_path, report_path)) => eprintln!(
+            "Partial witness saved to {} and failure report to {}",
+            witness_path.display(),
+            report_path.display()
+        ),
+        Err(message) => eprintln!("ERROR: {message}"),
+    }
+}
+
+/// Decodes an inputs document passed base64 encoded in the launch arguments
+fn decode_inputs_document(encoded: &str) -> Result<String, String> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|error| error.to_string())?;
+    String::from_utf8(bytes).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_launch_arguments() {
+        let data = serde_json::json!({
+            "projectFolder": "/project",
+            "proverInputs": base64::engine::general_purpose::STANDARD.encode("x = 1"),
+            "generateAcir": true,
+        });
+        let launch = parse_launch_arguments(data.as_object().unwrap()).unwrap();
+        assert_eq!(launch.project_folder, "/project");
+        assert_eq!(launch.prover_name, PROVER_INPUT_FILE);
+        assert_eq!(launch.inputs_document.as_deref(), Some("x = 1"));
+        assert!(launch.generate_acir && launch.skip_instrumentation);
+
+        let missing_folder = serde_json::json!({ "package": "main" });
+        assert!(parse_launch_arguments(missing_folder.as_object().unwrap()).is_err());
+    }
+
+    #[test]
+    fn reads_inline_inputs_as_json_document() {
+        let data = serde_json::json!({
+            "projectFolder": "/project",
+            "inputs": { "x": "1", "y": ["2", "3"] },
+        });
+        let launch = parse_launch_arguments(data.as_object().unwrap()).unwrap();
+        let document: Value =
+            serde_json::from_str(launch.inputs_document.as_deref().unwrap()).unwrap();
+        assert_eq!(document, data["inputs"]);
+
+        let both = serde_json::json!({
+            "projectFolder": "/project",
+            "inputs": { "x": "1" },
+            "proverInputs": base64::engine::general_purpose::STANDARD.encode("x = 1"),
+        });
+        assert!(parse_launch_arguments(both.as_object().unwrap()).is_err());
+        let not_an_object = serde_json::json!({ "projectFolder": "/project", "inputs": "x = 1" });
+        assert!(parse_launch_arguments(not_an_object.as_object().unwrap()).is_err());
+    }
+
+    #[test]
+    fn reads_oracle_resolver_options() {
+        let config =
+            parse_oracle_resolver(&Value::from("http://localhost:5555"), "/project").unwrap();
+        assert_eq!(config.resolver.unwrap().url, "http://localhost:5555");
+
+        let options = serde_json::json!({
+            "url": "http://localhost:5555",
+            "timeout": 1500,
+            "headers": { "Authorization": "Bearer token" },
+        });
+        let resolver = parse_oracle_resolver(&options, "/project").unwrap().resolver.unwrap();
+        assert_eq!(resolver.timeout, Some(Duration::from_millis(1500)));
+        assert_eq!(resolver.headers, vec![("Authorization".into(), "Bearer token".into())]);
+
+        let headers_without_url = serde_json::json!({ "headers": { "X-Key": "1" } });
+        assert!(parse_oracle_resolver(&headers_without_url, "/project").is_err());
+        let missing_mocks = serde_json::json!({ "mocks": "missing.json" });
+        assert!(parse_oracle_resolver(&missing_mocks, "/nonexistent").is_err());
+        assert!(parse_oracle_resolver(&Value::from("localhost:5555"), "/project").is_err());
+        let empty_host = serde_json::json!({ "url": "http://" });
+        assert!(parse_oracle_resolver(&empty_host, "/project").is_err());
+    }
+}
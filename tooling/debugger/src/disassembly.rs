@@ -0,0 +1,138 @@
+use acvm::acir::brillig::{
+    HeapArray, HeapVector, MemoryAddress, Opcode as BrilligOpcode, ValueOrArray,
+};
+use acvm::{AcirField, FieldElement};
+
+/// Symbol of the unconstrained function with the given id, used to label its
+/// opcodes and the targets of its jumps
+pub(super) fn brillig_function_symbol(function_id: u32) -> String {
+    format!("brillig_{function_id}")
+}
+
+/// Renders a Brillig opcode of the unconstrained function `function_id` in a
+/// compact form, with memory operands shown as `@address`, pointers
+/// dereferenced as `*@address` and jump targets labelled with the symbol of
+/// the function, eg. `@3 = Equals @0, @2 (field)` or `jump_if @3 to brillig_0+7`.
+pub(super) fn render_brillig_opcode(
+    opcode: &BrilligOpcode<FieldElement>,
+    function_id: u32,
+) -> String {
+    let label = |location: &usize| format!("{}+{location}", brillig_function_symbol(function_id));
+    match opcode {
+        BrilligOpcode::BinaryFieldOp { destination, op, lhs, rhs } => {
+            format!("{} = {op:?} {}, {} (field)", at(destination), at(lhs), at(rhs))
+        }
+        BrilligOpcode::BinaryIntOp { destination, op, bit_size, lhs, rhs } => {
+            format!("{} = {op:?} {}, {} (u{bit_size})", at(destination), at(lhs), at(rhs))
+        }
+        BrilligOpcode::Cast { destination, source, bit_size } => {
+            format!("{} = cast {} to {}", at(destination), at(source), type_name(*bit_size))
+        }
+        BrilligOpcode::JumpIfNot { condition, location } => {
+            format!("jump_if_not {} to {}", at(condition), label(location))
+        }
+        BrilligOpcode::JumpIf { condition, location } => {
+            format!("jump_if {} to {}", at(condition), label(location))
+        }
+        BrilligOpcode::Jump { location } => format!("jump to {}", label(location)),
+        BrilligOpcode::Call { location } => format!("call {}", label(location)),
+        BrilligOpcode::CalldataCopy { destination_address, size, offset } => format!(
+            "@{}..@{} = calldata[{offset}..{}]",
+            destination_address.to_usize(),
+            destination_address.to_usize() + size,
+            offset + size
+        ),
+        BrilligOpcode::Const { destination, bit_size, value } => {
+            format!("{} = {value} ({})", at(destination), type_name(*bit_size))
+        }
+        BrilligOpcode::Return => String::from("return"),
+        BrilligOpcode::ForeignCall { function, destinations, inputs, .. } => {
+            let inputs: Vec<_> = inputs.iter().map(value_or_array).collect();
+            let call = format!("foreign_call {function}({})", inputs.join(", "));
+            if destinations.is_empty() {
+                return call;
+            }
+            let destinations: Vec<_> = destinations.iter().map(value_or_array).collect();
+            format!("{} = {call}", destinations.join(", "))
+        }
+        BrilligOpcode::Mov { destination, source } => {
+            format!("{} = {}", at(destination), at(source))
+        }
+        BrilligOpcode::ConditionalMov { destination, source_a, source_b, condition } => {
+            format!("{} = {} ? {} : {}", at(destination), at(condition), at(source_a), at(source_b))
+        }
+        BrilligOpcode::Load { destination, source_pointer } => {
+            format!("{} = *{}", at(destination), at(source_pointer))
+        }
+        BrilligOpcode::Store { destination_pointer, source } => {
+            format!("*{} = {}", at(destination_pointer), at(source))
+        }
+        BrilligOpcode::BlackBox(op) => format!("black_box {op:?}"),
+        BrilligOpcode::Trap { revert_data } => format!("trap {}", heap_array(revert_data)),
+        BrilligOpcode::Stop { return_data_offset, return_data_size } => format!(
+            "stop returning @{return_data_offset}..@{}",
+            return_data_offset + return_data_size
+        ),
+    }
+}
+
+fn at(address: &MemoryAddress) -> String {
+    format!("@{}", address.to_usize())
+}
+
+fn type_name(bit_size: u32) -> String {
+    if bit_size == FieldElement::max_num_bits() {
+        String::from("field")
+    } else {
+        format!("u{bit_size}")
+    }
+}
+
+fn heap_array(array: &HeapArray) -> String {
+    format!("*{}[{}]", at(&array.pointer), array.size)
+}
+
+fn heap_vector(vector: &HeapVector) -> String {
+    format!("*{}[{}]", at(&vector.pointer), at(&vector.size))
+}
+
+fn value_or_array(value: &ValueOrArray) -> String {
+    match value {
+        ValueOrArray::MemoryAddress(address) => at(address),
+        ValueOrArray::HeapArray(array) => heap_array(array),
+        ValueOrArray::HeapVector(vector) => heap_vector(vector),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use acvm::acir::brillig::BinaryFieldOp;
+
+    #[test]
+    fn renders_brillig_opcodes_with_memory_operands_and_labels() {
+        let equals = BrilligOpcode::BinaryFieldOp {
+            destination: MemoryAddress::from(3),
+            op: BinaryFieldOp::Equals,
+            lhs: MemoryAddress::from(0),
+            rhs: MemoryAddress::from(2),
+        };
+        assert_eq!(render_brillig_opcode(&equals, 0), "@3 = Equals @0, @2 (field)");
+
+        let jump = BrilligOpcode::JumpIf { condition: MemoryAddress::from(3), location: 7 };
+        assert_eq!(render_brillig_opcode(&jump, 1), "jump_if @3 to brillig_1+7");
+
+        let call = BrilligOpcode::ForeignCall {
+            function: String::from("print"),
+            destinations: vec![],
+            destination_value_types: vec![],
+            inputs: vec![
+                ValueOrArray::MemoryAddress(MemoryAddress::from(1)),
+                ValueOrArray::HeapArray(HeapArray { pointer: MemoryAddress::from(2), size: 4 }),
+            ],
+            input_value_types: vec![],
+        };
+        assert_eq!(render_brillig_opcode(&call, 0), "foreign_call print(@1, *@2[4])");
+    }
+}
@@ -0,0 +1,83 @@
+use std::fmt::Display;
+
+use acvm::acir::circuit::ExpressionWidth;
+use noirc_driver::{CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
+use serde::Serialize;
+
+/// Toolchain versions and compilation settings a debugging session was started with.
+///
+/// This is printed by the REPL `version` command and written to the session report so
+/// that bug reports carry everything needed to reproduce the session.
+#[derive(Debug, Clone, Serialize)]
+pub struct DebugEnvironment {
+    pub nargo_version: String,
+    pub noirc_version: String,
+    pub acvm_version: String,
+    pub blackbox_solver: String,
+    pub expression_width: ExpressionWidth,
+    pub instrument_debug: bool,
+    pub force_brillig: bool,
+    pub compile_options: Vec<String>,
+}
+
+impl DebugEnvironment {
+    pub fn new(
+        nargo_version: &str,
+        blackbox_solver: &str,
+        compile_options: &CompileOptions,
+    ) -> Self {
+        let flags = [
+            ("--force", compile_options.force_compile),
+            ("--deny-warnings", compile_options.deny_warnings),
+            ("--silence-warnings", compile_options.silence_warnings),
+            ("--disable-macros", compile_options.disable_macros),
+            ("--use-legacy", compile_options.use_legacy),
+        ];
+
+        Self {
+            nargo_version: nargo_version.to_string(),
+            noirc_version: NOIR_ARTIFACT_VERSION_STRING.to_string(),
+            acvm_version: acvm::ACVM_VERSION.to_string(),
+            blackbox_solver: blackbox_solver.to_string(),
+            expression_width: compile_options.expression_width,
+            instrument_debug: compile_options.instrument_debug,
+            force_brillig: compile_options.force_brillig,
+            compile_options: flags
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(flag, _)| flag.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl Display for DebugEnvironment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let expression_width = match self.expression_width {
+            ExpressionWidth::Unbounded => "unbounded".to_string(),
+            ExpressionWidth::Bounded { width } => width.to_string(),
+        };
+        let compile_options = if self.compile_options.is_empty() {
+            "(defaults)".to_string()
+        } else {
+            self.compile_options.join(" ")
+        };
+
+        writeln!(f, "nargo version: {}", self.nargo_version)?;
+        writeln!(f, "noirc version: {}", self.noirc_version)?;
+        writeln!(f, "acvm version: {}", self.acvm_version)?;
+        writeln!(f, "black box solver: {}", self.blackbox_solver)?;
+        writeln!(f, "expression width: {expression_width}")?;
+        writeln!(f, "debug instrumentation: {}", on_off(self.instrument_debug))?;
+        writeln!(f, "force brillig: {}", on_off(self.force_brillig))?;
+        write!(f, "compile options: {compile_options}")
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled {
+        "on"
+    } else {
+        "off"
+    }
+}
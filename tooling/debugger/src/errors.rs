@@ -1,5 +1,72 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+
+use acvm::FieldElement;
+use nargo::NargoError;
 use thiserror::Error;
 
+/// Class of a debugger failure. Each class has a stable exit code for the
+/// `nargo debug` and `nargo dap` commands, and a category that prefixes the
+/// errors reported to DAP clients, so that callers can tell failures apart
+/// without matching on messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebuggerErrorKind {
+    /// The program could not be compiled (exit code 2)
+    Compile,
+    /// The project, the program inputs or the debugger arguments are invalid (exit code 3)
+    Input,
+    /// A foreign call could not be resolved (exit code 4)
+    Oracle,
+    /// Executing the program failed (exit code 5)
+    Solver,
+    /// Communicating with the DAP client failed (exit code 6)
+    Protocol,
+    /// The debugger itself failed (exit code 7)
+    Internal,
+}
+
+impl DebuggerErrorKind {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            DebuggerErrorKind::Compile => 2,
+            DebuggerErrorKind::Input => 3,
+            DebuggerErrorKind::Oracle => 4,
+            DebuggerErrorKind::Solver => 5,
+            DebuggerErrorKind::Protocol => 6,
+            DebuggerErrorKind::Internal => 7,
+        }
+    }
+
+    pub fn category(self) -> &'static str {
+        match self {
+            DebuggerErrorKind::Compile => "compile",
+            DebuggerErrorKind::Input => "input",
+            DebuggerErrorKind::Oracle => "oracle",
+            DebuggerErrorKind::Solver => "solver",
+            DebuggerErrorKind::Protocol => "protocol",
+            DebuggerErrorKind::Internal => "internal",
+        }
+    }
+}
+
+impl From<&NargoError<FieldElement>> for DebuggerErrorKind {
+    fn from(error: &NargoError<FieldElement>) -> Self {
+        match error {
+            NargoError::CompilationError => DebuggerErrorKind::Compile,
+            NargoError::ExecutionError(_) => DebuggerErrorKind::Solver,
+            NargoError::ForeignCallError(_) => DebuggerErrorKind::Oracle,
+        }
+    }
+}
+
+/// A failed debugger command along with the class of the failure
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct DebuggerError {
+    pub kind: DebuggerErrorKind,
+    pub message: String,
+}
+
 #[derive(Debug, Error)]
 pub enum DapError {
     #[error("{0}")]
@@ -10,10 +77,98 @@ pub enum DapError {
 
     #[error(transparent)]
     ServerError(#[from] dap::errors::ServerError),
+
+    /// Listening for or accepting DAP clients failed
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+impl DapError {
+    pub fn kind(&self) -> DebuggerErrorKind {
+        match self {
+            DapError::PreFlightGenericError(_) => DebuggerErrorKind::Input,
+            DapError::LoadError(error) => error.kind(),
+            DapError::ServerError(_) | DapError::Io(_) => DebuggerErrorKind::Protocol,
+        }
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum LoadError {
     #[error("{0}")]
-    Generic(String),
+    Compile(String),
+
+    #[error("{0}")]
+    Input(String),
+
+    /// More than one target matches the launch arguments, so the client has
+    /// to pick one of the candidates
+    #[error("{message}")]
+    Ambiguous { message: String, candidates: Vec<LaunchCandidate> },
+
+    /// The program could not be compiled, with the errors and warnings the
+    /// compiler reported
+    #[error("{}", compile_failure_message(.0))]
+    CompileFailed(Vec<CompileDiagnostic>),
+}
+
+/// An error or warning reported by the compiler
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileDiagnostic {
+    pub message: String,
+    pub is_error: bool,
+    pub location: Option<DiagnosticLocation>,
+}
+
+/// Position of a diagnostic in the source, with 1-based line and column
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticLocation {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Renders the diagnostic as `path:line:column: error: message`
+impl Display for CompileDiagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(location) = &self.location {
+            write!(f, "{}:{}:{}: ", location.path.display(), location.line, location.column)?;
+        }
+        let severity = if self.is_error { "error" } else { "warning" };
+        write!(f, "{severity}: {}", self.message)
+    }
+}
+
+fn compile_failure_message(diagnostics: &[CompileDiagnostic]) -> String {
+    let errors: Vec<String> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error)
+        .map(ToString::to_string)
+        .collect();
+    if errors.is_empty() {
+        "Failed to compile project".to_string()
+    } else {
+        format!("Failed to compile project:\n{}", errors.join("\n"))
+    }
+}
+
+/// A target a launch can be narrowed down to, eg. a package of the workspace
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchCandidate {
+    pub name: String,
+    pub path: String,
+}
+
+impl LoadError {
+    pub fn kind(&self) -> DebuggerErrorKind {
+        match self {
+            LoadError::Compile(_) | LoadError::CompileFailed(_) => DebuggerErrorKind::Compile,
+            LoadError::Input(_) | LoadError::Ambiguous { .. } => DebuggerErrorKind::Input,
+        }
+    }
+
+    /// Message reported to DAP clients, prefixed by the error category
+    pub fn dap_message(&self) -> String {
+        format!("[{}] {self}", self.kind().category())
+    }
 }
@@ -0,0 +1,176 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use acvm::FieldElement;
+use noirc_printable_type::{PrintableType, PrintableValue};
+
+/// A step into a compound value: a struct field or tuple element (`.x`,
+/// `.0`), or an array or slice element (`[2]`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum PathSegment {
+    Member(String),
+    Index(usize),
+}
+
+impl Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathSegment::Member(name) => write!(f, ".{name}"),
+            PathSegment::Index(index) => write!(f, "[{index}]"),
+        }
+    }
+}
+
+/// An access path into a variable, such as `point.x` or `rows[2].1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct VariablePath {
+    pub(super) root: String,
+    pub(super) segments: Vec<PathSegment>,
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn split_name(text: &str) -> (&str, &str) {
+    text.split_at(text.find(|c| !is_name_char(c)).unwrap_or(text.len()))
+}
+
+impl FromStr for VariablePath {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim();
+        let (root, mut rest) = split_name(text);
+        if root.is_empty() {
+            return Err(format!("Invalid expression {text}; expected a variable name"));
+        }
+
+        let mut segments = vec![];
+        while !rest.is_empty() {
+            if let Some(member) = rest.strip_prefix('.') {
+                let (name, after_name) = split_name(member);
+                if name.is_empty() {
+                    return Err(format!("Invalid expression {text}; expected a name after ."));
+                }
+                segments.push(PathSegment::Member(name.to_string()));
+                rest = after_name;
+            } else if let Some(index) = rest.strip_prefix('[') {
+                let Some((index, after_index)) = index.split_once(']') else {
+                    return Err(format!("Invalid expression {text}; missing ]"));
+                };
+                let Ok(index) = index.trim().parse() else {
+                    return Err(format!("Invalid index {index} in {text}"));
+                };
+                segments.push(PathSegment::Index(index));
+                rest = after_index;
+            } else {
+                return Err(format!("Invalid expression {text}; unexpected {rest}"));
+            }
+        }
+        Ok(VariablePath { root: root.to_string(), segments })
+    }
+}
+
+/// Returns the components a value can be expanded into: the fields of a
+/// struct, or the elements of an array, slice or tuple
+pub(super) fn value_components(
+    value: &PrintableValue<FieldElement>,
+    typ: &PrintableType,
+) -> Vec<(String, PrintableValue<FieldElement>, PrintableType)> {
+    match (value, typ) {
+        (
+            PrintableValue::Vec { array_elements, .. },
+            PrintableType::Array { typ, .. } | PrintableType::Slice { typ },
+        ) => array_elements
+            .iter()
+            .enumerate()
+            .map(|(index, element)| (format!("[{index}]"), element.clone(), typ.as_ref().clone()))
+            .collect(),
+        (PrintableValue::Vec { array_elements, .. }, PrintableType::Tuple { types }) => {
+            array_elements
+                .iter()
+                .zip(types)
+                .enumerate()
+                .map(|(index, (element, typ))| (index.to_string(), element.clone(), typ.clone()))
+                .collect()
+        }
+        (PrintableValue::Struct(values), PrintableType::Struct { fields, .. }) => fields
+            .iter()
+            .filter_map(|(name, typ)| {
+                values.get(name).map(|value| (name.clone(), value.clone(), typ.clone()))
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Follows the segments of `path` into `value`, the value of its root variable
+pub(super) fn resolve_path(
+    path: &VariablePath,
+    value: &PrintableValue<FieldElement>,
+    typ: &PrintableType,
+) -> Result<(PrintableValue<FieldElement>, PrintableType), String> {
+    let mut resolved = path.root.clone();
+    let mut current = (value.clone(), typ.clone());
+    for segment in &path.segments {
+        let component_name = match segment {
+            PathSegment::Member(name) => name.clone(),
+            PathSegment::Index(index) => format!("[{index}]"),
+        };
+        current = value_components(&current.0, &current.1)
+            .into_iter()
+            .find(|(name, ..)| *name == component_name)
+            .map(|(_, value, typ)| (value, typ))
+            .ok_or_else(|| format!("{resolved} has no {segment}"))?;
+        resolved.push_str(&segment.to_string());
+    }
+    Ok(current)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use acvm::AcirField;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn resolves_paths_into_structs_arrays_and_tuples() {
+        let point_type = PrintableType::Struct {
+            name: "Point".to_string(),
+            fields: vec![("x".to_string(), PrintableType::Field)],
+        };
+        let typ = PrintableType::Array {
+            length: 2,
+            typ: Box::new(PrintableType::Tuple { types: vec![PrintableType::Boolean, point_type] }),
+        };
+        let point = |x: u128| {
+            PrintableValue::Struct(BTreeMap::from([(
+                "x".to_string(),
+                PrintableValue::Field(FieldElement::from(x)),
+            )]))
+        };
+        let row = |x: u128| PrintableValue::Vec {
+            array_elements: vec![PrintableValue::Field(FieldElement::one()), point(x)],
+            is_slice: false,
+        };
+        let value = PrintableValue::Vec { array_elements: vec![row(3), row(4)], is_slice: false };
+
+        let path: VariablePath = " rows[1].1.x ".parse().unwrap();
+        assert_eq!(path.root, "rows");
+        assert_eq!(
+            resolve_path(&path, &value, &typ),
+            Ok((PrintableValue::Field(FieldElement::from(4u128)), PrintableType::Field))
+        );
+
+        let path = "rows[2]".parse().unwrap();
+        assert_eq!(resolve_path(&path, &value, &typ), Err("rows has no [2]".to_string()));
+        let path = "rows[0].1.y".parse().unwrap();
+        assert_eq!(resolve_path(&path, &value, &typ), Err("rows[0].1 has no .y".to_string()));
+
+        assert!("rows[".parse::<VariablePath>().is_err());
+        assert!("rows.".parse::<VariablePath>().is_err());
+        assert!("rows + 1".parse::<VariablePath>().is_err());
+    }
+}
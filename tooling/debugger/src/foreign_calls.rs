@@ -1,12 +1,259 @@
 use acvm::{
-    acir::brillig::{ForeignCallParam, ForeignCallResult},
+    acir::brillig::{
+        ForeignCallParam, ForeignCallResult, HeapArray, HeapValueType, HeapVector, ValueOrArray,
+    },
+    brillig_vm::MemoryValue,
     pwg::ForeignCallWaitInfo,
     AcirField, FieldElement,
 };
-use nargo::ops::{DefaultForeignCallExecutor, ForeignCallExecutor};
+use nargo::ops::{
+    DefaultForeignCallExecutor, ForeignCallExecutor, OracleResolverOptions, PrintOutput,
+};
 use noirc_artifacts::debug::{DebugArtifact, DebugVars, StackFrame};
 use noirc_errors::debug_info::{DebugFnId, DebugVarId};
-use noirc_printable_type::ForeignCallError;
+use noirc_printable_type::{decode_value, ForeignCallError, PrintableType, PrintableValueDisplay};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::path::Path;
+
+/// How many member assignments are kept in the assignment log
+const MAX_ASSIGNMENT_LOG_ENTRIES: usize = 100;
+
+/// Checks that the values returned for a foreign call have the shape expected
+/// by the Brillig bytecode, describing the first mismatch found. This catches
+/// misbehaving oracles before their results end up in the VM memory.
+pub(crate) fn validate_foreign_call_outputs(
+    outputs: &[ForeignCallParam<FieldElement>],
+    value_types: &[HeapValueType],
+) -> Result<(), String> {
+    if outputs.len() != value_types.len() {
+        return Err(format!("returned {} values, expected {}", outputs.len(), value_types.len()));
+    }
+    for (output, value_type) in outputs.iter().zip(value_types) {
+        let valid = match (output, value_type) {
+            (ForeignCallParam::Single(_), HeapValueType::Simple(_)) => true,
+            (ForeignCallParam::Array(values), HeapValueType::Array { size, .. }) => {
+                // Nested arrays may be returned flattened
+                values.len() == *size || Some(values.len()) == flattened_size(value_type)
+            }
+            (ForeignCallParam::Array(_), HeapValueType::Vector { .. }) => true,
+            _ => false,
+        };
+        if !valid {
+            let returned = match output {
+                ForeignCallParam::Single(_) => "a single field".to_string(),
+                ForeignCallParam::Array(values) => format!("{} fields", values.len()),
+            };
+            let expected = match value_type {
+                HeapValueType::Simple(_) => "a single field".to_string(),
+                HeapValueType::Array { size, .. } => format!("array of {size}"),
+                HeapValueType::Vector { .. } => "a vector".to_string(),
+            };
+            return Err(format!("returned {returned}, expected {expected}"));
+        }
+    }
+    Ok(())
+}
+
+/// A zeroed value of the given type, shaped as an oracle would return it
+pub(crate) fn default_foreign_call_output(
+    value_type: &HeapValueType,
+) -> ForeignCallParam<FieldElement> {
+    match value_type {
+        HeapValueType::Simple(_) => ForeignCallParam::Single(FieldElement::zero()),
+        HeapValueType::Array { .. } => ForeignCallParam::Array(vec![
+            FieldElement::zero();
+            flattened_size(value_type)
+                .unwrap_or_default()
+        ]),
+        HeapValueType::Vector { .. } => ForeignCallParam::Array(vec![]),
+    }
+}
+
+/// Number of fields a value of the given type takes once flattened, if fixed
+fn flattened_size(value_type: &HeapValueType) -> Option<usize> {
+    match value_type {
+        HeapValueType::Simple(_) => Some(1),
+        HeapValueType::Array { value_types, size } => value_types
+            .iter()
+            .map(flattened_size)
+            .sum::<Option<usize>>()
+            .map(|element_size| element_size * size),
+        HeapValueType::Vector { .. } => None,
+    }
+}
+
+/// Reads the arguments of a foreign call a Brillig opcode is about to make
+/// from VM memory, as the VM passes them to the executor. Returns None if an
+/// operand doesn't match its type or points outside of memory.
+pub(crate) fn read_foreign_call_inputs(
+    memory: &[MemoryValue<FieldElement>],
+    inputs: &[ValueOrArray],
+    value_types: &[HeapValueType],
+) -> Option<Vec<ForeignCallParam<FieldElement>>> {
+    inputs
+        .iter()
+        .zip(value_types)
+        .map(|(input, value_type)| match (input, value_type) {
+            (ValueOrArray::MemoryAddress(address), HeapValueType::Simple(_)) => {
+                Some(ForeignCallParam::Single(memory.get(address.to_usize())?.to_field()))
+            }
+            (
+                ValueOrArray::HeapArray(HeapArray { pointer, size }),
+                HeapValueType::Array { value_types, .. },
+            ) => {
+                let start = read_address(memory, pointer.to_usize())?;
+                read_memory_slice(memory, start, *size, value_types).map(ForeignCallParam::Array)
+            }
+            (
+                ValueOrArray::HeapVector(HeapVector { pointer, size }),
+                HeapValueType::Vector { value_types },
+            ) => {
+                let start = read_address(memory, pointer.to_usize())?;
+                let size = read_address(memory, size.to_usize())?;
+                read_memory_slice(memory, start, size, value_types).map(ForeignCallParam::Array)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Reads `size` cells from `start`, following the pointers to the nested
+/// arrays and vectors of the elements given by `value_types`
+fn read_memory_slice(
+    memory: &[MemoryValue<FieldElement>],
+    start: usize,
+    size: usize,
+    value_types: &[HeapValueType],
+) -> Option<Vec<FieldElement>> {
+    if value_types.iter().all(|value_type| matches!(value_type, HeapValueType::Simple(_))) {
+        let cells = memory.get(start..start.checked_add(size)?)?;
+        return Some(cells.iter().map(MemoryValue::to_field).collect());
+    }
+    let mut fields = vec![];
+    for (index, value_type) in (0..size).zip(value_types.iter().cycle()) {
+        let address = start + index;
+        match value_type {
+            HeapValueType::Simple(_) => fields.push(memory.get(address)?.to_field()),
+            HeapValueType::Array { value_types, size } => {
+                let array = read_address(memory, address)?;
+                let array_start = read_address(memory, array)?;
+                fields.extend(read_memory_slice(memory, array_start, *size, value_types)?);
+            }
+            HeapValueType::Vector { value_types } => {
+                let vector = read_address(memory, address)?;
+                let vector_start = read_address(memory, vector)?;
+                let vector_size = read_address(memory, vector + 1)?;
+                fields.extend(read_memory_slice(memory, vector_start, vector_size, value_types)?);
+            }
+        }
+    }
+    Some(fields)
+}
+
+fn read_address(memory: &[MemoryValue<FieldElement>], address: usize) -> Option<usize> {
+    memory.get(address)?.to_field().try_to_u64()?.try_into().ok()
+}
+
+/// Renders the message a `print` call with the given arguments prints
+pub(crate) fn format_print_message(inputs: &[ForeignCallParam<FieldElement>]) -> Option<String> {
+    // The first argument tells whether a newline follows the message
+    let (_, message) = inputs.split_first()?;
+    PrintableValueDisplay::try_from(message).ok().map(|display| display.to_string())
+}
+
+/// How the foreign calls of the program that are not handled by the debugger
+/// get resolved
+#[derive(Debug, Clone, Default)]
+pub struct ForeignCallResolverConfig {
+    /// External oracle resolver to send the calls to
+    pub resolver: Option<OracleResolverOptions>,
+    /// Canned results, tried before the resolver
+    pub mocks: Vec<OracleMock>,
+}
+
+/// Result to return for calls to an oracle, optionally only when called with
+/// the given arguments
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleMock {
+    pub name: String,
+    pub params: Option<Vec<ForeignCallParam<FieldElement>>>,
+    pub returns: Vec<ForeignCallParam<FieldElement>>,
+}
+
+impl OracleMock {
+    fn matches(&self, foreign_call: &ForeignCallWaitInfo<FieldElement>) -> bool {
+        self.name == foreign_call.function
+            && self.params.as_ref().map_or(true, |params| *params == foreign_call.inputs)
+    }
+}
+
+/// Reads oracle mocks from a JSON fixture file, holding an array of objects
+/// with the `name` of the oracle, its `returns` and optionally the `params` it
+/// must be called with. Values are fields, given as numbers or decimal or hex
+/// strings, or arrays of fields.
+pub fn load_oracle_mocks(path: &Path) -> Result<Vec<OracleMock>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|error| format!("could not read {}: {error}", path.display()))?;
+    let fixture: Value = serde_json::from_str(&contents)
+        .map_err(|error| format!("invalid JSON in {}: {error}", path.display()))?;
+    parse_oracle_mocks(&fixture).map_err(|error| format!("{}: {error}", path.display()))
+}
+
+fn parse_oracle_mocks(fixture: &Value) -> Result<Vec<OracleMock>, String> {
+    let Some(entries) = fixture.as_array() else {
+        return Err("expected an array of mocks".to_string());
+    };
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let name = entry
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("mock {index} has no name"))?;
+            let params = match entry.get("params") {
+                Some(params) => Some(parse_mock_values(params).map_err(|error| {
+                    format!("invalid params in mock {index} ({name}): {error}")
+                })?),
+                None => None,
+            };
+            let returns = match entry.get("returns") {
+                Some(returns) => parse_mock_values(returns).map_err(|error| {
+                    format!("invalid returns in mock {index} ({name}): {error}")
+                })?,
+                None => vec![],
+            };
+            Ok(OracleMock { name: name.to_string(), params, returns })
+        })
+        .collect()
+}
+
+fn parse_mock_values(values: &Value) -> Result<Vec<ForeignCallParam<FieldElement>>, String> {
+    let Some(values) = values.as_array() else {
+        return Err("expected an array".to_string());
+    };
+    values
+        .iter()
+        .map(|value| match value {
+            Value::Array(fields) => fields
+                .iter()
+                .map(parse_mock_field)
+                .collect::<Result<_, _>>()
+                .map(ForeignCallParam::Array),
+            _ => parse_mock_field(value).map(ForeignCallParam::Single),
+        })
+        .collect()
+}
+
+fn parse_mock_field(value: &Value) -> Result<FieldElement, String> {
+    let field = match value {
+        Value::String(string) => FieldElement::try_from_str(string),
+        Value::Number(number) => number.as_u64().map(|number| FieldElement::from(number as u128)),
+        _ => None,
+    };
+    field.ok_or_else(|| format!("{value} is not a field"))
+}
 
 pub(crate) enum DebugForeignCall {
     VarAssign,
@@ -39,18 +286,48 @@ impl DebugForeignCall {
 pub trait DebugForeignCallExecutor: ForeignCallExecutor<FieldElement> {
     fn get_variables(&self) -> Vec<StackFrame<FieldElement>>;
     fn current_stack_frame(&self) -> Option<StackFrame<FieldElement>>;
+    fn lookup_variable(&self, name: &str) -> Option<(DebugVarId, PrintableType)>;
+    fn assign_variable(&mut self, var_id: DebugVarId, values: &[FieldElement]);
+    /// Returns the most recent assignments to array elements and struct
+    /// members, oldest first
+    fn get_assignment_log(&self) -> Vec<String>;
+    /// Returns and clears the warnings raised while tracking variables, such
+    /// as assignments through an out of range index
+    fn take_warnings(&mut self) -> Vec<String>;
+    /// Resets the execution state (output, mocks and stack frames) so that the
+    /// executor can be reused to run the program from the start
+    fn reset(&mut self);
 }
 
 pub struct DefaultDebugForeignCallExecutor {
     executor: DefaultForeignCallExecutor<FieldElement>,
     pub debug_vars: DebugVars<FieldElement>,
+    output: PrintOutput,
+    resolver_config: ForeignCallResolverConfig,
+    assignment_log: VecDeque<String>,
+    warnings: Vec<String>,
 }
 
 impl DefaultDebugForeignCallExecutor {
     pub fn new(show_output: bool) -> Self {
+        Self::with_output(if show_output { PrintOutput::Stdout } else { PrintOutput::None })
+    }
+
+    pub fn with_output(output: PrintOutput) -> Self {
+        Self::with_resolver(output, ForeignCallResolverConfig::default())
+    }
+
+    pub fn with_resolver(output: PrintOutput, resolver_config: ForeignCallResolverConfig) -> Self {
         Self {
-            executor: DefaultForeignCallExecutor::new(show_output, None),
+            executor: DefaultForeignCallExecutor::with_resolver(
+                output.clone(),
+                resolver_config.resolver.as_ref(),
+            ),
             debug_vars: DebugVars::default(),
+            output,
+            resolver_config,
+            assignment_log: VecDeque::new(),
+            warnings: vec![],
         }
     }
 
@@ -68,6 +345,52 @@ impl DefaultDebugForeignCallExecutor {
         };
         self.debug_vars.insert_debug_info(info);
     }
+
+    /// Records an assignment to a member of a variable in the assignment log,
+    /// returning whether all the indexes are within the lengths of the arrays
+    /// they index. A length of zero stands for a struct or tuple member.
+    fn log_member_assign(
+        &mut self,
+        var_id: DebugVarId,
+        indexes: &[u32],
+        lengths: &[u32],
+        values: &[FieldElement],
+    ) -> bool {
+        let out_of_range = indexes
+            .iter()
+            .zip(lengths)
+            .find(|(index, length)| **length > 0 && index >= length)
+            .map(|(index, length)| (*index, *length));
+
+        let entry = match self.debug_vars.describe_field(var_id, indexes) {
+            Some((path, typ)) => {
+                let value = decode_value(&mut values.iter().copied(), typ);
+                format!("{path} = {}", PrintableValueDisplay::Plain(value, typ.clone()))
+            }
+            None => format!("<variable {}>{indexes:?} = {values:?}", var_id.0),
+        };
+        match out_of_range {
+            Some((index, length)) => {
+                self.warnings.push(format!(
+                    "Index {index} is out of range for an array of length {length} in `{entry}`; \
+                     the variable was not updated"
+                ));
+                self.push_assignment(format!("{entry} (index out of range)"));
+                false
+            }
+            None => {
+                self.push_assignment(entry);
+                true
+            }
+        }
+    }
+
+    fn push_assignment(&mut self, entry: String) {
+        if self.assignment_log.len() == MAX_ASSIGNMENT_LOG_ENTRIES {
+            self.assignment_log.pop_front();
+        }
+        self.assignment_log.push_back(entry);
+    }
 }
 
 impl DebugForeignCallExecutor for DefaultDebugForeignCallExecutor {
@@ -78,6 +401,34 @@ impl DebugForeignCallExecutor for DefaultDebugForeignCallExecutor {
     fn current_stack_frame(&self) -> Option<StackFrame<FieldElement>> {
         self.debug_vars.current_stack_frame()
     }
+
+    fn lookup_variable(&self, name: &str) -> Option<(DebugVarId, PrintableType)> {
+        self.debug_vars
+            .lookup_var_in_current_frame(name)
+            .map(|(var_id, ptype)| (var_id, ptype.clone()))
+    }
+
+    fn assign_variable(&mut self, var_id: DebugVarId, values: &[FieldElement]) {
+        self.debug_vars.assign_var(var_id, values);
+    }
+
+    fn get_assignment_log(&self) -> Vec<String> {
+        self.assignment_log.iter().cloned().collect()
+    }
+
+    fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
+
+    fn reset(&mut self) {
+        self.executor = DefaultForeignCallExecutor::with_resolver(
+            self.output.clone(),
+            self.resolver_config.resolver.as_ref(),
+        );
+        self.debug_vars.clear_frames();
+        self.assignment_log.clear();
+        self.warnings.clear();
+    }
 }
 
 fn debug_var_id(value: &FieldElement) -> DebugVarId {
@@ -88,6 +439,14 @@ fn debug_fn_id(value: &FieldElement) -> DebugFnId {
     DebugFnId(value.to_u128() as u32)
 }
 
+fn single_u32(param: &ForeignCallParam<FieldElement>) -> u32 {
+    if let ForeignCallParam::Single(v) = param {
+        v.to_u128() as u32
+    } else {
+        panic!("expected ForeignCallParam::Single(v)");
+    }
+}
+
 impl ForeignCallExecutor<FieldElement> for DefaultDebugForeignCallExecutor {
     fn execute(
         &mut self,
@@ -118,26 +477,21 @@ impl ForeignCallExecutor<FieldElement> for DefaultDebugForeignCallExecutor {
                     let arity = arity as usize;
                     let var_id = debug_var_id(var_id_value);
                     let n = foreign_call.inputs.len();
-                    let indexes: Vec<u32> = foreign_call.inputs[(n - arity)..n]
+                    // The inputs are the var id, the value, the indexes and
+                    // the lengths of the arrays they index (0 for members)
+                    let indexes: Vec<u32> = foreign_call.inputs[(n - 2 * arity)..(n - arity)]
                         .iter()
-                        .map(|fcp_v| {
-                            if let ForeignCallParam::Single(v) = fcp_v {
-                                v.to_u128() as u32
-                            } else {
-                                panic!("expected ForeignCallParam::Single(v)");
-                            }
-                        })
+                        .map(single_u32)
                         .collect();
-                    let values: Vec<FieldElement> = (0..n - 1 - arity)
-                        .flat_map(|i| {
-                            foreign_call
-                                .inputs
-                                .get(1 + i)
-                                .map(|fci| fci.fields())
-                                .unwrap_or_default()
-                        })
+                    let lengths: Vec<u32> =
+                        foreign_call.inputs[(n - arity)..n].iter().map(single_u32).collect();
+                    let values: Vec<FieldElement> = foreign_call.inputs[1..(n - 2 * arity)]
+                        .iter()
+                        .flat_map(|fci| fci.fields())
                         .collect();
-                    self.debug_vars.assign_field(var_id, indexes, &values);
+                    if self.log_member_assign(var_id, &indexes, &lengths, &values) {
+                        self.debug_vars.assign_field(var_id, indexes, &values);
+                    }
                 }
                 Ok(ForeignCallResult::default())
             }
@@ -163,7 +517,121 @@ impl ForeignCallExecutor<FieldElement> for DefaultDebugForeignCallExecutor {
                 self.debug_vars.pop_fn();
                 Ok(ForeignCallResult::default())
             }
-            None => self.executor.execute(foreign_call),
+            None => {
+                let mock =
+                    self.resolver_config.mocks.iter().find(|mock| mock.matches(foreign_call));
+                match mock {
+                    Some(mock) => Ok(mock.returns.clone().into()),
+                    None => self.executor.execute(foreign_call),
+                }
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_matches_oracle_mocks() {
+        let fixture = serde_json::json!([
+            { "name": "get_price", "params": [1], "returns": ["0x10"] },
+            { "name": "get_price", "returns": [["2", 3]] },
+        ]);
+        let mocks = parse_oracle_mocks(&fixture).unwrap();
+        assert_eq!(mocks[0].params, Some(vec![ForeignCallParam::Single(FieldElement::one())]));
+        assert_eq!(
+            mocks[1].returns,
+            vec![ForeignCallParam::Array(vec![
+                FieldElement::from(2u128),
+                FieldElement::from(3u128)
+            ])]
+        );
+
+        let call = |input: u128| ForeignCallWaitInfo {
+            function: "get_price".to_string(),
+            inputs: vec![ForeignCallParam::Single(FieldElement::from(input))],
+        };
+        assert!(mocks[0].matches(&call(1)));
+        assert!(!mocks[0].matches(&call(2)));
+        assert!(mocks[1].matches(&call(2)));
+
+        let invalid = serde_json::json!([{ "name": "get_price", "returns": [true] }]);
+        assert_eq!(
+            parse_oracle_mocks(&invalid),
+            Err("invalid returns in mock 0 (get_price): true is not a field".to_string())
+        );
+    }
+
+    #[test]
+    fn validates_foreign_call_output_shapes() {
+        let field = ForeignCallParam::Single(FieldElement::one());
+        let array = |len| ForeignCallParam::Array(vec![FieldElement::zero(); len]);
+        let pair = HeapValueType::Array { value_types: vec![HeapValueType::field(); 2], size: 2 };
+
+        assert!(validate_foreign_call_outputs(&[field.clone()], &[HeapValueType::field()]).is_ok());
+        assert!(validate_foreign_call_outputs(&[array(2)], &[pair.clone()]).is_ok());
+        let nested = HeapValueType::Array { value_types: vec![pair], size: 3 };
+        assert!(validate_foreign_call_outputs(&[array(12)], &[nested]).is_ok());
+        let vector = HeapValueType::Vector { value_types: vec![HeapValueType::field()] };
+        assert!(validate_foreign_call_outputs(&[array(5)], &[vector]).is_ok());
+
+        let bytes = HeapValueType::Array { value_types: vec![HeapValueType::Simple(8)], size: 32 };
+        assert_eq!(
+            validate_foreign_call_outputs(&[array(3)], &[bytes.clone()]),
+            Err("returned 3 fields, expected array of 32".to_string())
+        );
+        assert_eq!(
+            validate_foreign_call_outputs(&[field], &[bytes]),
+            Err("returned a single field, expected array of 32".to_string())
+        );
+        assert_eq!(
+            validate_foreign_call_outputs(&[], &[HeapValueType::field()]),
+            Err("returned 0 values, expected 1".to_string())
+        );
+    }
+
+    #[test]
+    fn reads_foreign_call_inputs_from_memory() {
+        let memory: Vec<MemoryValue<FieldElement>> =
+            [7usize, 3, 2, 10, 20, 30].into_iter().map(MemoryValue::from).collect();
+        let inputs = [
+            ValueOrArray::MemoryAddress(0usize.into()),
+            ValueOrArray::HeapArray(HeapArray { pointer: 1usize.into(), size: 3 }),
+            ValueOrArray::HeapVector(HeapVector { pointer: 1usize.into(), size: 2usize.into() }),
+        ];
+        let value_types = [
+            HeapValueType::field(),
+            HeapValueType::Array { value_types: vec![HeapValueType::field()], size: 3 },
+            HeapValueType::Vector { value_types: vec![HeapValueType::field()] },
+        ];
+        let field = |value: u128| FieldElement::from(value);
+
+        assert_eq!(
+            read_foreign_call_inputs(&memory, &inputs, &value_types),
+            Some(vec![
+                ForeignCallParam::Single(field(7)),
+                ForeignCallParam::Array(vec![field(10), field(20), field(30)]),
+                ForeignCallParam::Array(vec![field(10), field(20)]),
+            ])
+        );
+        let out_of_bounds =
+            [ValueOrArray::HeapArray(HeapArray { pointer: 1usize.into(), size: 4 })];
+        assert_eq!(read_foreign_call_inputs(&memory, &out_of_bounds, &value_types[1..]), None);
+    }
+
+    #[test]
+    fn default_outputs_have_the_expected_shapes() {
+        let pair = HeapValueType::Array { value_types: vec![HeapValueType::field(); 2], size: 2 };
+        let value_types = vec![
+            HeapValueType::field(),
+            HeapValueType::Array { value_types: vec![pair], size: 3 },
+            HeapValueType::Vector { value_types: vec![HeapValueType::field()] },
+        ];
+        let outputs: Vec<_> = value_types.iter().map(default_foreign_call_output).collect();
+
+        assert_eq!(outputs[1], ForeignCallParam::Array(vec![FieldElement::zero(); 12]));
+        assert!(validate_foreign_call_outputs(&outputs, &value_types).is_ok());
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Once;
+
+// Set by the Ctrl-C handler, checked between the steps of running commands
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+static INSTALL_HANDLER: Once = Once::new();
+// Set along with INTERRUPTED by the source watcher of `--watch` sessions, and
+// kept until the session ends
+static SOURCES_CHANGED: AtomicBool = AtomicBool::new(false);
+
+/// Installs a Ctrl-C handler which interrupts the running command instead of
+/// terminating the process. The prompt reads Ctrl-C as a key press, so this
+/// only has an effect while a command is executing.
+pub(crate) fn install_handler() {
+    INSTALL_HANDLER.call_once(|| {
+        if let Err(err) = ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst)) {
+            println!("Could not install Ctrl-C handler: {err}");
+        }
+    });
+}
+
+/// Returns the flag raised by the Ctrl-C handler
+pub(crate) fn flag() -> &'static AtomicBool {
+    &INTERRUPTED
+}
+
+/// Forgets any Ctrl-C pressed before a command started. Commands started
+/// after the sources changed are still interrupted.
+pub(crate) fn clear() {
+    INTERRUPTED.store(SOURCES_CHANGED.load(Ordering::SeqCst), Ordering::SeqCst);
+}
+
+/// Interrupts the running command, if any, because the sources of the
+/// program changed
+pub(crate) fn raise_sources_changed() {
+    SOURCES_CHANGED.store(true, Ordering::SeqCst);
+    INTERRUPTED.store(true, Ordering::SeqCst);
+}
+
+/// Returns whether the sources of the program changed during the session
+pub(crate) fn sources_changed() -> bool {
+    SOURCES_CHANGED.load(Ordering::SeqCst)
+}
+
+/// Returns whether the sources of the program changed during the session,
+/// and clears both flags for the next session
+pub(crate) fn take_sources_changed() -> bool {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    SOURCES_CHANGED.swap(false, Ordering::SeqCst)
+}
+
+/// Describes why the running command was interrupted
+pub(crate) fn reason() -> &'static str {
+    if sources_changed() {
+        "source files changed"
+    } else {
+        "Ctrl-C pressed"
+    }
+}
+
+/// Returns whether Ctrl-C was pressed since the flag was last cleared, and
+/// clears it
+pub(crate) fn take() -> bool {
+    INTERRUPTED.swap(false, Ordering::SeqCst)
+}
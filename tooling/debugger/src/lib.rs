@@ -1,38 +1,399 @@
+mod completion;
+mod conditions;
 mod context;
 mod dap;
+pub mod dap_input;
+mod dap_server;
+mod disassembly;
+pub mod environment;
 pub mod errors;
+mod expressions;
 mod foreign_calls;
+mod interrupt;
+mod memory_view;
+mod oracle_schema;
 mod repl;
+mod server;
 mod source_code_printer;
+mod source_paths;
+mod timeline;
+pub mod tutorial;
+pub mod watch;
 
-use std::io::{Read, Write};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::io::{BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 
 use ::dap::errors::ServerError;
 use ::dap::server::Server;
 use acvm::acir::circuit::brillig::BrilligBytecode;
-use acvm::{acir::circuit::Circuit, acir::native_types::WitnessMap};
+use acvm::acir::circuit::OpcodeLocation;
+use acvm::acir::circuit::{Circuit, Program};
+use acvm::acir::native_types::{Witness, WitnessMap, WitnessStack};
 use acvm::{BlackBoxFunctionSolver, FieldElement};
+use serde::{Deserialize, Serialize};
 
 use noirc_artifacts::debug::DebugArtifact;
 
+use environment::DebugEnvironment;
+use foreign_calls::DefaultDebugForeignCallExecutor;
+pub use foreign_calls::{ForeignCallResolverConfig, OracleMock};
+
+use nargo::ops::PrintOutput;
 use nargo::NargoError;
 use noirc_driver::CompiledProgram;
 
+pub use dap::DapSessionOptions;
+pub use dap_server::{
+    run_dap_server, DapServerOptions, DapTransport, LaunchArguments, LoadedProgram, ProgramReloader,
+};
+pub use oracle_schema::{OracleSchema, ORACLE_SCHEMA_FILE};
+pub use repl::SavedBreakpoint;
+pub use source_paths::PathSubstitutions;
+
+/// Options controlling how an interactive debugging session behaves
+#[derive(Debug, Clone, Default)]
+pub struct DebuggerOptions {
+    /// Start a guided walk-through of the debugger on the program
+    pub tutorial: bool,
+    /// Continue execution right away, printing the most recently executed
+    /// opcodes if it fails
+    pub run_to_failure: bool,
+    /// End the session when the program's source files change, so that it can
+    /// be recompiled and debugged again
+    pub watch: bool,
+    /// Breakpoints to set when the session starts
+    pub breakpoints: Vec<SavedBreakpoint>,
+    /// Whether the output uses ANSI colors
+    pub color: ColorChoice,
+    /// How much is printed every time execution stops
+    pub verbosity: Verbosity,
+    /// Rewrites of source paths, eg. from the embedded stdlib to a checkout on disk
+    pub path_substitutions: PathSubstitutions,
+    /// Check that oracles return values of the shape the program expects
+    pub validate_oracle_outputs: bool,
+    /// Signatures of the program's oracles, which their calls are checked against
+    pub oracle_schema: OracleSchema,
+    /// How the program's foreign calls not handled by the debugger are resolved
+    pub resolver_config: ForeignCallResolverConfig,
+    /// Time after which `continue` pauses execution, reporting a possible hang
+    pub continue_timeout: Option<Duration>,
+    /// Warnings the compiler reported for the program, rendered as
+    /// `path:line:column: warning: message`
+    pub compile_warnings: Vec<String>,
+}
+
+/// How much the REPL prints every time execution stops
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verbosity {
+    /// Nothing; the status can be requested with the `status` command
+    Silent,
+    /// A single line with the current opcode and source position
+    Quiet,
+    /// The current opcode and an excerpt of the source code around it
+    #[default]
+    Normal,
+    /// Like `Normal`, plus the variables that changed and some execution counters
+    Verbose,
+}
+
+impl FromStr for Verbosity {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "silent" => Ok(Verbosity::Silent),
+            "quiet" => Ok(Verbosity::Quiet),
+            "normal" => Ok(Verbosity::Normal),
+            "verbose" | "full" => Ok(Verbosity::Verbose),
+            _ => Err(format!(
+                "invalid verbosity {value}; expected silent, quiet, normal or verbose (full)"
+            )),
+        }
+    }
+}
+
+/// When the debugger output uses ANSI colors
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    Always,
+    Never,
+    /// Use colors when standard output is a terminal, unless the `NO_COLOR`
+    /// environment variable is set
+    #[default]
+    Auto,
+}
+
+impl ColorChoice {
+    pub fn use_colors(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color =
+                    std::env::var_os("NO_COLOR").map_or(false, |value| !value.is_empty());
+                !no_color && std::io::stdout().is_terminal()
+            }
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            "auto" => Ok(ColorChoice::Auto),
+            _ => Err(format!("invalid color choice {value}; expected always, never or auto")),
+        }
+    }
+}
+
+/// Metadata of the program being debugged, shown by `info program`
+#[derive(Debug, Clone, Default)]
+pub struct ProgramInfo {
+    /// Number of ACIR circuits in the program. Only the first one is debugged.
+    pub circuits: usize,
+    /// Inputs declared in the program's ABI, eg. `x: pub Field`
+    pub abi_inputs: Vec<String>,
+    /// Witnesses holding the value of each ABI input, by input name
+    pub input_witnesses: Vec<(String, Vec<Witness>)>,
+}
+
+/// Values of the variables selected for a breakpoint, captured when it was hit
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BreakpointSnapshot {
+    /// Number of the run of the program taking the snapshot, counting from 1
+    /// and increasing on every restart
+    pub run: usize,
+    pub breakpoint_id: usize,
+    pub location: OpcodeLocation,
+    /// Number of the hit, counting from 1 since the run started
+    pub hit: usize,
+    /// Rendered values by variable name, `<unknown>` for variables not in scope
+    pub values: BTreeMap<String, String>,
+}
+
+/// Resource limits the debugger enforces on Brillig execution, which the VM
+/// itself does not bound, eg. to stop runaway recursion in programs compiled
+/// with `--force-brillig`. `None` leaves a resource unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct BrilligLimits {
+    /// Number of memory cells
+    pub max_memory: Option<usize>,
+    /// Number of nested calls, counting the function called from ACIR
+    pub max_depth: Option<usize>,
+}
+
+/// What was recorded during an interactive debugging session, to be exported
+/// in the session report
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionLog {
+    pub brillig_limits: BrilligLimits,
+    pub breakpoint_snapshots: Vec<BreakpointSnapshot>,
+    pub summary: SessionSummary,
+    /// Flat profile of the last profiled re-run of the program, if any
+    pub profile: Option<ProfileReport>,
+}
+
+/// Opcodes executed by a full run of the program, per function and per
+/// source location
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ProfileReport {
+    pub solved: bool,
+    pub executed_opcodes: usize,
+    pub functions: Vec<ProfiledFunction>,
+    /// Source locations as `path:line:column`, most executed first
+    pub locations: Vec<ProfiledLocation>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfiledFunction {
+    pub name: String,
+    pub calls: usize,
+    pub opcodes: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfiledLocation {
+    pub location: String,
+    pub opcodes: usize,
+}
+
+/// How execution went in a debugging session, printed when the session ends
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SessionSummary {
+    pub solved: bool,
+    pub executed_opcodes: usize,
+    /// Foreign calls made by the program, not counting the debugger's oracles
+    pub foreign_calls: usize,
+    /// The error execution stopped on, if any
+    pub error: Option<String>,
+    /// Source locations of the call stack the error happened in, outermost first
+    pub error_call_stack: Vec<String>,
+    /// Where the solved witness was written, if it was
+    pub witness_path: Option<PathBuf>,
+    /// Return values not solved when the session ended, eg. `return[1] (_7)`
+    pub unsolved_outputs: Vec<String>,
+    /// ABI inputs not read by any opcode executed when the session ended
+    pub unread_inputs: Vec<String>,
+}
+
+impl Display for SessionSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Session summary:")?;
+        writeln!(f, "  solved: {}", if self.solved { "yes" } else { "no" })?;
+        writeln!(f, "  opcodes executed: {}", self.executed_opcodes)?;
+        writeln!(f, "  foreign calls: {}", self.foreign_calls)?;
+        if let Some(error) = &self.error {
+            writeln!(f, "  error: {error}")?;
+            for location in self.error_call_stack.iter().rev() {
+                writeln!(f, "    at {location}")?;
+            }
+        }
+        if !self.unsolved_outputs.is_empty() {
+            writeln!(f, "  unsolved outputs: {}", self.unsolved_outputs.join(", "))?;
+        }
+        if !self.unread_inputs.is_empty() {
+            writeln!(f, "  inputs never read: {}", self.unread_inputs.join(", "))?;
+        }
+        match &self.witness_path {
+            Some(path) => write!(f, "  witness: {}", path.display()),
+            None => write!(f, "  witness: not written"),
+        }
+    }
+}
+
+/// State of a session that ended on an execution failure, such as an
+/// unsatisfied constraint, saved along with the partially solved witness
+#[derive(Debug, Clone, Serialize)]
+pub struct FailureReport {
+    pub error: String,
+    /// Opcode execution failed at, if it had not finished
+    pub location: Option<OpcodeLocation>,
+    /// Source locations of the call stack the error happened in, outermost first
+    pub call_stack: Vec<String>,
+    pub executed_opcodes: usize,
+    /// Number of witnesses solved before the failure
+    pub solved_witnesses: usize,
+}
+
+/// Writes the partial witness stack and the report of a failed session to
+/// `dir`, as `<name>.gz` and `<name>.json`, returning the paths written
+pub fn save_failure(
+    dir: &Path,
+    name: &str,
+    witness_stack: WitnessStack<FieldElement>,
+    report: &FailureReport,
+) -> Result<(PathBuf, PathBuf), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|error| format!("Could not create {}: {error}", dir.display()))?;
+    let witness_path = dir.join(name).with_extension(nargo::constants::WITNESS_EXT);
+    let witness: Vec<u8> = witness_stack
+        .try_into()
+        .map_err(|error| format!("Could not serialize the witness stack: {error}"))?;
+    std::fs::write(&witness_path, witness)
+        .map_err(|error| format!("Could not write {}: {error}", witness_path.display()))?;
+
+    let report_path = dir.join(name).with_extension("json");
+    let report =
+        serde_json::to_string_pretty(report).expect("Could not serialize the failure report");
+    std::fs::write(&report_path, report)
+        .map_err(|error| format!("Could not write {}: {error}", report_path.display()))?;
+    Ok((witness_path, report_path))
+}
+
+/// How an interactive debugging session ended
+pub enum DebugOutcome {
+    /// Execution finished and the circuit witness was solved
+    Solved(WitnessMap<FieldElement>),
+    /// The session was quit before solving the circuit
+    Halted,
+    /// The session was quit after execution failed, eg. on an unsatisfied
+    /// constraint. The witness stack solved up to the failure is returned.
+    Failed(WitnessStack<FieldElement>, FailureReport),
+    /// The source files changed while watching them. The session's breakpoints
+    /// are returned so that they can be restored after recompiling.
+    SourcesChanged(Vec<SavedBreakpoint>),
+}
+
+/// Debugs the first circuit of the program in an interactive REPL session
 pub fn debug_circuit<B: BlackBoxFunctionSolver<FieldElement>>(
     blackbox_solver: &B,
-    circuit: &Circuit<FieldElement>,
+    program: &Program<FieldElement>,
     debug_artifact: DebugArtifact,
     initial_witness: WitnessMap<FieldElement>,
+    environment: &DebugEnvironment,
+    program_info: &ProgramInfo,
+    options: &DebuggerOptions,
+) -> Result<(DebugOutcome, SessionLog), NargoError<FieldElement>> {
+    repl::run(
+        blackbox_solver,
+        program,
+        &debug_artifact,
+        initial_witness,
+        environment,
+        program_info,
+        options,
+    )
+}
+
+/// Serves a debugging session over a line-based JSON protocol, reading
+/// requests from `input` and writing responses to `output`
+pub fn run_json_server<R: BufRead, W: Write, B: BlackBoxFunctionSolver<FieldElement>>(
+    input: R,
+    output: W,
+    solver: &B,
+    circuit: &Circuit<FieldElement>,
+    debug_artifact: &DebugArtifact,
+    initial_witness: WitnessMap<FieldElement>,
     unconstrained_functions: &[BrilligBytecode<FieldElement>],
-) -> Result<Option<WitnessMap<FieldElement>>, NargoError<FieldElement>> {
-    repl::run(blackbox_solver, circuit, &debug_artifact, initial_witness, unconstrained_functions)
+) -> std::io::Result<()> {
+    server::run(
+        input,
+        output,
+        solver,
+        circuit,
+        debug_artifact,
+        initial_witness,
+        unconstrained_functions,
+    )
+}
+
+/// Executes a compiled program to completion outside of a debugging session,
+/// resolving the calls to the debugger's oracles if it is instrumented. What
+/// the program prints goes to `output`.
+pub fn execute_program<B: BlackBoxFunctionSolver<FieldElement>>(
+    blackbox_solver: &B,
+    program: &CompiledProgram,
+    initial_witness: WitnessMap<FieldElement>,
+    output: PrintOutput,
+    resolver_config: ForeignCallResolverConfig,
+) -> Result<WitnessStack<FieldElement>, NargoError<FieldElement>> {
+    let debug_artifact =
+        DebugArtifact { debug_symbols: program.debug.clone(), file_map: program.file_map.clone() };
+    let mut foreign_call_executor =
+        DefaultDebugForeignCallExecutor::with_resolver(output, resolver_config);
+    foreign_call_executor.load_artifact(&debug_artifact);
+    nargo::ops::execute_program(
+        &program.program,
+        initial_witness,
+        blackbox_solver,
+        &mut foreign_call_executor,
+    )
 }
 
 pub fn run_dap_loop<R: Read, W: Write, B: BlackBoxFunctionSolver<FieldElement>>(
     server: Server<R, W>,
     solver: &B,
-    program: CompiledProgram,
-    initial_witness: WitnessMap<FieldElement>,
-) -> Result<(), ServerError> {
-    dap::run_session(server, solver, program, initial_witness)
+    program: LoadedProgram,
+    options: DapSessionOptions<'_>,
+) -> Result<DebugOutcome, ServerError> {
+    dap::run_session(server, solver, program, options)
 }
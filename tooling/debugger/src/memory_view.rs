@@ -0,0 +1,284 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use acvm::brillig_vm::MemoryValue;
+use acvm::{AcirField, FieldElement};
+
+/// Number of bytes each cell takes in the byte view of Brillig memory given to
+/// IDEs, where it holds its value as a big-endian field element
+pub(super) const CELL_BYTES: usize = 32;
+
+/// Type Brillig memory cells are shown as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CellType {
+    Field,
+    /// Unsigned integer of the given bit size, at most 128
+    Unsigned(u32),
+}
+
+impl CellType {
+    pub(super) fn of(value: &MemoryValue<FieldElement>) -> Self {
+        match value {
+            MemoryValue::Field(_) => CellType::Field,
+            MemoryValue::Integer(_, bit_size) => CellType::Unsigned(*bit_size),
+        }
+    }
+}
+
+impl FromStr for CellType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let bit_size = match value {
+            "field" => return Ok(CellType::Field),
+            "bool" => Some(1),
+            _ => value.strip_prefix('u').and_then(|bits| bits.parse().ok()),
+        };
+        match bit_size {
+            Some(bit_size @ 1..=128) => Ok(CellType::Unsigned(bit_size)),
+            _ => Err(format!("invalid cell type {value}; expected field, bool or u1 to u128")),
+        }
+    }
+}
+
+impl Display for CellType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellType::Field => write!(f, "field"),
+            CellType::Unsigned(bit_size) => write!(f, "u{bit_size}"),
+        }
+    }
+}
+
+/// Renders the value of a cell. When shown as an unsigned integer narrower
+/// than the value, it is split into limbs of that size, least significant
+/// first, eg. `[1, 2]` for a field packing two `u32`s as `2 * 2^32 + 1`.
+pub(super) fn format_cell_value(value: &MemoryValue<FieldElement>, as_type: CellType) -> String {
+    let field = value.to_field();
+    let CellType::Unsigned(bit_size) = as_type else {
+        return field.to_string();
+    };
+    let limbs = split_into_limbs(&field.to_be_bytes(), bit_size);
+    match limbs.as_slice() {
+        [] => String::from("0"),
+        [limb] => limb.to_string(),
+        limbs => {
+            let limbs: Vec<_> = limbs.iter().map(u128::to_string).collect();
+            format!("[{}]", limbs.join(", "))
+        }
+    }
+}
+
+/// Splits a big-endian number into limbs of `bit_size` bits, least significant
+/// first, leaving out the most significant limbs that are zero
+fn split_into_limbs(bytes: &[u8], bit_size: u32) -> Vec<u128> {
+    let total_bits = bytes.len() as u32 * 8;
+    let bit = |index: u32| {
+        let byte = bytes[bytes.len() - 1 - (index / 8) as usize];
+        (byte >> (index % 8)) & 1 == 1
+    };
+    let mut limbs: Vec<u128> = (0..total_bits)
+        .step_by(bit_size as usize)
+        .map(|start| {
+            (start..(start + bit_size).min(total_bits))
+                .filter(|index| bit(*index))
+                .fold(0, |limb, index| limb | 1 << (index - start))
+        })
+        .collect();
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+    limbs
+}
+
+/// Renders the given memory cells, as `(address, value)` pairs in increasing
+/// address order, in lines of at most `width` characters. Consecutive cells
+/// of the same type, such as the elements of an array, share a line as in
+/// `10..13 = [1, 2, 3]: u8`. Cells are shown as `as_type` if given, and as
+/// their own type otherwise.
+pub(super) fn format_memory_cells(
+    cells: &[(usize, &MemoryValue<FieldElement>)],
+    as_type: Option<CellType>,
+    width: usize,
+) -> Vec<String> {
+    let mut lines = vec![];
+    let mut group: Vec<(usize, String)> = vec![];
+    let mut group_type = None;
+    for (address, value) in cells {
+        let cell_type = as_type.unwrap_or_else(|| CellType::of(value));
+        let contiguous = group.last().map_or(false, |(last, _)| last + 1 == *address);
+        if !contiguous || group_type != Some(cell_type) {
+            flush_group(&mut lines, &mut group, group_type, width);
+        }
+        group_type = Some(cell_type);
+        group.push((*address, format_cell_value(value, cell_type)));
+    }
+    flush_group(&mut lines, &mut group, group_type, width);
+    lines
+}
+
+fn flush_group(
+    lines: &mut Vec<String>,
+    group: &mut Vec<(usize, String)>,
+    group_type: Option<CellType>,
+    width: usize,
+) {
+    let Some(cell_type) = group_type else {
+        return;
+    };
+    let format_line = |cells: &[(usize, String)]| match cells {
+        [(address, value)] => format!("{address} = {value}: {cell_type}"),
+        _ => {
+            let values: Vec<_> = cells.iter().map(|(_, value)| value.as_str()).collect();
+            let (first, last) = (cells[0].0, cells[cells.len() - 1].0);
+            format!("{first}..{} = [{}]: {cell_type}", last + 1, values.join(", "))
+        }
+    };
+
+    let mut start = 0;
+    while start < group.len() {
+        // Take as many cells as fit in the width, and at least one
+        let mut end = start + 1;
+        while end < group.len() && format_line(&group[start..=end]).len() <= width {
+            end += 1;
+        }
+        lines.push(format_line(&group[start..end]));
+        start = end;
+    }
+    group.clear();
+}
+
+fn cell_bytes(value: &MemoryValue<FieldElement>) -> [u8; CELL_BYTES] {
+    let bytes = value.to_field().to_be_bytes();
+    let mut cell = [0; CELL_BYTES];
+    cell[CELL_BYTES - bytes.len()..].copy_from_slice(&bytes);
+    cell
+}
+
+/// Returns up to `count` bytes of the byte view of `memory`, starting at byte
+/// `start`. Fewer bytes are returned when reaching the end of the memory.
+pub(super) fn read_memory_bytes(
+    memory: &[MemoryValue<FieldElement>],
+    start: usize,
+    count: usize,
+) -> Vec<u8> {
+    let end = start.saturating_add(count).min(memory.len() * CELL_BYTES);
+    let mut bytes = Vec::with_capacity(end.saturating_sub(start));
+    let mut byte = start;
+    while byte < end {
+        let cell_start = byte / CELL_BYTES * CELL_BYTES;
+        let cell_end = (cell_start + CELL_BYTES).min(end);
+        let cell = cell_bytes(&memory[byte / CELL_BYTES]);
+        bytes.extend_from_slice(&cell[byte - cell_start..cell_end - cell_start]);
+        byte = cell_end;
+    }
+    bytes
+}
+
+/// Overlays `data` on the byte view of `memory` from byte `start`, returning
+/// the new values of the cells it touches as `(address, value, bit size)`.
+/// Cells keep their type, so the new values must fit in it; writes past the
+/// end of the memory are rejected.
+pub(super) fn patch_memory_cells(
+    memory: &[MemoryValue<FieldElement>],
+    start: usize,
+    data: &[u8],
+) -> Result<Vec<(usize, FieldElement, u32)>, String> {
+    let end = match start.checked_add(data.len()) {
+        Some(end) if end <= memory.len() * CELL_BYTES => end,
+        _ => {
+            return Err(format!(
+                "Write of {} bytes at {start} is past the end of memory",
+                data.len()
+            ))
+        }
+    };
+    let mut cells = vec![];
+    for address in start / CELL_BYTES..end.div_ceil(CELL_BYTES) {
+        let cell_start = address * CELL_BYTES;
+        let mut bytes = cell_bytes(&memory[address]);
+        for (index, byte) in bytes.iter_mut().enumerate() {
+            if let Some(new_byte) =
+                (cell_start + index).checked_sub(start).and_then(|i| data.get(i))
+            {
+                *byte = *new_byte;
+            }
+        }
+        let value = FieldElement::from_be_bytes_reduce(&bytes);
+        let bit_size = memory[address].bit_size();
+        // Bytes over the field modulus would be silently reduced
+        if cell_bytes(&MemoryValue::new_field(value)) != bytes
+            || MemoryValue::new_checked(value, bit_size).is_none()
+        {
+            let cell_type = CellType::of(&memory[address]);
+            return Err(format!("Value written at cell {address} does not fit a {cell_type}"));
+        }
+        cells.push((address, value, bit_size));
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cell_types() {
+        assert_eq!("field".parse::<CellType>(), Ok(CellType::Field));
+        assert_eq!("bool".parse::<CellType>(), Ok(CellType::Unsigned(1)));
+        assert_eq!("u32".parse::<CellType>(), Ok(CellType::Unsigned(32)));
+        assert!("u0".parse::<CellType>().is_err());
+        assert!("i8".parse::<CellType>().is_err());
+    }
+
+    #[test]
+    fn splits_packed_values_into_limbs() {
+        let packed = MemoryValue::new_field(FieldElement::from((2u128 << 32) + 1));
+        assert_eq!(format_cell_value(&packed, CellType::Unsigned(32)), "[1, 2]");
+        assert_eq!(format_cell_value(&packed, CellType::Unsigned(64)), "8589934593");
+        let small = MemoryValue::new_field(FieldElement::from(7u128));
+        assert_eq!(format_cell_value(&small, CellType::Field), "7");
+        let zero = MemoryValue::new_field(FieldElement::zero());
+        assert_eq!(format_cell_value(&zero, CellType::Unsigned(8)), "0");
+    }
+
+    #[test]
+    fn groups_consecutive_cells_of_the_same_type() {
+        let byte = |value: u128| MemoryValue::new_from_field(FieldElement::from(value), 8);
+        let field = MemoryValue::new_field(FieldElement::from(7u128));
+        let (one, two, three) = (byte(1), byte(2), byte(3));
+        let cells = [(0, &field), (1, &one), (2, &two), (3, &three), (5, &one)];
+
+        assert_eq!(
+            format_memory_cells(&cells, None, 80),
+            vec!["0 = 7: field", "1..4 = [1, 2, 3]: u8", "5 = 1: u8"]
+        );
+        assert_eq!(
+            format_memory_cells(&cells, None, 17),
+            vec!["0 = 7: field", "1..3 = [1, 2]: u8", "3 = 3: u8", "5 = 1: u8"]
+        );
+        assert_eq!(
+            format_memory_cells(&cells, Some(CellType::Unsigned(32)), 80),
+            vec!["0..4 = [7, 1, 2, 3]: u32", "5 = 1: u32"]
+        );
+    }
+
+    #[test]
+    fn reads_and_patches_the_byte_view_of_memory() {
+        let memory = [
+            MemoryValue::new_field(FieldElement::from(0x0102u128)),
+            MemoryValue::new_from_field(FieldElement::from(3u128), 8),
+        ];
+        assert_eq!(read_memory_bytes(&memory, 30, 4), vec![1, 2, 0, 0]);
+        assert_eq!(read_memory_bytes(&memory, 62, 10), vec![0, 3]);
+        assert!(read_memory_bytes(&memory, 64, 1).is_empty());
+
+        assert_eq!(
+            patch_memory_cells(&memory, 31, &[5, 0]),
+            Ok(vec![(0, FieldElement::from(0x0105u128), 254), (1, FieldElement::from(3u128), 8)])
+        );
+        assert!(patch_memory_cells(&memory, 62, &[1, 0]).is_err());
+        assert!(patch_memory_cells(&memory, 63, &[1, 1]).is_err());
+        assert!(patch_memory_cells(&memory, usize::MAX, &[1]).is_err());
+    }
+}
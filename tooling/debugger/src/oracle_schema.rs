@@ -0,0 +1,651 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+use acvm::acir::brillig::ForeignCallParam;
+use acvm::{AcirField, FieldElement};
+use serde::Deserialize;
+
+/// File at the root of a package describing the oracles its program calls
+pub const ORACLE_SCHEMA_FILE: &str = "oracles.toml";
+
+/// Type of an oracle parameter or return value, written as in Noir. Structs
+/// are written as tuples of their fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OracleType {
+    Field,
+    Bool,
+    Unsigned(u32),
+    Signed(u32),
+    /// String of the given length, passed as an array of its bytes
+    Str(usize),
+    Array(Box<OracleType>, usize),
+    /// Array of any length, such as the contents of a slice
+    Slice(Box<OracleType>),
+    /// Tuple or struct, whose fields are passed as separate values
+    Tuple(Vec<OracleType>),
+}
+
+impl OracleType {
+    /// Number of fields a value of this type is flattened into inside an
+    /// array, or None for slices, which can't be nested in arrays
+    fn flattened_size(&self) -> Option<usize> {
+        match self {
+            OracleType::Array(element, length) => element.flattened_size()?.checked_mul(*length),
+            OracleType::Str(length) => Some(*length),
+            OracleType::Slice(_) => None,
+            OracleType::Tuple(fields) => fields.iter().map(OracleType::flattened_size).sum(),
+            _ => Some(1),
+        }
+    }
+
+    /// Number of values a value of this type is passed to, or returned by, an
+    /// oracle as. Tuples are split into one value per field, and slices into
+    /// their length followed by an array of their flattened elements.
+    fn param_count(&self) -> usize {
+        match self {
+            OracleType::Slice(_) => 2,
+            OracleType::Tuple(fields) => fields.iter().map(OracleType::param_count).sum(),
+            _ => 1,
+        }
+    }
+
+    fn contains_slice(&self) -> bool {
+        match self {
+            OracleType::Slice(_) => true,
+            OracleType::Array(element, _) => element.contains_slice(),
+            OracleType::Tuple(fields) => fields.iter().any(OracleType::contains_slice),
+            _ => false,
+        }
+    }
+
+    fn fits(&self, value: &FieldElement) -> bool {
+        match self {
+            OracleType::Bool => value.num_bits() <= 1,
+            OracleType::Unsigned(bit_size) | OracleType::Signed(bit_size) => {
+                value.num_bits() <= *bit_size
+            }
+            _ => true,
+        }
+    }
+
+    fn format_scalar(&self, value: &FieldElement) -> String {
+        match self {
+            OracleType::Bool if value.is_zero() => String::from("false"),
+            OracleType::Bool if value.is_one() => String::from("true"),
+            OracleType::Unsigned(bit_size) if *bit_size <= 128 => value.to_u128().to_string(),
+            OracleType::Signed(bit_size) if *bit_size <= 64 => {
+                let value = value.to_u128();
+                if value >> (bit_size - 1) & 1 == 1 {
+                    (value as i128 - (1i128 << bit_size)).to_string()
+                } else {
+                    value.to_string()
+                }
+            }
+            _ => value.to_string(),
+        }
+    }
+
+    /// Renders a value of this type from the `param_count` values it was
+    /// passed as
+    fn format_params(&self, params: &[ForeignCallParam<FieldElement>]) -> String {
+        match (self, params) {
+            (OracleType::Tuple(fields), _) => {
+                let mut rest = params;
+                let fields: Vec<_> = fields
+                    .iter()
+                    .map(|field| {
+                        let (field_params, field_rest) =
+                            rest.split_at(field.param_count().min(rest.len()));
+                        rest = field_rest;
+                        field.format_params(field_params)
+                    })
+                    .collect();
+                format!("({})", fields.join(", "))
+            }
+            (
+                OracleType::Slice(element),
+                [ForeignCallParam::Single(length), ForeignCallParam::Array(values)],
+            ) => match element.flattened_size() {
+                Some(0) => {
+                    format!("[{}; {}]", element.format_fields(&mut [].iter()), length.to_u128())
+                }
+                Some(size) => {
+                    let elements: Vec<_> = values
+                        .chunks(size)
+                        .map(|chunk| element.format_fields(&mut chunk.iter()))
+                        .collect();
+                    format!("[{}]", elements.join(", "))
+                }
+                None => String::from("?"),
+            },
+            (_, [ForeignCallParam::Single(value)]) => self.format_scalar(value),
+            (_, [ForeignCallParam::Array(values)]) => self.format_fields(&mut values.iter()),
+            _ => String::from("?"),
+        }
+    }
+
+    /// Renders a value of this type from the fields it is flattened into
+    fn format_fields(&self, values: &mut std::slice::Iter<FieldElement>) -> String {
+        let elements: Vec<_> = match self {
+            OracleType::Array(element, length) if element.flattened_size() == Some(0) => {
+                return format!("[{}; {length}]", element.format_fields(values));
+            }
+            OracleType::Array(element, length) => {
+                (0..*length).map(|_| element.format_fields(values)).collect()
+            }
+            OracleType::Str(length) => {
+                let bytes: Vec<_> =
+                    values.take(*length).map(|value| value.to_u128() as u8).collect();
+                return format!("{:?}", String::from_utf8_lossy(&bytes));
+            }
+            OracleType::Tuple(fields) => {
+                let fields: Vec<_> =
+                    fields.iter().map(|field| field.format_fields(values)).collect();
+                return format!("({})", fields.join(", "));
+            }
+            // Slices are never flattened into arrays
+            OracleType::Slice(_) => return String::from("?"),
+            scalar => {
+                return values.next().map_or(String::from("?"), |value| scalar.format_scalar(value))
+            }
+        };
+        format!("[{}]", elements.join(", "))
+    }
+
+    /// Checks that the `param_count` values passed to or returned by an
+    /// oracle hold a value of this type
+    fn check_params(&self, params: &[ForeignCallParam<FieldElement>]) -> Result<(), String> {
+        match (self, params) {
+            (OracleType::Tuple(fields), _) => {
+                let mut rest = params;
+                for field in fields {
+                    if rest.len() < field.param_count() {
+                        return Err(format!("{} values where a {self} was expected", params.len()));
+                    }
+                    let (field_params, field_rest) = rest.split_at(field.param_count());
+                    field.check_params(field_params)?;
+                    rest = field_rest;
+                }
+                Ok(())
+            }
+            (
+                OracleType::Slice(element),
+                [ForeignCallParam::Single(length), ForeignCallParam::Array(values)],
+            ) => {
+                let element_size = element.flattened_size().unwrap_or_default();
+                let size = (length.num_bits() <= 32)
+                    .then(|| (length.to_u128() as usize).checked_mul(element_size))
+                    .flatten();
+                if size != Some(values.len()) {
+                    return Err(format!(
+                        "{} fields where a {self} of length {length} was expected",
+                        values.len()
+                    ));
+                }
+                if element_size > 0 {
+                    for chunk in values.chunks(element_size) {
+                        element.check_fields(&mut chunk.iter())?;
+                    }
+                }
+                Ok(())
+            }
+            (OracleType::Slice(_), _) => Err(format!(
+                "values where a {self}, passed as its length and elements, was expected"
+            )),
+            (_, [param]) => self.check_value(param),
+            _ => Err(format!("{} values where a {self} was expected", params.len())),
+        }
+    }
+
+    /// Checks that a single value passed to or returned by an oracle has this
+    /// type, which is neither a slice nor a tuple
+    fn check_value(&self, param: &ForeignCallParam<FieldElement>) -> Result<(), String> {
+        match (self, param) {
+            (OracleType::Array(..) | OracleType::Str(_), ForeignCallParam::Single(_)) => {
+                Err(format!("a single field where a {self} was expected"))
+            }
+            (OracleType::Array(..) | OracleType::Str(_), ForeignCallParam::Array(values)) => {
+                if self.flattened_size() != Some(values.len()) {
+                    return Err(format!("{} fields where a {self} was expected", values.len()));
+                }
+                self.check_fields(&mut values.iter())
+            }
+            (_, ForeignCallParam::Single(value)) => self.check_fields(&mut [*value].iter()),
+            (_, ForeignCallParam::Array(values)) => {
+                Err(format!("{} fields where a {self} was expected", values.len()))
+            }
+        }
+    }
+
+    /// Checks the fields a value of this type is flattened into, of which
+    /// there are `flattened_size`
+    fn check_fields(&self, values: &mut std::slice::Iter<FieldElement>) -> Result<(), String> {
+        match self {
+            OracleType::Array(element, length) => {
+                if element.flattened_size() != Some(0) {
+                    for _ in 0..*length {
+                        element.check_fields(values)?;
+                    }
+                }
+                Ok(())
+            }
+            OracleType::Str(length) => {
+                OracleType::Array(Box::new(OracleType::Unsigned(8)), *length).check_fields(values)
+            }
+            OracleType::Tuple(fields) => {
+                fields.iter().try_for_each(|field| field.check_fields(values))
+            }
+            OracleType::Slice(_) => Err(format!("a {self} nested in an array")),
+            scalar => match values.next() {
+                Some(value) if !scalar.fits(value) => {
+                    Err(format!("{value} where a {scalar} was expected"))
+                }
+                Some(_) => Ok(()),
+                None => Err(format!("no field where a {scalar} was expected")),
+            },
+        }
+    }
+}
+
+/// Splits a list of types on the commas outside of brackets and parentheses
+fn split_types(list: &str) -> Vec<&str> {
+    let mut types = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, char) in list.char_indices() {
+        match char {
+            '[' | '(' | '<' => depth += 1,
+            ']' | ')' | '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                types.push(&list[start..index]);
+                start = index + 1;
+            }
+            _ => (),
+        }
+    }
+    types.push(&list[start..]);
+    types
+}
+
+impl FromStr for OracleType {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Some(inner) = value.strip_prefix('[').and_then(|value| value.strip_suffix(']')) {
+            let (typ, element) = match inner.rsplit_once(';') {
+                Some((element, length)) => {
+                    let length = length
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("invalid array length in `{value}`"))?;
+                    let element: OracleType = element.parse()?;
+                    (OracleType::Array(Box::new(element.clone()), length), element)
+                }
+                None => {
+                    let element: OracleType = inner.parse()?;
+                    (OracleType::Slice(Box::new(element.clone())), element)
+                }
+            };
+            if element.contains_slice() {
+                return Err(format!("slices can't be nested in arrays or slices, as in `{value}`"));
+            }
+            return Ok(typ);
+        }
+        if let Some(inner) = value.strip_prefix('(').and_then(|value| value.strip_suffix(')')) {
+            if inner.trim().is_empty() {
+                return Ok(OracleType::Tuple(vec![]));
+            }
+            let fields =
+                split_types(inner).into_iter().map(str::parse).collect::<Result<_, String>>()?;
+            return Ok(OracleType::Tuple(fields));
+        }
+        if let Some(length) = value.strip_prefix("str<").and_then(|value| value.strip_suffix('>')) {
+            let length =
+                length.trim().parse().map_err(|_| format!("invalid string length in `{value}`"))?;
+            return Ok(OracleType::Str(length));
+        }
+        let bit_size =
+            |bits: &str| bits.parse::<u32>().ok().filter(|bits| (1..=128).contains(bits));
+        match value {
+            "Field" => Ok(OracleType::Field),
+            "bool" => Ok(OracleType::Bool),
+            _ => match (value.strip_prefix('u'), value.strip_prefix('i')) {
+                (Some(bits), _) if bit_size(bits).is_some() => {
+                    Ok(OracleType::Unsigned(bit_size(bits).unwrap()))
+                }
+                (_, Some(bits)) if bit_size(bits).is_some() => {
+                    Ok(OracleType::Signed(bit_size(bits).unwrap()))
+                }
+                _ => Err(format!(
+                    "invalid type `{value}`; expected Field, bool, uN, iN, str<N>, [T; N], [T] or (T, ..)"
+                )),
+            },
+        }
+    }
+}
+
+impl Display for OracleType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OracleType::Field => write!(f, "Field"),
+            OracleType::Bool => write!(f, "bool"),
+            OracleType::Unsigned(bit_size) => write!(f, "u{bit_size}"),
+            OracleType::Signed(bit_size) => write!(f, "i{bit_size}"),
+            OracleType::Str(length) => write!(f, "str<{length}>"),
+            OracleType::Array(element, length) => write!(f, "[{element}; {length}]"),
+            OracleType::Slice(element) => write!(f, "[{element}]"),
+            OracleType::Tuple(fields) => {
+                let fields: Vec<_> = fields.iter().map(ToString::to_string).collect();
+                write!(f, "({})", fields.join(", "))
+            }
+        }
+    }
+}
+
+/// Declared parameters and return values of an oracle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleSignature {
+    pub description: Option<String>,
+    pub params: Vec<(String, OracleType)>,
+    pub returns: Vec<OracleType>,
+}
+
+impl OracleSignature {
+    /// Checks the arguments of a call against the declared parameters, as
+    /// flattened by the compiler: slices are passed as their length followed
+    /// by their elements, and tuples and structs as one argument per field
+    pub(super) fn check_inputs(
+        &self,
+        inputs: &[ForeignCallParam<FieldElement>],
+    ) -> Result<(), String> {
+        let expected: usize = self.params.iter().map(|(_, typ)| typ.param_count()).sum();
+        if inputs.len() != expected {
+            return Err(format!("was called with {} arguments, expected {expected}", inputs.len()));
+        }
+        let mut rest = inputs;
+        for (name, typ) in &self.params {
+            let (param_inputs, param_rest) = rest.split_at(typ.param_count());
+            typ.check_params(param_inputs)
+                .map_err(|reason| format!("got {reason} for `{name}`"))?;
+            rest = param_rest;
+        }
+        Ok(())
+    }
+
+    /// Checks the values returned by the oracle against the declared ones,
+    /// flattened as the arguments are
+    pub(super) fn check_outputs(
+        &self,
+        outputs: &[ForeignCallParam<FieldElement>],
+    ) -> Result<(), String> {
+        let expected: usize = self.returns.iter().map(OracleType::param_count).sum();
+        if outputs.len() != expected {
+            return Err(format!("returned {} values, expected {expected}", outputs.len()));
+        }
+        let mut rest = outputs;
+        for (index, typ) in self.returns.iter().enumerate() {
+            let (typ_outputs, typ_rest) = rest.split_at(typ.param_count());
+            typ.check_params(typ_outputs)
+                .map_err(|reason| format!("returned {reason} as value {index}"))?;
+            rest = typ_rest;
+        }
+        Ok(())
+    }
+
+    /// Renders a call with its arguments named after the parameters, eg.
+    /// `get_price(asset: 3, block: 120)`. Arguments beyond the declared
+    /// parameters are rendered as they were passed.
+    pub(super) fn format_call(
+        &self,
+        name: &str,
+        inputs: &[ForeignCallParam<FieldElement>],
+    ) -> String {
+        let mut rest = inputs;
+        let mut arguments: Vec<_> = self
+            .params
+            .iter()
+            .map(|(name, typ)| {
+                let (param_inputs, param_rest) = rest.split_at(typ.param_count().min(rest.len()));
+                rest = param_rest;
+                format!("{name}: {}", typ.format_params(param_inputs))
+            })
+            .collect();
+        arguments.extend(rest.iter().map(|input| format!("{input:?}")));
+        format!("{name}({})", arguments.join(", "))
+    }
+
+    /// Renders the signature, as `name(param: type, ..) -> type`
+    pub(super) fn format(&self, name: &str) -> String {
+        let params: Vec<_> =
+            self.params.iter().map(|(name, typ)| format!("{name}: {typ}")).collect();
+        let returns = match self.returns.as_slice() {
+            [] => String::new(),
+            [typ] => format!(" -> {typ}"),
+            returns => {
+                let returns: Vec<_> = returns.iter().map(ToString::to_string).collect();
+                format!(" -> ({})", returns.join(", "))
+            }
+        };
+        format!("{name}({}){returns}", params.join(", "))
+    }
+}
+
+/// Signatures of the oracles called by a program, read from a file such as
+///
+/// ```toml
+/// [get_price]
+/// description = "Price of an asset at a given block"
+/// params = [{ name = "asset", type = "Field" }, { name = "block", type = "u32" }]
+/// returns = ["u64"]
+/// ```
+///
+/// Calls to oracles declared in it are checked against their signature, so
+/// that mismatches between the program and its resolver are caught where the
+/// call is made.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OracleSchema {
+    oracles: BTreeMap<String, OracleSignature>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OracleEntry {
+    description: Option<String>,
+    #[serde(default)]
+    params: Vec<ParamEntry>,
+    #[serde(default)]
+    returns: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParamEntry {
+    name: String,
+    #[serde(rename = "type")]
+    typ: String,
+}
+
+impl OracleSchema {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let entries: BTreeMap<String, OracleEntry> =
+            toml::from_str(source).map_err(|error| error.to_string())?;
+        let mut oracles = BTreeMap::new();
+        for (name, entry) in entries {
+            let invalid = |error: String| format!("oracle `{name}`: {error}");
+            let params = entry
+                .params
+                .into_iter()
+                .map(|param| Ok((param.name, param.typ.parse::<OracleType>().map_err(invalid)?)))
+                .collect::<Result<_, String>>()?;
+            let returns = entry
+                .returns
+                .iter()
+                .map(|typ| typ.parse::<OracleType>().map_err(invalid))
+                .collect::<Result<_, String>>()?;
+            let signature = OracleSignature { description: entry.description, params, returns };
+            oracles.insert(name, signature);
+        }
+        Ok(OracleSchema { oracles })
+    }
+
+    /// Reads the schema in `dir`, if there is one
+    pub fn load_from_dir(dir: &Path) -> Result<Option<Self>, String> {
+        let path = dir.join(ORACLE_SCHEMA_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let source = std::fs::read_to_string(&path)
+            .map_err(|error| format!("Could not read {}: {error}", path.display()))?;
+        let schema = Self::parse(&source)
+            .map_err(|error| format!("Invalid oracle schema {}: {error}", path.display()))?;
+        Ok(Some(schema))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&OracleSignature> {
+        self.oracles.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &OracleSignature)> {
+        self.oracles.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.oracles.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        [get_price]
+        description = "Price of an asset"
+        params = [{ name = "asset", type = "Field" }, { name = "blocks", type = "[u8; 2]" }]
+        returns = ["u64", "bool"]
+    "#;
+
+    fn field(value: u128) -> FieldElement {
+        FieldElement::from(value)
+    }
+
+    #[test]
+    fn parses_types() {
+        assert_eq!("Field".parse(), Ok(OracleType::Field));
+        assert_eq!(
+            "[[u8; 2]; 3]".parse(),
+            Ok(OracleType::Array(
+                Box::new(OracleType::Array(Box::new(OracleType::Unsigned(8)), 2)),
+                3
+            ))
+        );
+        assert_eq!("[i32]".parse(), Ok(OracleType::Slice(Box::new(OracleType::Signed(32)))));
+        assert_eq!(
+            "(u8, [Field; 2], str<3>)".parse(),
+            Ok(OracleType::Tuple(vec![
+                OracleType::Unsigned(8),
+                OracleType::Array(Box::new(OracleType::Field), 2),
+                OracleType::Str(3),
+            ]))
+        );
+        assert!("u0".parse::<OracleType>().is_err());
+        assert!("[u8; x]".parse::<OracleType>().is_err());
+        assert!("[[u8]; 2]".parse::<OracleType>().is_err());
+        assert!("[(bool, [u8])]".parse::<OracleType>().is_err());
+    }
+
+    #[test]
+    fn checks_slices_and_tuples_as_flattened_by_the_compiler() {
+        let schema = OracleSchema::parse(
+            r#"
+            [store]
+            params = [{ name = "values", type = "[(u8, bool)]" }, { name = "point", type = "(Field, u8)" }]
+            returns = ["[u8]"]
+            "#,
+        )
+        .unwrap();
+        let signature = schema.get("store").unwrap();
+
+        // the slice is passed as its length and its flattened elements, and
+        // the tuple as one argument per field
+        let inputs = [
+            ForeignCallParam::Single(field(2)),
+            ForeignCallParam::Array(vec![field(1), field(0), field(2), field(1)]),
+            ForeignCallParam::Single(field(5)),
+            ForeignCallParam::Single(field(6)),
+        ];
+        assert_eq!(signature.check_inputs(&inputs), Ok(()));
+        assert_eq!(
+            signature.format_call("store", &inputs),
+            "store(values: [(1, false), (2, true)], point: (5, 6))"
+        );
+
+        let wrong_length = [
+            ForeignCallParam::Single(field(3)),
+            ForeignCallParam::Array(vec![field(1), field(0), field(2), field(1)]),
+            ForeignCallParam::Single(field(5)),
+            ForeignCallParam::Single(field(6)),
+        ];
+        assert!(signature.check_inputs(&wrong_length).is_err());
+        assert!(signature.check_inputs(&inputs[1..]).is_err());
+
+        let outputs =
+            [ForeignCallParam::Single(field(2)), ForeignCallParam::Array(vec![field(1), field(2)])];
+        assert_eq!(signature.check_outputs(&outputs), Ok(()));
+        assert!(signature.check_outputs(&outputs[1..]).is_err());
+    }
+
+    #[test]
+    fn handles_zero_sized_elements() {
+        let typ: OracleType = "[[Field; 0]]".parse().unwrap();
+        let empty = [ForeignCallParam::Single(field(3)), ForeignCallParam::Array(vec![])];
+        assert_eq!(typ.check_params(&empty), Ok(()));
+        assert_eq!(typ.format_params(&empty), "[[]; 3]");
+        let not_empty =
+            [ForeignCallParam::Single(field(3)), ForeignCallParam::Array(vec![field(1)])];
+        assert!(typ.check_params(&not_empty).is_err());
+
+        let typ: OracleType = "[[Field; 0]; 4]".parse().unwrap();
+        assert_eq!(typ.check_params(&[ForeignCallParam::Array(vec![])]), Ok(()));
+        assert_eq!(typ.format_params(&[ForeignCallParam::Array(vec![])]), "[[]; 4]");
+    }
+
+    #[test]
+    fn checks_calls_against_signatures() {
+        let schema = OracleSchema::parse(SCHEMA).unwrap();
+        let signature = schema.get("get_price").unwrap();
+        assert_eq!(
+            signature.format("get_price"),
+            "get_price(asset: Field, blocks: [u8; 2]) -> (u64, bool)"
+        );
+
+        let inputs =
+            [ForeignCallParam::Single(field(7)), ForeignCallParam::Array(vec![field(1), field(2)])];
+        assert_eq!(signature.check_inputs(&inputs), Ok(()));
+        assert_eq!(
+            signature.format_call("get_price", &inputs),
+            "get_price(asset: 7, blocks: [1, 2])"
+        );
+
+        let too_wide = [
+            ForeignCallParam::Single(field(7)),
+            ForeignCallParam::Array(vec![field(1), field(256)]),
+        ];
+        assert!(signature.check_inputs(&too_wide).is_err());
+        assert!(signature.check_inputs(&inputs[..1]).is_err());
+
+        let outputs = [ForeignCallParam::Single(field(3)), ForeignCallParam::Single(field(1))];
+        assert_eq!(signature.check_outputs(&outputs), Ok(()));
+        let not_bool = [ForeignCallParam::Single(field(3)), ForeignCallParam::Single(field(2))];
+        assert!(signature.check_outputs(&not_bool).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        assert!(OracleSchema::parse("[f]\nparams = [{ name = \"x\", type = \"u256\" }]").is_err());
+        assert!(OracleSchema::parse("[f]\nreturn = [\"Field\"]").is_err());
+    }
+}
@@ -1,29 +1,143 @@
-use crate::context::{DebugCommandResult, DebugContext};
+use crate::conditions::Condition;
+use crate::context::{
+    AssertInfo, DebugCommandResult, DebugContext, Divergence, FunctionCalls, IntegerOverflow,
+    OraclePolicy, RestartTimings, TracedCall, WitnessDependency, PRINT_ORACLE,
+};
+use crate::environment::DebugEnvironment;
+use crate::interrupt;
+use crate::tutorial::Tutorial;
+use crate::watch::SourceWatcher;
+use crate::{
+    DebugOutcome, DebuggerOptions, FailureReport, ProfileReport, ProfiledFunction,
+    ProfiledLocation, ProgramInfo, SessionLog, SessionSummary, Verbosity, ORACLE_SCHEMA_FILE,
+};
 
 use acvm::acir::circuit::brillig::BrilligBytecode;
-use acvm::acir::circuit::{Circuit, Opcode, OpcodeLocation};
+use acvm::acir::circuit::{Circuit, ExpressionWidth, Opcode, OpcodeLocation, Program};
 use acvm::acir::native_types::{Witness, WitnessMap};
 use acvm::brillig_vm::brillig::Opcode as BrilligOpcode;
+use acvm::brillig_vm::MemoryValue;
 use acvm::{BlackBoxFunctionSolver, FieldElement};
+use nargo::ops::PrintOutput;
 use nargo::NargoError;
 
-use crate::foreign_calls::DefaultDebugForeignCallExecutor;
-use noirc_artifacts::debug::DebugArtifact;
+use crate::foreign_calls::{
+    format_print_message, DefaultDebugForeignCallExecutor, ForeignCallResolverConfig,
+};
+use crate::source_paths::{find_source_file, FileMatch, PathSubstitutions};
+use fm::FileId;
+use noirc_artifacts::debug::{DebugArtifact, StackFrame};
 
-use easy_repl::{command, CommandStatus, Repl};
+use easy_repl::{command, CommandStatus, LoopStatus, Repl};
 use noirc_printable_type::PrintableValueDisplay;
+use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use crate::source_code_printer::print_source_code_location;
+use crate::memory_view::{format_cell_value, format_memory_cells, CellType};
+use crate::source_code_printer::{format_location_path, print_source_code_location};
+
+// How many levels of opcodes `deps` shows when no depth is given
+const DEFAULT_DEPS_DEPTH: usize = 3;
+
+// How many opcodes `opcodes` shows around the current location when no range is given
+const OPCODES_WINDOW: usize = 20;
+
+// How many source lines `list` shows before and after the line asked for
+const LIST_CONTEXT_LINES: usize = 5;
+
+// How many of the most recently executed opcodes `--run-to-failure` reports
+const RUN_TO_FAILURE_JOURNAL_SIZE: usize = 20;
+
+// How many of the most executed source locations `profile` shows
+const PROFILE_LOCATIONS_SHOWN: usize = 20;
+
+// How many opcodes `profile` executes at most when no limit is given
+const PROFILE_MAX_OPCODES: usize = 10_000_000;
+
+// How many of the most recently executed opcodes are looked at to find the
+// hottest source lines when `continue` times out
+const HANG_WINDOW: usize = 10_000;
+
+// How many of the hottest source lines are shown when `continue` times out
+const HANG_LINES_SHOWN: usize = 5;
+
+// Width of the lines `memory` prints, unless changed with `set memory-width`
+const DEFAULT_MEMORY_WIDTH: usize = 100;
+
+// Restarts taking longer than this report how long each phase took
+const RESTART_REPORT_THRESHOLD: Duration = Duration::from_millis(500);
+
+// How many opcodes `continue` executes between checks of whether its progress
+// line is due, so that the clock is not read on every step
+const PROGRESS_CHECK_STEPS: usize = 1024;
 
 pub struct ReplDebugger<'a, B: BlackBoxFunctionSolver<FieldElement>> {
     context: DebugContext<'a, B>,
-    blackbox_solver: &'a B,
-    circuit: &'a Circuit<FieldElement>,
     debug_artifact: &'a DebugArtifact,
     initial_witness: WitnessMap<FieldElement>,
     last_result: DebugCommandResult,
     unconstrained_functions: &'a [BrilligBytecode<FieldElement>],
+
+    // When set, values are rendered with the multi-line pretty-printer
+    print_pretty: bool,
+
+    // Whether output may use ANSI colors
+    colors: bool,
+
+    // When set, visual markers are replaced by textual descriptions and
+    // every stop is summarized in a sentence, for use with screen readers
+    accessible: bool,
+
+    // How much is printed every time execution stops
+    verbosity: Verbosity,
+
+    tutorial: Option<Tutorial>,
+
+    // Witness map as it was at the previous stop, to show what changed since
+    witness_snapshot: WitnessMap<FieldElement>,
+
+    // Brillig memory as it was at the previous stop, along with the ACIR
+    // index of the Brillig block it belongs to
+    memory_snapshot: Option<(usize, Vec<MemoryValue<FieldElement>>)>,
+
+    // Brillig memory saved with `memory-diff --save`, by name, in the same
+    // form as `memory_snapshot`
+    memory_checkpoints: BTreeMap<String, (usize, Vec<MemoryValue<FieldElement>>)>,
+
+    // Maximum width of the lines `memory` prints
+    memory_width: usize,
+
+    // Rendered values of the current frame's variables at the previous stop
+    variables_snapshot: HashMap<String, String>,
+
+    // File the locations of the opcodes executed by `continue` are appended to
+    trace: Option<BufWriter<File>>,
+
+    // Limits after which `continue` returns control to the prompt, if any
+    continue_timeout: Option<Duration>,
+    continue_opcode_budget: Option<usize>,
+
+    // How often `continue` redraws its progress line, if at all, and the width
+    // of the line currently drawn
+    progress_interval: Option<Duration>,
+    progress_width: usize,
+
+    // Rewrites of source paths, eg. from the embedded stdlib to a checkout on disk
+    path_substitutions: PathSubstitutions,
+
+    // How the program's foreign calls are resolved, also in profiled runs
+    resolver_config: ForeignCallResolverConfig,
+
+    // Result of the last `profile` command, exported in the session report
+    profile: Option<ProfileReport>,
 }
 
 impl<'a, B: BlackBoxFunctionSolver<FieldElement>> ReplDebugger<'a, B> {
@@ -33,10 +147,14 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> ReplDebugger<'a, B> {
         debug_artifact: &'a DebugArtifact,
         initial_witness: WitnessMap<FieldElement>,
         unconstrained_functions: &'a [BrilligBytecode<FieldElement>],
+        options: &DebuggerOptions,
     ) -> Self {
-        let foreign_call_executor =
-            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, debug_artifact));
-        let context = DebugContext::new(
+        let mut foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::with_resolver(
+            PrintOutput::Stdout,
+            options.resolver_config.clone(),
+        ));
+        foreign_call_executor.load_artifact(debug_artifact);
+        let mut context = DebugContext::new(
             blackbox_solver,
             circuit,
             debug_artifact,
@@ -44,6 +162,7 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> ReplDebugger<'a, B> {
             foreign_call_executor,
             unconstrained_functions,
         );
+        context.set_interrupt_flag(interrupt::flag());
         let last_result = if context.get_current_opcode_location().is_none() {
             // handle circuit with no opcodes
             DebugCommandResult::Done
@@ -52,69 +171,179 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> ReplDebugger<'a, B> {
         };
         Self {
             context,
-            blackbox_solver,
-            circuit,
             debug_artifact,
+            witness_snapshot: initial_witness.clone(),
+            memory_snapshot: None,
+            memory_checkpoints: BTreeMap::new(),
+            memory_width: DEFAULT_MEMORY_WIDTH,
+            variables_snapshot: HashMap::new(),
             initial_witness,
             last_result,
             unconstrained_functions,
+            print_pretty: false,
+            colors: options.color.use_colors(),
+            accessible: false,
+            verbosity: options.verbosity,
+            tutorial: None,
+            trace: None,
+            continue_timeout: None,
+            continue_opcode_budget: None,
+            progress_interval: None,
+            progress_width: 0,
+            path_substitutions: options.path_substitutions.clone(),
+            resolver_config: options.resolver_config.clone(),
+            profile: None,
         }
     }
 
     pub fn show_current_vm_status(&self) {
-        let location = self.context.get_current_opcode_location();
-        let opcodes = self.context.get_opcodes();
-
-        match location {
-            None => println!("Finished execution"),
-            Some(location) => {
-                match location {
-                    OpcodeLocation::Acir(ip) => {
-                        println!("At opcode {}: {}", ip, opcodes[ip]);
-                    }
-                    OpcodeLocation::Brillig { acir_index, brillig_index } => {
-                        let brillig_bytecode =
-                            if let Opcode::BrilligCall { id, .. } = opcodes[acir_index] {
-                                &self.unconstrained_functions[id as usize].bytecode
-                            } else {
-                                unreachable!("Brillig location does not contain Brillig opcodes");
-                            };
-                        println!(
-                            "At opcode {}.{}: {:?}",
-                            acir_index, brillig_index, brillig_bytecode[brillig_index]
-                        );
-                    }
-                }
-                let locations = self.context.get_source_location_for_opcode_location(&location);
-                print_source_code_location(self.debug_artifact, &locations);
+        match self.verbosity {
+            Verbosity::Silent => (),
+            Verbosity::Quiet => self.show_status_line(),
+            Verbosity::Normal => self.show_current_location(),
+            Verbosity::Verbose => {
+                self.show_current_location();
+                self.show_changed_variables();
+                self.show_counters();
             }
         }
     }
 
-    fn show_stack_frame(&self, index: usize, location: &OpcodeLocation) {
-        let opcodes = self.context.get_opcodes();
-        match location {
-            OpcodeLocation::Acir(instruction_pointer) => {
-                println!(
-                    "Frame #{index}, opcode {}: {}",
-                    instruction_pointer, opcodes[*instruction_pointer]
+    /// Shows the current location and the ACVM status regardless of the
+    /// verbosity setting
+    fn show_status(&self) {
+        self.show_current_location();
+        println!("ACVM status: {}", self.context.describe_status());
+    }
+
+    fn show_status_line(&self) {
+        let Some(location) = self.context.get_current_opcode_location() else {
+            println!("Finished execution");
+            return;
+        };
+        let source = self
+            .context
+            .get_source_location_for_opcode_location(&location)
+            .last()
+            .map(|source_location| {
+                format!(
+                    " ({})",
+                    format_location_path(
+                        self.debug_artifact,
+                        &self.path_substitutions,
+                        *source_location
+                    )
                 )
-            }
+            })
+            .unwrap_or_default();
+        println!("At opcode {location}{source}");
+    }
+
+    /// Formats an opcode location along with the opcode it points to
+    fn format_opcode(&self, location: &OpcodeLocation) -> String {
+        let opcodes = self.context.get_opcodes();
+        match *location {
+            OpcodeLocation::Acir(ip) => format!("{}: {}", ip, opcodes[ip]),
             OpcodeLocation::Brillig { acir_index, brillig_index } => {
-                let brillig_bytecode = if let Opcode::BrilligCall { id, .. } = opcodes[*acir_index]
-                {
+                let brillig_bytecode = if let Opcode::BrilligCall { id, .. } = opcodes[acir_index] {
                     &self.unconstrained_functions[id as usize].bytecode
                 } else {
                     unreachable!("Brillig location does not contain Brillig opcodes");
                 };
-                println!(
-                    "Frame #{index}, opcode {}.{}: {:?}",
-                    acir_index, brillig_index, brillig_bytecode[*brillig_index]
+                format!("{}.{}: {:?}", acir_index, brillig_index, brillig_bytecode[brillig_index])
+            }
+        }
+    }
+
+    fn show_current_location(&self) {
+        match self.context.get_current_opcode_location() {
+            None => println!("Finished execution"),
+            Some(location) => {
+                println!("At opcode {}", self.format_opcode(&location));
+                let locations = self.context.get_source_location_for_opcode_location(&location);
+                print_source_code_location(
+                    self.debug_artifact,
+                    &self.path_substitutions,
+                    &locations,
+                    self.colors,
+                    self.accessible,
                 );
             }
         }
+    }
+
+    /// Shows an arbitrary opcode and the source code it was generated from,
+    /// without moving execution
+    pub fn show_location(&self, location: OpcodeLocation) {
+        if !self.context.is_valid_opcode_location(&location) {
+            println!("Invalid opcode location {location}");
+            return;
+        }
+        println!("Opcode {}", self.format_opcode(&location));
+        let locations = self.context.get_source_location_for_opcode_location(&location);
+        if locations.is_empty() {
+            println!("No source code is associated with opcode {location}");
+        } else {
+            print_source_code_location(
+                self.debug_artifact,
+                &self.path_substitutions,
+                &locations,
+                self.colors,
+                self.accessible,
+            );
+        }
+    }
+
+    fn current_variables(&self) -> Vec<(String, String)> {
+        self.context
+            .current_stack_frame()
+            .map(|frame| {
+                frame
+                    .variables
+                    .iter()
+                    .map(|(name, value, var_type)| {
+                        let value =
+                            PrintableValueDisplay::Plain((*value).clone(), (*var_type).clone());
+                        (name.to_string(), value.to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn show_changed_variables(&self) {
+        for (name, value) in self.current_variables() {
+            if self.variables_snapshot.get(&name) != Some(&value) {
+                println!("  {name} = {value}");
+            }
+        }
+    }
+
+    fn show_counters(&self) {
+        let (mut assigned, mut changed) = (0, 0);
+        for (witness, value) in self.context.get_witness_map().clone().into_iter() {
+            assigned += 1;
+            if self.witness_snapshot.get(&witness) != Some(&value) {
+                changed += 1;
+            }
+        }
+        println!(
+            "Witnesses: {assigned} assigned, {changed} changed; breakpoints: {}; black box calls: {}",
+            self.context.get_breakpoints().count(),
+            self.context.get_blackbox_calls().len()
+        );
+    }
+
+    fn show_stack_frame(&self, index: usize, location: &OpcodeLocation) {
+        println!("Frame #{index}, opcode {}", self.format_opcode(location));
         let locations = self.context.get_source_location_for_opcode_location(location);
-        print_source_code_location(self.debug_artifact, &locations);
+        print_source_code_location(
+            self.debug_artifact,
+            &self.path_substitutions,
+            &locations,
+            self.colors,
+            self.accessible,
+        );
     }
 
     pub fn show_current_call_stack(&self) {
@@ -129,242 +358,2059 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> ReplDebugger<'a, B> {
         }
     }
 
+    /// Displays the source call stack, outermost frame first, with the
+    /// variables in scope of each function frame under it. Frames are paired
+    /// with function frames by position, as in the stack trace of the DAP.
+    pub fn show_current_call_stack_full(&self) {
+        let source_call_stack = self.context.get_source_call_stack();
+        if source_call_stack.is_empty() {
+            self.show_current_call_stack();
+            return;
+        }
+
+        let frames = self.context.get_variables();
+        for (index, (opcode_location, source_location)) in source_call_stack.iter().enumerate() {
+            println!("Frame #{index}, opcode {}", self.format_opcode(opcode_location));
+            print_source_code_location(
+                self.debug_artifact,
+                &self.path_substitutions,
+                &[*source_location],
+                self.colors,
+                self.accessible,
+            );
+            let Some(frame) = frames.get(index) else {
+                continue;
+            };
+            println!("Function {}({}):", frame.function_name, frame.function_params.join(", "));
+            if frame.variables.is_empty() {
+                println!("  (no variables)");
+            }
+            self.show_frame_variables(frame, None);
+        }
+    }
+
+    /// Displays a window of opcodes centered on the current location
     fn display_opcodes(&self) {
         let opcodes = self.context.get_opcodes();
-        let current_opcode_location = self.context.get_current_opcode_location();
-        let current_acir_index = match current_opcode_location {
-            Some(OpcodeLocation::Acir(ip)) => Some(ip),
-            Some(OpcodeLocation::Brillig { acir_index, .. }) => Some(acir_index),
-            None => None,
-        };
-        let current_brillig_index = match current_opcode_location {
-            Some(OpcodeLocation::Brillig { brillig_index, .. }) => brillig_index,
-            _ => 0,
-        };
-        let outer_marker = |acir_index| {
-            if current_acir_index == Some(acir_index) {
-                "->"
-            } else if self.context.is_breakpoint_set(&OpcodeLocation::Acir(acir_index)) {
-                " *"
-            } else {
-                ""
+        match self.context.get_current_opcode_location() {
+            Some(OpcodeLocation::Brillig { acir_index, brillig_index }) => {
+                let bytecode_len = self.brillig_bytecode_at(acir_index).map_or(0, |b| b.len());
+                self.display_brillig_opcodes(
+                    acir_index,
+                    window_around(brillig_index, bytecode_len, OPCODES_WINDOW),
+                );
             }
-        };
-        let brillig_marker = |acir_index, brillig_index| {
-            if current_acir_index == Some(acir_index) && brillig_index == current_brillig_index {
-                "->"
-            } else if self
-                .context
-                .is_breakpoint_set(&OpcodeLocation::Brillig { acir_index, brillig_index })
-            {
-                " *"
-            } else {
-                ""
+            Some(OpcodeLocation::Acir(ip)) => {
+                self.display_acir_opcodes(window_around(ip, opcodes.len(), OPCODES_WINDOW));
             }
-        };
-        let print_brillig_bytecode = |acir_index, bytecode: &[BrilligOpcode<FieldElement>]| {
-            for (brillig_index, brillig_opcode) in bytecode.iter().enumerate() {
+            None => {
+                let len = opcodes.len();
+                self.display_acir_opcodes(window_around(len, len, OPCODES_WINDOW));
+            }
+        }
+    }
+
+    fn display_all_opcodes(&self) {
+        for acir_index in 0..self.context.get_opcodes().len() {
+            self.print_acir_opcode(acir_index);
+            if let Some(bytecode) = self.brillig_bytecode_at(acir_index) {
+                for brillig_index in 0..bytecode.len() {
+                    self.print_brillig_opcode(acir_index, brillig_index);
+                }
+            }
+        }
+    }
+
+    fn display_acir_opcodes(&self, range: Range<usize>) {
+        let len = self.context.get_opcodes().len();
+        let range = clamp_range(range, len);
+        for acir_index in range.clone() {
+            self.print_acir_opcode(acir_index);
+            if let Some(bytecode) = self.brillig_bytecode_at(acir_index) {
                 println!(
-                    "{:>3}.{:<2} |{:2} {:?}",
-                    acir_index,
-                    brillig_index,
-                    brillig_marker(acir_index, brillig_index),
-                    brillig_opcode
+                    "       |       ({} Brillig opcodes, list them with `opcodes {acir_index} <range>`)",
+                    bytecode.len()
                 );
             }
+        }
+        if range.len() < len {
+            println!("(showing ACIR opcodes {}..{} of {len})", range.start, range.end);
+        }
+    }
+
+    fn display_brillig_opcodes(&self, acir_index: usize, range: Range<usize>) {
+        let Some(bytecode) = self.brillig_bytecode_at(acir_index) else {
+            println!("Opcode {acir_index} is not a Brillig call");
+            return;
         };
-        for (acir_index, opcode) in opcodes.iter().enumerate() {
-            let marker = outer_marker(acir_index);
-            match &opcode {
-                Opcode::BrilligCall { id, inputs, outputs, .. } => {
-                    println!(
-                        "{:>3} {:2} BRILLIG CALL id={} inputs={:?}",
-                        acir_index, marker, id, inputs
-                    );
-                    println!("       |       outputs={:?}", outputs);
-                    let bytecode = &self.unconstrained_functions[*id as usize].bytecode;
-                    print_brillig_bytecode(acir_index, bytecode);
-                }
-                _ => println!("{:>3} {:2} {:?}", acir_index, marker, opcode),
+        let range = clamp_range(range, bytecode.len());
+        self.print_acir_opcode(acir_index);
+        for brillig_index in range.clone() {
+            self.print_brillig_opcode(acir_index, brillig_index);
+        }
+        if range.len() < bytecode.len() {
+            println!(
+                "(showing Brillig opcodes {}..{} of {})",
+                range.start,
+                range.end,
+                bytecode.len()
+            );
+        }
+    }
+
+    fn brillig_bytecode_at(&self, acir_index: usize) -> Option<&[BrilligOpcode<FieldElement>]> {
+        match self.context.get_opcodes().get(acir_index) {
+            Some(Opcode::BrilligCall { id, .. }) => {
+                Some(&self.unconstrained_functions[*id as usize].bytecode)
+            }
+            _ => None,
+        }
+    }
+
+    fn print_acir_opcode(&self, acir_index: usize) {
+        let current_acir_index = match self.context.get_current_opcode_location() {
+            Some(OpcodeLocation::Acir(ip)) => Some(ip),
+            Some(OpcodeLocation::Brillig { acir_index, .. }) => Some(acir_index),
+            None => None,
+        };
+        let marker = opcode_marker(
+            current_acir_index == Some(acir_index),
+            self.context.is_breakpoint_set(&OpcodeLocation::Acir(acir_index)),
+            self.accessible,
+        );
+        let prefix = if self.accessible {
+            format!("opcode {acir_index}{marker}:")
+        } else {
+            format!("{:>3} {:2}", acir_index, marker)
+        };
+        match &self.context.get_opcodes()[acir_index] {
+            Opcode::BrilligCall { id, inputs, outputs, .. } => {
+                println!("{prefix} BRILLIG CALL id={} inputs={:?}", id, inputs);
+                println!("       |       outputs={:?}", outputs);
             }
+            opcode => println!("{prefix} {:?}", opcode),
         }
     }
 
+    fn print_brillig_opcode(&self, acir_index: usize, brillig_index: usize) {
+        let location = OpcodeLocation::Brillig { acir_index, brillig_index };
+        let marker = opcode_marker(
+            self.context.get_current_opcode_location() == Some(location),
+            self.context.is_breakpoint_set(&location),
+            self.accessible,
+        );
+        let bytecode = self.brillig_bytecode_at(acir_index).expect("Expected a Brillig call");
+        let prefix = if self.accessible {
+            format!("opcode {location}{marker}:")
+        } else {
+            format!("{:>3}.{:<2} |{:2}", acir_index, brillig_index, marker)
+        };
+        println!("{prefix} {:?}", bytecode[brillig_index]);
+    }
+
     fn add_breakpoint_at(&mut self, location: OpcodeLocation) {
         if !self.context.is_valid_opcode_location(&location) {
             println!("Invalid opcode location {location}");
         } else if self.context.add_breakpoint(location) {
-            println!("Added breakpoint at opcode {location}");
+            let id =
+                self.context.get_breakpoint_at(&location).map_or(0, |breakpoint| breakpoint.id);
+            println!("Added breakpoint {id} at opcode {location}");
         } else {
             println!("Breakpoint at opcode {location} already set");
         }
     }
 
-    fn delete_breakpoint_at(&mut self, location: OpcodeLocation) {
-        if self.context.delete_breakpoint(&location) {
-            println!("Breakpoint at opcode {location} deleted");
-        } else {
-            println!("Breakpoint at opcode {location} not set");
+    /// Adds a breakpoint at an opcode location or, for `assert#N`, at the
+    /// first opcode of assert N as listed by `info asserts`
+    fn add_breakpoint_at_target(&mut self, target: &str) {
+        if let Some((path, line)) = parse_source_position(target) {
+            let Some(file_id) = self.find_source_file_id(path) else {
+                return;
+            };
+            match self.context.find_opcode_for_source_location(&file_id, line as i64) {
+                Some(location) => self.add_breakpoint_at(location),
+                None => println!("No opcode found at or after line {line} of {path}"),
+            }
+            return;
         }
-    }
-
-    fn validate_in_progress(&self) -> bool {
-        match self.last_result {
-            DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(..) => true,
-            DebugCommandResult::Done => {
-                println!("Execution finished");
-                false
+        let Some(id) = target.strip_prefix("assert#") else {
+            match target.parse::<OpcodeLocation>() {
+                Ok(location) => self.add_breakpoint_at(location),
+                Err(_) => println!("Invalid opcode location {target}"),
             }
-            DebugCommandResult::Error(ref error) => {
-                println!("ERROR: {}", error);
-                self.show_current_vm_status();
-                false
+            return;
+        };
+        match id.parse().ok().and_then(|id| self.context.get_assert(id)) {
+            Some(AssertInfo { first_opcode: Some(first_opcode), .. }) => {
+                let first_opcode = *first_opcode;
+                println!("Assert #{id} starts at opcode {first_opcode}");
+                self.add_breakpoint_at(first_opcode);
             }
+            Some(_) => println!("Assert #{id} has no opcodes in the debugged circuit"),
+            None => println!("Unknown assert #{id}; use `info asserts` to list them"),
         }
     }
 
-    fn handle_debug_command_result(&mut self, result: DebugCommandResult) {
-        match &result {
-            DebugCommandResult::BreakpointReached(location) => {
-                println!("Stopped at breakpoint in opcode {}", location);
-            }
-            DebugCommandResult::Error(error) => {
-                println!("ERROR: {}", error);
-            }
-            _ => (),
+    /// Adds a breakpoint recording the values of `variables` (comma
+    /// separated) every time it is hit, or sets them for the breakpoint
+    /// already at the location
+    fn add_snapshot_breakpoint_at(&mut self, location: OpcodeLocation, variables: &str) {
+        if !self.context.is_valid_opcode_location(&location) {
+            println!("Invalid opcode location {location}");
+            return;
         }
-        self.last_result = result;
-        self.show_current_vm_status();
+        let variables: Vec<String> = variables
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .map(String::from)
+            .collect();
+        if variables.is_empty() {
+            println!("No variables to snapshot; expected eg. x,y");
+            return;
+        }
+        let added = self.context.add_breakpoint(location);
+        let Some(id) = self.context.get_breakpoint_at(&location).map(|breakpoint| breakpoint.id)
+        else {
+            return;
+        };
+        self.context.set_breakpoint_snapshot(id, variables.clone());
+        let verb = if added { "Added" } else { "Updated" };
+        println!("{verb} breakpoint {id} at opcode {location}, recording {}", variables.join(", "));
     }
 
-    fn step_acir_opcode(&mut self) {
-        if self.validate_in_progress() {
-            let result = self.context.step_acir_opcode();
-            self.handle_debug_command_result(result);
+    /// Adds a breakpoint that only stops execution when `condition` holds, or
+    /// sets the condition of the breakpoint already at the location
+    fn add_conditional_breakpoint_at(&mut self, location: OpcodeLocation, condition: &str) {
+        if !self.context.is_valid_opcode_location(&location) {
+            println!("Invalid opcode location {location}");
+            return;
         }
+        let condition: Condition = match condition.parse() {
+            Ok(condition) => condition,
+            Err(error) => {
+                println!("{error}");
+                return;
+            }
+        };
+        let added = self.context.add_breakpoint(location);
+        let Some(id) = self.context.get_breakpoint_at(&location).map(|breakpoint| breakpoint.id)
+        else {
+            return;
+        };
+        println!(
+            "{} breakpoint {id} at opcode {location}, stopping if {condition}",
+            if added { "Added" } else { "Updated" }
+        );
+        self.context.set_breakpoint_condition(id, Some(condition));
     }
 
-    fn step_into_opcode(&mut self) {
-        if self.validate_in_progress() {
-            let result = self.context.step_into_opcode();
-            self.handle_debug_command_result(result);
+    fn add_logpoint_at(&mut self, location: OpcodeLocation, message: String) {
+        if !self.context.is_valid_opcode_location(&location) {
+            println!("Invalid opcode location {location}");
+        } else if self.context.add_logpoint(location, message) {
+            let id =
+                self.context.get_breakpoint_at(&location).map_or(0, |breakpoint| breakpoint.id);
+            println!("Added logpoint {id} at opcode {location}");
+        } else {
+            println!("Breakpoint at opcode {location} already set");
         }
     }
 
-    fn next_into(&mut self) {
-        if self.validate_in_progress() {
-            let result = self.context.next_into();
-            self.handle_debug_command_result(result);
+    fn show_log_output(&mut self) {
+        let messages = self.context.take_log_output();
+        let warnings = self.context.take_debug_warnings();
+        if !messages.is_empty() || !warnings.is_empty() {
+            self.clear_progress_line();
+        }
+        for message in messages {
+            println!("{message}");
+        }
+        for warning in warnings {
+            println!("Warning: {warning}");
         }
     }
 
-    fn next_over(&mut self) {
-        if self.validate_in_progress() {
-            let result = self.context.next_over();
-            self.handle_debug_command_result(result);
+    /// Redraws the line showing how far `continue` got. In accessible mode
+    /// each update is printed on a line of its own, since screen readers do
+    /// not follow redrawn lines.
+    fn show_progress(&mut self, elapsed: Duration) {
+        let function = self
+            .context
+            .current_stack_frame()
+            .map(|frame| format!(" in {}", frame.function_name))
+            .unwrap_or_default();
+        let line = format!(
+            "[{:.0?}] {} opcodes executed, {} foreign calls{function}",
+            elapsed,
+            self.context.get_executed_opcodes(),
+            self.context.get_foreign_call_count(),
+        );
+        if self.accessible {
+            println!("{line}");
+            return;
         }
+        // Pad with spaces to erase the rest of a longer previous line
+        print!("\r{line:<width$}", width = self.progress_width);
+        let _ = std::io::stdout().flush();
+        self.progress_width = line.len();
     }
 
-    fn next_out(&mut self) {
-        if self.validate_in_progress() {
-            let result = self.context.next_out();
-            self.handle_debug_command_result(result);
+    fn clear_progress_line(&mut self) {
+        if self.progress_width > 0 {
+            print!("\r{:width$}\r", "", width = self.progress_width);
+            let _ = std::io::stdout().flush();
+            self.progress_width = 0;
         }
     }
 
-    fn cont(&mut self) {
-        if self.validate_in_progress() {
-            println!("(Continuing execution...)");
-            let result = self.context.cont();
-            self.handle_debug_command_result(result);
+    fn delete_breakpoint_at(&mut self, location: OpcodeLocation) {
+        if self.context.delete_breakpoint(&location) {
+            println!("Breakpoint at opcode {location} deleted");
+        } else {
+            println!("Breakpoint at opcode {location} not set");
         }
     }
 
-    fn restart_session(&mut self) {
-        let breakpoints: Vec<OpcodeLocation> =
-            self.context.iterate_breakpoints().copied().collect();
-        let foreign_call_executor =
-            Box::new(DefaultDebugForeignCallExecutor::from_artifact(true, self.debug_artifact));
-        self.context = DebugContext::new(
-            self.blackbox_solver,
-            self.circuit,
-            self.debug_artifact,
-            self.initial_witness.clone(),
-            foreign_call_executor,
-            self.unconstrained_functions,
-        );
-        for opcode_location in breakpoints {
-            self.context.add_breakpoint(opcode_location);
+    fn ignore_breakpoint(&mut self, id: usize, count: usize) {
+        if !self.context.set_breakpoint_ignore_count(id, count) {
+            println!("No breakpoint with id {id}");
+        } else if count == 0 {
+            println!("Will stop next time breakpoint {id} is reached");
+        } else {
+            println!("Will ignore next {count} crossings of breakpoint {id}");
         }
-        self.last_result = DebugCommandResult::Ok;
-        println!("Restarted debugging session.");
-        self.show_current_vm_status();
     }
 
-    pub fn show_witness_map(&self) {
-        let witness_map = self.context.get_witness_map();
-        // NOTE: we need to clone() here to get the iterator
-        for (witness, value) in witness_map.clone().into_iter() {
-            println!("_{} = {value}", witness.witness_index());
-        }
+    fn saved_breakpoints(&self) -> Vec<SavedBreakpoint> {
+        self.context
+            .get_breakpoints()
+            .map(|breakpoint| SavedBreakpoint {
+                location: breakpoint.location,
+                source: self.source_position_for(&breakpoint.location),
+                ignore_count: breakpoint.ignore_count,
+                log_message: breakpoint.log_message.clone(),
+                snapshot: breakpoint.snapshot_variables.clone(),
+                condition: breakpoint.condition.as_ref().map(|condition| condition.to_string()),
+            })
+            .collect()
     }
 
-    pub fn show_witness(&self, index: u32) {
-        if let Some(value) = self.context.get_witness_map().get_index(index) {
-            println!("_{} = {value}", index);
+    fn save_breakpoints(&self, file: &str) {
+        let breakpoints = self.saved_breakpoints();
+        let json =
+            serde_json::to_string_pretty(&breakpoints).expect("Could not serialize breakpoints");
+        match std::fs::write(file, json) {
+            Ok(()) => println!("Saved {} breakpoint(s) to {file}", breakpoints.len()),
+            Err(err) => println!("Could not write {file}: {err}"),
         }
     }
 
-    pub fn update_witness(&mut self, index: u32, value: String) {
-        let Some(field_value) = FieldElement::try_from_str(&value) else {
-            println!("Invalid witness value: {value}");
-            return;
+    fn load_breakpoints(&mut self, file: &str) {
+        let breakpoints: Vec<SavedBreakpoint> = match std::fs::read_to_string(file)
+            .map_err(|err| err.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|err| err.to_string()))
+        {
+            Ok(breakpoints) => breakpoints,
+            Err(err) => {
+                println!("Could not load breakpoints from {file}: {err}");
+                return;
+            }
         };
-
-        let witness = Witness::from(index);
-        _ = self.context.overwrite_witness(witness, field_value);
-        println!("_{} = {value}", index);
+        let loaded = self.restore_breakpoints(breakpoints);
+        println!("Loaded {loaded} breakpoint(s) from {file}");
     }
 
-    pub fn show_brillig_memory(&self) {
-        if !self.context.is_executing_brillig() {
-            println!("Not executing a Brillig block");
-            return;
+    /// Sets breakpoints saved by `saved_breakpoints`, returning how many were
+    /// set. Breakpoints are placed by their source position when it can still
+    /// be resolved, so that they survive recompiling the program, and by their
+    /// opcode location otherwise.
+    fn restore_breakpoints(&mut self, breakpoints: Vec<SavedBreakpoint>) -> usize {
+        let mut loaded = 0;
+        for saved in breakpoints {
+            let location = saved
+                .source
+                .as_ref()
+                .and_then(|source| self.opcode_for_source_position(source))
+                .or(Some(saved.location))
+                .filter(|location| self.context.is_valid_opcode_location(location));
+            let Some(location) = location else {
+                println!("Skipping breakpoint at opcode {}: no longer valid", saved.location);
+                continue;
+            };
+            match saved.log_message {
+                Some(message) => self.context.add_logpoint(location, message),
+                None => self.context.add_breakpoint(location),
+            };
+            if let Some(id) =
+                self.context.get_breakpoint_at(&location).map(|breakpoint| breakpoint.id)
+            {
+                self.context.set_breakpoint_ignore_count(id, saved.ignore_count);
+                self.context.set_breakpoint_snapshot(id, saved.snapshot);
+                match saved.condition.as_deref().map(str::parse::<Condition>) {
+                    Some(Ok(condition)) => {
+                        self.context.set_breakpoint_condition(id, Some(condition));
+                    }
+                    Some(Err(error)) => println!("Ignoring condition of breakpoint {id}: {error}"),
+                    None => (),
+                }
+            }
+            loaded += 1;
         }
+        loaded
+    }
 
-        let Some(memory) = self.context.get_brillig_memory() else {
-            // this can happen when just entering the Brillig block since ACVM
-            // would have not initialized the Brillig VM yet; in fact, the
-            // Brillig code may be skipped altogether
-            println!("Brillig VM memory not available");
-            return;
-        };
+    /// Finds the file of the program a path given by the user refers to,
+    /// which may be partial (eg. `main.nr`), explaining why if there isn't a
+    /// single one
+    fn find_source_file_id(&self, path: &str) -> Option<FileId> {
+        self.resolve_source_file(path).map_err(|error| println!("{error}")).ok()
+    }
 
-        for (index, value) in memory.iter().enumerate().filter(|(_, value)| value.bit_size() > 0) {
-            println!("{index} = {}", value);
+    fn resolve_source_file(&self, path: &str) -> Result<FileId, String> {
+        // Paths on disk under a substituted prefix are found by their
+        // original path in the artifact
+        let query = self.path_substitutions.reverse(Path::new(path));
+        let files = self
+            .debug_artifact
+            .file_map
+            .iter()
+            .map(|(file_id, file)| (*file_id, file.path.as_path()));
+        match find_source_file(files, &query.to_string_lossy()) {
+            FileMatch::Found(file_id) => Ok(file_id),
+            FileMatch::NotFound => Err(format!("No source file of the program matches {path}")),
+            FileMatch::Ambiguous(paths) => {
+                let mut error =
+                    format!("{path} matches several source files; give more of the path:");
+                for path in paths {
+                    error.push_str(&format!("\n  {}", path.display()));
+                }
+                Err(error)
+            }
         }
     }
 
-    pub fn write_brillig_memory(&mut self, index: usize, value: String, bit_size: u32) {
-        let Some(field_value) = FieldElement::try_from_str(&value) else {
-            println!("Invalid value: {value}");
+    /// Sets the breakpoints listed in a file, one `FILE:LINE [CONDITION]`
+    /// entry per line, reporting the outcome of each. Blank lines and lines
+    /// starting with `#` are skipped.
+    fn add_breakpoints_from_file(&mut self, file: &str) {
+        let contents = match std::fs::read_to_string(file) {
+            Ok(contents) => contents,
+            Err(err) => {
+                println!("Could not read {file}: {err}");
+                return;
+            }
+        };
+        let (mut set, mut failed) = (0, 0);
+        for (index, entry) in contents.lines().enumerate() {
+            let entry = entry.trim();
+            if entry.is_empty() || entry.starts_with('#') {
+                continue;
+            }
+            match self.add_breakpoint_from_entry(entry) {
+                Ok(report) => {
+                    set += 1;
+                    println!("{file}:{}: {report}", index + 1);
+                }
+                Err(error) => {
+                    failed += 1;
+                    println!("{file}:{}: {error}", index + 1);
+                }
+            }
+        }
+        println!("Set {set} breakpoint(s) from {file}, {failed} failed");
+    }
+
+    fn add_breakpoint_from_entry(&mut self, entry: &str) -> Result<String, String> {
+        let (position, condition) = match entry.split_once(char::is_whitespace) {
+            Some((position, condition)) => (position, Some(condition.trim())),
+            None => (entry, None),
+        };
+        let Some((path, line)) = parse_source_position(position) else {
+            return Err(format!("Invalid entry {entry}; expected FILE:LINE [CONDITION]"));
+        };
+        let condition = condition
+            .map(str::parse::<Condition>)
+            .transpose()
+            .map_err(|error| format!("Invalid condition: {error}"))?;
+        let file_id = self.resolve_source_file(path)?;
+        let Some(location) = self.context.find_opcode_for_source_location(&file_id, line as i64)
+        else {
+            return Err(format!("No opcode found at or after line {line} of {path}"));
+        };
+
+        let added = self.context.add_breakpoint(location);
+        let id = self.context.get_breakpoint_at(&location).map_or(0, |breakpoint| breakpoint.id);
+        let verb = if added { "Added" } else { "Updated" };
+        let mut report = format!("{verb} breakpoint {id} at opcode {location}");
+        // Lines without code place the breakpoint on the next one that has some
+        let actual_line = self.source_position_for(&location).map(|source| source.line);
+        if let Some(actual_line) = actual_line.filter(|actual_line| *actual_line != line) {
+            report.push_str(&format!(", moved from line {line} to {actual_line}"));
+        }
+        if let Some(condition) = condition {
+            report.push_str(&format!(", stopping if {condition}"));
+            self.context.set_breakpoint_condition(id, Some(condition));
+        }
+        Ok(report)
+    }
+
+    /// Shows the source lines around LINE of a file given as FILE[:LINE], or
+    /// around the current source location
+    fn list_source(&self, target: Option<&str>) {
+        let (file_id, line) = match target {
+            Some(target) => {
+                let (path, line) = match parse_source_position(target) {
+                    Some((path, line)) => (path, Some(line)),
+                    None => (target, None),
+                };
+                let Some(file_id) = self.find_source_file_id(path) else {
+                    return;
+                };
+                (file_id, line)
+            }
+            None => {
+                let location = self
+                    .context
+                    .get_current_source_location()
+                    .and_then(|locations| locations.last().copied());
+                let Some(location) = location else {
+                    println!("No current source location; expected list FILE[:LINE]");
+                    return;
+                };
+                (location.file, self.debug_artifact.location_line_number(location).ok())
+            }
+        };
+
+        let debug_file = &self.debug_artifact.file_map[&file_id];
+        let lines: Vec<_> = debug_file.source.lines().collect();
+        let center = line.unwrap_or(LIST_CONTEXT_LINES + 1);
+        let first = center.saturating_sub(LIST_CONTEXT_LINES).max(1);
+        let last = (center + LIST_CONTEXT_LINES).min(lines.len());
+        if first > last {
+            println!("{} has only {} lines", debug_file.path.display(), lines.len());
+            return;
+        }
+        println!("{}", debug_file.path.display());
+        for number in first..=last {
+            let marker = if Some(number) == line { ">" } else { " " };
+            println!("{marker}{number:>5}  {}", lines[number - 1]);
+        }
+    }
+
+    fn source_position_for(&self, location: &OpcodeLocation) -> Option<SavedSourcePosition> {
+        let source_location =
+            *self.context.get_source_location_for_opcode_location(location).last()?;
+        let debug_file = self.debug_artifact.file_map.get(&source_location.file)?;
+        let line = self.debug_artifact.location_line_number(source_location).ok()?;
+        Some(SavedSourcePosition { path: debug_file.path.clone(), line })
+    }
+
+    fn opcode_for_source_position(&self, source: &SavedSourcePosition) -> Option<OpcodeLocation> {
+        let (file_id, _) = self
+            .debug_artifact
+            .file_map
+            .iter()
+            .find(|(_, debug_file)| debug_file.path == source.path)?;
+        self.context.find_opcode_for_source_location(file_id, source.line as i64)
+    }
+
+    fn validate_in_progress(&self) -> bool {
+        match self.last_result {
+            DebugCommandResult::Ok | DebugCommandResult::BreakpointReached(..) => true,
+            DebugCommandResult::Done => {
+                println!("Execution finished");
+                false
+            }
+            DebugCommandResult::Error(ref error) => {
+                println!("ERROR: {}", error);
+                self.show_current_vm_status();
+                false
+            }
+        }
+    }
+
+    fn handle_debug_command_result(&mut self, result: DebugCommandResult) {
+        if self.accessible {
+            println!("{}", self.stop_summary(&result));
+        }
+        match &result {
+            DebugCommandResult::BreakpointReached(location) => {
+                match self.context.take_divergence() {
+                    Some(divergence) => self.show_divergence(&divergence),
+                    None if self.accessible => (),
+                    None if self.is_stopped_before_print() => {
+                        println!("Stopped before print in opcode {location} (see oracle-args)")
+                    }
+                    None => println!("Stopped at breakpoint in opcode {}", location),
+                }
+            }
+            DebugCommandResult::Error(error) => {
+                if !self.accessible {
+                    println!("ERROR: {}", error);
+                }
+                if let Some(divergence) = self.context.take_divergence() {
+                    self.show_divergence(&divergence);
+                }
+                if let Some(overflow) = self.context.get_integer_overflow(error) {
+                    self.show_integer_overflow(&overflow);
+                }
+                if let Some(message) = self.context.get_exceeded_brillig_limit() {
+                    self.show_exceeded_brillig_limit(message);
+                }
+            }
+            _ => (),
+        }
+        self.last_result = result;
+        self.show_current_vm_status();
+    }
+
+    /// Describes in one sentence why and where execution stopped, for
+    /// accessible mode
+    fn stop_summary(&self, result: &DebugCommandResult) -> String {
+        let Some(location) = self.context.get_current_opcode_location() else {
+            return match result {
+                DebugCommandResult::Error(error) => format!("Execution failed: {error}."),
+                _ => String::from("Execution finished."),
+            };
+        };
+        let source = self
+            .context
+            .get_source_location_for_opcode_location(&location)
+            .last()
+            .and_then(|source_location| {
+                let path = self.debug_artifact.file_map.get(&source_location.file)?.path.clone();
+                let line = self.debug_artifact.location_line_number(*source_location).ok()?;
+                let file_name = path.file_name().map_or(path.clone(), PathBuf::from);
+                Some(format!(", {} line {line}", file_name.display()))
+            })
+            .unwrap_or_default();
+        match result {
+            DebugCommandResult::BreakpointReached(_) => {
+                format!("Stopped at a breakpoint, opcode {location}{source}.")
+            }
+            DebugCommandResult::Error(error) => {
+                format!("Execution failed at opcode {location}{source}: {error}.")
+            }
+            _ => format!("Stopped at opcode {location}{source}."),
+        }
+    }
+
+    fn show_divergence(&self, divergence: &Divergence) {
+        let describe_call = |call: &TracedCall| {
+            let kind = if call.is_black_box { "black box function" } else { "oracle" };
+            format!("{kind} {}", call.name)
+        };
+        println!(
+            "Execution diverged from the previous run after {} steps, at opcode {}",
+            divergence.step, divergence.actual.location
+        );
+        match (&divergence.expected.call, &divergence.actual.call) {
+            (Some(expected), Some(actual)) if expected.name == actual.name => {
+                let what = if expected.inputs_hash != actual.inputs_hash {
+                    "was called with different inputs"
+                } else {
+                    "returned different results"
+                };
+                println!("The {} {what}", describe_call(actual));
+            }
+            (expected, actual) if expected.is_some() || actual.is_some() => {
+                let describe = |call: &Option<TracedCall>| {
+                    call.as_ref().map_or(String::from("no call"), describe_call)
+                };
+                println!(
+                    "The previous run made {} here, this one made {}",
+                    describe(expected),
+                    describe(actual)
+                );
+            }
+            _ => {
+                println!(
+                    "The previous run executed opcode {} instead",
+                    divergence.expected.location
+                );
+                if let Some(call) = &divergence.last_call {
+                    println!("The last call made before was to the {}", describe_call(call));
+                }
+            }
+        }
+    }
+
+    /// Shows which Brillig limit was exceeded along with the chain of calls
+    /// that led to it, innermost first
+    fn show_exceeded_brillig_limit(&self, message: &str) {
+        println!("{message}; raise it with `set brillig-max-memory` or `set brillig-max-depth`");
+        for (_, source_location) in self.context.get_source_call_stack().iter().rev() {
+            println!(
+                "  at {}",
+                format_location_path(
+                    self.debug_artifact,
+                    &self.path_substitutions,
+                    *source_location
+                )
+            );
+        }
+    }
+
+    fn show_integer_overflow(&self, overflow: &IntegerOverflow) {
+        let kind = overflow.bit_size.map_or(String::from("Integer overflow"), |bits| {
+            format!("{bits}-bit integer overflow")
+        });
+        let source = self
+            .context
+            .get_source_location_for_opcode_location(&overflow.location)
+            .last()
+            .map(|source_location| {
+                format!(
+                    " at {}",
+                    format_location_path(
+                        self.debug_artifact,
+                        &self.path_substitutions,
+                        *source_location
+                    )
+                )
+            })
+            .unwrap_or_default();
+        let value = overflow.value.map(|value| format!(" (value = {value})")).unwrap_or_default();
+        println!("{kind} in expression{source}{value}: {}", overflow.message);
+    }
+
+    /// Runs a stepping command up to `count` times, stopping early when a
+    /// breakpoint is reached, an error occurs, execution finishes or Ctrl-C
+    /// is pressed
+    fn repeat_step(
+        &mut self,
+        count: usize,
+        step: fn(&mut DebugContext<'a, B>) -> DebugCommandResult,
+    ) {
+        if !self.validate_in_progress() {
+            return;
+        }
+        self.take_snapshot();
+        interrupt::clear();
+        let mut result = DebugCommandResult::Ok;
+        for _ in 0..count {
+            result = step(&mut self.context);
+            self.show_log_output();
+            if interrupt::take() {
+                println!("Execution interrupted ({})", interrupt::reason());
+                break;
+            }
+            if !matches!(result, DebugCommandResult::Ok) {
+                break;
+            }
+        }
+        self.handle_debug_command_result(result);
+    }
+
+    fn step_acir_opcode(&mut self, count: usize) {
+        self.repeat_step(count, DebugContext::step_acir_opcode);
+    }
+
+    fn step_into_opcode(&mut self, count: usize) {
+        self.repeat_step(count, DebugContext::step_into_opcode);
+    }
+
+    fn next_into(&mut self, count: usize) {
+        self.repeat_step(count, DebugContext::next_into);
+    }
+
+    fn next_over(&mut self, count: usize) {
+        self.repeat_step(count, DebugContext::next_over);
+    }
+
+    fn next_out(&mut self, count: usize) {
+        self.repeat_step(count, DebugContext::next_out);
+    }
+
+    /// Continues execution, giving up after `timeout` if given or otherwise
+    /// after the session's default timeout
+    fn cont(&mut self, timeout: Option<Duration>) {
+        if self.validate_in_progress() {
+            println!("(Continuing execution...)");
+            self.take_snapshot();
+            let result = self.continue_execution(timeout.or(self.continue_timeout));
+            self.handle_debug_command_result(result);
+        }
+    }
+
+    /// Steps until execution stops, printing the output of the logpoints hit
+    /// along the way as soon as they are reached. Execution is also stopped
+    /// once `timeout` has elapsed, the session's opcode budget is spent or
+    /// Ctrl-C is pressed.
+    fn continue_execution(&mut self, timeout: Option<Duration>) -> DebugCommandResult {
+        let start = Instant::now();
+        let mut last_progress = start;
+        let mut steps = 0;
+        // Most recently executed opcodes, kept to diagnose a possible hang
+        let mut recent_locations = VecDeque::new();
+        // Ignore any Ctrl-C pressed before this execution started
+        interrupt::clear();
+        loop {
+            if let Some(location) = self.context.get_current_opcode_location() {
+                self.trace_opcode(location);
+                if timeout.is_some() {
+                    if recent_locations.len() == HANG_WINDOW {
+                        recent_locations.pop_front();
+                    }
+                    recent_locations.push_back(location);
+                }
+            }
+            let result = self.context.step_into_opcode();
+            self.show_log_output();
+            if !matches!(result, DebugCommandResult::Ok) {
+                self.clear_progress_line();
+                self.flush_trace();
+                return result;
+            }
+
+            steps += 1;
+            if let Some(interval) = self.progress_interval {
+                if steps % PROGRESS_CHECK_STEPS == 0 && last_progress.elapsed() >= interval {
+                    self.show_progress(start.elapsed());
+                    last_progress = Instant::now();
+                }
+            }
+            let interrupted = if interrupt::take() {
+                Some(interrupt::reason())
+            } else if self.continue_opcode_budget.map_or(false, |budget| steps >= budget) {
+                Some("opcode budget spent")
+            } else if timeout.map_or(false, |timeout| start.elapsed() >= timeout) {
+                Some("timed out")
+            } else {
+                None
+            };
+            if let Some(reason) = interrupted {
+                self.clear_progress_line();
+                self.flush_trace();
+                println!(
+                    "Execution interrupted after {steps} opcodes and {:.2?} ({reason})",
+                    start.elapsed()
+                );
+                if reason == "timed out" {
+                    self.show_possible_hang(&recent_locations);
+                }
+                return result;
+            }
+        }
+    }
+
+    /// Shows the source lines that were executed the most among the given
+    /// opcodes, which point to where the program may be looping forever
+    fn show_possible_hang(&self, recent_locations: &VecDeque<OpcodeLocation>) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for location in recent_locations {
+            let source_locations = self.context.get_source_location_for_opcode_location(location);
+            if let Some(source_location) = source_locations.last() {
+                let line = format_location_path(
+                    self.debug_artifact,
+                    &self.path_substitutions,
+                    *source_location,
+                );
+                *counts.entry(line).or_default() += 1;
+            }
+        }
+        if counts.is_empty() {
+            return;
+        }
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by(|(a_line, a), (b_line, b)| b.cmp(a).then_with(|| a_line.cmp(b_line)));
+
+        println!(
+            "Possible hang; hottest source lines in the last {} opcodes:",
+            recent_locations.len()
+        );
+        for (line, count) in counts.into_iter().take(HANG_LINES_SHOWN) {
+            println!("{count:>8}  {line}");
+        }
+    }
+
+    /// Appends an executed opcode location to the trace file, along with its
+    /// source position when known
+    fn trace_opcode(&mut self, location: OpcodeLocation) {
+        let Some(trace) = self.trace.as_mut() else {
+            return;
+        };
+        let source = self.context.get_source_location_for_opcode_location(&location).last().map(
+            |source_location| {
+                format_location_path(
+                    self.debug_artifact,
+                    &self.path_substitutions,
+                    *source_location,
+                )
+            },
+        );
+        let written = match source {
+            Some(source) => writeln!(trace, "{location}\t{source}"),
+            None => writeln!(trace, "{location}"),
+        };
+        if let Err(err) = written {
+            println!("Could not write to trace file: {err}; tracing disabled");
+            self.trace = None;
+        }
+    }
+
+    fn flush_trace(&mut self) {
+        if let Some(Err(err)) = self.trace.as_mut().map(|trace| trace.flush()) {
+            println!("Could not write to trace file: {err}; tracing disabled");
+            self.trace = None;
+        }
+    }
+
+    fn set_trace(&mut self, path: &str) {
+        self.flush_trace();
+        if path == "off" {
+            self.trace = None;
+            println!("Tracing is off");
+            return;
+        }
+        match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(file) => {
+                self.trace = Some(BufWriter::new(file));
+                println!("Tracing executed opcodes to {path}");
+            }
+            Err(err) => println!("Could not open trace file {path}: {err}"),
+        }
+    }
+
+    /// Records the state at the current stop so that the next stop can be
+    /// compared against it
+    fn take_snapshot(&mut self) {
+        self.witness_snapshot = self.context.get_witness_map().clone();
+        self.memory_snapshot = match self.context.get_current_opcode_location() {
+            Some(OpcodeLocation::Brillig { acir_index, .. }) => {
+                self.context.get_brillig_memory().map(|memory| (acir_index, memory.to_vec()))
+            }
+            _ => None,
+        };
+        self.variables_snapshot = self.current_variables().into_iter().collect();
+    }
+
+    /// Continues execution until it fails, finishes or reaches a breakpoint.
+    /// On failure, the opcodes executed right before it are shown along with
+    /// the witnesses they wrote.
+    fn run_to_failure(&mut self) {
+        if !self.validate_in_progress() {
+            return;
+        }
+        println!("(Running until failure...)");
+        self.context.enable_journal(RUN_TO_FAILURE_JOURNAL_SIZE);
+        self.take_snapshot();
+        let result = self.continue_execution(self.continue_timeout);
+        let failed = matches!(result, DebugCommandResult::Error(_));
+        self.handle_debug_command_result(result);
+        if failed {
+            self.show_journal();
+        }
+    }
+
+    fn show_journal(&self) {
+        println!("Recent activity (last {RUN_TO_FAILURE_JOURNAL_SIZE} opcodes):");
+        for entry in self.context.get_journal() {
+            let opcode = self.context.render_opcode_at_location(&entry.location);
+            println!("{:>12} {opcode}", entry.location.to_string());
+            for (witness, value) in &entry.written {
+                println!("{:>12}   _{} = {value}", "", witness.witness_index());
+            }
+        }
+    }
+
+    fn restart_context(&mut self) -> RestartTimings {
+        let timings = self.context.restart(self.initial_witness.clone());
+        self.witness_snapshot = self.initial_witness.clone();
+        self.memory_snapshot = None;
+        self.variables_snapshot.clear();
+        self.last_result = if self.context.get_current_opcode_location().is_none() {
+            DebugCommandResult::Done
+        } else {
+            DebugCommandResult::Ok
+        };
+        timings
+    }
+
+    /// Lists the foreign calls the program made, which `rewind-to-call` can
+    /// move execution back to
+    fn show_foreign_call_checkpoints(&self) {
+        let checkpoints = self.context.get_foreign_call_checkpoints();
+        if checkpoints.is_empty() {
+            println!("No foreign calls made yet");
+        }
+        for (index, checkpoint) in checkpoints.iter().enumerate() {
+            println!(
+                "#{}: {} (after {} opcode(s))",
+                index + 1,
+                checkpoint.function,
+                checkpoint.executed_opcodes
+            );
+        }
+    }
+
+    /// Moves execution back to right before the `call`th foreign call of the
+    /// program, replaying it from the start
+    fn rewind_to_foreign_call(&mut self, call: usize) {
+        self.take_snapshot();
+        interrupt::clear();
+        match self.context.rewind_to_foreign_call(self.initial_witness.clone(), call) {
+            Ok(_) if interrupt::take() => {
+                println!(
+                    "Rewinding was interrupted before foreign call #{call} ({})",
+                    interrupt::reason()
+                );
+                self.show_current_vm_status();
+            }
+            Ok(result) => {
+                println!("Rewound to foreign call #{call}");
+                self.handle_debug_command_result(result);
+            }
+            Err(message) => println!("{message}"),
+        }
+    }
+
+    fn restart_session(&mut self) {
+        let timings = self.restart_context();
+        println!("Restarted debugging session.");
+        if self.context.is_checking_determinism() {
+            println!("Execution stops where it diverges from the previous run");
+        }
+        if timings.total() > RESTART_REPORT_THRESHOLD {
+            println!(
+                "Restart took {:.2?} (VM: {:.2?}, foreign call executor: {:.2?})",
+                timings.total(),
+                timings.vm,
+                timings.foreign_call_executor
+            );
+        }
+        self.show_current_vm_status();
+    }
+
+    /// Fingerprint of the program being debugged, to tell whether a saved
+    /// session belongs to it. A BLAKE3 digest is used as, unlike the standard
+    /// library's hasher, it is stable across Rust versions.
+    fn program_hash(&self) -> String {
+        let mut program =
+            serde_json::to_vec(self.context.get_opcodes()).expect("Could not serialize opcodes");
+        program.extend(
+            serde_json::to_vec(self.unconstrained_functions)
+                .expect("Could not serialize Brillig functions"),
+        );
+        let digest = acvm::blackbox_solver::blake3(&program).expect("Could not hash the program");
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    fn save_session(&self, file: &str) {
+        let session = SavedSession {
+            program_hash: self.program_hash(),
+            initial_witness: self
+                .initial_witness
+                .clone()
+                .into_iter()
+                .map(|(witness, value)| (witness.witness_index(), value))
+                .collect(),
+            location: self.context.get_current_opcode_location(),
+            brillig_steps: self.context.get_brillig_steps(),
+            breakpoints: self.saved_breakpoints(),
+            verbosity: self.verbosity,
+            print_pretty: self.print_pretty,
+        };
+        let json = serde_json::to_string_pretty(&session).expect("Could not serialize session");
+        match std::fs::write(file, json) {
+            Ok(()) => println!("Saved session to {file}"),
+            Err(err) => println!("Could not write {file}: {err}"),
+        }
+    }
+
+    /// Restores a session written by `save_session` by running the program
+    /// again from the start with the saved inputs up to the saved point
+    fn load_session(&mut self, file: &str) {
+        let session: SavedSession = match std::fs::read_to_string(file)
+            .map_err(|err| err.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|err| err.to_string()))
+        {
+            Ok(session) => session,
+            Err(err) => {
+                println!("Could not load session from {file}: {err}");
+                return;
+            }
+        };
+        if session.program_hash != self.program_hash() {
+            println!("Session in {file} was saved for a different program");
+            return;
+        }
+
+        self.initial_witness = session
+            .initial_witness
+            .into_iter()
+            .map(|(index, value)| (Witness(index), value))
+            .collect::<BTreeMap<_, _>>()
+            .into();
+        self.context.clear_breakpoints();
+        self.restart_context();
+        interrupt::clear();
+        let result = self.context.replay_to(session.location, session.brillig_steps);
+        let interrupted = interrupt::take();
+        // Logpoints from the original session are not set yet, but drop any
+        // output rendered during the replay anyway
+        self.context.take_log_output();
+        let reached = self.context.get_current_opcode_location() == session.location
+            && self.context.get_brillig_steps() == session.brillig_steps;
+        if let DebugCommandResult::Error(_) | DebugCommandResult::Done = result {
+            self.last_result = result;
+        }
+
+        let restored = self.restore_breakpoints(session.breakpoints);
+        self.verbosity = session.verbosity;
+        self.print_pretty = session.print_pretty;
+        self.take_snapshot();
+        if reached {
+            println!("Loaded session from {file} ({restored} breakpoint(s))");
+        } else if interrupted {
+            println!(
+                "Loaded session from {file}, but the replay was interrupted before the saved point ({})",
+                interrupt::reason()
+            );
+        } else {
+            println!(
+                "Loaded session from {file}, but replaying did not reach the saved point; the program's foreign calls may not be deterministic"
+            );
+        }
+        self.show_current_vm_status();
+    }
+
+    pub fn show_witness_map(&self) {
+        let witness_map = self.context.get_witness_map();
+        // NOTE: we need to clone() here to get the iterator
+        for (witness, value) in witness_map.clone().into_iter() {
+            println!("_{} = {value}", witness.witness_index());
+        }
+    }
+
+    /// Shows the witnesses that were assigned or modified since the previous stop
+    pub fn show_changed_witnesses(&self) {
+        let witness_map = self.context.get_witness_map();
+        let mut any_changed = false;
+        for (witness, value) in witness_map.clone().into_iter() {
+            if self.witness_snapshot.get(&witness) != Some(&value) {
+                println!("_{} = {value}", witness.witness_index());
+                any_changed = true;
+            }
+        }
+        if !any_changed {
+            println!("No witnesses changed since the previous stop");
+        }
+    }
+
+    /// Shows the witness map of the main circuit in the layout of a witness
+    /// stack. The debugger doesn't execute calls to other ACIR functions, so
+    /// the stack never holds the witness maps of callees.
+    pub fn show_main_witness_stack(&self) {
+        let mut witness_stack = self.context.get_witness_stack();
+        while let Some(item) = witness_stack.pop() {
+            println!("Circuit {}:", item.index);
+            for (witness, value) in item.witness.into_iter() {
+                println!("  _{} = {value}", witness.witness_index());
+            }
+        }
+    }
+
+    pub fn show_witness(&self, index: u32) {
+        if let Some(value) = self.context.get_witness_map().get_index(index) {
+            println!("_{} = {value}", index);
+        }
+    }
+
+    pub fn update_witness(&mut self, index: u32, value: String) {
+        let Some(field_value) = FieldElement::try_from_str(&value) else {
+            println!("Invalid witness value: {value}");
+            return;
+        };
+
+        let witness = Witness::from(index);
+        _ = self.context.overwrite_witness(witness, field_value);
+        println!("_{} = {value}", index);
+    }
+
+    pub fn show_witness_dependencies(&self, witness: &str, max_depth: usize) {
+        let Ok(index) = witness.trim_start_matches('_').parse::<u32>() else {
+            println!("Invalid witness {witness}; expected eg. _42");
+            return;
+        };
+        let dependency = self.context.get_witness_dependencies(Witness(index), max_depth);
+        self.print_witness_dependency(&dependency, 0);
+    }
+
+    fn print_witness_dependency(&self, dependency: &WitnessDependency, depth: usize) {
+        let indent = "  ".repeat(depth);
+        let witness_index = dependency.witness.witness_index();
+        let Some(acir_index) = dependency.acir_index else {
+            println!("{indent}_{witness_index} (input)");
+            return;
+        };
+
+        let location = OpcodeLocation::Acir(acir_index);
+        let source = self
+            .context
+            .get_source_location_for_opcode_location(&location)
+            .last()
+            .map(|source_location| {
+                format!(
+                    " at {}",
+                    format_location_path(
+                        self.debug_artifact,
+                        &self.path_substitutions,
+                        *source_location
+                    )
+                )
+            })
+            .unwrap_or_default();
+        let opcode = &self.context.get_opcodes()[acir_index];
+        println!("{indent}_{witness_index} <- opcode {acir_index}: {opcode}{source}");
+
+        for child in &dependency.dependencies {
+            self.print_witness_dependency(child, depth + 1);
+        }
+    }
+
+    /// Shows the Brillig memory cells, or only those written since the
+    /// previous stop if `changed_only` is set
+    fn show_brillig_memory(&self, changed_only: bool, as_type: Option<CellType>) {
+        if !self.context.is_executing_brillig() {
+            println!("Not executing a Brillig block");
+            return;
+        }
+
+        let Some(memory) = self.context.get_brillig_memory() else {
+            // this can happen when just entering the Brillig block since ACVM
+            // would have not initialized the Brillig VM yet; in fact, the
+            // Brillig code may be skipped altogether
+            println!("Brillig VM memory not available");
+            return;
+        };
+
+        // Cells from a snapshot taken in a different Brillig block are not comparable
+        let previous_memory =
+            match (&self.memory_snapshot, self.context.get_current_opcode_location()) {
+                (
+                    Some((snapshot_index, memory)),
+                    Some(OpcodeLocation::Brillig { acir_index, .. }),
+                ) if *snapshot_index == acir_index => memory.as_slice(),
+                _ => &[],
+            };
+
+        let cells: Vec<_> = memory
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.bit_size() > 0)
+            .filter(|(index, value)| !changed_only || previous_memory.get(*index) != Some(value))
+            .collect();
+        if changed_only && cells.is_empty() {
+            println!("No memory cells changed since the previous stop");
+        }
+        for line in format_memory_cells(&cells, as_type, self.memory_width) {
+            println!("{line}");
+        }
+    }
+
+    /// Saves the current Brillig memory under `name`, for `memory-diff` to
+    /// compare against later in the same Brillig block
+    fn save_memory_checkpoint(&mut self, name: String) {
+        let (Some(OpcodeLocation::Brillig { acir_index, .. }), Some(memory)) =
+            (self.context.get_current_opcode_location(), self.context.get_brillig_memory())
+        else {
+            println!("Brillig VM memory not available");
+            return;
+        };
+        println!("Saved Brillig memory as {name}");
+        self.memory_checkpoints.insert(name, (acir_index, memory.to_vec()));
+    }
+
+    /// Shows the Brillig memory cells that changed since the previous stop, or
+    /// since the checkpoint with the given name, with their old and new values
+    /// and the variable they hold, if any
+    fn show_memory_diff(&self, checkpoint: Option<&str>) {
+        let (Some(OpcodeLocation::Brillig { acir_index, .. }), Some(memory)) =
+            (self.context.get_current_opcode_location(), self.context.get_brillig_memory())
+        else {
+            println!("Brillig VM memory not available");
+            return;
+        };
+        let baseline = match checkpoint {
+            Some(name) => match self.memory_checkpoints.get(name) {
+                Some(checkpoint) => checkpoint,
+                None => {
+                    println!("No memory checkpoint named {name}");
+                    return;
+                }
+            },
+            None => match &self.memory_snapshot {
+                Some(snapshot) => snapshot,
+                None => {
+                    println!("No Brillig memory snapshot from the previous stop");
+                    return;
+                }
+            },
+        };
+        // Cells from a different Brillig block are not comparable
+        let (baseline_index, previous_memory) = baseline;
+        if *baseline_index != acir_index {
+            println!("The memory to compare against belongs to a different Brillig block");
+            return;
+        }
+
+        let render = |value: Option<&MemoryValue<FieldElement>>| match value {
+            Some(value) if value.bit_size() > 0 => {
+                let cell_type = CellType::of(value);
+                format!("{}: {cell_type}", format_cell_value(value, cell_type))
+            }
+            _ => String::from("-"),
+        };
+        let mut changed = 0;
+        for address in 0..memory.len().max(previous_memory.len()) {
+            let (old, new) = (previous_memory.get(address), memory.get(address));
+            if old == new {
+                continue;
+            }
+            changed += 1;
+            let owner = self
+                .context
+                .variable_at_brillig_address(address)
+                .map(|name| format!(" ({name})"))
+                .unwrap_or_default();
+            println!("{address}: {} -> {}{owner}", render(old), render(new));
+        }
+        if changed == 0 {
+            println!("No memory cells changed");
+        }
+    }
+
+    /// Writes a Brillig memory cell. Writes likely to corrupt execution, such
+    /// as ones changing the type of the cell, are refused unless `force` is set.
+    pub fn write_brillig_memory(
+        &mut self,
+        index: usize,
+        value: String,
+        bit_size: u32,
+        force: bool,
+    ) {
+        let Some(field_value) = FieldElement::try_from_str(&value) else {
+            println!("Invalid value: {value}");
+            return;
+        };
+        let warnings = match self.context.check_brillig_memory_write(index, field_value, bit_size) {
+            Ok(warnings) => warnings,
+            Err(error) => {
+                println!("{error}");
+                return;
+            }
+        };
+        for warning in &warnings {
+            println!("Warning: {warning}");
+        }
+        if !warnings.is_empty() && !force {
+            println!(
+                "Memory not written; use memset {index} {value} {bit_size} --force to write anyway"
+            );
+            return;
+        }
+        self.context.write_brillig_memory(index, field_value, bit_size);
+    }
+
+    /// Prints the variables of every stack frame, or of a single frame if
+    /// `frame_index` is given (0 being the innermost frame). If a `filter` is
+    /// given, only variables whose names match it are printed.
+    pub fn show_vars(&self, filter: Option<&str>, frame_index: Option<usize>) {
+        let frames = self.context.get_variables();
+        let selected_frames: Vec<_> = match frame_index {
+            Some(index) if index < frames.len() => {
+                frames.into_iter().rev().skip(index).take(1).collect()
+            }
+            Some(index) => {
+                println!("Invalid frame index {index}; there are {} frames", frames.len());
+                return;
+            }
+            None => frames,
+        };
+
+        for frame in selected_frames {
+            println!("{}({})", frame.function_name, frame.function_params.join(", "));
+            self.show_frame_variables(&frame, filter);
+        }
+    }
+
+    fn show_frame_variables(&self, frame: &StackFrame<FieldElement>, filter: Option<&str>) {
+        for (var_name, value, var_type) in frame.variables.iter() {
+            if !filter.map_or(true, |filter| matches_var_filter(var_name, filter)) {
+                continue;
+            }
+            let printable_value =
+                PrintableValueDisplay::Plain((*value).clone(), (*var_type).clone());
+            if self.print_pretty {
+                println!("  {var_name}:{var_type:?} = {:#}", printable_value);
+            } else {
+                println!("  {var_name}:{var_type:?} = {}", printable_value);
+            }
+        }
+    }
+
+    /// Lists the asserts of the program with their ids, followed by a summary
+    /// of the ones evaluated so far
+    pub fn show_asserts(&self) {
+        let asserts = self.context.get_asserts();
+        if asserts.is_empty() {
+            println!("No asserts found in the debug information");
+            return;
+        }
+
+        let (mut passed, mut failed) = (0, 0);
+        for assert in asserts {
+            let location = format_location_path(
+                self.debug_artifact,
+                &self.path_substitutions,
+                assert.location,
+            );
+            let outcome = match self.context.get_assert_outcome(assert.id) {
+                Some(outcome) if outcome.failed => {
+                    failed += 1;
+                    format!("failed after {} evaluation(s)", outcome.evaluations)
+                }
+                Some(outcome) => {
+                    passed += 1;
+                    format!("passed {} time(s)", outcome.evaluations)
+                }
+                None => String::from("not evaluated"),
+            };
+            let opcode = match assert.first_opcode {
+                Some(first_opcode) => format!("opcode {first_opcode}"),
+                None => String::from("no opcodes"),
+            };
+            println!("assert#{} at {location} ({opcode}): {outcome}", assert.id);
+        }
+        println!(
+            "{} asserts: {passed} passed, {failed} failed, {} not evaluated",
+            asserts.len(),
+            asserts.len() - passed - failed
+        );
+    }
+
+    /// Lists how many times each function of the program was called and how
+    /// many opcodes were spent in it, the most expensive first
+    pub fn show_function_calls(&self) {
+        let mut function_calls: Vec<_> = self.context.get_function_calls().into_iter().collect();
+        if function_calls.is_empty() {
+            println!("No function calls tracked yet; calls are only tracked with instrumentation");
+            return;
+        }
+        function_calls.sort_by(|(_, a), (_, b)| b.opcodes.cmp(&a.opcodes));
+
+        print_function_calls(&function_calls);
+    }
+
+    /// Shows the signature of the oracle `name` declared in the oracle
+    /// schema, or of all the declared oracles
+    pub fn describe_oracles(&self, name: Option<&str>) {
+        let schema = self.context.get_oracle_schema();
+        if schema.is_empty() {
+            println!(
+                "No oracles declared; describe them in {ORACLE_SCHEMA_FILE} at the package root"
+            );
+            return;
+        }
+        let oracles: Vec<_> = match name {
+            Some(name) => match schema.get(name) {
+                Some(signature) => vec![(name.to_string(), signature)],
+                None => {
+                    println!("Oracle {name} is not declared in {ORACLE_SCHEMA_FILE}");
+                    return;
+                }
+            },
+            None => schema.iter().map(|(name, signature)| (name.clone(), signature)).collect(),
+        };
+        for (name, signature) in oracles {
+            println!("{}", signature.format(&name));
+            if let Some(description) = &signature.description {
+                println!("    {description}");
+            }
+        }
+    }
+
+    fn is_stopped_before_print(&self) -> bool {
+        self.context.is_break_on_print()
+            && self
+                .context
+                .get_pending_foreign_call()
+                .map_or(false, |foreign_call| foreign_call.function == PRINT_ORACLE)
+    }
+
+    /// Shows the foreign call the current Brillig opcode is about to make,
+    /// with the message it prints for `print` calls
+    fn show_oracle_args(&self) {
+        let Some(foreign_call) = self.context.get_pending_foreign_call() else {
+            println!("Not stopped at a foreign call");
+            return;
+        };
+        if foreign_call.function == PRINT_ORACLE {
+            match format_print_message(&foreign_call.inputs) {
+                Some(message) => println!("print: {}", message.trim_end()),
+                None => println!("print: <message could not be decoded>"),
+            }
+            return;
+        }
+        match self.context.format_oracle_call(&foreign_call) {
+            Some(call) => println!("{call}"),
+            None => {
+                let arguments: Vec<_> =
+                    foreign_call.inputs.iter().map(|input| format!("{input:?}")).collect();
+                println!("{}({})", foreign_call.function, arguments.join(", "));
+            }
+        }
+    }
+
+    fn set_break_on(&mut self, event: &str, value: &str) {
+        let Some(enabled) = parse_on_off(value) else {
+            println!("Invalid value {value}; expected on or off");
+            return;
+        };
+        match event {
+            "print" => {
+                self.context.set_break_on_print(enabled);
+                println!("Breaking on print is {value}");
+            }
+            _ => println!("Unknown event {event}; expected print"),
+        }
+    }
+
+    /// Re-runs the program from the start, leaving the session as it is, and
+    /// shows where its opcodes were spent. The run stops after `max_opcodes`.
+    pub fn profile(&mut self, max_opcodes: usize) {
+        println!("(Profiling a run of the program from the start...)");
+        let mut foreign_call_executor = Box::new(DefaultDebugForeignCallExecutor::with_resolver(
+            PrintOutput::None,
+            self.resolver_config.clone(),
+        ));
+        foreign_call_executor.load_artifact(self.debug_artifact);
+        interrupt::clear();
+        let profile =
+            self.context.profile(self.initial_witness.clone(), foreign_call_executor, max_opcodes);
+        interrupt::clear();
+        match &profile.result {
+            DebugCommandResult::Error(error) => {
+                println!("Profiled run failed after {} opcodes: {error}", profile.executed_opcodes)
+            }
+            DebugCommandResult::Ok if profile.executed_opcodes >= max_opcodes => println!(
+                "Profiled run stopped after {max_opcodes} opcodes; use `profile <max-opcodes>` to run it further"
+            ),
+            DebugCommandResult::Ok => println!(
+                "Profiled run interrupted after {} opcodes ({})",
+                profile.executed_opcodes,
+                interrupt::reason()
+            ),
+            _ => println!("Profiled run finished after {} opcodes", profile.executed_opcodes),
+        }
+
+        let mut function_calls: Vec<_> = profile.function_calls.into_iter().collect();
+        function_calls.sort_by(|(_, a), (_, b)| b.opcodes.cmp(&a.opcodes));
+        if !function_calls.is_empty() {
+            print_function_calls(&function_calls);
+        }
+        let locations: Vec<_> = profile
+            .locations
+            .iter()
+            .map(|(location, opcodes)| ProfiledLocation {
+                location: format_location_path(
+                    self.debug_artifact,
+                    &self.path_substitutions,
+                    *location,
+                ),
+                opcodes: *opcodes,
+            })
+            .collect();
+        if !locations.is_empty() {
+            println!("{:>10}  source location", "opcodes");
+            for location in locations.iter().take(PROFILE_LOCATIONS_SHOWN) {
+                println!("{:>10}  {}", location.opcodes, location.location);
+            }
+            if locations.len() > PROFILE_LOCATIONS_SHOWN {
+                println!(
+                    "(and {} more in the session report)",
+                    locations.len() - PROFILE_LOCATIONS_SHOWN
+                );
+            }
+        }
+
+        self.profile = Some(ProfileReport {
+            solved: matches!(profile.result, DebugCommandResult::Done),
+            executed_opcodes: profile.executed_opcodes,
+            functions: function_calls
+                .into_iter()
+                .map(|(name, calls)| ProfiledFunction {
+                    name,
+                    calls: calls.calls,
+                    opcodes: calls.opcodes,
+                })
+                .collect(),
+            locations,
+        });
+    }
+
+    /// Lists the black box function calls executed so far, followed by a
+    /// summary per function. Calls repeating the inputs of an earlier call to
+    /// the same function are counted as duplicates.
+    pub fn show_blackbox_calls(&self) {
+        let calls = self.context.get_blackbox_calls();
+        if calls.is_empty() {
+            println!("No black box functions called yet");
+            return;
+        }
+
+        let format_hash =
+            |hash: Option<u64>| hash.map_or_else(|| "-".to_string(), |hash| format!("{hash:016x}"));
+        let mut summary: BTreeMap<&str, (usize, Duration, usize)> = BTreeMap::new();
+        let mut seen_inputs = HashSet::new();
+        for call in calls {
+            println!(
+                "{}: {} ({:.2?}) inputs {} outputs {}",
+                call.location,
+                call.name,
+                call.duration,
+                format_hash(call.inputs_hash),
+                format_hash(call.outputs_hash)
+            );
+            let (count, total_time, duplicates) = summary.entry(call.name).or_default();
+            *count += 1;
+            *total_time += call.duration;
+            if let Some(inputs_hash) = call.inputs_hash {
+                if !seen_inputs.insert((call.name, inputs_hash)) {
+                    *duplicates += 1;
+                }
+            }
+        }
+
+        println!();
+        for (name, (count, total_time, duplicates)) in summary {
+            print!("{name}: {count} call(s), {total_time:.2?} total");
+            if duplicates > 0 {
+                print!(", {duplicates} with repeated inputs");
+            }
+            println!();
+        }
+    }
+
+    pub fn show_breakpoints(&self) {
+        let mut breakpoints = self.context.get_breakpoints().peekable();
+        if breakpoints.peek().is_none() {
+            println!("No breakpoints set");
+            return;
+        }
+        for breakpoint in breakpoints {
+            let kind = if breakpoint.log_message.is_some() { "Logpoint" } else { "Breakpoint" };
+            let source = self
+                .context
+                .get_source_location_for_opcode_location(&breakpoint.location)
+                .last()
+                .map(|source_location| {
+                    format!(
+                        " at {}",
+                        format_location_path(
+                            self.debug_artifact,
+                            &self.path_substitutions,
+                            *source_location
+                        )
+                    )
+                })
+                .unwrap_or_default();
+            print!(
+                "{kind} {}: opcode {}{source}, hit {} time(s)",
+                breakpoint.id, breakpoint.location, breakpoint.hit_count
+            );
+            if breakpoint.ignore_count > 0 {
+                print!(", ignoring the next {}", breakpoint.ignore_count);
+            }
+            if let Some(message) = &breakpoint.log_message {
+                print!(", logging \"{message}\"");
+            }
+            if let Some(condition) = &breakpoint.condition {
+                print!(", if {condition}");
+            }
+            if let Some(hit_condition) = &breakpoint.hit_condition {
+                print!(", if hit {hit_condition}");
+            }
+            println!();
+        }
+    }
+
+    pub fn show_frame_info(&self) {
+        let Some(location) = self.context.get_current_opcode_location() else {
+            println!("Finished execution");
+            return;
+        };
+        let frames = self.context.get_variables();
+        match frames.last() {
+            Some(frame) => {
+                println!(
+                    "Frame #{} in {}({}), {} variable(s) in scope",
+                    frames.len() - 1,
+                    frame.function_name,
+                    frame.function_params.join(", "),
+                    frame.variables.len()
+                );
+            }
+            None => println!("No instrumented function is executing"),
+        }
+        println!("At opcode {}", self.format_opcode(&location));
+        if let OpcodeLocation::Brillig { acir_index, .. } = location {
+            println!(
+                "In the Brillig call of ACIR opcode {acir_index}, call depth {}",
+                self.context.get_call_stack().len()
+            );
+        }
+    }
+
+    pub fn show_circuit_info(&self) {
+        let circuit = self.context.get_circuit();
+        let expression_width = match circuit.expression_width {
+            ExpressionWidth::Unbounded => "unbounded".to_string(),
+            ExpressionWidth::Bounded { width } => width.to_string(),
+        };
+        println!("Circuit 0 (the entry point), {} ACIR opcodes", circuit.opcodes.len());
+        println!(
+            "Witnesses: {} ({} private parameters, {} public parameters, {} return values)",
+            circuit.current_witness_index + 1,
+            circuit.private_parameters.len(),
+            circuit.public_parameters.0.len(),
+            circuit.return_values.0.len()
+        );
+        println!("Expression width: {expression_width}");
+        let functions = self.context.get_unconstrained_functions();
+        println!(
+            "Brillig functions: {} with {} opcodes in total",
+            functions.len(),
+            functions.iter().map(|function| function.bytecode.len()).sum::<usize>()
+        );
+    }
+
+    pub fn show_program_info(&self, environment: &DebugEnvironment, program: &ProgramInfo) {
+        println!(
+            "Circuits: {} (debugging circuit 0); Brillig functions: {}",
+            program.circuits,
+            self.context.get_unconstrained_functions().len()
+        );
+        if program.abi_inputs.is_empty() {
+            println!("Inputs: none");
+        } else {
+            println!("Inputs: {}", program.abi_inputs.join(", "));
+        }
+        let mode = if environment.force_brillig { "Brillig" } else { "ACIR" };
+        println!("Execution mode: {mode}, black box solver: {}", environment.blackbox_solver);
+        println!("ACVM status: {}", self.context.describe_status());
+    }
+
+    pub fn show_breakpoint_snapshots(&self) {
+        let snapshots = self.context.get_breakpoint_snapshots();
+        if snapshots.is_empty() {
+            println!("No breakpoint snapshots taken yet");
+            return;
+        }
+        for snapshot in snapshots {
+            let values: Vec<String> =
+                snapshot.values.iter().map(|(name, value)| format!("{name} = {value}")).collect();
+            println!(
+                "Run {}, breakpoint {} hit #{} at opcode {}: {}",
+                snapshot.run,
+                snapshot.breakpoint_id,
+                snapshot.hit,
+                snapshot.location,
+                values.join(", ")
+            );
+        }
+    }
+
+    fn session_log(&self, program_info: &ProgramInfo) -> SessionLog {
+        let (error, error_call_stack) = match &self.last_result {
+            DebugCommandResult::Error(error) => {
+                let call_stack = self
+                    .context
+                    .get_source_call_stack()
+                    .iter()
+                    .map(|(_, source_location)| {
+                        format_location_path(
+                            self.debug_artifact,
+                            &self.path_substitutions,
+                            *source_location,
+                        )
+                    })
+                    .collect();
+                (Some(error.to_string()), call_stack)
+            }
+            _ => (None, vec![]),
+        };
+        let solved = self.is_solved();
+        let (unsolved_outputs, unread_inputs) = if solved {
+            (vec![], vec![])
+        } else {
+            (self.unsolved_outputs(), self.unread_inputs(program_info))
+        };
+        let summary = SessionSummary {
+            solved,
+            executed_opcodes: self.context.get_executed_opcodes(),
+            foreign_calls: self.context.get_foreign_call_count(),
+            error,
+            error_call_stack,
+            witness_path: None,
+            unsolved_outputs,
+            unread_inputs,
+        };
+        SessionLog {
+            brillig_limits: self.context.get_brillig_limits(),
+            breakpoint_snapshots: self.context.get_breakpoint_snapshots().to_vec(),
+            summary,
+            profile: self.profile.clone(),
+        }
+    }
+
+    /// Names the return values that have not been solved yet
+    fn unsolved_outputs(&self) -> Vec<String> {
+        let return_witnesses = self.context.get_return_witnesses();
+        let witness_map = self.context.get_witness_map();
+        return_witnesses
+            .iter()
+            .enumerate()
+            .filter(|(_, witness)| witness_map.get(witness).is_none())
+            .map(|(index, witness)| {
+                let name = if return_witnesses.len() == 1 {
+                    "return".to_string()
+                } else {
+                    format!("return[{index}]")
+                };
+                format!("{name} (_{})", witness.witness_index())
+            })
+            .collect()
+    }
+
+    /// Names the ABI inputs whose witnesses no executed opcode has read
+    fn unread_inputs(&self, program_info: &ProgramInfo) -> Vec<String> {
+        let read_witnesses = self.context.get_read_witnesses();
+        program_info
+            .input_witnesses
+            .iter()
+            .flat_map(|(name, witnesses)| {
+                witnesses.iter().enumerate().map(move |(index, witness)| {
+                    let name = if witnesses.len() == 1 {
+                        name.clone()
+                    } else {
+                        format!("{name}[{index}]")
+                    };
+                    (name, witness)
+                })
+            })
+            .filter(|(_, witness)| !read_witnesses.contains(witness))
+            .map(|(name, witness)| format!("{name} (_{})", witness.witness_index()))
+            .collect()
+    }
+
+    pub fn show_assignment_log(&self) {
+        let assignments = self.context.get_assignment_log();
+        if assignments.is_empty() {
+            println!("No array element or member assignments yet");
             return;
-        };
-        if !self.context.is_executing_brillig() {
-            println!("Not executing a Brillig block");
+        }
+        for assignment in assignments {
+            println!("{assignment}");
+        }
+    }
+
+    fn set_path_substitution(&mut self, from: &str, to: &str) {
+        if to == "off" {
+            if self.path_substitutions.remove(Path::new(from)) {
+                println!("Removed substitution of {from}");
+            } else {
+                println!("No substitution of {from} set");
+            }
             return;
         }
-        self.context.write_brillig_memory(index, field_value, bit_size);
+        let to_path = Path::new(to);
+        if !to_path.is_dir() {
+            println!("Warning: {to} is not a directory");
+        }
+        self.path_substitutions.add(from, to_path);
+        println!("Substituting {from} with {to} in source paths");
     }
 
-    pub fn show_vars(&self) {
-        for frame in self.context.get_variables() {
-            println!("{}({})", frame.function_name, frame.function_params.join(", "));
-            for (var_name, value, var_type) in frame.variables.iter() {
-                let printable_value =
-                    PrintableValueDisplay::Plain((*value).clone(), (*var_type).clone());
-                println!("  {var_name}:{var_type:?} = {}", printable_value);
+    fn set_oracle_policy(&mut self, name: &str, value: &str) {
+        let policy = match value {
+            "off" => None,
+            "skip" => Some(OraclePolicy::Skip),
+            _ => match value.parse::<usize>() {
+                Ok(retries) if retries > 0 => Some(OraclePolicy::Retry(retries)),
+                _ => {
+                    println!(
+                        "Invalid retry count {value}; expected a positive number, skip or off"
+                    );
+                    return;
+                }
+            },
+        };
+        self.context.set_oracle_policy(name, policy);
+        match policy {
+            Some(OraclePolicy::Retry(retries)) => {
+                println!(
+                    "Failed calls to oracle {name} are retried up to {retries} times, backing off between attempts"
+                )
+            }
+            Some(OraclePolicy::Skip) => {
+                println!("Failed calls to oracle {name} are skipped, returning default values")
+            }
+            None => println!("Failed calls to oracle {name} stop execution"),
+        }
+    }
+
+    fn show_path_substitutions(&self) {
+        let mut substitutions = self.path_substitutions.iter().peekable();
+        if substitutions.peek().is_none() {
+            println!("No source path substitutions set");
+        }
+        for (from, to) in substitutions {
+            println!("{} => {}", from.display(), to.display());
+        }
+    }
+
+    fn set_option_with_arg(&mut self, option: &str, arg: &str, value: &str) {
+        match (option, arg) {
+            ("print", "pretty") => {
+                let Some(enabled) = parse_on_off(value) else {
+                    println!("Invalid value {value}; expected on or off");
+                    return;
+                };
+                self.print_pretty = enabled;
+                println!("Pretty printing is {value}");
+            }
+            ("var", name) => self.set_variable(name, value),
+            ("substitute-path", from) => self.set_path_substitution(from, value),
+            ("oracle-retry", name) => self.set_oracle_policy(name, value),
+            _ => println!("Unknown option {option} {arg}"),
+        }
+    }
+
+    fn set_option(&mut self, option: &str, value: &str) {
+        match option {
+            "verbosity" => match value.parse() {
+                Ok(verbosity) => {
+                    self.verbosity = verbosity;
+                    println!("Verbosity is {value}");
+                }
+                Err(error) => println!("{error}"),
+            },
+            "trace" => self.set_trace(value),
+            "a11y" => match parse_on_off(value) {
+                Some(accessible) => {
+                    self.accessible = accessible;
+                    println!("Accessible mode is {value}");
+                }
+                None => println!("Invalid value {value}; expected on or off"),
+            },
+            "check-determinism" => match parse_on_off(value) {
+                Some(check) => {
+                    self.context.set_check_determinism(check);
+                    if check {
+                        println!("Each run is compared with the previous one after a restart");
+                    } else {
+                        println!("Runs are not compared");
+                    }
+                }
+                None => println!("Invalid value {value}; expected on or off"),
+            },
+            "timeout" => match parse_timeout(value) {
+                Some(timeout) => {
+                    self.continue_timeout = timeout;
+                    println!("Continue timeout is {value}");
+                }
+                None => println!("Invalid timeout {value}; expected a number of seconds or off"),
+            },
+            "opcount" => match parse_timeout(value) {
+                Some(interval) => {
+                    self.progress_interval = interval;
+                    match interval {
+                        Some(_) => println!("Continue shows its progress every {value} seconds"),
+                        None => println!("Continue progress is off"),
+                    }
+                }
+                None => println!("Invalid interval {value}; expected a number of seconds or off"),
+            },
+            "brillig-max-memory" | "brillig-max-depth" => {
+                let limit = match value {
+                    "off" => None,
+                    _ => match value.parse::<usize>() {
+                        Ok(limit) if limit > 0 => Some(limit),
+                        _ => {
+                            println!("Invalid limit {value}; expected a positive number or off");
+                            return;
+                        }
+                    },
+                };
+                let mut limits = self.context.get_brillig_limits();
+                if option == "brillig-max-memory" {
+                    limits.max_memory = limit;
+                } else {
+                    limits.max_depth = limit;
+                }
+                self.context.set_brillig_limits(limits);
+                println!("Brillig {} limit is {value}", option.trim_start_matches("brillig-max-"));
             }
+            "memory-width" => match value.parse::<usize>() {
+                Ok(width) if width > 0 => {
+                    self.memory_width = width;
+                    println!("Memory is shown in lines of up to {width} characters");
+                }
+                _ => println!("Invalid memory width {value}; expected a positive number"),
+            },
+            "opcode-budget" => match value {
+                "off" => {
+                    self.continue_opcode_budget = None;
+                    println!("Opcode budget is off");
+                }
+                _ => match value.parse::<usize>() {
+                    Ok(budget) if budget > 0 => {
+                        self.continue_opcode_budget = Some(budget);
+                        println!("Continue stops after {budget} opcodes");
+                    }
+                    _ => {
+                        println!("Invalid opcode budget {value}; expected a positive number or off")
+                    }
+                },
+            },
+            _ => println!("Unknown option {option}"),
+        }
+    }
+
+    fn set_variable(&mut self, name: &str, value: &str) {
+        let Some(field_value) = FieldElement::try_from_str(value) else {
+            println!("Invalid value: {value}");
+            return;
+        };
+
+        match self.context.set_variable(name, field_value) {
+            Ok(true) => println!("{name} = {value}"),
+            Ok(false) => println!(
+                "{name} = {value} (only the tracked value was updated; {name} is not held in Brillig memory at this point)"
+            ),
+            Err(error) => println!("{error}"),
+        }
+    }
+
+    fn start_tutorial(&mut self) {
+        let tutorial = Tutorial::new();
+        tutorial.start();
+        self.tutorial = Some(tutorial);
+    }
+
+    /// Lets the tutorial (if running) check whether the command that was just
+    /// executed completes its current step
+    fn check_tutorial_step(&mut self, command: &str) {
+        let succeeded = match command {
+            "break" => self.context.iterate_breakpoints().next().is_some(),
+            "continue" => matches!(self.last_result, DebugCommandResult::BreakpointReached(..)),
+            _ => true,
+        };
+        if let Some(tutorial) = self.tutorial.as_mut() {
+            tutorial.command_executed(command, succeeded);
         }
     }
 
@@ -375,113 +2421,511 @@ impl<'a, B: BlackBoxFunctionSolver<FieldElement>> ReplDebugger<'a, B> {
     fn finalize(self) -> WitnessMap<FieldElement> {
         self.context.finalize()
     }
+
+    /// Describes the failure execution last stopped on, if it did
+    fn failure_report(&self) -> Option<FailureReport> {
+        match &self.last_result {
+            DebugCommandResult::Error(error) => self.context.failure_report(error),
+            _ => None,
+        }
+    }
+}
+
+/// Debugging session state as written by `session save`. Instead of the
+/// state of the VMs, the point of execution is stored, which is reached again
+/// by replaying the program from the start with the same inputs.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedSession {
+    program_hash: String,
+    initial_witness: Vec<(u32, FieldElement)>,
+    /// Current opcode location, or `None` if execution had finished
+    location: Option<OpcodeLocation>,
+    /// Steps taken in the Brillig block being executed
+    brillig_steps: usize,
+    breakpoints: Vec<SavedBreakpoint>,
+    verbosity: Verbosity,
+    print_pretty: bool,
+}
+
+/// A breakpoint as written by `save-breakpoints`, or carried over to the next
+/// session when `--watch` recompiles the program
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedBreakpoint {
+    location: OpcodeLocation,
+    /// Source position of the breakpoint, used to place it again after recompiling
+    source: Option<SavedSourcePosition>,
+    #[serde(default)]
+    ignore_count: usize,
+    #[serde(default)]
+    log_message: Option<String>,
+    #[serde(default)]
+    snapshot: Vec<String>,
+    #[serde(default)]
+    condition: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SavedSourcePosition {
+    path: PathBuf,
+    line: usize,
+}
+
+/// Parses a `FILE:LINE` source position, where FILE may be partial
+fn parse_source_position(position: &str) -> Option<(&str, usize)> {
+    let (path, line) = position.rsplit_once(':')?;
+    let line = line.parse().ok().filter(|line| *line > 0)?;
+    (!path.is_empty()).then_some((path, line))
+}
+
+/// Parses an opcode range such as `100..200`, `100..` or `..200`. A single
+/// index is taken as a range of one opcode.
+fn parse_opcode_range(range: &str) -> Option<Range<usize>> {
+    let Some((start, end)) = range.split_once("..") else {
+        let index = range.parse::<usize>().ok()?;
+        return index.checked_add(1).map(|end| index..end);
+    };
+    let start = if start.is_empty() { 0 } else { start.parse().ok()? };
+    let end = if end.is_empty() { usize::MAX } else { end.parse().ok()? };
+    Some(start..end)
+}
+
+fn clamp_range(range: Range<usize>, len: usize) -> Range<usize> {
+    let end = range.end.min(len);
+    range.start.min(end)..end
+}
+
+/// Returns a range of at most `size` indices below `len` centered on `center`
+fn window_around(center: usize, len: usize, size: usize) -> Range<usize> {
+    let end = center.saturating_add(size / 2).max(size).min(len);
+    end.saturating_sub(size)..end
+}
+
+/// Parses a timeout in (possibly fractional) seconds, or `off` for no timeout
+fn parse_timeout(value: &str) -> Option<Option<Duration>> {
+    if value == "off" {
+        return Some(None);
+    }
+    match value.parse::<f64>() {
+        Ok(seconds) if seconds.is_finite() && seconds > 0.0 => {
+            Some(Some(Duration::from_secs_f64(seconds)))
+        }
+        _ => None,
+    }
+}
+
+fn print_function_calls(function_calls: &[(String, FunctionCalls)]) {
+    println!("{:>8} {:>10}  function", "calls", "opcodes");
+    for (name, calls) in function_calls {
+        println!("{:>8} {:>10}  {name}", calls.calls, calls.opcodes);
+    }
+}
+
+fn show_compile_warnings(warnings: &[String]) {
+    if warnings.is_empty() {
+        println!("The program compiled without warnings");
+    }
+    for warning in warnings {
+        println!("{warning}");
+    }
+}
+
+/// Marker of the current opcode (`->`) or of a breakpoint (`*`) in opcode
+/// listings, spelled out in accessible mode
+fn opcode_marker(is_current: bool, has_breakpoint: bool, accessible: bool) -> &'static str {
+    match (is_current, has_breakpoint, accessible) {
+        (true, _, false) => "->",
+        (true, _, true) => ", current opcode",
+        (false, true, false) => " *",
+        (false, true, true) => ", breakpoint set",
+        (false, false, _) => "",
+    }
+}
+
+fn parse_on_off(value: &str) -> Option<bool> {
+    match value {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// Returns whether a variable name matches a `vars` filter. Filters containing
+/// `*` or `?` are treated as glob patterns over the whole name; otherwise they
+/// match any name containing the filter as a substring.
+fn matches_var_filter(name: &str, filter: &str) -> bool {
+    if !filter.contains(|c: char| c == '*' || c == '?') {
+        return name.contains(filter);
+    }
+
+    fn glob_match(name: &[char], pattern: &[char]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some('*'), _) => {
+                glob_match(name, &pattern[1..])
+                    || (!name.is_empty() && glob_match(&name[1..], pattern))
+            }
+            (Some('?'), Some(_)) => glob_match(&name[1..], &pattern[1..]),
+            (Some(p), Some(n)) if p == n => glob_match(&name[1..], &pattern[1..]),
+            _ => false,
+        }
+    }
+
+    let name: Vec<char> = name.chars().collect();
+    let pattern: Vec<char> = filter.chars().collect();
+    glob_match(&name, &pattern)
 }
 
 pub fn run<B: BlackBoxFunctionSolver<FieldElement>>(
     blackbox_solver: &B,
-    circuit: &Circuit<FieldElement>,
+    program: &Program<FieldElement>,
     debug_artifact: &DebugArtifact,
     initial_witness: WitnessMap<FieldElement>,
-    unconstrained_functions: &[BrilligBytecode<FieldElement>],
-) -> Result<Option<WitnessMap<FieldElement>>, NargoError<FieldElement>> {
+    environment: &DebugEnvironment,
+    program_info: &ProgramInfo,
+    options: &DebuggerOptions,
+) -> Result<(DebugOutcome, SessionLog), NargoError<FieldElement>> {
     let context = RefCell::new(ReplDebugger::new(
         blackbox_solver,
-        circuit,
+        &program.functions[0],
         debug_artifact,
         initial_witness,
-        unconstrained_functions,
+        &program.unconstrained_functions,
+        options,
     ));
     let ref_context = &context;
 
+    interrupt::install_handler();
+    if options.validate_oracle_outputs {
+        ref_context.borrow_mut().context.set_validate_foreign_calls(true);
+    }
+    ref_context.borrow_mut().context.set_oracle_schema(options.oracle_schema.clone());
     ref_context.borrow().show_current_vm_status();
+    if !options.compile_warnings.is_empty() {
+        println!(
+            "The program compiled with {} warning(s); `info warnings` lists them",
+            options.compile_warnings.len()
+        );
+    }
+    if !options.breakpoints.is_empty() {
+        let restored = ref_context.borrow_mut().restore_breakpoints(options.breakpoints.clone());
+        println!("Restored {restored} of {} breakpoint(s)", options.breakpoints.len());
+    }
+    ref_context.borrow_mut().continue_timeout = options.continue_timeout;
+    if options.tutorial {
+        ref_context.borrow_mut().start_tutorial();
+    }
+    if options.run_to_failure {
+        ref_context.borrow_mut().run_to_failure();
+    }
 
     let mut repl = Repl::builder()
         .add(
-            "step",
+            "step",
+            command! {
+                "step to the next ACIR opcode",
+                () => || {
+                    ref_context.borrow_mut().step_acir_opcode(1);
+                    ref_context.borrow_mut().check_tutorial_step("step");
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "step",
+            command! {
+                "step N ACIR opcodes",
+                (count: usize) => |count| {
+                    ref_context.borrow_mut().step_acir_opcode(count);
+                    ref_context.borrow_mut().check_tutorial_step("step");
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "into",
+            command! {
+                "step into to the next opcode",
+                () => || {
+                    ref_context.borrow_mut().step_into_opcode(1);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "into",
+            command! {
+                "step into the next N opcodes",
+                (count: usize) => |count| {
+                    ref_context.borrow_mut().step_into_opcode(count);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "next",
+            command! {
+                "step until a new source location is reached",
+                () => || {
+                    ref_context.borrow_mut().next_into(1);
+                    ref_context.borrow_mut().check_tutorial_step("next");
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "next",
+            command! {
+                "step until a new source location is reached, N times",
+                (count: usize) => |count| {
+                    ref_context.borrow_mut().next_into(count);
+                    ref_context.borrow_mut().check_tutorial_step("next");
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "over",
+            command! {
+                "step until a new source location is reached without diving into function calls",
+                () => || {
+                    ref_context.borrow_mut().next_over(1);
+                    Ok(CommandStatus::Done)
+                }
+            }
+        )
+        .add(
+            "over",
+            command! {
+                "step over N source locations without diving into function calls",
+                (count: usize) => |count| {
+                    ref_context.borrow_mut().next_over(count);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "out",
+            command! {
+                "step until a new source location is reached and the current stack frame is finished",
+                () => || {
+                    ref_context.borrow_mut().next_out(1);
+                    Ok(CommandStatus::Done)
+                }
+            }
+        )
+        .add(
+            "out",
+            command! {
+                "step out of N stack frames",
+                (count: usize) => |count| {
+                    ref_context.borrow_mut().next_out(count);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "continue",
+            command! {
+                "continue execution until the end of the program",
+                () => || {
+                    ref_context.borrow_mut().cont(None);
+                    ref_context.borrow_mut().check_tutorial_step("continue");
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "continue",
+            command! {
+                "continue execution, returning to the prompt after a time limit (continue --timeout <secs>)",
+                (option: String, value: String) => |option, value| {
+                    match (option.as_str(), parse_timeout(&value)) {
+                        ("--timeout", Some(Some(timeout))) => {
+                            ref_context.borrow_mut().cont(Some(timeout));
+                            ref_context.borrow_mut().check_tutorial_step("continue");
+                        }
+                        ("--timeout", _) => println!("Invalid timeout {value}; expected a number of seconds"),
+                        _ => println!("Unknown option {option}; expected --timeout"),
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "restart",
+            command! {
+                "restart the debugging session",
+                () => || {
+                    ref_context.borrow_mut().restart_session();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "rewind-to-call",
+            command! {
+                "list the foreign calls made so far, which execution can be rewound to",
+                () => || {
+                    ref_context.borrow().show_foreign_call_checkpoints();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "rewind-to-call",
+            command! {
+                "move execution back to right before the Nth foreign call of the program",
+                (N: usize) => |call| {
+                    ref_context.borrow_mut().rewind_to_foreign_call(call);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "where",
+            command! {
+                "show the opcode at a location and its source code without moving execution",
+                (LOCATION:OpcodeLocation) => |location| {
+                    ref_context.borrow().show_location(location);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "list",
+            command! {
+                "show the source code around the current location",
+                () => || {
+                    ref_context.borrow().list_source(None);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "list",
             command! {
-                "step to the next ACIR opcode",
-                () => || {
-                    ref_context.borrow_mut().step_acir_opcode();
+                "show the source code of FILE around LINE, given as FILE[:LINE] with FILE possibly partial (eg. main.nr:12)",
+                (SOURCE: String) => |source| {
+                    ref_context.borrow().list_source(Some(&source));
                     Ok(CommandStatus::Done)
                 }
             },
         )
         .add(
-            "into",
+            "opcodes",
             command! {
-                "step into to the next opcode",
+                "display opcodes around the current location",
                 () => || {
-                    ref_context.borrow_mut().step_into_opcode();
+                    ref_context.borrow().display_opcodes();
                     Ok(CommandStatus::Done)
                 }
             },
         )
         .add(
-            "next",
+            "opcodes",
             command! {
-                "step until a new source location is reached",
-                () => || {
-                    ref_context.borrow_mut().next_into();
+                "display ACIR opcodes in a range (eg. 100..200), or all of them",
+                (range: String) => |range| {
+                    if range == "all" {
+                        ref_context.borrow().display_all_opcodes();
+                    } else if let Some(range) = parse_opcode_range(&range) {
+                        ref_context.borrow().display_acir_opcodes(range);
+                    } else {
+                        println!("Invalid range {range}; expected eg. 100..200");
+                    }
                     Ok(CommandStatus::Done)
                 }
             },
         )
         .add(
-            "over",
+            "opcodes",
             command! {
-                "step until a new source location is reached without diving into function calls",
-                () => || {
-                    ref_context.borrow_mut().next_over();
+                "display Brillig opcodes of an ACIR Brillig call in a range (eg. 0 100..200)",
+                (acir_index: usize, range: String) => |acir_index, range| {
+                    if let Some(range) = parse_opcode_range(&range) {
+                        ref_context.borrow().display_brillig_opcodes(acir_index, range);
+                    } else {
+                        println!("Invalid range {range}; expected eg. 100..200");
+                    }
                     Ok(CommandStatus::Done)
                 }
-            }
+            },
         )
         .add(
-            "out",
+            "break",
             command! {
-                "step until a new source location is reached and the current stack frame is finished",
-                () => || {
-                    ref_context.borrow_mut().next_out();
+                "add a breakpoint at an opcode location, a source line (eg. break main.nr:12) or an assert (eg. break assert#3)",
+                (LOCATION:String) => |location| {
+                    ref_context.borrow_mut().add_breakpoint_at_target(&location);
+                    ref_context.borrow_mut().check_tutorial_step("break");
                     Ok(CommandStatus::Done)
                 }
-            }
+            },
         )
         .add(
-            "continue",
+            "break",
             command! {
-                "continue execution until the end of the program",
-                () => || {
-                    ref_context.borrow_mut().cont();
+                "add the breakpoints listed in FILE, one FILE:LINE [CONDITION] per line (eg. break --from-file bps.txt)",
+                (OPTION:String, FILE:String) => |option, file| {
+                    if option == "--from-file" {
+                        ref_context.borrow_mut().add_breakpoints_from_file(&file);
+                    } else {
+                        println!("Unknown breakpoint option {option}; expected --from-file");
+                    }
                     Ok(CommandStatus::Done)
                 }
             },
         )
         .add(
-            "restart",
+            "break",
             command! {
-                "restart the debugging session",
-                () => || {
-                    ref_context.borrow_mut().restart_session();
+                "add a breakpoint recording variables each time it is hit (eg. break 3 snapshot x,y)",
+                (LOCATION:OpcodeLocation, option: String, variables: String) => |location, option, variables| {
+                    if option == "snapshot" {
+                        ref_context.borrow_mut().add_snapshot_breakpoint_at(location, &variables);
+                    } else {
+                        println!("Unknown breakpoint option {option}; expected snapshot");
+                    }
                     Ok(CommandStatus::Done)
                 }
             },
         )
         .add(
-            "opcodes",
+            "break-if",
             command! {
-                "display ACIR opcodes",
-                () => || {
-                    ref_context.borrow().display_opcodes();
+                "add a breakpoint stopping only if CONDITION holds (eg. \"i > 10 && _12 == 0 && hits % 5 == 0\")",
+                (LOCATION:OpcodeLocation, CONDITION:String) => |location, condition| {
+                    ref_context.borrow_mut().add_conditional_breakpoint_at(location, &condition);
                     Ok(CommandStatus::Done)
                 }
             },
         )
         .add(
-            "break",
+            "break-on",
             command! {
-                "add a breakpoint at an opcode location",
-                (LOCATION:OpcodeLocation) => |location| {
-                    ref_context.borrow_mut().add_breakpoint_at(location);
+                "stop right before each occurrence of EVENT (print)",
+                (EVENT: String) => |event| {
+                    ref_context.borrow_mut().set_break_on(&event, "on");
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "break-on",
+            command! {
+                "enable or disable stopping right before each occurrence of EVENT (print)",
+                (EVENT: String, VALUE: String) => |event, value| {
+                    ref_context.borrow_mut().set_break_on(&event, &value);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "logpoint",
+            command! {
+                "add a logpoint printing MESSAGE, with {variable} placeholders, without stopping",
+                (LOCATION:OpcodeLocation, MESSAGE:String) => |location, message| {
+                    ref_context.borrow_mut().add_logpoint_at(location, message);
                     Ok(CommandStatus::Done)
                 }
             },
@@ -496,12 +2940,57 @@ pub fn run<B: BlackBoxFunctionSolver<FieldElement>>(
                 }
             },
         )
+        .add(
+            "save-breakpoints",
+            command! {
+                "save all breakpoints to a JSON file",
+                (file: String) => |file| {
+                    ref_context.borrow().save_breakpoints(&file);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "load-breakpoints",
+            command! {
+                "load breakpoints from a JSON file written by save-breakpoints",
+                (file: String) => |file| {
+                    ref_context.borrow_mut().load_breakpoints(&file);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "session",
+            command! {
+                "save the debugging session to a file, or load it to resume it (session save|load <file>)",
+                (action: String, file: String) => |action, file| {
+                    match action.as_str() {
+                        "save" => ref_context.borrow().save_session(&file),
+                        "load" => ref_context.borrow_mut().load_session(&file),
+                        _ => println!("Unknown session action {action}; expected save or load"),
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "ignore",
+            command! {
+                "skip a breakpoint the next COUNT times it is reached",
+                (id: usize, count: usize) => |id, count| {
+                    ref_context.borrow_mut().ignore_breakpoint(id, count);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "witness",
             command! {
                 "show witness map",
                 () => || {
                     ref_context.borrow().show_witness_map();
+                    ref_context.borrow_mut().check_tutorial_step("witness");
                     Ok(CommandStatus::Done)
                 }
             },
@@ -516,6 +3005,20 @@ pub fn run<B: BlackBoxFunctionSolver<FieldElement>>(
                 }
             },
         )
+        .add(
+            "witness",
+            command! {
+                "show only witnesses assigned or modified since the previous stop (--changed)",
+                (option: String) => |option| {
+                    if option == "--changed" {
+                        ref_context.borrow().show_changed_witnesses();
+                    } else {
+                        println!("Unknown option {option}; expected a witness index or --changed");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "witness",
             command! {
@@ -526,12 +3029,120 @@ pub fn run<B: BlackBoxFunctionSolver<FieldElement>>(
                 }
             },
         )
+        .add(
+            "status",
+            command! {
+                "show the current location and the ACVM status, eg. whether it is waiting on a foreign call",
+                () => || {
+                    ref_context.borrow().show_status();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "main-witness-stack",
+            command! {
+                "show the witness map of the main circuit as a witness stack; callees' witness maps are not included, as ACIR calls are not supported",
+                () => || {
+                    ref_context.borrow().show_main_witness_stack();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "deps",
+            command! {
+                "show the tree of opcodes a witness depends on",
+                (witness: String) => |witness| {
+                    ref_context.borrow().show_witness_dependencies(&witness, DEFAULT_DEPS_DEPTH);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "deps",
+            command! {
+                "show the tree of opcodes a witness depends on, up to --depth N levels",
+                (witness: String, option: String, depth: usize) => |witness, option, depth| {
+                    if option == "--depth" {
+                        ref_context.borrow().show_witness_dependencies(&witness, depth);
+                    } else {
+                        println!("Unknown option {option}; expected --depth");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "memory",
             command! {
                 "show Brillig memory (valid when executing a Brillig block)",
                 () => || {
-                    ref_context.borrow().show_brillig_memory();
+                    ref_context.borrow().show_brillig_memory(false, None);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memory",
+            command! {
+                "show only Brillig memory cells written since the previous stop (--changed)",
+                (option: String) => |option| {
+                    if option == "--changed" {
+                        ref_context.borrow().show_brillig_memory(true, None);
+                    } else {
+                        println!("Unknown option {option}; expected --changed");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memory",
+            command! {
+                "show Brillig memory cells as TYPE (field, bool or u1 to u128), splitting wider values into limbs",
+                (option: String, TYPE: String) => |option, cell_type| {
+                    match (option.as_str(), cell_type.parse::<CellType>()) {
+                        ("--as", Ok(cell_type)) => {
+                            ref_context.borrow().show_brillig_memory(false, Some(cell_type))
+                        }
+                        ("--as", Err(error)) => println!("{error}"),
+                        _ => println!("Unknown option {option}; expected --as"),
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memory-diff",
+            command! {
+                "show the Brillig memory cells changed since the previous stop, with their old and new values",
+                () => || {
+                    ref_context.borrow().show_memory_diff(None);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memory-diff",
+            command! {
+                "show the Brillig memory cells changed since the checkpoint NAME",
+                (NAME: String) => |name| {
+                    ref_context.borrow().show_memory_diff(Some(&name));
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memory-diff",
+            command! {
+                "save the current Brillig memory as checkpoint NAME to compare against later (--save)",
+                (option: String, NAME: String) => |option, name| {
+                    if option == "--save" {
+                        ref_context.borrow_mut().save_memory_checkpoint(name);
+                    } else {
+                        println!("Unknown option {option}; expected --save");
+                    }
                     Ok(CommandStatus::Done)
                 }
             },
@@ -539,9 +3150,115 @@ pub fn run<B: BlackBoxFunctionSolver<FieldElement>>(
         .add(
             "memset",
             command! {
-                "update a Brillig memory cell with the given value",
+                "update a Brillig memory cell with the given value, checking the index and bit size",
                 (index: usize, value: String, bit_size: u32) => |index, value, bit_size| {
-                    ref_context.borrow_mut().write_brillig_memory(index, value, bit_size);
+                    ref_context.borrow_mut().write_brillig_memory(index, value, bit_size, false);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "memset",
+            command! {
+                "update a Brillig memory cell even if it is past the end of memory, changes type or holds a variable (--force)",
+                (index: usize, value: String, bit_size: u32, option: String) => |index, value, bit_size, option| {
+                    if option == "--force" {
+                        ref_context.borrow_mut().write_brillig_memory(index, value, bit_size, true);
+                    } else {
+                        println!("Unknown option {option}; expected --force");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "info",
+            command! {
+                "show information about the session (breakpoints, snapshots, frame, circuit, program, blackbox, assignments, asserts, substitute-path, warnings)",
+                (topic: String) => |topic| {
+                    match topic.as_str() {
+                        "breakpoints" => ref_context.borrow().show_breakpoints(),
+                        "snapshots" => ref_context.borrow().show_breakpoint_snapshots(),
+                        "frame" => ref_context.borrow().show_frame_info(),
+                        "circuit" => ref_context.borrow().show_circuit_info(),
+                        "program" => {
+                            ref_context.borrow().show_program_info(environment, program_info)
+                        }
+                        "blackbox" => ref_context.borrow().show_blackbox_calls(),
+                        "assignments" => ref_context.borrow().show_assignment_log(),
+                        "asserts" => ref_context.borrow().show_asserts(),
+                        "substitute-path" => ref_context.borrow().show_path_substitutions(),
+                        "warnings" => show_compile_warnings(&options.compile_warnings),
+                        _ => println!("Unknown info topic {topic}; expected breakpoints, snapshots, frame, circuit, program, blackbox, assignments, asserts, substitute-path or warnings"),
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "oracle",
+            command! {
+                "show the signatures of the oracles declared in oracles.toml (describe)",
+                (subcommand: String) => |subcommand| {
+                    if subcommand == "describe" {
+                        ref_context.borrow().describe_oracles(None);
+                    } else {
+                        println!("Unknown subcommand {subcommand}; expected describe");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "oracle",
+            command! {
+                "show the signature of the oracle NAME declared in oracles.toml (describe)",
+                (subcommand: String, NAME: String) => |subcommand, name| {
+                    if subcommand == "describe" {
+                        ref_context.borrow().describe_oracles(Some(&name));
+                    } else {
+                        println!("Unknown subcommand {subcommand}; expected describe");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "oracle-args",
+            command! {
+                "show the arguments of the foreign call about to be made, and the message of print calls",
+                () => || {
+                    ref_context.borrow().show_oracle_args();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "calls",
+            command! {
+                "show how many times each function was called and the opcodes spent in it",
+                () => || {
+                    ref_context.borrow().show_function_calls();
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "profile",
+            command! {
+                "re-run the program from the start, without breakpoints, and show where its opcodes were spent",
+                () => || {
+                    ref_context.borrow_mut().profile(PROFILE_MAX_OPCODES);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "profile",
+            command! {
+                "profile a re-run of the program, stopping it after N opcodes",
+                (max_opcodes: usize) => |max_opcodes| {
+                    ref_context.borrow_mut().profile(max_opcodes);
                     Ok(CommandStatus::Done)
                 }
             },
@@ -556,12 +3273,80 @@ pub fn run<B: BlackBoxFunctionSolver<FieldElement>>(
                 }
             },
         )
+        .add(
+            "stacktrace",
+            command! {
+                "display the current stack trace along with the variables of each function frame (stacktrace full)",
+                (mode: String) => |mode| {
+                    match mode.as_str() {
+                        "full" => ref_context.borrow().show_current_call_stack_full(),
+                        _ => println!("Unknown stacktrace mode {mode}; expected full"),
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
         .add(
             "vars",
             command! {
                 "show variables for each function scope available at this point in execution",
                 () => || {
-                    ref_context.borrow_mut().show_vars();
+                    ref_context.borrow().show_vars(None, None);
+                    ref_context.borrow_mut().check_tutorial_step("vars");
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "vars",
+            command! {
+                "show variables whose names match a substring or glob pattern (eg. foo*)",
+                (filter: String) => |filter| {
+                    ref_context.borrow().show_vars(Some(&filter), None);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "vars",
+            command! {
+                "show variables of a single stack frame (--frame N, 0 being the current frame)",
+                (option: String, frame: usize) => |option, frame| {
+                    if option == "--frame" {
+                        ref_context.borrow().show_vars(None, Some(frame));
+                    } else {
+                        println!("Unknown option {option}; expected --frame");
+                    }
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "version",
+            command! {
+                "show toolchain versions and compile settings of this session",
+                () => || {
+                    println!("{environment}");
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "set",
+            command! {
+                "change a debugger setting (eg. set verbosity silent|quiet|normal|verbose, set trace <path>|off, set timeout <secs>|off, set opcode-budget <count>|off, set opcount <secs>|off, set brillig-max-memory <cells>|off, set brillig-max-depth <calls>|off, set memory-width <columns>, set check-determinism on|off, set a11y on|off)",
+                (option: String, value: String) => |option, value| {
+                    ref_context.borrow_mut().set_option(&option, &value);
+                    Ok(CommandStatus::Done)
+                }
+            },
+        )
+        .add(
+            "set",
+            command! {
+                "change a debugger setting or variable (eg. set print pretty on|off, set var x 42, set substitute-path std <dir>|off, set oracle-retry <oracle> <count>|skip|off)",
+                (option: String, arg: String, value: String) => |option, arg, value| {
+                    ref_context.borrow_mut().set_option_with_arg(&option, &arg, &value);
                     Ok(CommandStatus::Done)
                 }
             },
@@ -569,16 +3354,56 @@ pub fn run<B: BlackBoxFunctionSolver<FieldElement>>(
         .build()
         .expect("Failed to initialize debugger repl");
 
-    repl.run().expect("Debugger error");
+    // Sources are watched from another thread, which interrupts the running
+    // command when they change. The REPL blocks while waiting for input, so
+    // the session restarts once the command being run or typed completes.
+    let stop_watching = Arc::new(AtomicBool::new(false));
+    let watcher = options.watch.then(|| {
+        SourceWatcher::new(debug_artifact.file_map.values().map(|file| file.path.clone()))
+            .watch_in_background(stop_watching.clone())
+    });
+    let mut sources_changed = false;
+    while let LoopStatus::Continue = repl.next().expect("Debugger error") {
+        if interrupt::sources_changed() {
+            sources_changed = true;
+            break;
+        }
+    }
+    stop_watching.store(true, Ordering::SeqCst);
+    if let Some(watcher) = watcher {
+        let _ = watcher.join();
+    }
+    interrupt::take_sources_changed();
 
     // REPL execution has finished.
     // Drop it so that we can move fields out from `context` again.
     drop(repl);
 
-    if context.borrow().is_solved() {
+    let session_log = context.borrow().session_log(program_info);
+    if sources_changed {
+        println!("Source files changed; restarting the debugging session...");
+        Ok((DebugOutcome::SourcesChanged(context.borrow().saved_breakpoints()), session_log))
+    } else if context.borrow().is_solved() {
         let solved_witness = context.into_inner().finalize();
-        Ok(Some(solved_witness))
+        Ok((DebugOutcome::Solved(solved_witness), session_log))
+    } else if let Some(report) = context.borrow().failure_report() {
+        let witness_stack = context.borrow().context.get_witness_stack();
+        Ok((DebugOutcome::Failed(witness_stack, report), session_log))
     } else {
-        Ok(None)
+        Ok((DebugOutcome::Halted, session_log))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::opcode_marker;
+
+    #[test]
+    fn spells_out_opcode_markers_in_accessible_mode() {
+        assert_eq!(opcode_marker(true, true, false), "->");
+        assert_eq!(opcode_marker(false, true, false), " *");
+        assert_eq!(opcode_marker(true, true, true), ", current opcode");
+        assert_eq!(opcode_marker(false, true, true), ", breakpoint set");
+        assert_eq!(opcode_marker(false, false, true), "");
     }
 }
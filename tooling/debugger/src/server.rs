@@ -0,0 +1,263 @@
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+use acvm::acir::circuit::brillig::BrilligBytecode;
+use acvm::acir::circuit::{Circuit, OpcodeLocation};
+use acvm::acir::native_types::WitnessMap;
+use acvm::{BlackBoxFunctionSolver, FieldElement};
+use noirc_artifacts::debug::DebugArtifact;
+use noirc_printable_type::PrintableValueDisplay;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::context::{DebugCommandResult, DebugContext};
+use crate::foreign_calls::DefaultDebugForeignCallExecutor;
+use crate::source_code_printer::format_location_path;
+use crate::source_paths::PathSubstitutions;
+
+/// A request of the JSON protocol, sent as a single line such as
+/// `{"command": "break", "location": "3.12"}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+enum ServerRequest {
+    /// Step into the next opcode, ACIR or Brillig
+    Step,
+    /// Step into the next source location
+    Next,
+    Over,
+    Out,
+    Continue,
+    Break {
+        location: String,
+    },
+    Delete {
+        location: String,
+    },
+    Status,
+    Vars,
+    Witness,
+    Stacktrace,
+    Restart,
+    Quit,
+}
+
+struct JsonServer<'a, B: BlackBoxFunctionSolver<FieldElement>> {
+    context: DebugContext<'a, B>,
+    debug_artifact: &'a DebugArtifact,
+    initial_witness: WitnessMap<FieldElement>,
+    last_result: DebugCommandResult,
+}
+
+impl<'a, B: BlackBoxFunctionSolver<FieldElement>> JsonServer<'a, B> {
+    fn handle_request(&mut self, request: ServerRequest) -> Value {
+        let result = match request {
+            ServerRequest::Step => self.context.step_into_opcode(),
+            ServerRequest::Next => self.context.next_into(),
+            ServerRequest::Over => self.context.next_over(),
+            ServerRequest::Out => self.context.next_out(),
+            ServerRequest::Continue => self.context.cont(),
+            ServerRequest::Break { location } => return self.set_breakpoint(&location, true),
+            ServerRequest::Delete { location } => return self.set_breakpoint(&location, false),
+            ServerRequest::Status => return self.status(),
+            ServerRequest::Vars => return self.variables(),
+            ServerRequest::Witness => return self.witness_map(),
+            ServerRequest::Stacktrace => return self.stack_trace(),
+            ServerRequest::Restart => {
+                self.context.restart(self.initial_witness.clone());
+                self.last_result = DebugCommandResult::Ok;
+                return self.status();
+            }
+            ServerRequest::Quit => return json!({ "status": "ok" }),
+        };
+        self.last_result = result;
+        self.status()
+    }
+
+    fn set_breakpoint(&mut self, location: &str, enabled: bool) -> Value {
+        let location = match location.parse::<OpcodeLocation>() {
+            Ok(location) if self.context.is_valid_opcode_location(&location) => location,
+            _ => return error_response(format!("Invalid opcode location {location}")),
+        };
+        let changed = if enabled {
+            self.context.add_breakpoint(location)
+        } else {
+            self.context.delete_breakpoint(&location)
+        };
+        json!({ "status": "ok", "changed": changed })
+    }
+
+    fn status(&self) -> Value {
+        if self.context.get_current_opcode_location().is_none() {
+            return json!({ "status": "finished", "solved": self.context.is_solved() });
+        }
+        let location = self.current_location();
+        match &self.last_result {
+            DebugCommandResult::Error(error) => {
+                json!({ "status": "failed", "error": error.to_string(), "location": location })
+            }
+            DebugCommandResult::BreakpointReached(_) => {
+                json!({ "status": "stopped", "reason": "breakpoint", "location": location })
+            }
+            _ => json!({ "status": "stopped", "reason": "step", "location": location }),
+        }
+    }
+
+    fn current_location(&self) -> Value {
+        let source = self
+            .context
+            .get_current_source_location()
+            .and_then(|locations| locations.last().copied())
+            .map(|location| {
+                format_location_path(self.debug_artifact, &PathSubstitutions::default(), location)
+            });
+        json!({
+            "opcode": self.context.get_current_opcode_location().map(|location| location.to_string()),
+            "source": source,
+        })
+    }
+
+    fn variables(&self) -> Value {
+        let values: BTreeMap<_, _> = self
+            .context
+            .current_stack_frame()
+            .map(|frame| {
+                frame
+                    .variables
+                    .iter()
+                    .map(|(name, value, typ)| {
+                        let value = PrintableValueDisplay::Plain((*value).clone(), (*typ).clone());
+                        (name.to_string(), value.to_string())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        json!({ "status": "ok", "variables": values })
+    }
+
+    fn witness_map(&self) -> Value {
+        let values: BTreeMap<_, _> = self
+            .context
+            .get_witness_map()
+            .clone()
+            .into_iter()
+            .map(|(witness, value)| (format!("_{}", witness.witness_index()), value.to_string()))
+            .collect();
+        json!({ "status": "ok", "witnesses": values })
+    }
+
+    fn stack_trace(&self) -> Value {
+        let frames: Vec<_> = self
+            .context
+            .get_source_call_stack()
+            .iter()
+            .rev()
+            .map(|(opcode_location, source_location)| {
+                json!({
+                    "opcode": opcode_location.to_string(),
+                    "source": format_location_path(
+                        self.debug_artifact,
+                        &PathSubstitutions::default(),
+                        *source_location,
+                    ),
+                })
+            })
+            .collect();
+        json!({ "status": "ok", "frames": frames })
+    }
+}
+
+fn error_response(message: String) -> Value {
+    json!({ "status": "error", "message": message })
+}
+
+/// Serves a debugging session over a line-based JSON protocol: every line
+/// read from `input` holds a request, answered by a line written to
+/// `output`. The program's own output is not shown, as it would be mixed
+/// with the responses. Returns when `input` ends or on a `quit` request.
+pub(super) fn run<R: BufRead, W: Write, B: BlackBoxFunctionSolver<FieldElement>>(
+    input: R,
+    mut output: W,
+    solver: &B,
+    circuit: &Circuit<FieldElement>,
+    debug_artifact: &DebugArtifact,
+    initial_witness: WitnessMap<FieldElement>,
+    unconstrained_functions: &[BrilligBytecode<FieldElement>],
+) -> std::io::Result<()> {
+    let context = DebugContext::new(
+        solver,
+        circuit,
+        debug_artifact,
+        initial_witness.clone(),
+        Box::new(DefaultDebugForeignCallExecutor::from_artifact(false, debug_artifact)),
+        unconstrained_functions,
+    );
+    let mut server = JsonServer {
+        context,
+        debug_artifact,
+        initial_witness,
+        last_result: DebugCommandResult::Ok,
+    };
+
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, quit) = match serde_json::from_str::<ServerRequest>(&line) {
+            Ok(request) => {
+                let quit = matches!(request, ServerRequest::Quit);
+                (server.handle_request(request), quit)
+            }
+            Err(error) => (error_response(format!("Invalid request: {error}")), false),
+        };
+        writeln!(output, "{response}")?;
+        output.flush()?;
+        if quit {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use acvm::blackbox_solver::StubbedBlackBoxSolver;
+
+    #[test]
+    fn answers_each_request_with_a_line() {
+        let circuit = &Circuit::default();
+        let debug_artifact = &DebugArtifact { debug_symbols: vec![], file_map: BTreeMap::new() };
+        let input = [
+            r#"{"command": "status"}"#,
+            r#"{"command": "break", "location": "7"}"#,
+            "not json",
+            r#"{"command": "quit"}"#,
+            r#"{"command": "status"}"#,
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+        run(
+            input.as_bytes(),
+            &mut output,
+            &StubbedBlackBoxSolver,
+            circuit,
+            debug_artifact,
+            WitnessMap::new(),
+            &[],
+        )
+        .unwrap();
+
+        let responses: Vec<Value> = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 4);
+        assert_eq!(responses[0], json!({ "status": "finished", "solved": true }));
+        assert_eq!(responses[1]["message"], "Invalid opcode location 7");
+        assert_eq!(responses[2]["status"], "error");
+        assert_eq!(responses[3], json!({ "status": "ok" }));
+    }
+}
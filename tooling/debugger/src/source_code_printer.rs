@@ -1,7 +1,9 @@
+use crate::source_paths::PathSubstitutions;
 use codespan_reporting::files::Files;
 use noirc_artifacts::debug::DebugArtifact;
 use noirc_errors::Location;
 use owo_colors::OwoColorize;
+use std::fmt::Display;
 use std::ops::Range;
 
 #[derive(Debug, PartialEq)]
@@ -29,61 +31,125 @@ struct LocationPrintContext {
 
 // Given a DebugArtifact and an OpcodeLocation, prints all the source code
 // locations the OpcodeLocation maps to, with some surrounding context and
-// visual aids to highlight the location itself.
-pub(super) fn print_source_code_location(debug_artifact: &DebugArtifact, locations: &[Location]) {
+// visual aids to highlight the location itself. Text outside the location is
+// dimmed when `colors` is set. When `accessible` is set, the visual aids are
+// replaced by textual descriptions suited to screen readers. File paths are
+// shown after `substitutions`.
+pub(super) fn print_source_code_location(
+    debug_artifact: &DebugArtifact,
+    substitutions: &PathSubstitutions,
+    locations: &[Location],
+    colors: bool,
+    accessible: bool,
+) {
     let locations = locations.iter();
 
     for loc in locations {
-        print_location_path(debug_artifact, *loc);
+        print_location_path(debug_artifact, substitutions, *loc);
 
         let lines = render_location(debug_artifact, loc);
 
         for line in lines {
             match line {
                 PrintedLine::Skip => {}
-                PrintedLine::Ellipsis { line_number } => print_ellipsis(line_number),
+                PrintedLine::Ellipsis { .. } if accessible => {}
+                PrintedLine::Ellipsis { line_number } => print_ellipsis(line_number, colors),
+                PrintedLine::Content { line_number, cursor, content, highlight } if accessible => {
+                    println!(
+                        "{}",
+                        format_content_accessible(line_number, cursor, content, highlight)
+                    )
+                }
                 PrintedLine::Content { line_number, cursor, content, highlight } => {
-                    print_content(line_number, cursor, content, highlight)
+                    print_content(line_number, cursor, content, highlight, colors)
                 }
             }
         }
     }
 }
 
-fn print_location_path(debug_artifact: &DebugArtifact, loc: Location) {
+fn print_location_path(
+    debug_artifact: &DebugArtifact,
+    substitutions: &PathSubstitutions,
+    loc: Location,
+) {
+    println!("At {}", format_location_path(debug_artifact, substitutions, loc));
+}
+
+/// Formats a source location as `path:line:column`, with the path rewritten
+/// by `substitutions`
+pub(super) fn format_location_path(
+    debug_artifact: &DebugArtifact,
+    substitutions: &PathSubstitutions,
+    loc: Location,
+) -> String {
     let line_number = debug_artifact.location_line_number(loc).unwrap();
     let column_number = debug_artifact.location_column_number(loc).unwrap();
+    let path = substitutions.apply(&debug_artifact.file_map[&loc.file].path);
 
-    println!("At {}:{line_number}:{column_number}", debug_artifact.name(loc.file).unwrap());
+    format!("{}:{line_number}:{column_number}", path.display())
 }
 
-fn print_ellipsis(line_number: usize) {
-    println!("{:>3} {:2} {}", line_number.dimmed(), "", "...".dimmed());
+fn dimmed(text: impl Display, colors: bool) -> String {
+    if colors {
+        text.dimmed().to_string()
+    } else {
+        text.to_string()
+    }
 }
 
-fn print_content(line_number: usize, cursor: &str, content: &str, highlight: Option<Range<usize>>) {
+fn print_ellipsis(line_number: usize, colors: bool) {
+    println!("{} {:2} {}", dimmed(format!("{line_number:>3}"), colors), "", dimmed("...", colors));
+}
+
+fn print_content(
+    line_number: usize,
+    cursor: &str,
+    content: &str,
+    highlight: Option<Range<usize>>,
+    colors: bool,
+) {
     match highlight {
         Some(highlight) => {
             println!(
                 "{:>3} {:2} {}{}{}",
                 line_number,
                 cursor,
-                content[0..highlight.start].to_string().dimmed(),
+                dimmed(&content[0..highlight.start], colors),
                 &content[highlight.start..highlight.end],
-                content[highlight.end..].to_string().dimmed(),
+                dimmed(&content[highlight.end..], colors),
             );
         }
         None => {
             println!(
-                "{:>3} {:2} {}",
-                line_number.dimmed(),
-                cursor.dimmed(),
-                content.to_string().dimmed(),
+                "{} {} {}",
+                dimmed(format!("{line_number:>3}"), colors),
+                dimmed(format!("{cursor:2}"), colors),
+                dimmed(content, colors),
             );
         }
     }
 }
 
+/// Formats a line of source code describing in words whether it is the line
+/// where the current location starts, or part of the location
+fn format_content_accessible(
+    line_number: usize,
+    cursor: &str,
+    content: &str,
+    highlight: Option<Range<usize>>,
+) -> String {
+    match highlight {
+        Some(highlight) if !cursor.is_empty() => format!(
+            "line {line_number}, current line: {}\ncurrent code: {}",
+            content.trim(),
+            content[highlight].trim()
+        ),
+        Some(_) => format!("line {line_number}, in current location: {}", content.trim()),
+        None => format!("line {line_number}: {}", content.trim()),
+    }
+}
+
 fn render_line(
     current: usize,
     content: &str,
@@ -220,8 +286,8 @@ fn render_location<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::source_code_printer::render_location;
     use crate::source_code_printer::PrintedLine::Content;
+    use crate::source_code_printer::{format_content_accessible, render_location};
     use acvm::acir::circuit::OpcodeLocation;
     use fm::FileManager;
     use noirc_artifacts::debug::DebugArtifact;
@@ -274,6 +340,7 @@ mod tests {
             BTreeMap::default(),
             BTreeMap::default(),
             BTreeMap::default(),
+            BTreeMap::default(),
         )];
         let debug_artifact = DebugArtifact::new(debug_symbols, &fm);
 
@@ -312,4 +379,17 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn format_accessible_lines() {
+        assert_eq!(
+            format_content_accessible(2, "->", "    state = permute(", Some(12..20)),
+            "line 2, current line: state = permute(\ncurrent code: permute("
+        );
+        assert_eq!(
+            format_content_accessible(3, "", "        consts::x5_2_config(),", Some(0..30)),
+            "line 3, in current location: consts::x5_2_config(),"
+        );
+        assert_eq!(format_content_accessible(6, "", "    state", None), "line 6: state");
+    }
 }
@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+
+/// Rewrites of source path prefixes, like gdb's `set substitute-path`. They
+/// map the virtual paths of sources embedded in the compiler, such as the
+/// standard library's `std/...`, to files on disk that editors can open.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathSubstitutions {
+    rules: Vec<(PathBuf, PathBuf)>,
+}
+
+impl PathSubstitutions {
+    /// Adds a rule rewriting paths under `from` to be under `to`, replacing
+    /// any existing rule for `from`
+    pub fn add(&mut self, from: impl Into<PathBuf>, to: impl Into<PathBuf>) {
+        let (from, to) = (from.into(), to.into());
+        match self.rules.iter_mut().find(|(rule_from, _)| *rule_from == from) {
+            Some(rule) => rule.1 = to,
+            None => self.rules.push((from, to)),
+        }
+    }
+
+    /// Parses a `FROM=TO` rule, as given on the command line
+    pub fn add_rule(&mut self, rule: &str) -> Result<(), String> {
+        match rule.split_once('=') {
+            Some((from, to)) if !from.is_empty() && !to.is_empty() => {
+                self.add(from, to);
+                Ok(())
+            }
+            _ => Err(format!("invalid path substitution {rule}; expected FROM=TO")),
+        }
+    }
+
+    /// Removes the rule for `from`, returning whether there was one
+    pub fn remove(&mut self, from: &Path) -> bool {
+        let len = self.rules.len();
+        self.rules.retain(|(rule_from, _)| rule_from != from);
+        self.rules.len() != len
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &Path)> {
+        self.rules.iter().map(|(from, to)| (from.as_path(), to.as_path()))
+    }
+
+    /// Maps a path as found in the debug artifact to a path on disk, using
+    /// the first rule whose prefix matches
+    pub fn apply(&self, path: &Path) -> PathBuf {
+        Self::rewrite(self.iter(), path)
+    }
+
+    /// Maps a path on disk back to the path found in the debug artifact
+    pub fn reverse(&self, path: &Path) -> PathBuf {
+        Self::rewrite(self.iter().map(|(from, to)| (to, from)), path)
+    }
+
+    fn rewrite<'a>(mut rules: impl Iterator<Item = (&'a Path, &'a Path)>, path: &Path) -> PathBuf {
+        rules
+            .find_map(|(from, to)| path.strip_prefix(from).ok().map(|rest| to.join(rest)))
+            .unwrap_or_else(|| path.to_path_buf())
+    }
+}
+
+/// Outcome of looking up a file of the program by a path given by the user
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum FileMatch<T> {
+    Found(T),
+    NotFound,
+    /// Paths of all the files matching, sorted
+    Ambiguous(Vec<PathBuf>),
+}
+
+/// Finds the file a path given by the user refers to among `files`, as found
+/// in the debug artifact. The path may be the whole path, its trailing whole
+/// components (eg. `src/main.nr` or `main.nr`), or else any suffix of it, as
+/// long as it matches a single file. Separators are compared regardless of
+/// the OS the program was compiled on.
+pub(super) fn find_source_file<'f, T: Copy>(
+    files: impl IntoIterator<Item = (T, &'f Path)>,
+    path: &str,
+) -> FileMatch<T> {
+    let normalize = |path: &str| path.replace('\\', "/");
+    let query = normalize(path);
+    let query = query.trim_start_matches("./");
+    if query.is_empty() {
+        return FileMatch::NotFound;
+    }
+    let component_suffix = format!("/{query}");
+
+    let files: Vec<_> = files
+        .into_iter()
+        .map(|(file, path)| (file, path, normalize(&path.to_string_lossy())))
+        .collect();
+    let matching = |matches: &dyn Fn(&str) -> bool| -> Vec<_> {
+        files.iter().filter(|(_, _, normalized)| matches(normalized)).collect()
+    };
+    let mut candidates = matching(&|normalized| normalized == query);
+    if candidates.is_empty() {
+        candidates = matching(&|normalized| normalized.ends_with(&component_suffix));
+    }
+    if candidates.is_empty() {
+        candidates = matching(&|normalized| normalized.ends_with(query));
+    }
+    match candidates.as_slice() {
+        [] => FileMatch::NotFound,
+        [(file, _, _)] => FileMatch::Found(*file),
+        _ => {
+            let mut paths: Vec<_> =
+                candidates.iter().map(|(_, path, _)| path.to_path_buf()).collect();
+            paths.sort();
+            FileMatch::Ambiguous(paths)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_paths_under_a_prefix() {
+        let mut substitutions = PathSubstitutions::default();
+        substitutions.add_rule("std=/opt/noir/noir_stdlib/src").unwrap();
+
+        let virtual_path = Path::new("std/hash/mod.nr");
+        let disk_path = PathBuf::from("/opt/noir/noir_stdlib/src/hash/mod.nr");
+        assert_eq!(substitutions.apply(virtual_path), disk_path);
+        assert_eq!(substitutions.reverse(&disk_path), virtual_path);
+
+        // Only whole path components are matched
+        assert_eq!(substitutions.apply(Path::new("stdx/lib.nr")), Path::new("stdx/lib.nr"));
+
+        assert!(substitutions.add_rule("std").is_err());
+        assert!(substitutions.remove(Path::new("std")));
+        assert_eq!(substitutions.apply(virtual_path), virtual_path);
+    }
+
+    #[test]
+    fn finds_source_files_by_partial_paths() {
+        let paths = [
+            Path::new("/home/user/project/src/main.nr"),
+            Path::new("/home/user/project/src/utils.nr"),
+            Path::new("/home/user/project/lib/src/utils.nr"),
+            Path::new("std/hash/mod.nr"),
+        ];
+        let find = |path| find_source_file(paths.iter().copied().enumerate(), path);
+
+        assert_eq!(find("/home/user/project/src/main.nr"), FileMatch::Found(0));
+        assert_eq!(find("src/main.nr"), FileMatch::Found(0));
+        assert_eq!(find("./main.nr"), FileMatch::Found(0));
+        assert_eq!(find("src\\main.nr"), FileMatch::Found(0));
+        assert_eq!(find("ain.nr"), FileMatch::Found(0));
+        assert_eq!(find("project/src/utils.nr"), FileMatch::Found(1));
+        assert_eq!(find("hash/mod.nr"), FileMatch::Found(3));
+        assert_eq!(
+            find("src/utils.nr"),
+            FileMatch::Ambiguous(vec![paths[2].to_path_buf(), paths[1].to_path_buf()])
+        );
+        assert_eq!(find("other.nr"), FileMatch::NotFound);
+        assert_eq!(find(""), FileMatch::NotFound);
+    }
+}
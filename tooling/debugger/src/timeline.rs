@@ -0,0 +1,241 @@
+use std::collections::VecDeque;
+
+use acvm::acir::circuit::OpcodeLocation;
+use serde::{Deserialize, Serialize};
+
+/// Version of the schema of `noir/timeline` responses, raised on changes that
+/// are not backwards compatible
+pub(super) const TIMELINE_SCHEMA_VERSION: u32 = 1;
+
+/// Number of events returned by a `noir/timeline` request not giving a count
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Number of events of the program's execution kept in the timeline, the
+/// oldest ones being dropped to make room for new ones
+const MAX_TIMELINE_EVENTS: usize = 100_000;
+
+/// Something that happened during the session, to show in the timeline of
+/// the execution
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct TimelineEvent {
+    /// Number of steps taken when the event happened, counting the step that
+    /// caused it
+    pub(super) step: usize,
+    pub(super) location: Option<OpcodeLocation>,
+    pub(super) kind: TimelineEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub(super) enum TimelineEventKind {
+    /// Execution stopped and control went back to the user
+    Stop {
+        reason: &'static str,
+    },
+    /// Foreign call of the program, not counting the debugger's oracles
+    ForeignCall {
+        function: String,
+    },
+    BlackBox {
+        name: &'static str,
+        duration_micros: u64,
+    },
+    /// Evaluation of an assert, whose id is the one shown by `info asserts`
+    Assert {
+        assert_id: usize,
+        failed: bool,
+    },
+}
+
+/// Events of the program's execution, keeping at most `capacity` of the most
+/// recent ones
+#[derive(Debug, Clone)]
+pub(super) struct Timeline {
+    events: VecDeque<TimelineEvent>,
+    capacity: usize,
+    // Number of events dropped since the timeline was last cleared
+    dropped: usize,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self::with_capacity(MAX_TIMELINE_EVENTS)
+    }
+}
+
+impl Timeline {
+    pub(super) fn with_capacity(capacity: usize) -> Self {
+        Self { events: VecDeque::new(), capacity, dropped: 0 }
+    }
+
+    pub(super) fn push(&mut self, event: TimelineEvent) {
+        if self.events.len() == self.capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.events.clear();
+        self.dropped = 0;
+    }
+
+    /// Returns the events kept, from the oldest to the most recent
+    pub(super) fn events(&self) -> &VecDeque<TimelineEvent> {
+        &self.events
+    }
+
+    pub(super) fn events_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut TimelineEvent> {
+        self.events.iter_mut()
+    }
+
+    /// Returns the number of events dropped to make room for newer ones
+    pub(super) fn dropped(&self) -> usize {
+        self.dropped
+    }
+}
+
+/// Arguments of a `noir/timeline` request
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TimelineArguments {
+    pub(super) start_index: Option<usize>,
+    pub(super) count: Option<usize>,
+}
+
+/// Body of the response to a `noir/timeline` request
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TimelinePage<'e> {
+    pub(super) version: u32,
+    /// Index of the oldest event kept, older ones having been dropped
+    pub(super) first_index: usize,
+    pub(super) total_events: usize,
+    pub(super) events: Vec<TimelineEntry<'e>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct TimelineEntry<'e> {
+    /// Position of the event in the whole timeline
+    pub(super) index: usize,
+    pub(super) step: usize,
+    /// Opcode location, as in the instruction references of the disassembly
+    pub(super) address: Option<String>,
+    pub(super) source: Option<TimelineSource>,
+    #[serde(flatten)]
+    pub(super) kind: &'e TimelineEventKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(super) struct TimelineSource {
+    pub(super) path: String,
+    pub(super) line: usize,
+    pub(super) column: usize,
+}
+
+/// Merges the events of the program's execution with the stops of the
+/// session, both in the order they happened. A stop comes after the events of
+/// the step it stopped at.
+pub(super) fn merge_timeline<'e>(
+    execution: &'e VecDeque<TimelineEvent>,
+    stops: &'e [TimelineEvent],
+) -> Vec<&'e TimelineEvent> {
+    let mut merged = Vec::with_capacity(execution.len() + stops.len());
+    let mut stops = stops.iter().peekable();
+    for event in execution {
+        while let Some(stop) = stops.next_if(|stop| stop.step < event.step) {
+            merged.push(stop);
+        }
+        merged.push(event);
+    }
+    merged.extend(stops);
+    merged
+}
+
+/// Returns the range of the events of the timeline the arguments ask for,
+/// among the ones kept, from `first` up to `total`
+pub(super) fn page_range(
+    arguments: &TimelineArguments,
+    first: usize,
+    total: usize,
+) -> std::ops::Range<usize> {
+    let start = arguments.start_index.unwrap_or(first).clamp(first, total);
+    let count = arguments.count.unwrap_or(DEFAULT_PAGE_SIZE);
+    start..start.saturating_add(count).min(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(step: usize, kind: TimelineEventKind) -> TimelineEvent {
+        TimelineEvent { step, location: Some(OpcodeLocation::Acir(step)), kind }
+    }
+
+    #[test]
+    fn merges_stops_after_the_events_of_their_step() {
+        let execution = VecDeque::from([
+            event(2, TimelineEventKind::ForeignCall { function: "get_value".into() }),
+            event(5, TimelineEventKind::Assert { assert_id: 1, failed: false }),
+        ]);
+        let stops = [
+            event(0, TimelineEventKind::Stop { reason: "entry" }),
+            event(2, TimelineEventKind::Stop { reason: "step" }),
+            event(7, TimelineEventKind::Stop { reason: "breakpoint" }),
+        ];
+        let merged: Vec<_> =
+            merge_timeline(&execution, &stops).into_iter().map(|event| event.step).collect();
+        assert_eq!(merged, vec![0, 2, 2, 5, 7]);
+        assert_eq!(merge_timeline(&execution, &stops)[2].kind, stops[1].kind);
+    }
+
+    #[test]
+    fn pages_are_clamped_to_the_timeline() {
+        let arguments = TimelineArguments { start_index: Some(3), count: Some(10) };
+        assert_eq!(page_range(&arguments, 0, 8), 3..8);
+        assert_eq!(page_range(&arguments, 0, 2), 2..2);
+        assert_eq!(page_range(&arguments, 5, 8), 5..8);
+        assert_eq!(page_range(&TimelineArguments::default(), 0, 250), 0..DEFAULT_PAGE_SIZE);
+        assert_eq!(page_range(&TimelineArguments::default(), 40, 50), 40..50);
+    }
+
+    #[test]
+    fn drops_the_oldest_events_past_its_capacity() {
+        let mut timeline = Timeline::with_capacity(2);
+        for step in 1..=3 {
+            timeline.push(event(step, TimelineEventKind::ForeignCall { function: "f".into() }));
+        }
+        assert_eq!(timeline.events().iter().map(|event| event.step).collect::<Vec<_>>(), [2, 3]);
+        assert_eq!(timeline.dropped(), 1);
+
+        timeline.clear();
+        assert!(timeline.events().is_empty());
+        assert_eq!(timeline.dropped(), 0);
+    }
+
+    #[test]
+    fn entries_follow_the_schema() {
+        let kind = TimelineEventKind::Assert { assert_id: 3, failed: true };
+        let entry = TimelineEntry {
+            index: 4,
+            step: 12,
+            address: Some("1.5".into()),
+            source: Some(TimelineSource { path: "src/main.nr".into(), line: 7, column: 5 }),
+            kind: &kind,
+        };
+        assert_eq!(
+            serde_json::to_value(entry).unwrap(),
+            serde_json::json!({
+                "index": 4,
+                "step": 12,
+                "address": "1.5",
+                "source": { "path": "src/main.nr", "line": 7, "column": 5 },
+                "kind": "assert",
+                "assertId": 3,
+                "failed": true,
+            })
+        );
+    }
+}
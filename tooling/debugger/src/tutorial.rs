@@ -0,0 +1,118 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+const TUTORIAL_NARGO_TOML: &str = include_str!("../tutorial/Nargo.toml");
+const TUTORIAL_PROVER_TOML: &str = include_str!("../tutorial/Prover.toml");
+const TUTORIAL_MAIN_NR: &str = include_str!("../tutorial/src/main.nr");
+
+/// Writes the example program walked through by the tutorial into the given
+/// directory, so that it can be compiled as a regular package.
+pub fn write_tutorial_program(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(dir.join("Nargo.toml"), TUTORIAL_NARGO_TOML)?;
+    fs::write(dir.join("Prover.toml"), TUTORIAL_PROVER_TOML)?;
+    fs::write(dir.join("src").join("main.nr"), TUTORIAL_MAIN_NR)
+}
+
+struct TutorialStep {
+    // REPL command the user is asked to run
+    command: &'static str,
+    instructions: &'static str,
+    // Shown when the command was run but did not have the expected effect
+    retry_hint: &'static str,
+}
+
+const TUTORIAL_STEPS: &[TutorialStep] = &[
+    TutorialStep {
+        command: "step",
+        instructions: "Type `step` to execute the next ACIR opcode. The debugger shows the \
+            opcode it stopped at and the source code it was compiled from.",
+        retry_hint: "",
+    },
+    TutorialStep {
+        command: "next",
+        instructions: "Type `next` to keep executing until a new source location is reached.",
+        retry_hint: "",
+    },
+    TutorialStep {
+        command: "vars",
+        instructions: "Type `vars` to show the variables in scope and their current values.",
+        retry_hint: "",
+    },
+    TutorialStep {
+        command: "witness",
+        instructions: "Type `witness` to show the witness map: the values solved so far for \
+            the circuit, starting with the inputs `x` and `y`.",
+        retry_hint: "",
+    },
+    TutorialStep {
+        command: "break",
+        instructions: "Type `opcodes` to list the opcodes of the circuit, then set a breakpoint \
+            on an opcode after the current one with `break <opcode>` (eg. `break 0.40`).",
+        retry_hint: "No breakpoint was set; pick a location listed by `opcodes`.",
+    },
+    TutorialStep {
+        command: "continue",
+        instructions: "Type `continue` to run until the breakpoint is reached.",
+        retry_hint: "Execution did not stop at a breakpoint. Type `restart` to start over \
+            (breakpoints are kept) and `continue` again.",
+    },
+];
+
+/// Guided walk-through of the core debugger commands, driven by the REPL
+/// reporting each command it executes.
+pub(super) struct Tutorial {
+    current_step: usize,
+}
+
+impl Tutorial {
+    pub(super) fn new() -> Self {
+        Self { current_step: 0 }
+    }
+
+    pub(super) fn start(&self) {
+        println!("Welcome to the Noir debugger tutorial!");
+        println!("You are debugging a small example program. Follow the instructions below;");
+        println!("type `help` at any time to see all the available commands.");
+        self.show_instructions();
+    }
+
+    fn show_instructions(&self) {
+        if let Some(step) = TUTORIAL_STEPS.get(self.current_step) {
+            println!();
+            println!(
+                "[tutorial {}/{}] {}",
+                self.current_step + 1,
+                TUTORIAL_STEPS.len(),
+                step.instructions
+            );
+        }
+    }
+
+    /// Advances the tutorial when `command` is the one expected by the current
+    /// step and `succeeded` tells it had the expected effect.
+    pub(super) fn command_executed(&mut self, command: &str, succeeded: bool) {
+        let Some(step) = TUTORIAL_STEPS.get(self.current_step) else {
+            return;
+        };
+        if step.command != command {
+            return;
+        }
+        if !succeeded {
+            println!("[tutorial] {}", step.retry_hint);
+            return;
+        }
+
+        self.current_step += 1;
+        if self.current_step < TUTORIAL_STEPS.len() {
+            println!("[tutorial] Well done!");
+            self.show_instructions();
+        } else {
+            println!("[tutorial] Congratulations, you completed the tutorial!");
+            println!(
+                "Keep exploring the program, type `help` for more commands or `quit` to exit."
+            );
+        }
+    }
+}
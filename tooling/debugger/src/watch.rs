@@ -0,0 +1,77 @@
+use crate::interrupt;
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, SystemTime};
+
+// How often files are checked while waiting for a change
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Detects changes to a set of source files by polling their modification times.
+/// Files which can't be read, such as the standard library sources embedded in
+/// the compiler, are never reported as changed unless they are created.
+pub struct SourceWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl SourceWatcher {
+    pub fn new(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let modified = modified_time(&path);
+                (path, modified)
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Returns whether any of the files changed since the previous check
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, last_modified) in self.files.iter_mut() {
+            let modified = modified_time(path);
+            if modified != *last_modified {
+                *last_modified = modified;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Blocks until any of the files changes, returning whether one did. Once
+    /// a debugging session installed its Ctrl-C handler, pressing Ctrl-C
+    /// stops waiting and returns `false`.
+    pub fn wait_for_change(&mut self) -> bool {
+        interrupt::clear();
+        while !self.changed() {
+            if interrupt::take() {
+                return false;
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        true
+    }
+
+    /// Polls the files from a background thread until `stop` is raised. When
+    /// any of them changes, the running command is interrupted and the
+    /// thread ends.
+    pub(crate) fn watch_in_background(mut self, stop: Arc<AtomicBool>) -> JoinHandle<()> {
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::SeqCst) {
+                if self.changed() {
+                    interrupt::raise_sources_changed();
+                    println!("\nSource files changed; the debugging session restarts after the current command");
+                    return;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        })
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
@@ -3,10 +3,24 @@ use acvm::{
     pwg::ForeignCallWaitInfo,
     AcirField,
 };
-use jsonrpc::{arg as build_json_rpc_arg, minreq_http::Builder, Client};
+use jsonrpc::{arg as build_json_rpc_arg, minreq_http::Builder, Client, Transport};
 use noirc_printable_type::{decode_string_value, ForeignCallError, PrintableValueDisplay};
 use rand::Rng;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Where the output of [`ForeignCall::Print`] calls goes
+#[derive(Debug, Clone, Default)]
+pub enum PrintOutput {
+    /// Output is discarded
+    #[default]
+    None,
+    Stdout,
+    /// Output is appended to a buffer shared with the caller, for hosts whose
+    /// stdout is taken, such as a debug adapter talking over it
+    Buffer(Arc<Mutex<String>>),
+}
 
 pub trait ForeignCallExecutor<F> {
     fn execute(
@@ -108,8 +122,8 @@ pub struct DefaultForeignCallExecutor<F> {
     last_mock_id: usize,
     /// The registered mocks
     mocked_responses: Vec<MockedCall<F>>,
-    /// Whether to print [`ForeignCall::Print`] output.
-    show_output: bool,
+    /// Where to write [`ForeignCall::Print`] output.
+    output: PrintOutput,
     /// JSON RPC client to resolve foreign calls
     external_resolver: Option<Client>,
 }
@@ -128,22 +142,106 @@ struct ResolveForeignCallRequest<F> {
     function_call: ForeignCallWaitInfo<F>,
 }
 
+/// How to reach the external oracle resolver
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OracleResolverOptions {
+    pub url: String,
+    pub timeout: Option<Duration>,
+    /// Extra HTTP headers sent with every request, eg. to authenticate
+    pub headers: Vec<(String, String)>,
+}
+
+impl OracleResolverOptions {
+    /// Options for the resolver at `url`, with the timeout in milliseconds
+    /// given by `NARGO_FOREIGN_CALL_TIMEOUT`, if set
+    pub fn from_url(url: &str) -> Self {
+        let timeout = std::env::var("NARGO_FOREIGN_CALL_TIMEOUT")
+            .ok()
+            .and_then(|timeout| timeout.parse().ok())
+            .map(Duration::from_millis);
+        OracleResolverOptions { url: url.to_string(), timeout, headers: vec![] }
+    }
+
+    fn build_client(&self) -> Client {
+        if !self.headers.is_empty() {
+            return Client::with_transport(HttpTransportWithHeaders(self.clone()));
+        }
+        let mut transport_builder =
+            Builder::new().url(&self.url).expect("Invalid oracle resolver URL");
+        if let Some(timeout) = self.timeout {
+            transport_builder = transport_builder.timeout(timeout);
+        }
+        Client::with_transport(transport_builder.build())
+    }
+}
+
+/// JSON-RPC transport over HTTP sending the extra headers of the resolver
+/// options, which the transport of `jsonrpc` has no way to add
+struct HttpTransportWithHeaders(OracleResolverOptions);
+
+impl HttpTransportWithHeaders {
+    fn post<T: Serialize, R: DeserializeOwned>(&self, body: &T) -> Result<R, jsonrpc::Error> {
+        let transport_error = |error: minreq::Error| jsonrpc::Error::Transport(Box::new(error));
+        let options = &self.0;
+        let mut request = minreq::post(&options.url).with_json(body).map_err(transport_error)?;
+        for (name, value) in &options.headers {
+            request = request.with_header(name, value);
+        }
+        let Some(timeout) = options.timeout else {
+            return request.send().map_err(transport_error)?.json().map_err(transport_error);
+        };
+        // minreq only takes whole seconds, so its timeout is rounded up to
+        // end the request eventually, while the response is waited for on
+        // another thread for exactly the given timeout
+        let seconds = timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0);
+        let request = request.with_timeout(seconds.max(1));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = sender.send(request.send());
+        });
+        let response = receiver.recv_timeout(timeout).map_err(|_| {
+            let error = std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                format!("oracle resolver did not answer within {timeout:?}"),
+            );
+            jsonrpc::Error::Transport(Box::new(error))
+        })?;
+        response.map_err(transport_error)?.json().map_err(transport_error)
+    }
+}
+
+impl Transport for HttpTransportWithHeaders {
+    fn send_request(&self, request: jsonrpc::Request) -> Result<jsonrpc::Response, jsonrpc::Error> {
+        self.post(&request)
+    }
+
+    fn send_batch(
+        &self,
+        requests: &[jsonrpc::Request],
+    ) -> Result<Vec<jsonrpc::Response>, jsonrpc::Error> {
+        self.post(&requests)
+    }
+
+    fn fmt_target(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0.url)
+    }
+}
+
 impl<F> DefaultForeignCallExecutor<F> {
     pub fn new(show_output: bool, resolver_url: Option<&str>) -> Self {
-        let oracle_resolver = resolver_url.map(|resolver_url| {
-            let mut transport_builder =
-                Builder::new().url(resolver_url).expect("Invalid oracle resolver URL");
-
-            if let Some(Ok(timeout)) =
-                std::env::var("NARGO_FOREIGN_CALL_TIMEOUT").ok().map(|timeout| timeout.parse())
-            {
-                let timeout_duration = std::time::Duration::from_millis(timeout);
-                transport_builder = transport_builder.timeout(timeout_duration);
-            };
-            Client::with_transport(transport_builder.build())
-        });
+        let output = if show_output { PrintOutput::Stdout } else { PrintOutput::None };
+        Self::with_output(output, resolver_url)
+    }
+
+    pub fn with_output(output: PrintOutput, resolver_url: Option<&str>) -> Self {
+        let resolver = resolver_url.map(OracleResolverOptions::from_url);
+        Self::with_resolver(output, resolver.as_ref())
+    }
+
+    pub fn with_resolver(output: PrintOutput, resolver: Option<&OracleResolverOptions>) -> Self {
+        let oracle_resolver = resolver.map(OracleResolverOptions::build_client);
         DefaultForeignCallExecutor {
-            show_output,
+            output,
             external_resolver: oracle_resolver,
             id: rand::thread_rng().gen(),
             mocked_responses: Vec::new(),
@@ -177,14 +275,23 @@ impl<F: AcirField> DefaultForeignCallExecutor<F> {
         decode_string_value(&fields)
     }
 
-    fn execute_print(foreign_call_inputs: &[ForeignCallParam<F>]) -> Result<(), ForeignCallError> {
+    fn execute_print(
+        foreign_call_inputs: &[ForeignCallParam<F>],
+        output: &PrintOutput,
+    ) -> Result<(), ForeignCallError> {
         let skip_newline = foreign_call_inputs[0].unwrap_field().is_zero();
 
         let foreign_call_inputs =
             foreign_call_inputs.split_first().ok_or(ForeignCallError::MissingForeignCallInputs)?.1;
         let display_string = Self::format_printable_value(foreign_call_inputs, skip_newline)?;
 
-        print!("{display_string}");
+        match output {
+            PrintOutput::None => (),
+            PrintOutput::Stdout => print!("{display_string}"),
+            PrintOutput::Buffer(buffer) => {
+                buffer.lock().expect("print output buffer poisoned").push_str(&display_string);
+            }
+        }
 
         Ok(())
     }
@@ -211,8 +318,8 @@ impl<F: AcirField + Serialize + for<'a> Deserialize<'a>> ForeignCallExecutor<F>
         let foreign_call_name = foreign_call.function.as_str();
         match ForeignCall::lookup(foreign_call_name) {
             Some(ForeignCall::Print) => {
-                if self.show_output {
-                    Self::execute_print(&foreign_call.inputs)?;
+                if !matches!(self.output, PrintOutput::None) {
+                    Self::execute_print(&foreign_call.inputs, &self.output)?;
                 }
                 Ok(ForeignCallResult::default())
             }
@@ -331,13 +438,15 @@ impl<F: AcirField + Serialize + for<'a> Deserialize<'a>> ForeignCallExecutor<F>
 mod tests {
     use acvm::{
         acir::brillig::ForeignCallParam, brillig_vm::brillig::ForeignCallResult,
-        pwg::ForeignCallWaitInfo, FieldElement,
+        pwg::ForeignCallWaitInfo, AcirField, FieldElement,
     };
     use jsonrpc_core::Result as RpcResult;
     use jsonrpc_derive::rpc;
     use jsonrpc_http_server::{Server, ServerBuilder};
 
-    use crate::ops::{DefaultForeignCallExecutor, ForeignCallExecutor};
+    use std::sync::{Arc, Mutex};
+
+    use crate::ops::{DefaultForeignCallExecutor, ForeignCallExecutor, PrintOutput};
 
     use super::ResolveForeignCallRequest;
 
@@ -462,4 +571,28 @@ mod tests {
 
         server.close();
     }
+
+    #[test]
+    fn print_output_is_written_to_buffer() {
+        let buffer = Arc::new(Mutex::new(String::new()));
+        let mut executor = DefaultForeignCallExecutor::<FieldElement>::with_output(
+            PrintOutput::Buffer(buffer.clone()),
+            None,
+        );
+
+        let printable_type =
+            "{\"kind\":\"field\"}".bytes().map(|byte| FieldElement::from(byte as u128));
+        let foreign_call = ForeignCallWaitInfo {
+            function: "print".to_string(),
+            inputs: vec![
+                ForeignCallParam::Single(FieldElement::one()),
+                ForeignCallParam::Single(FieldElement::from(42u128)),
+                ForeignCallParam::Array(printable_type.collect()),
+                ForeignCallParam::Single(FieldElement::zero()),
+            ],
+        };
+        executor.execute(&foreign_call).unwrap();
+
+        assert_eq!(*buffer.lock().unwrap(), "0x2a\n");
+    }
 }
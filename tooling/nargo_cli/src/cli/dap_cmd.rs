@@ -4,28 +4,31 @@ use acvm::FieldElement;
 use bn254_blackbox_solver::Bn254BlackBoxSolver;
 use clap::Args;
 use nargo::constants::PROVER_INPUT_FILE;
+use nargo::package::Package;
 use nargo::workspace::Workspace;
+use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_abi::input_parser::Format;
-use noirc_driver::{CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING};
+use noirc_abi::Abi;
+use noirc_driver::{file_manager_with_stdlib, CompileOptions, NOIR_ARTIFACT_VERSION_STRING};
 use noirc_frontend::graph::CrateName;
+use rayon::prelude::*;
 
-use std::io::{BufReader, BufWriter, Read, Write};
+use std::net::TcpListener;
 use std::path::Path;
 
-use dap::requests::Command;
-use dap::responses::ResponseBody;
-use dap::server::Server;
-use dap::types::Capabilities;
-use serde_json::Value;
-
-use super::debug_cmd::compile_bin_package_for_debugging;
-use super::fs::inputs::read_inputs_from_file;
+use super::debug_cmd::{compile_bin_package_for_debugging, compile_parsed_package};
+use super::fs::inputs::{
+    input_file_format, read_inputs_document_from_stdin, read_inputs_from_document,
+    read_inputs_from_file, STDIN_PROVER_NAME,
+};
 use crate::errors::CliError;
 
 use super::NargoConfig;
 
-use noir_debugger::errors::{DapError, LoadError};
+use noir_debugger::errors::{DapError, DebuggerError, LaunchCandidate, LoadError};
+use noir_debugger::{
+    run_dap_server, DapServerOptions, DapTransport, LaunchArguments, LoadedProgram, OracleSchema,
+};
 
 #[derive(Debug, Clone, Args)]
 pub(crate) struct DapCommand {
@@ -50,6 +53,25 @@ pub(crate) struct DapCommand {
 
     #[clap(long)]
     preflight_skip_instrumentation: bool,
+
+    /// Compile every binary package of the workspace in the preflight check,
+    /// in parallel, instead of only the package to debug
+    #[clap(long)]
+    preflight_all_packages: bool,
+
+    /// Serve DAP sessions to clients connecting to this TCP port instead of
+    /// over stdin and stdout
+    #[clap(long)]
+    port: Option<u16>,
+
+    /// Address to listen on with --port
+    #[clap(long, requires = "port", default_value = "127.0.0.1")]
+    host: String,
+
+    /// With --port, keep accepting clients one session after another instead
+    /// of exiting when the first session ends
+    #[clap(long, requires = "port")]
+    keep_listening: bool,
 }
 
 fn parse_expression_width(input: &str) -> Result<ExpressionWidth, std::io::Error> {
@@ -70,7 +92,7 @@ fn find_workspace(project_folder: &str, package: Option<&str>) -> Option<Workspa
         eprintln!("ERROR: Failed to get package manifest");
         return None;
     };
-    let package = package.and_then(|p| serde_json::from_str::<CrateName>(p).ok());
+    let package = package.and_then(|p| p.parse::<CrateName>().ok());
     let selection = package.map_or(PackageSelection::DefaultOrAll, PackageSelection::Selected);
     match resolve_workspace_from_toml(
         &toml_path,
@@ -95,132 +117,177 @@ fn workspace_not_found_error_msg(project_folder: &str, package: Option<&str>) ->
     }
 }
 
+/// Loads and compiles the package to debug, reading its inputs from the
+/// prover file `prover_name` unless an inputs document is given
 fn load_and_compile_project(
     project_folder: &str,
     package: Option<&str>,
     prover_name: &str,
+    inputs_document: Option<&str>,
     expression_width: ExpressionWidth,
     acir_mode: bool,
     skip_instrumentation: bool,
-) -> Result<(CompiledProgram, WitnessMap<FieldElement>), LoadError> {
+) -> Result<LoadedProgram, LoadError> {
     let workspace = find_workspace(project_folder, package)
-        .ok_or(LoadError::Generic(workspace_not_found_error_msg(project_folder, package)))?;
-    let package = workspace
-        .into_iter()
-        .find(|p| p.is_binary())
-        .ok_or(LoadError::Generic("No matching binary packages found in workspace".into()))?;
+        .ok_or(LoadError::Input(workspace_not_found_error_msg(project_folder, package)))?;
+    let binary_packages: Vec<&Package> = workspace.into_iter().filter(|p| p.is_binary()).collect();
+    let requested_package = package;
+    let package = match binary_packages.as_slice() {
+        [] => {
+            return Err(LoadError::Input("No matching binary packages found in workspace".into()))
+        }
+        [package] => *package,
+        // Without a package argument the first binary package is debugged
+        [package, ..] if requested_package.is_none() => *package,
+        // A package argument that is not a valid package name selects them all
+        packages => {
+            let candidates: Vec<_> = packages
+                .iter()
+                .map(|package| LaunchCandidate {
+                    name: package.name.to_string(),
+                    path: package.root_dir.display().to_string(),
+                })
+                .collect();
+            let names: Vec<_> =
+                candidates.iter().map(|candidate| candidate.name.as_str()).collect();
+            return Err(LoadError::Ambiguous {
+                message: format!(
+                    "Package {} is not a valid package name and binary packages {} match; choose one to debug with the package argument",
+                    requested_package.unwrap_or_default(),
+                    names.join(", ")
+                ),
+                candidates,
+            });
+        }
+    };
 
-    let compiled_program = compile_bin_package_for_debugging(
+    let (compiled_program, compile_warnings) = compile_bin_package_for_debugging(
         &workspace,
         package,
         acir_mode,
         skip_instrumentation,
         CompileOptions::default(),
     )
-    .map_err(|_| LoadError::Generic("Failed to compile project".into()))?;
+    .map_err(|error| LoadError::CompileFailed(error.diagnostics))?;
 
     let compiled_program = nargo::ops::transform_program(compiled_program, expression_width);
+    let initial_witness =
+        read_initial_witness(package, prover_name, inputs_document, &compiled_program.abi)?;
 
-    let (inputs_map, _) =
-        read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &compiled_program.abi)
-            .map_err(|_| {
-                LoadError::Generic(format!("Failed to read program inputs from {}", prover_name))
-            })?;
-    let initial_witness = compiled_program
-        .abi
-        .encode(&inputs_map, None)
-        .map_err(|_| LoadError::Generic("Failed to encode inputs".into()))?;
-
-    Ok((compiled_program, initial_witness))
-}
+    // Calls to the oracles declared in the package's oracles.toml are
+    // checked against their signatures, as in the REPL
+    let oracle_schema = OracleSchema::load_from_dir(&package.root_dir)
+        .map_err(LoadError::Input)?
+        .unwrap_or_default();
 
-fn loop_uninitialized_dap<R: Read, W: Write>(
-    mut server: Server<R, W>,
-    expression_width: ExpressionWidth,
-) -> Result<(), DapError> {
-    loop {
-        let req = match server.poll_request()? {
-            Some(req) => req,
-            None => break,
-        };
+    Ok(LoadedProgram { compiled_program, initial_witness, compile_warnings, oracle_schema })
+}
 
-        match req.command {
-            Command::Initialize(_) => {
-                let rsp = req.success(ResponseBody::Initialize(Capabilities {
-                    supports_disassemble_request: Some(true),
-                    supports_instruction_breakpoints: Some(true),
-                    supports_stepping_granularity: Some(true),
-                    ..Default::default()
-                }));
-                server.respond(rsp)?;
-            }
+/// Reads the program inputs from the given document, or else from the
+/// package's prover file, and encodes them as the initial witness
+fn read_initial_witness(
+    package: &Package,
+    prover_name: &str,
+    inputs_document: Option<&str>,
+    abi: &Abi,
+) -> Result<WitnessMap<FieldElement>, LoadError> {
+    let (inputs_map, _) = match inputs_document {
+        Some(document) => read_inputs_from_document(document, abi),
+        None => {
+            let format = input_file_format(&package.root_dir, prover_name);
+            read_inputs_from_file(&package.root_dir, prover_name, format, abi)
+        }
+    }
+    .map_err(|error| {
+        let source = if inputs_document.is_some() { "the launch inputs" } else { prover_name };
+        LoadError::Input(format!("Failed to read program inputs from {source}: {error}"))
+    })?;
+    abi.encode(&inputs_map, None).map_err(|_| LoadError::Input("Failed to encode inputs".into()))
+}
 
-            Command::Launch(ref arguments) => {
-                let Some(Value::Object(ref additional_data)) = arguments.additional_data else {
-                    server.respond(req.error("Missing launch arguments"))?;
-                    continue;
-                };
-                let Some(Value::String(ref project_folder)) = additional_data.get("projectFolder")
-                else {
-                    server.respond(req.error("Missing project folder argument"))?;
-                    continue;
-                };
-
-                let project_folder = project_folder.as_str();
-                let package = additional_data.get("package").and_then(|v| v.as_str());
-                let prover_name = additional_data
-                    .get("proverName")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or(PROVER_INPUT_FILE);
-
-                let generate_acir =
-                    additional_data.get("generateAcir").and_then(|v| v.as_bool()).unwrap_or(false);
-                let skip_instrumentation = additional_data
-                    .get("skipInstrumentation")
-                    .and_then(|v| v.as_bool())
-                    .unwrap_or(generate_acir);
-
-                eprintln!("Project folder: {}", project_folder);
-                eprintln!("Package: {}", package.unwrap_or("(default)"));
-                eprintln!("Prover name: {}", prover_name);
-
-                match load_and_compile_project(
-                    project_folder,
-                    package,
-                    prover_name,
-                    expression_width,
-                    generate_acir,
-                    skip_instrumentation,
-                ) {
-                    Ok((compiled_program, initial_witness)) => {
-                        server.respond(req.ack()?)?;
-
-                        noir_debugger::run_dap_loop(
-                            server,
-                            &Bn254BlackBoxSolver,
-                            compiled_program,
-                            initial_witness,
-                        )?;
-                        break;
-                    }
-                    Err(LoadError::Generic(message)) => {
-                        server.respond(req.error(message.as_str()))?;
-                    }
-                }
-            }
+/// Compiles every binary package of the workspace in parallel, out of a
+/// single parse of its files, and checks their inputs. A single report lists
+/// the errors of every package rather than stopping at the first one.
+fn compile_all_packages(
+    project_folder: &str,
+    prover_name: &str,
+    acir_mode: bool,
+    skip_instrumentation: bool,
+) -> Result<(), LoadError> {
+    let workspace = get_package_manifest(Path::new(project_folder))
+        .ok()
+        .and_then(|toml_path| {
+            resolve_workspace_from_toml(
+                &toml_path,
+                PackageSelection::All,
+                Some(NOIR_ARTIFACT_VERSION_STRING.to_string()),
+            )
+            .ok()
+        })
+        .ok_or_else(|| LoadError::Input(workspace_not_found_error_msg(project_folder, None)))?;
+    let packages: Vec<&Package> = workspace.into_iter().filter(|p| p.is_binary()).collect();
+    if packages.is_empty() {
+        return Err(LoadError::Input("No matching binary packages found in workspace".into()));
+    }
 
-            Command::Disconnect(_) => {
-                server.respond(req.ack()?)?;
-                break;
-            }
+    let mut file_manager = file_manager_with_stdlib(Path::new(""));
+    insert_all_files_for_workspace_into_file_manager(&workspace, &mut file_manager);
+    let parsed_files = parse_all(&file_manager);
+    let compile_options = CompileOptions {
+        instrument_debug: !skip_instrumentation,
+        force_brillig: !acir_mode,
+        ..CompileOptions::default()
+    };
 
-            _ => {
-                let command = req.command;
-                eprintln!("ERROR: unhandled command: {command:?}");
+    // The errors of each package, in the order of the workspace
+    let errors: Vec<Vec<String>> = packages
+        .par_iter()
+        .map(|package| {
+            let (compilation_result, _, diagnostics) = compile_parsed_package(
+                &file_manager,
+                parsed_files.clone(),
+                package,
+                &compile_options,
+            );
+            match compilation_result {
+                Ok((compiled_program, _)) => {
+                    read_initial_witness(package, prover_name, None, &compiled_program.abi)
+                        .err()
+                        .map(|error| error.to_string())
+                        .into_iter()
+                        .collect()
+                }
+                Err(_) => diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.is_error)
+                    .map(ToString::to_string)
+                    .collect(),
             }
+        })
+        .collect();
+
+    let mut failed = vec![];
+    for (package, errors) in packages.iter().zip(errors) {
+        if errors.is_empty() {
+            eprintln!("[{}] OK", package.name);
+            continue;
+        }
+        eprintln!("[{}] {} error(s):", package.name, errors.len());
+        for error in errors {
+            eprintln!("  {error}");
         }
+        failed.push(package.name.to_string());
+    }
+    eprintln!(
+        "Checked {} of {} package(s) without errors",
+        packages.len() - failed.len(),
+        packages.len()
+    );
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(LoadError::Compile(format!("Failed to check package(s) {}", failed.join(", "))))
     }
-    Ok(())
 }
 
 fn run_preflight_check(
@@ -235,11 +302,37 @@ fn run_preflight_check(
 
     let package = args.preflight_package.as_deref();
     let prover_name = args.preflight_prover_name.as_deref().unwrap_or(PROVER_INPUT_FILE);
+    if args.preflight_all_packages {
+        if prover_name == STDIN_PROVER_NAME {
+            return Err(DapError::PreFlightGenericError(
+                "The inputs of every package can't be read from stdin; name their prover file instead".into(),
+            ));
+        }
+        compile_all_packages(
+            project_folder.as_str(),
+            prover_name,
+            args.preflight_generate_acir,
+            args.preflight_skip_instrumentation,
+        )?;
+        return Ok(());
+    }
+
+    // The inputs the IDE will pass in the launch arguments can be piped
+    // through stdin to check them as well
+    let inputs_document = if prover_name == STDIN_PROVER_NAME {
+        Some(
+            read_inputs_document_from_stdin()
+                .map_err(|error| DapError::PreFlightGenericError(error.to_string()))?,
+        )
+    } else {
+        None
+    };
 
     let _ = load_and_compile_project(
         project_folder.as_str(),
         package,
         prover_name,
+        inputs_document.as_deref(),
         expression_width,
         args.preflight_generate_acir,
         args.preflight_skip_instrumentation,
@@ -249,6 +342,11 @@ fn run_preflight_check(
 }
 
 pub(crate) fn run(args: DapCommand, _config: NargoConfig) -> Result<(), CliError> {
+    run_dap(args)
+        .map_err(|error| DebuggerError { kind: error.kind(), message: error.to_string() }.into())
+}
+
+fn run_dap(args: DapCommand) -> Result<(), DapError> {
     // When the --preflight-check flag is present, we run Noir's DAP server in "pre-flight mode", which test runs
     // the DAP initialization code without actually starting the DAP server.
     //
@@ -262,12 +360,43 @@ pub(crate) fn run(args: DapCommand, _config: NargoConfig) -> Result<(), CliError
     // the DAP loop is established, which otherwise are considered "out of band" by the maintainers of the DAP spec.
     // More details here: https://github.com/microsoft/vscode/issues/108138
     if args.preflight_check {
-        return run_preflight_check(args.expression_width, args).map_err(CliError::DapError);
+        return run_preflight_check(args.expression_width, args);
     }
 
-    let output = BufWriter::new(std::io::stdout());
-    let input = BufReader::new(std::io::stdin());
-    let server = Server::new(input, output);
+    let expression_width = args.expression_width;
+    let server_options = || {
+        let load_program = move |launch: &LaunchArguments| {
+            load_and_compile_project(
+                &launch.project_folder,
+                launch.package.as_deref(),
+                &launch.prover_name,
+                launch.inputs_document.as_deref(),
+                expression_width,
+                launch.generate_acir,
+                launch.skip_instrumentation,
+            )
+        };
+        DapServerOptions { solver: &Bn254BlackBoxSolver, load_program: Box::new(load_program) }
+    };
 
-    loop_uninitialized_dap(server, args.expression_width).map_err(CliError::DapError)
+    let Some(port) = args.port else {
+        return run_dap_server(DapTransport::stdio(), server_options());
+    };
+    let listener = TcpListener::bind((args.host.as_str(), port))?;
+    eprintln!("Listening for DAP clients on {}", listener.local_addr()?);
+    for stream in listener.incoming() {
+        let stream = stream?;
+        if let Ok(client) = stream.peer_addr() {
+            eprintln!("Serving DAP client {client}");
+        }
+        let result = run_dap_server(DapTransport::tcp(stream)?, server_options());
+        if !args.keep_listening {
+            return result;
+        }
+        // A failed session doesn't stop serving the next clients
+        if let Err(error) = result {
+            eprintln!("ERROR: DAP session failed: {error}");
+        }
+    }
+    Ok(())
 }
@@ -1,31 +1,59 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use acvm::acir::native_types::{WitnessMap, WitnessStack};
+use acvm::acir::brillig::Opcode as BrilligOpcode;
+use acvm::acir::circuit::Program;
+use acvm::acir::native_types::{Witness, WitnessStack};
 use acvm::FieldElement;
-use bn254_blackbox_solver::Bn254BlackBoxSolver;
+use bn254_blackbox_solver::{Bn254BlackBoxSolver, BN254_BLACKBOX_SOLVER_VERSION};
 use clap::Args;
 
-use fm::FileManager;
+use fm::{FileId, FileManager};
 use nargo::constants::PROVER_INPUT_FILE;
 use nargo::errors::CompileError;
-use nargo::ops::{compile_program, compile_program_with_debug_instrumenter, report_errors};
+use nargo::ops::{
+    compile_program, compile_program_with_debug_instrumenter, report_errors, OracleResolverOptions,
+    PrintOutput,
+};
 use nargo::package::Package;
 use nargo::workspace::Workspace;
 use nargo::{insert_all_files_for_workspace_into_file_manager, parse_all};
 use nargo_toml::{get_package_manifest, resolve_workspace_from_toml, PackageSelection};
-use noirc_abi::input_parser::{Format, InputValue};
-use noirc_abi::InputMap;
+use noir_debugger::environment::DebugEnvironment;
+use noir_debugger::errors::{
+    CompileDiagnostic, DebuggerError, DebuggerErrorKind, DiagnosticLocation,
+};
+use noir_debugger::watch::SourceWatcher;
+use noir_debugger::{
+    ColorChoice, DebugOutcome, DebuggerOptions, ForeignCallResolverConfig, OracleSchema,
+    PathSubstitutions, ProgramInfo, SavedBreakpoint, SessionLog, Verbosity,
+};
+use noirc_abi::input_parser::InputValue;
+use noirc_abi::{Abi, AbiType, AbiVisibility, InputMap, Sign};
 use noirc_artifacts::debug::DebugArtifact;
 use noirc_driver::{
-    file_manager_with_stdlib, CompileOptions, CompiledProgram, NOIR_ARTIFACT_VERSION_STRING,
+    file_manager_with_stdlib, CompilationResult, CompileOptions, CompiledProgram, DebugFile,
+    NOIR_ARTIFACT_VERSION_STRING,
 };
+use noirc_errors::reporter::line_and_column_from_span;
+use noirc_errors::FileDiagnostic;
 use noirc_frontend::debug::DebugInstrumenter;
 use noirc_frontend::graph::CrateName;
 use noirc_frontend::hir::ParsedFiles;
-
-use super::fs::{inputs::read_inputs_from_file, witness::save_witness_to_dir};
-use super::NargoConfig;
-use crate::errors::CliError;
+use serde::Serialize;
+
+use super::fs::{
+    inputs::{
+        apply_input_overrides, input_file_format, read_inputs_document_from_stdin,
+        read_inputs_from_document, read_inputs_from_file, STDIN_PROVER_NAME,
+    },
+    program::read_program_from_file,
+    witness::save_witness_to_dir,
+    write_to_file,
+};
+use super::{NargoConfig, NARGO_VERSION};
+use crate::errors::{CliError, FilesystemError};
 
 /// Executes a circuit in debug mode
 #[derive(Debug, Clone, Args)]
@@ -33,10 +61,18 @@ pub(crate) struct DebugCommand {
     /// Write the execution witness to named file
     witness_name: Option<String>,
 
-    /// The name of the toml file which contains the inputs for the prover
+    /// Save the partial witness stack and a state report to the target directory if the session ends on an execution failure
+    #[clap(long)]
+    save_witness_on_failure: bool,
+
+    /// The name of the toml or json file which contains the inputs for the prover, or `-` to read them from stdin (the REPL then ends once stdin is exhausted)
     #[clap(long, short, default_value = PROVER_INPUT_FILE)]
     prover_name: String,
 
+    /// Override a program input with a TOML value (eg. `--input x=3`); can be repeated
+    #[clap(long = "input", value_name = "NAME=VALUE")]
+    inputs: Vec<String>,
+
     /// The name of the package to execute
     #[clap(long)]
     package: Option<CrateName>,
@@ -51,14 +87,149 @@ pub(crate) struct DebugCommand {
     /// Disable vars debug instrumentation (enabled by default)
     #[clap(long)]
     skip_instrumentation: Option<bool>,
+
+    /// Write a report with the toolchain versions, compile settings, instrumentation metrics and breakpoint snapshots of the session, and the error it failed with if any, to the given file
+    #[clap(long)]
+    report: Option<PathBuf>,
+
+    /// Print how many statements were instrumented and the opcode overhead of the instrumentation, which takes an extra compilation without it
+    #[clap(long)]
+    instrumentation_metrics: bool,
+
+    /// Start a guided walk-through of the debugger on a bundled example program
+    #[clap(long)]
+    tutorial: bool,
+
+    /// Continue execution right away and, on failure, show the most recently executed opcodes
+    #[clap(long)]
+    run_to_failure: bool,
+
+    /// Recompile and restart the session, keeping breakpoints, when the program's source files change
+    #[clap(long)]
+    watch: bool,
+
+    /// When to use colors in the output: always, never or auto (honoring NO_COLOR)
+    #[clap(long, default_value = "auto")]
+    color: ColorChoice,
+
+    /// Print a single status line every time execution stops
+    #[clap(long, short, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Also print changed variables and execution counters every time execution stops
+    #[clap(long, short)]
+    verbose: bool,
+
+    /// Show source paths starting with FROM as starting with TO instead (eg. `--substitute-path std=/path/to/noir_stdlib/src`); can be repeated
+    #[clap(long = "substitute-path", value_name = "FROM=TO")]
+    substitute_paths: Vec<String>,
+
+    /// Check that the values returned by oracles have the shape the program expects
+    #[clap(long)]
+    validate_oracle_outputs: bool,
+
+    /// JSON RPC url to solve oracle calls
+    #[clap(long)]
+    oracle_resolver: Option<String>,
+
+    /// Pause `continue` after this many seconds, showing the hottest source lines in case the program hangs
+    #[clap(long, value_name = "SECONDS")]
+    continue_timeout: Option<u64>,
+
+    /// Debug the program in the given compiled artifact instead of compiling the package
+    #[clap(long, value_name = "PATH", conflicts_with = "watch")]
+    artifact: Option<PathBuf>,
+
+    /// Only warn, instead of failing, when the source files have changed since the artifact was compiled
+    #[clap(long, requires = "artifact")]
+    allow_stale: bool,
+
+    /// Before starting the session, run the program built with and without debug instrumentation on the same inputs and report whether their results differ
+    #[clap(long, conflicts_with = "artifact")]
+    verify_instrumentation: bool,
+
+    /// Inputs document read from stdin when the prover name is `-`
+    #[clap(skip)]
+    stdin_inputs: Option<String>,
 }
 
 pub(crate) fn run(args: DebugCommand, config: NargoConfig) -> Result<(), CliError> {
+    run_debugger(args, config).map_err(|error| {
+        DebuggerError { kind: error.debugger_error_kind(), message: error.to_string() }.into()
+    })
+}
+
+fn run_debugger(args: DebugCommand, config: NargoConfig) -> Result<(), CliError> {
     let acir_mode = args.acir_mode;
     let skip_instrumentation = args.skip_instrumentation.unwrap_or(acir_mode);
+    let environment = DebugEnvironment::new(
+        NARGO_VERSION,
+        &format!("bn254_blackbox_solver {BN254_BLACKBOX_SOLVER_VERSION}"),
+        &CompileOptions {
+            instrument_debug: !skip_instrumentation,
+            force_brillig: !acir_mode,
+            ..args.compile_options.clone()
+        },
+    );
+    let report_path = args.report.clone();
+    let mut metrics = None;
+    let mut session_log = SessionLog::default();
+    let result = debug_package(
+        args,
+        config,
+        acir_mode,
+        skip_instrumentation,
+        &environment,
+        &mut metrics,
+        &mut session_log,
+    );
+
+    // Sessions that fail to start are the ones most often reported, so the
+    // report is written with the error as well
+    if let Some(report_path) = &report_path {
+        let error = result.as_ref().err().map(ToString::to_string);
+        write_report(report_path, &environment, metrics.as_ref(), &session_log, error.as_deref());
+    }
+
+    result
+}
 
-    let toml_path = get_package_manifest(&config.program_dir)?;
-    let selection = args.package.map_or(PackageSelection::DefaultOrAll, PackageSelection::Selected);
+fn debug_package(
+    mut args: DebugCommand,
+    config: NargoConfig,
+    acir_mode: bool,
+    skip_instrumentation: bool,
+    environment: &DebugEnvironment,
+    metrics: &mut Option<InstrumentationMetrics>,
+    session_log: &mut SessionLog,
+) -> Result<(), CliError> {
+    // Inputs piped through stdin are read once, as sessions restarted by
+    // `--watch` reuse them
+    if args.prover_name == STDIN_PROVER_NAME {
+        args.stdin_inputs = Some(read_inputs_document_from_stdin()?);
+    }
+
+    // The tutorial program is written to a fresh directory of its own, which
+    // is removed when the session ends
+    let tutorial_dir = args
+        .tutorial
+        .then(|| {
+            let dir = tempfile::Builder::new().prefix("nargo_debugger_tutorial").tempdir()?;
+            noir_debugger::tutorial::write_tutorial_program(dir.path())?;
+            Ok(dir)
+        })
+        .transpose()
+        .map_err(|err: std::io::Error| {
+            CliError::Generic(format!("Could not write the tutorial program: {err}"))
+        })?;
+    let program_dir = match &tutorial_dir {
+        Some(dir) => dir.path().to_path_buf(),
+        None => config.program_dir,
+    };
+
+    let toml_path = get_package_manifest(&program_dir)?;
+    let selection =
+        args.package.clone().map_or(PackageSelection::DefaultOrAll, PackageSelection::Selected);
     let workspace = resolve_workspace_from_toml(
         &toml_path,
         selection,
@@ -73,18 +244,249 @@ pub(crate) fn run(args: DebugCommand, config: NargoConfig) -> Result<(), CliErro
         return Ok(());
     };
 
-    let compiled_program = compile_bin_package_for_debugging(
-        &workspace,
-        package,
-        acir_mode,
-        skip_instrumentation,
-        args.compile_options.clone(),
-    )?;
+    let mut path_substitutions = PathSubstitutions::default();
+    for rule in &args.substitute_paths {
+        path_substitutions.add_rule(rule).map_err(CliError::Generic)?;
+    }
+    // Calls to the oracles declared in the package's oracles.toml are
+    // checked against their signatures
+    let oracle_schema = OracleSchema::load_from_dir(&package.root_dir)
+        .map_err(|message| DebuggerError { kind: DebuggerErrorKind::Input, message })?
+        .unwrap_or_default();
+    let mut options = DebuggerOptions {
+        tutorial: args.tutorial,
+        run_to_failure: args.run_to_failure,
+        watch: args.watch,
+        breakpoints: vec![],
+        color: args.color,
+        verbosity: match (args.quiet, args.verbose) {
+            (true, _) => Verbosity::Quiet,
+            (_, true) => Verbosity::Verbose,
+            _ => Verbosity::Normal,
+        },
+        path_substitutions,
+        validate_oracle_outputs: args.validate_oracle_outputs,
+        oracle_schema,
+        resolver_config: ForeignCallResolverConfig {
+            resolver: args.oracle_resolver.as_deref().map(OracleResolverOptions::from_url),
+            ..ForeignCallResolverConfig::default()
+        },
+        continue_timeout: args.continue_timeout.map(Duration::from_secs),
+        compile_warnings: vec![],
+    };
+
+    // Source files of the last successful compilation, watched when recompiling fails
+    let mut watched_files: Option<Vec<PathBuf>> = None;
+    loop {
+        if let Some(artifact_path) = &args.artifact {
+            let compiled_program = load_artifact_for_debugging(artifact_path, args.allow_stale)?;
+            run_async(
+                package,
+                compiled_program,
+                &args,
+                target_dir,
+                environment,
+                &options,
+                session_log,
+            )?;
+            break;
+        }
+
+        let compiled_program = match compile_bin_package_with_metrics(
+            &workspace,
+            package,
+            acir_mode,
+            skip_instrumentation,
+            args.compile_options.clone(),
+            args.instrumentation_metrics || args.report.is_some(),
+        ) {
+            Ok((compiled_program, compile_metrics, compile_warnings)) => {
+                if let Some(compile_metrics) = &compile_metrics {
+                    println!("[{}] {compile_metrics}", package.name);
+                }
+                *metrics = compile_metrics;
+                options.compile_warnings = compile_warnings;
+                compiled_program
+            }
+            Err(error) => {
+                let Some(files) = &watched_files else {
+                    return Err(error.into());
+                };
+                println!("[{}] Compilation failed; waiting for source changes", package.name);
+                if !SourceWatcher::new(files.clone()).wait_for_change() {
+                    break;
+                }
+                continue;
+            }
+        };
+        if args.watch {
+            watched_files =
+                Some(compiled_program.file_map.values().map(|file| file.path.clone()).collect());
+        }
+
+        let compiled_program =
+            nargo::ops::transform_program(compiled_program, args.compile_options.expression_width);
+
+        if args.verify_instrumentation {
+            if skip_instrumentation {
+                println!("[{}] The program is not instrumented; nothing to verify", package.name);
+            } else {
+                verify_instrumentation(
+                    &workspace,
+                    package,
+                    &args,
+                    acir_mode,
+                    &compiled_program,
+                    &options.resolver_config,
+                )?;
+            }
+        }
 
-    let compiled_program =
-        nargo::ops::transform_program(compiled_program, args.compile_options.expression_width);
+        match run_async(
+            package,
+            compiled_program,
+            &args,
+            target_dir,
+            environment,
+            &options,
+            session_log,
+        )? {
+            Some(breakpoints) => {
+                options.breakpoints = breakpoints;
+                // the tutorial only runs on the first session
+                options.tutorial = false;
+            }
+            None => break,
+        }
+    }
+
+    Ok(())
+}
 
-    run_async(package, compiled_program, &args.prover_name, &args.witness_name, target_dir)
+fn write_report(
+    report_path: &Path,
+    environment: &DebugEnvironment,
+    metrics: Option<&InstrumentationMetrics>,
+    session_log: &SessionLog,
+    error: Option<&str>,
+) {
+    let report = serde_json::json!({
+        "environment": environment,
+        "instrumentation": metrics,
+        "session": session_log,
+        "error": error,
+    });
+    let report =
+        serde_json::to_string_pretty(&report).expect("Could not serialize debugger report");
+    let report_path = write_to_file(report.as_bytes(), report_path);
+    println!("Debugger report saved to {report_path}");
+}
+
+/// Measures the cost of the debug instrumentation of a program
+#[derive(Debug, Serialize)]
+struct InstrumentationMetrics {
+    /// Number of let, assignment and for statements instrumented to track variables
+    instrumented_statements: usize,
+    /// Number of calls to the debugger oracles in the compiled program
+    injected_oracles: usize,
+    /// Number of ACIR and Brillig opcodes of the instrumented program
+    opcodes: usize,
+    /// Number of opcodes of the program compiled without instrumentation, if it compiles
+    baseline_opcodes: Option<usize>,
+}
+
+impl std::fmt::Display for InstrumentationMetrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Instrumented {} statements with {} debugger oracle calls; {} opcodes",
+            self.instrumented_statements, self.injected_oracles, self.opcodes
+        )?;
+        match self.baseline_opcodes {
+            Some(baseline) if baseline > 0 => {
+                let overhead = self.opcodes as f64 / baseline as f64 * 100.0 - 100.0;
+                write!(f, " vs {baseline} without instrumentation ({overhead:+.1}%)")
+            }
+            Some(baseline) => write!(f, " vs {baseline} without instrumentation"),
+            None => Ok(()),
+        }
+    }
+}
+
+fn count_opcodes(program: &Program<FieldElement>) -> usize {
+    let acir: usize = program.functions.iter().map(|circuit| circuit.opcodes.len()).sum();
+    let brillig: usize =
+        program.unconstrained_functions.iter().map(|function| function.bytecode.len()).sum();
+    acir + brillig
+}
+
+fn count_debug_oracle_calls(program: &Program<FieldElement>) -> usize {
+    program
+        .unconstrained_functions
+        .iter()
+        .flat_map(|function| &function.bytecode)
+        .filter(|opcode| match opcode {
+            BrilligOpcode::ForeignCall { function, .. } => function.starts_with("__debug_"),
+            _ => false,
+        })
+        .count()
+}
+
+/// Reads a compiled program to debug, checking that the sources embedded in
+/// it still match the files on disk, so that the code shown while stepping is
+/// the code that was compiled
+fn load_artifact_for_debugging(
+    artifact_path: &Path,
+    allow_stale: bool,
+) -> Result<CompiledProgram, CliError> {
+    let compiled_program: CompiledProgram = read_program_from_file(artifact_path)?.into();
+
+    let changed_files = find_changed_source_files(&compiled_program.file_map);
+    if !changed_files.is_empty() {
+        let files: Vec<_> =
+            changed_files.iter().map(|path| format!("  {}", path.display())).collect();
+        let message = format!(
+            "The source files have changed since {} was compiled:\n{}",
+            artifact_path.display(),
+            files.join("\n")
+        );
+        if !allow_stale {
+            return Err(DebuggerError {
+                kind: DebuggerErrorKind::Input,
+                message: format!("{message}\nRecompile the program or pass --allow-stale"),
+            }
+            .into());
+        }
+        eprintln!("Warning: {message}");
+    }
+    Ok(compiled_program)
+}
+
+/// Returns the paths of the files embedded in a compiled program whose contents
+/// differ from the ones on disk, or which no longer exist. Files without an
+/// absolute path, such as the ones of the standard library, are not checked.
+fn find_changed_source_files(file_map: &BTreeMap<FileId, DebugFile>) -> Vec<PathBuf> {
+    file_map
+        .values()
+        .filter(|file| file.path.is_absolute())
+        .filter(|file| {
+            std::fs::read_to_string(&file.path).map_or(true, |source| source != file.source)
+        })
+        .map(|file| file.path.clone())
+        .collect()
+}
+
+/// A failed compilation for debugging, with the diagnostics that explain it
+#[derive(Debug)]
+pub(crate) struct DebugCompileError {
+    pub(crate) error: CompileError,
+    pub(crate) diagnostics: Vec<CompileDiagnostic>,
+}
+
+impl From<DebugCompileError> for CliError {
+    fn from(error: DebugCompileError) -> Self {
+        CliError::CompileError(error.error)
+    }
 }
 
 pub(crate) fn compile_bin_package_for_debugging(
@@ -93,10 +495,33 @@ pub(crate) fn compile_bin_package_for_debugging(
     acir_mode: bool,
     skip_instrumentation: bool,
     compile_options: CompileOptions,
-) -> Result<CompiledProgram, CompileError> {
+) -> Result<(CompiledProgram, Vec<String>), DebugCompileError> {
+    compile_bin_package_with_metrics(
+        workspace,
+        package,
+        acir_mode,
+        skip_instrumentation,
+        compile_options,
+        false,
+    )
+    .map(|(compiled_program, _, warnings)| (compiled_program, warnings))
+}
+
+/// Compiles the package for debugging and, when instrumenting it and `measure`
+/// is set, measures the instrumentation against a compilation of the same
+/// package without it. The compile warnings are also returned rendered, to be
+/// shown during the session.
+fn compile_bin_package_with_metrics(
+    workspace: &Workspace,
+    package: &Package,
+    acir_mode: bool,
+    skip_instrumentation: bool,
+    compile_options: CompileOptions,
+    measure: bool,
+) -> Result<(CompiledProgram, Option<InstrumentationMetrics>, Vec<String>), DebugCompileError> {
     let mut workspace_file_manager = file_manager_with_stdlib(std::path::Path::new(""));
     insert_all_files_for_workspace_into_file_manager(workspace, &mut workspace_file_manager);
-    let mut parsed_files = parse_all(&workspace_file_manager);
+    let parsed_files = parse_all(&workspace_file_manager);
 
     let compile_options = CompileOptions {
         instrument_debug: !skip_instrumentation,
@@ -104,28 +529,112 @@ pub(crate) fn compile_bin_package_for_debugging(
         ..compile_options
     };
 
-    let compilation_result = if !skip_instrumentation {
-        let debug_state =
-            instrument_package_files(&mut parsed_files, &workspace_file_manager, package);
+    let (compilation_result, instrumented_statements, diagnostics) =
+        compile_parsed_package(&workspace_file_manager, parsed_files, package, &compile_options);
+    let warnings = match &compilation_result {
+        Ok(_) if !compile_options.silence_warnings => diagnostics
+            .iter()
+            .filter(|diagnostic| !diagnostic.is_error)
+            .map(ToString::to_string)
+            .collect(),
+        _ => vec![],
+    };
+    let compiled_program = report_errors(
+        compilation_result,
+        &workspace_file_manager,
+        compile_options.deny_warnings,
+        compile_options.silence_warnings,
+    )
+    .map_err(|error| DebugCompileError { error, diagnostics })?;
+
+    let metrics = instrumented_statements.filter(|_| measure).map(|instrumented_statements| {
+        // The instrumented files were modified in place, so parse them again
+        let parsed_files = parse_all(&workspace_file_manager);
+        let baseline_options = CompileOptions { instrument_debug: false, ..compile_options };
+        let baseline_opcodes = compile_program(
+            &workspace_file_manager,
+            &parsed_files,
+            package,
+            &baseline_options,
+            None,
+        )
+        .ok()
+        .map(|(baseline, _)| count_opcodes(&baseline.program));
+
+        InstrumentationMetrics {
+            instrumented_statements,
+            injected_oracles: count_debug_oracle_calls(&compiled_program.program),
+            opcodes: count_opcodes(&compiled_program.program),
+            baseline_opcodes,
+        }
+    });
+
+    Ok((compiled_program, metrics, warnings))
+}
+
+/// Compiles the package out of the parsed files of its workspace, which are
+/// instrumented if `compile_options` asks for it, without reporting the
+/// diagnostics. They are returned located in the source instead, along with
+/// the number of instrumented statements.
+pub(crate) fn compile_parsed_package(
+    file_manager: &FileManager,
+    mut parsed_files: ParsedFiles,
+    package: &Package,
+    compile_options: &CompileOptions,
+) -> (CompilationResult<CompiledProgram>, Option<usize>, Vec<CompileDiagnostic>) {
+    let mut instrumented_statements = None;
+    let compilation_result = if compile_options.instrument_debug {
+        let debug_state = instrument_package_files(&mut parsed_files, file_manager, package);
+        instrumented_statements = Some(debug_state.instrumented_statements);
 
         compile_program_with_debug_instrumenter(
-            &workspace_file_manager,
+            file_manager,
             &parsed_files,
             package,
-            &compile_options,
+            compile_options,
             None,
             debug_state,
         )
     } else {
-        compile_program(&workspace_file_manager, &parsed_files, package, &compile_options, None)
+        compile_program(file_manager, &parsed_files, package, compile_options, None)
     };
 
-    report_errors(
-        compilation_result,
-        &workspace_file_manager,
-        compile_options.deny_warnings,
-        compile_options.silence_warnings,
-    )
+    let diagnostics = match &compilation_result {
+        Ok((_, warnings)) => compile_diagnostics(warnings, file_manager),
+        Err(errors) => compile_diagnostics(errors, file_manager),
+    };
+    (compilation_result, instrumented_statements, diagnostics)
+}
+
+/// Locates the compiler's diagnostics in the source. Warnings are printed
+/// when compiling, but debugging sessions show them again since they often
+/// explain why a variable is missing, and DAP clients are sent the errors of
+/// a failed launch.
+fn compile_diagnostics(
+    diagnostics: &[FileDiagnostic],
+    file_manager: &FileManager,
+) -> Vec<CompileDiagnostic> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let file_id = diagnostic.file_id;
+            let location = diagnostic.diagnostic.secondaries.first().and_then(|label| {
+                let path = file_manager.path(file_id)?;
+                let source = file_manager.fetch_file(file_id)?;
+                let (line, column) = line_and_column_from_span(source, &label.span);
+                Some(DiagnosticLocation {
+                    path: path.to_path_buf(),
+                    line: line as usize,
+                    column: column as usize,
+                })
+            });
+            CompileDiagnostic {
+                message: diagnostic.diagnostic.message.clone(),
+                is_error: !diagnostic.diagnostic.is_warning(),
+                location,
+            }
+        })
+        .collect()
 }
 
 /// Add debugging instrumentation to all parsed files belonging to the package
@@ -143,13 +652,23 @@ fn instrument_package_files(
 
     let mut debug_instrumenter = DebugInstrumenter::default();
 
-    for (file_id, parsed_file) in parsed_files.iter_mut() {
-        let file_path =
-            file_manager.path(*file_id).expect("Parsed file ID not found in file manager");
+    // Files are instrumented in order of their path, so that the IDs given
+    // to their asserts don't change from one compilation to the next
+    let mut parsed_files: Vec<_> = parsed_files
+        .iter_mut()
+        .map(|(file_id, parsed_file)| {
+            let file_path =
+                file_manager.path(*file_id).expect("Parsed file ID not found in file manager");
+            (file_path, *file_id, parsed_file)
+        })
+        .collect();
+    parsed_files.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+    for (file_path, file_id, parsed_file) in parsed_files {
         for ancestor in file_path.ancestors() {
             if ancestor == entry_path_parent {
                 // file is in package
-                debug_instrumenter.instrument_module(&mut parsed_file.0);
+                debug_instrumenter.instrument_module(&mut parsed_file.0, file_id);
             }
         }
     }
@@ -160,78 +679,255 @@ fn instrument_package_files(
 fn run_async(
     package: &Package,
     program: CompiledProgram,
-    prover_name: &str,
-    witness_name: &Option<String>,
+    args: &DebugCommand,
     target_dir: &PathBuf,
-) -> Result<(), CliError> {
+    environment: &DebugEnvironment,
+    options: &DebuggerOptions,
+    session_log: &mut SessionLog,
+) -> Result<Option<Vec<SavedBreakpoint>>, CliError> {
     use tokio::runtime::Builder;
     let runtime = Builder::new_current_thread().enable_all().build().unwrap();
 
     runtime.block_on(async {
         println!("[{}] Starting debugger", package.name);
-        let (return_value, solved_witness) =
-            debug_program_and_decode(program, package, prover_name)?;
-
-        if let Some(solved_witness) = solved_witness {
-            println!("[{}] Circuit witness successfully solved", package.name);
-
-            if let Some(return_value) = return_value {
-                println!("[{}] Circuit output: {return_value:?}", package.name);
+        let (return_value, outcome, log) =
+            debug_program_and_decode(program, package, args, environment, options)?;
+        // Sessions restarted by `--watch` add to the log of the previous ones
+        session_log.breakpoint_snapshots.extend(log.breakpoint_snapshots);
+        if log.profile.is_some() {
+            session_log.profile = log.profile;
+        }
+        let mut summary = log.summary;
+
+        match outcome {
+            DebugOutcome::Solved(solved_witness) => {
+                println!("[{}] Circuit witness successfully solved", package.name);
+
+                if let Some(return_value) = return_value {
+                    println!("[{}] Circuit output: {return_value:?}", package.name);
+                }
+
+                if let Some(witness_name) = &args.witness_name {
+                    let witness_path = save_witness_to_dir(
+                        WitnessStack::from(solved_witness),
+                        witness_name,
+                        target_dir,
+                    )?;
+
+                    println!("[{}] Witness saved to {}", package.name, witness_path.display());
+                    summary.witness_path = Some(witness_path);
+                }
             }
-
-            if let Some(witness_name) = witness_name {
-                let witness_path = save_witness_to_dir(
-                    WitnessStack::from(solved_witness),
-                    witness_name,
-                    target_dir,
-                )?;
-
-                println!("[{}] Witness saved to {}", package.name, witness_path.display());
+            DebugOutcome::Halted => println!("Debugger execution halted."),
+            DebugOutcome::Failed(witness_stack, report) => {
+                println!("Debugger execution halted after a failure.");
+                if args.save_witness_on_failure {
+                    let name = format!("{}-failure", package.name);
+                    let (witness_path, report_path) =
+                        noir_debugger::save_failure(target_dir, &name, witness_stack, &report)
+                            .map_err(CliError::Generic)?;
+                    println!(
+                        "[{}] Partial witness saved to {} and failure report to {}",
+                        package.name,
+                        witness_path.display(),
+                        report_path.display()
+                    );
+                }
             }
-        } else {
-            println!("Debugger execution halted.");
+            DebugOutcome::SourcesChanged(breakpoints) => return Ok(Some(breakpoints)),
         }
 
-        Ok(())
+        println!("[{}] {summary}", package.name);
+        session_log.summary = summary;
+
+        Ok(None)
     })
 }
 
 fn debug_program_and_decode(
     program: CompiledProgram,
     package: &Package,
-    prover_name: &str,
-) -> Result<(Option<InputValue>, Option<WitnessMap<FieldElement>>), CliError> {
-    // Parse the initial witness values from Prover.toml
-    let (inputs_map, _) =
-        read_inputs_from_file(&package.root_dir, prover_name, Format::Toml, &program.abi)?;
-    let solved_witness = debug_program(&program, &inputs_map)?;
-
-    match solved_witness {
-        Some(witness) => {
+    args: &DebugCommand,
+    environment: &DebugEnvironment,
+    options: &DebuggerOptions,
+) -> Result<(Option<InputValue>, DebugOutcome, SessionLog), CliError> {
+    let inputs_map = read_debug_inputs(&program.abi, package, args)?;
+    let (outcome, session_log) = debug_program(&program, &inputs_map, environment, options)?;
+
+    match outcome {
+        DebugOutcome::Solved(witness) => {
             let (_, return_value) = program.abi.decode(&witness)?;
-            Ok((return_value, Some(witness)))
+            Ok((return_value, DebugOutcome::Solved(witness), session_log))
+        }
+        outcome => Ok((None, outcome, session_log)),
+    }
+}
+
+/// Parses the initial witness values from Prover.toml, Prover.json or stdin,
+/// applying the overrides given with `--input`
+fn read_debug_inputs(
+    abi: &Abi,
+    package: &Package,
+    args: &DebugCommand,
+) -> Result<InputMap, CliError> {
+    let (mut inputs_map, _) = match &args.stdin_inputs {
+        Some(document) => read_inputs_from_document(document, abi)?,
+        None => {
+            let format = input_file_format(&package.root_dir, &args.prover_name);
+            read_inputs_from_file(&package.root_dir, &args.prover_name, format, abi)?
+        }
+    };
+    apply_input_overrides(&mut inputs_map, &args.inputs, abi).map_err(FilesystemError::from)?;
+    Ok(inputs_map)
+}
+
+/// Runs the program built with and without debug instrumentation on the same
+/// inputs, warning if their return values or failure status differ. The
+/// oracle calls added by the instrumentation may change the behavior of
+/// programs relying on unconstrained nondeterminism. When both fail with
+/// different errors, which may only differ in the opcodes they point at, the
+/// result is reported as inconclusive.
+fn verify_instrumentation(
+    workspace: &Workspace,
+    package: &Package,
+    args: &DebugCommand,
+    acir_mode: bool,
+    instrumented: &CompiledProgram,
+    resolver_config: &ForeignCallResolverConfig,
+) -> Result<(), CliError> {
+    let (uninstrumented, _) = compile_bin_package_for_debugging(
+        workspace,
+        package,
+        acir_mode,
+        true,
+        args.compile_options.clone(),
+    )?;
+    let uninstrumented =
+        nargo::ops::transform_program(uninstrumented, args.compile_options.expression_width);
+    let inputs_map = read_debug_inputs(&instrumented.abi, package, args)?;
+
+    // The return value of a successful execution, or the error of a failed one
+    let execute =
+        |program: &CompiledProgram| -> Result<Result<Option<InputValue>, String>, CliError> {
+            let initial_witness = program.abi.encode(&inputs_map, None)?;
+            match noir_debugger::execute_program(
+                &Bn254BlackBoxSolver,
+                program,
+                initial_witness,
+                PrintOutput::None,
+                resolver_config.clone(),
+            ) {
+                Ok(witness_stack) => {
+                    let main_witness = &witness_stack
+                        .peek()
+                        .expect("Should have at least one witness on the stack")
+                        .witness;
+                    let (_, return_value) = program.abi.decode(main_witness)?;
+                    Ok(Ok(return_value))
+                }
+                Err(error) => Ok(Err(error.to_string())),
+            }
+        };
+    let describe = |outcome: &Result<Option<InputValue>, String>| match outcome {
+        Ok(Some(return_value)) => format!("returns {return_value:?}"),
+        Ok(None) => String::from("succeeds"),
+        Err(error) => format!("fails: {error}"),
+    };
+    let with_instrumentation = execute(instrumented)?;
+    let without_instrumentation = execute(&uninstrumented)?;
+
+    let difference = match (&with_instrumentation, &without_instrumentation) {
+        (Ok(with), Ok(without)) if with == without => None,
+        (Err(with), Err(without)) if with == without => None,
+        (Err(_), Err(_)) => Some(
+            "Instrumentation inconclusive: the program fails with and without it, with different errors",
+        ),
+        _ => Some("WARNING: instrumentation changes the program's behavior"),
+    };
+    match difference {
+        None => println!(
+            "[{}] Instrumentation verified: the program {} with and without it",
+            package.name,
+            describe(&with_instrumentation)
+        ),
+        Some(difference) => {
+            println!("[{}] {difference}", package.name);
+            println!("  instrumented:   {}", describe(&with_instrumentation));
+            println!("  uninstrumented: {}", describe(&without_instrumentation));
         }
-        None => Ok((None, None)),
     }
+    Ok(())
 }
 
 pub(crate) fn debug_program(
     compiled_program: &CompiledProgram,
     inputs_map: &InputMap,
-) -> Result<Option<WitnessMap<FieldElement>>, CliError> {
+    environment: &DebugEnvironment,
+    options: &DebuggerOptions,
+) -> Result<(DebugOutcome, SessionLog), CliError> {
     let initial_witness = compiled_program.abi.encode(inputs_map, None)?;
 
     let debug_artifact = DebugArtifact {
         debug_symbols: compiled_program.debug.clone(),
         file_map: compiled_program.file_map.clone(),
     };
+    let program_info = ProgramInfo {
+        circuits: compiled_program.program.functions.len(),
+        abi_inputs: compiled_program
+            .abi
+            .parameters
+            .iter()
+            .map(|parameter| {
+                let visibility = match parameter.visibility {
+                    AbiVisibility::Public => "pub ",
+                    AbiVisibility::Private => "",
+                    AbiVisibility::DataBus => "call_data ",
+                };
+                format!("{}: {visibility}{}", parameter.name, format_abi_type(&parameter.typ))
+            })
+            .collect(),
+        input_witnesses: abi_input_witnesses(&compiled_program.abi),
+    };
 
     noir_debugger::debug_circuit(
         &Bn254BlackBoxSolver,
-        &compiled_program.program.functions[0],
+        &compiled_program.program,
         debug_artifact,
         initial_witness,
-        &compiled_program.program.unconstrained_functions,
+        environment,
+        &program_info,
+        options,
     )
     .map_err(CliError::from)
 }
+
+/// Lays out the ABI inputs over witnesses the way `Abi::encode` does: their
+/// flattened values in declaration order, starting at witness 0
+fn abi_input_witnesses(abi: &Abi) -> Vec<(String, Vec<Witness>)> {
+    let mut next_witness = 0;
+    abi.parameters
+        .iter()
+        .map(|parameter| {
+            let field_count = parameter.typ.field_count();
+            let witnesses = (next_witness..next_witness + field_count).map(Witness).collect();
+            next_witness += field_count;
+            (parameter.name.clone(), witnesses)
+        })
+        .collect()
+}
+
+/// Renders an ABI type the way it is written in Noir source
+fn format_abi_type(typ: &AbiType) -> String {
+    match typ {
+        AbiType::Field => "Field".to_string(),
+        AbiType::Array { length, typ } => format!("[{}; {length}]", format_abi_type(typ)),
+        AbiType::Integer { sign: Sign::Unsigned, width } => format!("u{width}"),
+        AbiType::Integer { sign: Sign::Signed, width } => format!("i{width}"),
+        AbiType::Boolean => "bool".to_string(),
+        AbiType::Struct { path, .. } => path.clone(),
+        AbiType::Tuple { fields } => {
+            format!("({})", fields.iter().map(format_abi_type).collect::<Vec<_>>().join(", "))
+        }
+        AbiType::String { length } => format!("str<{length}>"),
+    }
+}
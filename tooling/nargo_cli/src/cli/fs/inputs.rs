@@ -1,11 +1,105 @@
 use noirc_abi::{
+    errors::InputParserError,
     input_parser::{Format, InputValue},
     Abi, InputMap, MAIN_RETURN_NAME,
 };
-use std::{collections::BTreeMap, path::Path};
+use std::{collections::BTreeMap, io::Read, path::Path};
 
 use crate::errors::FilesystemError;
 
+/// Prover name standing for the standard input (eg. `nargo debug --prover-name -`)
+pub(crate) const STDIN_PROVER_NAME: &str = "-";
+
+/// Returns the format of the inputs file `file_name` in `path`.
+///
+/// An explicit `.json` or `.toml` extension decides the format. Otherwise TOML is
+/// preferred, falling back to JSON when only a JSON file is present.
+pub(crate) fn input_file_format<P: AsRef<Path>>(path: P, file_name: &str) -> Format {
+    match Path::new(file_name).extension().and_then(|extension| extension.to_str()) {
+        Some("json") => Format::Json,
+        Some("toml") => Format::Toml,
+        _ => {
+            let file_path = path.as_ref().join(file_name);
+            let toml_exists = file_path.with_extension(Format::Toml.ext()).exists();
+            let json_exists = file_path.with_extension(Format::Json.ext()).exists();
+            if !toml_exists && json_exists {
+                Format::Json
+            } else {
+                Format::Toml
+            }
+        }
+    }
+}
+
+/// Returns the format of an inputs document that does not come from a file:
+/// JSON if it holds a JSON object, TOML otherwise.
+pub(crate) fn input_document_format(document: &str) -> Format {
+    if document.trim_start().starts_with('{') {
+        Format::Json
+    } else {
+        Format::Toml
+    }
+}
+
+/// Reads a whole inputs document from the standard input
+pub(crate) fn read_inputs_document_from_stdin() -> Result<String, FilesystemError> {
+    let mut document = String::new();
+    std::io::stdin().read_to_string(&mut document).map_err(FilesystemError::StdinInputs)?;
+    Ok(document)
+}
+
+/// Returns the circuit's parameters and its return value, if one exists, from
+/// an inputs document in TOML or JSON format that does not come from a file,
+/// such as one piped through the standard input.
+pub(crate) fn read_inputs_from_document(
+    document: &str,
+    abi: &Abi,
+) -> Result<(InputMap, Option<InputValue>), FilesystemError> {
+    if abi.is_empty() {
+        return Ok((BTreeMap::new(), None));
+    }
+
+    let mut input_map = input_document_format(document).parse(document, abi)?;
+    let return_value = input_map.remove(MAIN_RETURN_NAME);
+
+    Ok((input_map, return_value))
+}
+
+/// Overrides values of `input_map` with `name=value` assignments, where each value
+/// is written in TOML syntax (eg. `x=3` or `xs=[1, 2]`). Values which are not valid
+/// TOML are taken as strings, so that `x=0x2a` works without quoting.
+pub(crate) fn apply_input_overrides(
+    input_map: &mut InputMap,
+    overrides: &[String],
+    abi: &Abi,
+) -> Result<(), InputParserError> {
+    for input_override in overrides {
+        let Some((name, value)) = input_override.split_once('=') else {
+            return Err(InputParserError::ParseInputMap(format!(
+                "expected an input override of the form name=value, got `{input_override}`"
+            )));
+        };
+        let (name, value) = (name.trim(), value.trim());
+        let Some(parameter) = abi.parameters.iter().find(|parameter| parameter.name == name) else {
+            return Err(InputParserError::ParseInputMap(format!(
+                "`{name}` is not a parameter of the program"
+            )));
+        };
+
+        let parameter_abi = Abi {
+            parameters: vec![parameter.clone()],
+            return_type: None,
+            error_types: BTreeMap::new(),
+        };
+        let parsed_input = Format::Toml
+            .parse(&format!("{name} = {value}"), &parameter_abi)
+            .or_else(|_| Format::Toml.parse(&format!("{name} = {value:?}"), &parameter_abi))?;
+        input_map.extend(parsed_input);
+    }
+
+    Ok(())
+}
+
 /// Returns the circuit's parameters and its return value, if one exists.
 /// # Examples
 ///
@@ -1,7 +1,7 @@
 use acvm::{acir::native_types::WitnessStackError, FieldElement};
 use nargo::{errors::CompileError, NargoError};
 use nargo_toml::ManifestError;
-use noir_debugger::errors::DapError;
+use noir_debugger::errors::{DapError, DebuggerError, DebuggerErrorKind};
 use noirc_abi::errors::{AbiError, InputParserError};
 use std::path::PathBuf;
 use thiserror::Error;
@@ -26,6 +26,9 @@ pub(crate) enum FilesystemError {
 
     #[error("Error: could not deserialize build program: {0}")]
     ProgramSerializationError(String),
+
+    #[error("Error: could not read the program inputs from stdin: {0}")]
+    StdinInputs(std::io::Error),
 }
 
 #[derive(Debug, Error)]
@@ -52,6 +55,10 @@ pub(crate) enum CliError {
     #[error(transparent)]
     DapError(#[from] DapError),
 
+    /// Error from a debugger command, which exits with the code of its class
+    #[error(transparent)]
+    DebuggerError(#[from] DebuggerError),
+
     /// Error from Nargo
     #[error(transparent)]
     NargoError(#[from] NargoError<FieldElement>),
@@ -64,3 +71,30 @@ pub(crate) enum CliError {
     #[error(transparent)]
     CompileError(#[from] CompileError),
 }
+
+impl CliError {
+    /// Classifies the error for the debugger commands, which exit with a code
+    /// specific to each class of failure
+    pub(crate) fn debugger_error_kind(&self) -> DebuggerErrorKind {
+        match self {
+            CliError::CompileError(_) => DebuggerErrorKind::Compile,
+            CliError::NargoError(error) => error.into(),
+            CliError::DapError(error) => error.kind(),
+            CliError::DebuggerError(error) => error.kind,
+            CliError::AbiError(_)
+            | CliError::FilesystemError(_)
+            | CliError::ManifestError(_)
+            | CliError::InvalidPackageName(_)
+            | CliError::DestinationAlreadyExists(_) => DebuggerErrorKind::Input,
+            CliError::Generic(_) | CliError::LspError(_) => DebuggerErrorKind::Internal,
+        }
+    }
+
+    /// Exit code of the process when the command fails with this error
+    pub(crate) fn exit_code(&self) -> i32 {
+        match self {
+            CliError::DebuggerError(error) => error.kind.exit_code(),
+            _ => 1,
+        }
+    }
+}
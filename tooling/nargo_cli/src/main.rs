@@ -12,6 +12,8 @@ mod errors;
 
 use std::env;
 
+use errors::CliError;
+
 use color_eyre::config::HookBuilder;
 
 use tracing_appender::rolling;
@@ -44,6 +46,6 @@ fn main() {
 
     if let Err(report) = cli::start_cli() {
         eprintln!("{report}");
-        std::process::exit(1);
+        std::process::exit(report.downcast_ref::<CliError>().map_or(1, CliError::exit_code));
     }
 }
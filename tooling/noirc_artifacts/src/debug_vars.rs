@@ -34,6 +34,19 @@ impl<F: AcirField> DebugVars<F> {
         self.frames.last().map(|(fn_id, frame)| self.build_stack_frame(fn_id, frame))
     }
 
+    /// Finds a variable of the innermost stack frame by name. When a name is
+    /// shadowed, the most recently declared variable is returned.
+    pub fn lookup_var_in_current_frame(&self, name: &str) -> Option<(DebugVarId, &PrintableType)> {
+        let (_, frame) = self.frames.last()?;
+        frame
+            .keys()
+            .filter_map(|var_id| {
+                let (var_name, ptype) = self.lookup_var(*var_id)?;
+                (var_name == name).then_some((*var_id, ptype))
+            })
+            .max_by_key(|(var_id, _)| *var_id)
+    }
+
     fn lookup_var(&self, var_id: DebugVarId) -> Option<(&str, &PrintableType)> {
         self.variables.get(&var_id).and_then(|debug_var| {
             let ptype = self.types.get(&debug_var.debug_type_id)?;
@@ -146,6 +159,37 @@ impl<F: AcirField> DebugVars<F> {
         *cursor = decode_value(&mut values.iter().copied(), cursor_type);
     }
 
+    /// Renders the path of a member of a variable as it would be written in
+    /// source (eg. `arr[7].x`), along with the type of the member. Returns
+    /// `None` if the indexes don't match the variable's type.
+    pub fn describe_field(
+        &self,
+        var_id: DebugVarId,
+        indexes: &[u32],
+    ) -> Option<(String, &PrintableType)> {
+        let (name, mut cursor_type) = self.lookup_var(var_id)?;
+        let mut path = name.to_string();
+        for index in indexes {
+            cursor_type = match cursor_type {
+                PrintableType::Array { typ, .. } | PrintableType::Slice { typ } => {
+                    path.push_str(&format!("[{index}]"));
+                    typ
+                }
+                PrintableType::Struct { fields, .. } => {
+                    let (field_name, typ) = fields.get(*index as usize)?;
+                    path.push_str(&format!(".{field_name}"));
+                    typ
+                }
+                PrintableType::Tuple { types } => {
+                    path.push_str(&format!(".{index}"));
+                    types.get(*index as usize)?
+                }
+                _ => return None,
+            };
+        }
+        Some((path, cursor_type))
+    }
+
     pub fn assign_deref(&mut self, _var_id: DebugVarId, _values: &[F]) {
         unimplemented![]
     }
@@ -158,6 +202,11 @@ impl<F: AcirField> DebugVars<F> {
         self.frames.last_mut().expect("unexpected empty stack frames").1.remove(&var_id);
     }
 
+    /// Drops all stack frames, keeping the loaded debug information
+    pub fn clear_frames(&mut self) {
+        self.frames.clear();
+    }
+
     pub fn push_fn(&mut self, fn_id: DebugFnId) {
         self.frames.push((fn_id, HashMap::default()));
     }
@@ -400,6 +400,7 @@ mod tests {
                 BTreeMap::default(),
                 BTreeMap::default(),
                 BTreeMap::default(),
+                BTreeMap::default(),
             )],
         };
 